@@ -7,10 +7,12 @@ pub fn bot_callback_derive(item: TokenStream) -> TokenStream {
     let ast: DeriveInput = syn::parse(item).unwrap();
     let callback_impl = impl_trait(&ast);
     let display_impl = impl_display(&ast);
+    let name_impl = impl_name(&ast);
 
     quote! {
         #callback_impl
         #display_impl
+        #name_impl
     }.into()
 }
 
@@ -32,7 +34,7 @@ fn impl_trait(ast: &DeriveInput) -> proc_macro2::TokenStream {
             Fields::Unit => {
                 quote! {
                     Self::#variant_ident => {
-                        self.#handler_ident(&bot, query).await
+                        self.#handler_ident(app, session).await
                     }
                 }
             }
@@ -48,7 +50,7 @@ fn impl_trait(ast: &DeriveInput) -> proc_macro2::TokenStream {
 
                 quote! {
                     Self::#variant_ident(#(#field_names),*) => {
-                        self.#handler_ident(&bot, query, #(#field_names),*).await
+                        self.#handler_ident(app, session, #(#field_names),*).await
                     }
                 }
             }
@@ -61,7 +63,7 @@ fn impl_trait(ast: &DeriveInput) -> proc_macro2::TokenStream {
 
                 quote! {
                     Self::#variant_ident { #(#field_names),* } => {
-                        self.#handler_ident(&bot, query, #(#field_names),*).await
+                        self.#handler_ident(app, session, #(#field_names),*).await
                     }
                 }
             }
@@ -128,7 +130,7 @@ fn impl_trait(ast: &DeriveInput) -> proc_macro2::TokenStream {
     quote! {
             #[async_trait]
             impl CallbackCommand for #name {
-                async fn callback(&self, bot: Arc<TarantulaBot>, query: CallbackQuery) -> BotResult<()> {
+                async fn callback(&self, app: &App, session: &Session) -> BotResult<Outcome> {
                     match self {
                         #(#match_arms)*
                     }
@@ -171,6 +173,35 @@ fn to_snake_case(s: &str) -> String {
     result
 }
 
+fn impl_name(ast: &DeriveInput) -> proc_macro2::TokenStream {
+    let name = &ast.ident;
+
+    let variants = match &ast.data {
+        Data::Enum(enum_data) => &enum_data.variants,
+        _ => panic!("BotCallback can only be derived for enums"),
+    };
+
+    let name_arms = variants.iter().map(|variant| {
+        let variant_ident = &variant.ident;
+        let variant_str = to_snake_case(&variant_ident.to_string());
+
+        quote! {
+            Self::#variant_ident { .. } => #variant_str
+        }
+    });
+
+    quote! {
+        impl #name {
+            /// The variant as it starts the callback data, without arguments.
+            pub fn name(&self) -> &'static str {
+                match self {
+                    #(#name_arms),*
+                }
+            }
+        }
+    }
+}
+
 fn impl_display(ast: &DeriveInput) -> proc_macro2::TokenStream {
     let name = &ast.ident;
 
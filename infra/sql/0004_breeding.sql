@@ -0,0 +1,83 @@
+-- auto-generated definition
+create table if not exists pairing_outcomes
+(
+    id           INTEGER
+        primary key,
+    outcome_name VARCHAR(50) not null
+        unique,
+    description  TEXT
+);
+
+-- auto-generated definition
+create table if not exists egg_sac_statuses
+(
+    id          INTEGER
+        primary key,
+    status_name VARCHAR(50) not null
+        unique,
+    description TEXT
+);
+
+-- auto-generated definition
+create table if not exists breeding_pairings
+(
+    id           INTEGER
+        primary key,
+    female_id    INTEGER
+        references tarantulas,
+    male_id      INTEGER
+        references tarantulas,
+    pairing_date DATE not null,
+    outcome_id   INTEGER
+        references pairing_outcomes,
+    notes        TEXT,
+    created_at   TIMESTAMP default CURRENT_TIMESTAMP,
+    user_id      BIGINT
+        references telegram_users (telegram_id)
+);
+
+create index if not exists idx_breeding_pairings_female
+    on breeding_pairings (female_id);
+
+create index if not exists idx_breeding_pairings_male
+    on breeding_pairings (male_id);
+
+create index if not exists idx_breeding_pairings_user_id
+    on breeding_pairings (user_id);
+
+-- auto-generated definition
+create table if not exists egg_sacs
+(
+    id                 INTEGER
+        primary key,
+    pairing_id         INTEGER
+        references breeding_pairings,
+    laid_date          DATE not null,
+    expected_pull_date DATE,
+    pulled_date        DATE,
+    status_id          INTEGER
+        references egg_sac_statuses,
+    egg_count          INTEGER,
+    nymph_count        INTEGER,
+    sling_count        INTEGER,
+    notes              TEXT,
+    created_at         TIMESTAMP default CURRENT_TIMESTAMP,
+    user_id            BIGINT
+        references telegram_users (telegram_id)
+);
+
+create index if not exists idx_egg_sacs_pairing
+    on egg_sacs (pairing_id);
+
+create index if not exists idx_egg_sacs_expected_pull_date
+    on egg_sacs (expected_pull_date);
+
+create index if not exists idx_egg_sacs_user_id
+    on egg_sacs (user_id);
+
+alter table tarantulas
+    add column egg_sac_id INTEGER
+        references egg_sacs;
+
+create index if not exists idx_tarantulas_egg_sac
+    on tarantulas (egg_sac_id);
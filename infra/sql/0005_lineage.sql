@@ -0,0 +1,33 @@
+alter table tarantulas
+    add column mother_id INTEGER
+        references tarantulas;
+
+alter table tarantulas
+    add column father_id INTEGER
+        references tarantulas;
+
+alter table tarantulas
+    add column mother_external VARCHAR(100);
+
+alter table tarantulas
+    add column father_external VARCHAR(100);
+
+create index if not exists idx_tarantulas_mother
+    on tarantulas (mother_id);
+
+create index if not exists idx_tarantulas_father
+    on tarantulas (father_id);
+
+-- Slings created from an egg sac before parent links existed
+UPDATE tarantulas
+SET mother_id = (SELECT bp.female_id
+                 FROM egg_sacs es
+                          JOIN breeding_pairings bp ON es.pairing_id = bp.id
+                 WHERE es.id = tarantulas.egg_sac_id),
+    father_id = (SELECT bp.male_id
+                 FROM egg_sacs es
+                          JOIN breeding_pairings bp ON es.pairing_id = bp.id
+                 WHERE es.id = tarantulas.egg_sac_id)
+WHERE egg_sac_id IS NOT NULL
+  AND mother_id IS NULL
+  AND father_id IS NULL;
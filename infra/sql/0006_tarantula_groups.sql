@@ -0,0 +1,24 @@
+-- auto-generated definition
+create table if not exists tarantula_groups
+(
+    id               INTEGER
+        primary key,
+    name             VARCHAR(50) not null,
+    species_id       INTEGER
+        references tarantula_species,
+    acquisition_date DATE not null,
+    notes            TEXT,
+    created_at       TIMESTAMP default CURRENT_TIMESTAMP,
+    user_id          BIGINT
+        references telegram_users (telegram_id)
+);
+
+create index if not exists idx_tarantula_groups_user_id
+    on tarantula_groups (user_id);
+
+alter table tarantulas
+    add column group_id INTEGER
+        references tarantula_groups;
+
+create index if not exists idx_tarantulas_group
+    on tarantulas (group_id);
@@ -0,0 +1,22 @@
+-- auto-generated definition
+create table if not exists feeding_overrides
+(
+    id           INTEGER
+        primary key,
+    tarantula_id INTEGER not null
+        unique
+        references tarantulas,
+    frequency_id INTEGER
+        references feeding_frequencies,
+    prey_count   INTEGER,
+    prey_size_id INTEGER
+        references cricket_size_types,
+    expires_on   DATE,
+    notes        TEXT,
+    created_at   TIMESTAMP default CURRENT_TIMESTAMP,
+    user_id      BIGINT
+        references telegram_users (telegram_id)
+);
+
+create index if not exists idx_feeding_overrides_user_id
+    on feeding_overrides (user_id);
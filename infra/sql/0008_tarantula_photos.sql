@@ -0,0 +1,19 @@
+-- auto-generated definition
+create table if not exists tarantula_photos
+(
+    id           INTEGER
+        primary key,
+    tarantula_id INTEGER not null
+        references tarantulas,
+    file_id      TEXT    not null,
+    caption      TEXT,
+    taken_at     TIMESTAMP default CURRENT_TIMESTAMP,
+    user_id      BIGINT
+        references telegram_users (telegram_id)
+);
+
+create index if not exists idx_tarantula_photos_tarantula_id
+    on tarantula_photos (tarantula_id);
+
+create index if not exists idx_tarantula_photos_user_id
+    on tarantula_photos (user_id);
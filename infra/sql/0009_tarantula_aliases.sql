@@ -0,0 +1,2 @@
+alter table tarantulas
+    add column aliases TEXT;
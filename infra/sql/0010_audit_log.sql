@@ -0,0 +1,16 @@
+-- auto-generated definition
+create table if not exists audit_log
+(
+    id         INTEGER
+        primary key,
+    user_id    BIGINT    not null
+        references telegram_users (telegram_id),
+    kind       INTEGER   not null,
+    before     TEXT      not null,
+    after      TEXT      not null,
+    changed_at TIMESTAMP not null,
+    undone_at  TIMESTAMP
+);
+
+create index if not exists idx_audit_log_user_id
+    on audit_log (user_id);
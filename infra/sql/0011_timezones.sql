@@ -0,0 +1,59 @@
+-- Each keeper's timezone by name, a tz database zone such as 'Europe/Berlin'
+-- that follows daylight saving, or a fixed offset written as '+05:30'.
+-- Everyone starts on UTC, the clock times have been shown on so far.
+alter table telegram_users
+    add column timezone TEXT not null default 'UTC';
+
+-- Timestamps are UTC instants written as 'YYYY-MM-DD HH:MM:SS'. Rows written
+-- with a 'T', a zone or fractions of a second are brought into that form.
+update feeding_events
+set feeding_date = datetime(feeding_date)
+where datetime(feeding_date) is not null
+  and feeding_date <> datetime(feeding_date);
+
+update audit_log
+set changed_at = datetime(changed_at)
+where datetime(changed_at) is not null
+  and changed_at <> datetime(changed_at);
+
+update audit_log
+set undone_at = datetime(undone_at)
+where datetime(undone_at) is not null
+  and undone_at <> datetime(undone_at);
+
+update tarantula_photos
+set taken_at = datetime(taken_at)
+where datetime(taken_at) is not null
+  and taken_at <> datetime(taken_at);
+
+-- DATE columns hold the keeper's day as 'YYYY-MM-DD'. Health checks and molts
+-- used to get a time of day as well, which is dropped; on UTC the day stays.
+update health_check_records
+set check_date = date(check_date)
+where date(check_date) is not null
+  and check_date <> date(check_date);
+
+update molt_records
+set molt_date = date(molt_date)
+where date(molt_date) is not null
+  and molt_date <> date(molt_date);
+
+update tarantulas
+set last_molt_date = date(last_molt_date)
+where date(last_molt_date) is not null
+  and last_molt_date <> date(last_molt_date);
+
+update tarantulas
+set last_health_check_date = date(last_health_check_date)
+where date(last_health_check_date) is not null
+  and last_health_check_date <> date(last_health_check_date);
+
+update maintenance_records
+set maintenance_date = date(maintenance_date)
+where date(maintenance_date) is not null
+  and maintenance_date <> date(maintenance_date);
+
+update egg_sacs
+set pulled_date = date(pulled_date)
+where date(pulled_date) is not null
+  and pulled_date <> date(pulled_date);
@@ -0,0 +1,176 @@
+//! The keeper's own changes: every feeding, refusal, health check, molt and
+//! colony count the store logs, each of which can be undone once, from the
+//! message confirming it or from `/history`.
+
+use crate::app::callbacks::BotCallback;
+use crate::app::callbacks::BotCallback::MainMenu;
+use crate::app::keyboards::paging;
+use crate::app::screen::{Button, Keyboard, Outcome, Screen};
+use crate::app::App;
+use crate::models::audit::Change;
+use crate::models::enums::{HealthStatus, MoltStage};
+use crate::BotResult;
+use std::collections::HashMap;
+
+const PAGE_SIZE: u32 = 10;
+/// Undo buttons per row on the history screen.
+const UNDO_ROW: usize = 5;
+/// More tarantulas than this in one change are counted rather than named.
+const MAX_NAMED: usize = 3;
+
+/// Names to show changes with, by tarantula and colony id.
+struct Names {
+    tarantulas: HashMap<i64, String>,
+    colonies: HashMap<i64, String>,
+}
+
+impl App {
+    async fn names(&self, user_id: u64) -> BotResult<Names> {
+        Ok(Names {
+            tarantulas: self
+                .db
+                .get_all_tarantulas(user_id)
+                .await?
+                .into_iter()
+                .map(|t| (t.id, t.name))
+                .collect(),
+            colonies: self
+                .db
+                .get_colony_status(user_id)
+                .await?
+                .into_iter()
+                .map(|c| (c.id, c.colony_name))
+                .collect(),
+        })
+    }
+
+    pub(crate) async fn undo(&self, user_id: u64, change_id: i64) -> BotResult<Outcome> {
+        let change = self.db.undo_change(user_id, change_id).await?;
+        let names = self.names(user_id).await?;
+        Ok(Outcome::replace(Screen::new(
+            format!("↩️ Undone: {}", describe(&change, &names)),
+            vec![vec![
+                Button::callback("🕰 My Changes", BotCallback::ChangeHistory(0)),
+                Button::callback("« Back to Menu", MainMenu),
+            ]],
+        )))
+    }
+
+    pub(crate) async fn change_history(&self, user_id: u64, page: u32) -> BotResult<Screen> {
+        let mut changes = self
+            .db
+            .get_changes(user_id, page * PAGE_SIZE, PAGE_SIZE + 1)
+            .await?;
+        let has_next = changes.len() > PAGE_SIZE as usize;
+        changes.truncate(PAGE_SIZE as usize);
+        let names = self.names(user_id).await?;
+
+        let mut message = String::from("🕰 *Your Changes*\n\n");
+        if changes.is_empty() {
+            message.push_str(
+                "Nothing logged yet. Feedings, health checks, molts and colony counts show up here.",
+            );
+        } else {
+            let lines: Vec<String> = changes
+                .iter()
+                .enumerate()
+                .map(|(i, change)| {
+                    format!(
+                        "{}. `{}` {}{}",
+                        i + 1,
+                        change.changed_at.format("%Y-%m-%d %H:%M"),
+                        describe(change, &names),
+                        if change.undone_at.is_some() {
+                            " _(undone)_"
+                        } else {
+                            ""
+                        }
+                    )
+                })
+                .collect();
+            message.push_str(&lines.join("\n"));
+        }
+        if page > 0 || has_next {
+            message.push_str(&format!("\n\nPage {}", page + 1));
+        }
+
+        let undo_buttons: Vec<Button> = changes
+            .iter()
+            .enumerate()
+            .filter(|(_, change)| change.undone_at.is_none())
+            .map(|(i, change)| {
+                Button::callback(format!("↩️ {}", i + 1), BotCallback::Undo(change.id))
+            })
+            .collect();
+        let mut keyboard: Keyboard = undo_buttons
+            .chunks(UNDO_ROW)
+            .map(<[Button]>::to_vec)
+            .collect();
+        keyboard.extend(paging(page, has_next, BotCallback::ChangeHistory));
+        keyboard.push(vec![Button::callback("« Back to Menu", MainMenu)]);
+        Ok(Screen::new(message, keyboard))
+    }
+}
+
+/// One line for a change: what it was, who it touched, and what it moved
+/// from before to after.
+fn describe(change: &Change, names: &Names) -> String {
+    let kind = change.kind;
+    let mut parts = vec![format!("{} {}", kind.emoji(), kind.label())];
+
+    let tarantulas: Vec<&str> = change
+        .after
+        .tarantulas
+        .keys()
+        .map(|id| names.tarantulas.get(id).map_or("?", String::as_str))
+        .collect();
+    match tarantulas.len() {
+        0 => {}
+        n if n <= MAX_NAMED => parts.push(tarantulas.join(", ")),
+        n => parts.push(format!("{} tarantulas", n)),
+    }
+
+    for (id, before) in &change.before.colonies {
+        let after = change.after.colonies.get(id).copied().unwrap_or(*before);
+        let name = names.colonies.get(id).map_or("?", String::as_str);
+        parts.push(format!("{} {} → {}", name, before, after));
+    }
+
+    // A group usually moves every member the same way, so each move is
+    // listed once.
+    let mut moves: Vec<String> = Vec::new();
+    let mut moved = |from: &str, to: &str| {
+        let step = format!("{} → {}", from, to);
+        if !moves.contains(&step) {
+            moves.push(step);
+        }
+    };
+    for (id, before) in &change.before.tarantulas {
+        let Some(after) = change.after.tarantulas.get(id) else {
+            continue;
+        };
+        if before.molt_stage_id != after.molt_stage_id {
+            moved(
+                stage_name(before.molt_stage_id),
+                stage_name(after.molt_stage_id),
+            );
+        }
+        if before.health_status_id != after.health_status_id {
+            moved(
+                status_name(before.health_status_id),
+                status_name(after.health_status_id),
+            );
+        }
+    }
+    parts.extend(moves);
+
+    parts.join(" · ")
+}
+
+fn stage_name(id: Option<i64>) -> &'static str {
+    id.map_or("Unknown", |id| MoltStage::from_id(id).to_db_name())
+}
+
+fn status_name(id: Option<i64>) -> &'static str {
+    id.map_or("Unknown", |id| HealthStatus::from_id(id).to_db_name())
+}
@@ -0,0 +1,316 @@
+use crate::app::callbacks::BotCallback;
+use crate::app::dialogue::DialogueState;
+use crate::app::screen::{Button, Keyboard, Outcome, Screen};
+use crate::app::App;
+use crate::error::BotError;
+use crate::models::breeding::{EggSacCounts, EggSacRecord, PairingRecord};
+use crate::models::enums::{EggSacStatus, PairingOutcome};
+use crate::BotResult;
+
+const MAX_LISTED: usize = 10;
+
+impl App {
+    pub(crate) async fn breeding_menu(&self, user_id: u64) -> BotResult<Screen> {
+        let pairings = self.db.get_pairings(user_id).await?;
+        let egg_sacs = self.db.get_egg_sacs(user_id).await?;
+
+        let mut message = String::from("🥚 *Breeding Projects*\n\n");
+        if pairings.is_empty() {
+            message.push_str(
+                "No pairings logged yet.\nUse /addpairing female_id male_id date notes to log one.",
+            );
+        } else {
+            message.push_str("*Pairings*\n");
+            for p in pairings.iter().take(MAX_LISTED) {
+                message.push_str(&format!(
+                    "• {} × {} - {} ({})\n",
+                    p.female_name, p.male_name, p.pairing_date, p.outcome
+                ));
+            }
+        }
+
+        let open_sacs: Vec<&EggSacRecord> = egg_sacs
+            .iter()
+            .filter(|s| s.status != EggSacStatus::Failed.to_db_name())
+            .take(MAX_LISTED)
+            .collect();
+        if !open_sacs.is_empty() {
+            message.push_str("\n*Egg Sacs*\n");
+            for s in &open_sacs {
+                message.push_str(&format!(
+                    "• Sac #{} from {} - {}{}\n",
+                    s.id,
+                    s.female_name,
+                    s.status,
+                    s.expected_pull_date
+                        .filter(|_| s.status == EggSacStatus::Incubating.to_db_name())
+                        .map_or(String::new(), |d| format!(", pull by {}", d))
+                ));
+            }
+        }
+
+        let mut keyboard: Keyboard = pairings
+            .iter()
+            .take(MAX_LISTED)
+            .map(|p| {
+                vec![Button::callback(
+                    format!("💑 {} × {}", p.female_name, p.male_name),
+                    BotCallback::PairingDetails(p.id),
+                )]
+            })
+            .collect();
+        keyboard.extend(open_sacs.chunks(2).map(|chunk| {
+            chunk
+                .iter()
+                .map(|s| {
+                    Button::callback(
+                        format!("🥚 Sac #{} ({})", s.id, s.female_name),
+                        BotCallback::EggSacDetails(s.id),
+                    )
+                })
+                .collect()
+        }));
+        keyboard.push(vec![Button::callback(
+            "« Back to Menu",
+            BotCallback::MainMenu,
+        )]);
+
+        Ok(Screen::new(message, keyboard))
+    }
+
+    async fn pairing(&self, pairing_id: i64, user_id: u64) -> BotResult<PairingRecord> {
+        self.db
+            .get_pairings(user_id)
+            .await?
+            .into_iter()
+            .find(|p| p.id == pairing_id)
+            .ok_or_else(|| BotError::NotFound("Pairing not found".to_string()))
+    }
+
+    async fn egg_sac(&self, egg_sac_id: i64, user_id: u64) -> BotResult<EggSacRecord> {
+        self.db
+            .get_egg_sacs(user_id)
+            .await?
+            .into_iter()
+            .find(|s| s.id == egg_sac_id)
+            .ok_or_else(|| BotError::NotFound("Egg sac not found".to_string()))
+    }
+
+    pub(crate) async fn pairing_details(&self, pairing_id: i64, user_id: u64) -> BotResult<Screen> {
+        let pairing = self.pairing(pairing_id, user_id).await?;
+
+        let message = format!(
+            "💑 *{} × {}*\n\n\
+            • Paired: {}\n\
+            • Outcome: {}\n\
+            • Egg sacs: {}\n\
+            {}\n\
+            Log a sac with /addeggsac {} laid_date notes",
+            pairing.female_name,
+            pairing.male_name,
+            pairing.pairing_date,
+            pairing.outcome,
+            pairing.egg_sac_count,
+            pairing.notes.unwrap_or_default(),
+            pairing.id
+        );
+
+        let keyboard = vec![
+            vec![
+                Button::callback(
+                    "✅ Successful",
+                    BotCallback::SetPairingOutcome(pairing_id, PairingOutcome::Successful as i64),
+                ),
+                Button::callback(
+                    "😐 No interest",
+                    BotCallback::SetPairingOutcome(pairing_id, PairingOutcome::NoInterest as i64),
+                ),
+            ],
+            vec![
+                Button::callback(
+                    "⚠️ Aggression",
+                    BotCallback::SetPairingOutcome(pairing_id, PairingOutcome::Aggression as i64),
+                ),
+                Button::callback(
+                    "💀 Male killed",
+                    BotCallback::SetPairingOutcome(pairing_id, PairingOutcome::MaleKilled as i64),
+                ),
+            ],
+            vec![Button::callback("« Back", BotCallback::BreedingMenu)],
+        ];
+
+        Ok(Screen::new(message, keyboard))
+    }
+
+    pub(crate) async fn set_pairing_outcome(
+        &self,
+        pairing_id: i64,
+        outcome: PairingOutcome,
+        user_id: u64,
+    ) -> BotResult<Screen> {
+        self.db
+            .update_pairing_outcome(user_id, pairing_id, outcome)
+            .await?;
+        self.pairing_details(pairing_id, user_id).await
+    }
+
+    pub(crate) async fn egg_sac_details(&self, egg_sac_id: i64, user_id: u64) -> BotResult<Screen> {
+        let sac = self.egg_sac(egg_sac_id, user_id).await?;
+        let incubating = sac.status == EggSacStatus::Incubating.to_db_name();
+
+        let pull_line = match (sac.pulled_date, sac.expected_pull_date) {
+            (Some(pulled), _) => format!("• Pulled: {}\n", pulled),
+            (None, Some(expected)) if incubating => {
+                let days = (expected - self.now(user_id).await?.date()).num_days();
+                if days >= 0 {
+                    format!("• Pull by: {} (in {} days)\n", expected, days)
+                } else {
+                    format!("• Pull by: {} ({} days overdue)\n", expected, -days)
+                }
+            }
+            _ => String::new(),
+        };
+        let count = |c: Option<i32>| c.map_or("-".to_string(), |c| c.to_string());
+
+        let message = format!(
+            "🥚 *Egg Sac #{}*\n\
+            {} × {}\n\n\
+            • Status: {}\n\
+            • Laid: {}\n\
+            {}\
+            • Eggs: {}\n\
+            • Nymphs: {}\n\
+            • Slings: {}\n\
+            • Slings added to collection: {}\n\
+            {}",
+            sac.id,
+            sac.female_name,
+            sac.male_name,
+            sac.status,
+            sac.laid_date,
+            pull_line,
+            count(sac.egg_count),
+            count(sac.nymph_count),
+            count(sac.sling_count),
+            sac.slings_created,
+            sac.notes.unwrap_or_default()
+        );
+
+        let mut keyboard = Vec::new();
+        if incubating {
+            keyboard.push(vec![
+                Button::callback("🥚 Mark Pulled", BotCallback::PullEggSac(egg_sac_id)),
+                Button::callback("❌ Mark Failed", BotCallback::FailEggSac(egg_sac_id)),
+            ]);
+        }
+        keyboard.push(vec![
+            Button::callback("🔢 Record Counts", BotCallback::EggSacCounts(egg_sac_id)),
+            Button::callback("🕷 Add Slings", BotCallback::AddSlings(egg_sac_id)),
+        ]);
+        keyboard.push(vec![Button::callback("« Back", BotCallback::BreedingMenu)]);
+
+        Ok(Screen::new(message, keyboard))
+    }
+
+    pub(crate) async fn set_egg_sac_status(
+        &self,
+        egg_sac_id: i64,
+        status: EggSacStatus,
+        user_id: u64,
+    ) -> BotResult<Screen> {
+        self.db
+            .update_egg_sac_status(user_id, egg_sac_id, status)
+            .await?;
+        self.egg_sac_details(egg_sac_id, user_id).await
+    }
+
+    pub(crate) fn egg_sac_counts_prompt(&self, egg_sac_id: i64) -> Outcome {
+        Outcome::send(Screen::text(
+            "Please enter the counts as: eggs nymphs slings (use - to skip one, e.g. 120 - -)",
+        ))
+        .enter(DialogueState::RecordEggSacCounts { egg_sac_id })
+    }
+
+    pub(crate) fn add_slings_prompt(&self, egg_sac_id: i64) -> Outcome {
+        Outcome::send(Screen::text(
+            "How many slings should be added? Optionally follow with a name prefix (e.g. 40 Hamorii)",
+        ))
+        .enter(DialogueState::AddSlings { egg_sac_id })
+    }
+
+    pub(crate) async fn record_egg_sac_counts(
+        &self,
+        egg_sac_id: i64,
+        counts: EggSacCounts,
+        user_id: u64,
+    ) -> BotResult<Screen> {
+        self.db
+            .update_egg_sac_counts(user_id, egg_sac_id, counts)
+            .await?;
+        Ok(Screen::new(
+            "✅ Egg sac counts recorded",
+            back_to_sac_keyboard(egg_sac_id),
+        ))
+    }
+
+    pub(crate) async fn add_slings(
+        &self,
+        egg_sac_id: i64,
+        count: i32,
+        name_prefix: &str,
+        user_id: u64,
+    ) -> BotResult<Screen> {
+        let ids = self
+            .db
+            .create_slings_from_egg_sac(user_id, egg_sac_id, count, name_prefix)
+            .await?;
+        Ok(Screen::new(
+            format!("✅ Added {} slings to your collection", ids.len()),
+            back_to_sac_keyboard(egg_sac_id),
+        ))
+    }
+}
+
+fn back_to_sac_keyboard(egg_sac_id: i64) -> Keyboard {
+    vec![vec![Button::callback(
+        "« Back to Egg Sac",
+        BotCallback::EggSacDetails(egg_sac_id),
+    )]]
+}
+
+/// Parses "eggs nymphs slings", where `-` leaves a count unchanged.
+pub(crate) fn parse_egg_sac_counts(text: &str) -> Option<EggSacCounts> {
+    let parts: Vec<&str> = text.split_whitespace().collect();
+    if parts.is_empty() || parts.len() > 3 {
+        return None;
+    }
+
+    let mut values = [None; 3];
+    for (value, part) in values.iter_mut().zip(parts) {
+        if part != "-" {
+            *value = Some(part.parse::<i32>().ok().filter(|v| *v >= 0)?);
+        }
+    }
+
+    Some(EggSacCounts {
+        egg_count: values[0],
+        nymph_count: values[1],
+        sling_count: values[2],
+    })
+}
+
+/// Parses "count [name prefix]" for bulk sling creation.
+pub(crate) fn parse_sling_batch(text: &str) -> Option<(i32, String)> {
+    let text = text.trim();
+    let (count, prefix) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+    let count = count.parse::<i32>().ok()?;
+    let prefix = prefix.trim();
+    Some((
+        count,
+        if prefix.is_empty() {
+            "Sling".to_string()
+        } else {
+            prefix.to_string()
+        },
+    ))
+}
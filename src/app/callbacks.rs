@@ -0,0 +1,932 @@
+use crate::app::history::RecordView;
+use crate::app::listing::{ListPurpose, ListView};
+use crate::app::overrides::PREY_SIZES;
+use crate::app::screen::Outcome;
+use crate::app::when::{from_stamp, Backdate};
+use crate::app::{App, Session};
+use crate::models::enums::{EggSacStatus, HealthStatus, PairingOutcome};
+use crate::BotError;
+use crate::BotResult;
+use async_trait::async_trait;
+use bot_macros::BotCallback;
+
+#[derive(BotCallback, Debug, Clone)]
+pub enum BotCallback {
+    MainMenu,
+    ListTarantulas,
+    FeedingSchedule,
+    HealthAlerts,
+    Maintenance,
+    Colonies,
+    StatusOverview,
+    RecordFeeding,
+    RecordHealthCheck,
+    MoltHistory,
+    RecordMolt,
+    ColonyMaintenance,
+    ViewRecords,
+    ViewFeedingRecords,
+    ViewHealthRecords,
+    ViewMoltRecords,
+    BreedingMenu,
+    ConfirmPairing,
+    CancelPairing,
+    GroupsMenu,
+    ConfirmImport,
+    CancelImport,
+
+    FeedTarantula(i64),
+    HealthCheck(i64),
+    HealthStatus(i64, i64), // tarantula_id, health_status_id
+    MoltSimple(i64),        // size cm after, tarantula_id
+    ColonyMaintenanceMenu(i64),
+    FeedSelectColony(i64, i64),           // tarantula_id, colony_id
+    FeedConfirm(i64, i64, i32),           // tarantula_id, colony_id, count
+    FeedEarlier(i64, i64),                // tarantula_id, colony_id
+    BackdatedFeed(i64, i64, i32, i64),    // tarantula_id, colony_id, count, Unix seconds
+    HealthEarlier(i64),                   // tarantula_id
+    BackdatedHealthStatus(i64, i64, i64), // tarantula_id, health_status_id, Unix seconds
+    ColonyGetCount(i64),
+    ColonyCountUpdate(i64, i32), // colony_id, adjustment
+
+    ViewFeedingSchedule(i64), // tarantula_id
+
+    PairingDetails(i64),
+    SetPairingOutcome(i64, i64), // pairing_id, outcome_id
+    EggSacDetails(i64),
+    PullEggSac(i64),
+    FailEggSac(i64),
+    EggSacCounts(i64),
+    AddSlings(i64), // egg_sac_id
+    Pedigree(i64),  // tarantula_id
+
+    GroupDetails(i64),
+    GroupFeedMenu(i64),
+    GroupFeedColony(i64, i64),       // group_id, colony_id
+    GroupFeedConfirm(i64, i64, i32), // group_id, colony_id, crickets per sling
+    GroupHealthCheck(i64),
+    GroupHealthStatus(i64, i64),            // group_id, health_status_id
+    GroupFeedEarlier(i64, i64),             // group_id, colony_id
+    BackdatedGroupFeed(i64, i64, i32, i64), // group_id, colony_id, crickets per sling, Unix seconds
+    GroupHealthEarlier(i64),                // group_id
+    BackdatedGroupHealthStatus(i64, i64, i64), // group_id, health_status_id, Unix seconds
+    GroupMolt(i64),
+    GroupSplitMenu(i64, u32), // group_id, page
+    SplitFromGroup(i64),      // tarantula_id
+
+    OverrideSchedule(i64), // tarantula_id
+    ClearOverride(i64),    // tarantula_id
+
+    RecordPage(u8, i64, i64, i32, i32, u32), // kind, tarantula_id, status_id, from, to, page
+    RecordFilters(u8, i64, i64, i32, i32),   // kind, tarantula_id, status_id, from, to
+    RecordDates(u8, i64, i64),               // kind, tarantula_id, status_id
+    Timeline(i64, u32),                      // tarantula_id, page
+
+    TarantulaProfile(i64),
+    EditTarantula(i64),
+    EditField(i64, u8),        // tarantula_id, field
+    TarantulaPhotos(i64, u32), // tarantula_id, index from the newest
+    AddPhoto(i64),             // tarantula_id
+    DeletePhoto(i64, i64),     // tarantula_id, photo_id
+
+    TarantulaList(u8, u8, u8, u32), // purpose, sort, filter, page
+    ListSearch(u8),                 // purpose
+
+    QuickFeed(i64, i64, i32, u8, i64), // tarantula_id, colony_id, count, size, Unix seconds; 0 works it out
+    QuickRefused(i64, i64),            // tarantula_id, Unix seconds; 0 is now
+    QuickMolt(i64, i32, i64),          // tarantula_id, length in mm, Unix seconds; 0 asks or is now
+    QuickColony(i64, i32),             // colony_id, adjustment
+
+    Undo(i64),          // change_id
+    ChangeHistory(u32), // page
+}
+
+#[async_trait]
+pub(crate) trait CallbackCommand {
+    async fn callback(&self, app: &App, session: &Session) -> BotResult<Outcome>;
+}
+
+impl BotCallback {
+    async fn handle_main_menu(&self, app: &App, session: &Session) -> BotResult<Outcome> {
+        Ok(Outcome::send(app.welcome(session.user_id).await?))
+    }
+
+    async fn handle_list_tarantulas(&self, app: &App, session: &Session) -> BotResult<Outcome> {
+        Ok(Outcome::replace(
+            app.tarantula_list(session.user_id, ListView::new(ListPurpose::Browse))
+                .await?,
+        ))
+    }
+
+    async fn handle_feeding_schedule(&self, app: &App, session: &Session) -> BotResult<Outcome> {
+        Ok(Outcome::replace(
+            app.feeding_schedule(session.user_id).await?,
+        ))
+    }
+
+    async fn handle_feed_tarantula(
+        &self,
+        app: &App,
+        session: &Session,
+        tarantula_id: &i64,
+    ) -> BotResult<Outcome> {
+        Ok(Outcome::replace(
+            app.feed_command(*tarantula_id, session.user_id).await?,
+        ))
+    }
+
+    async fn handle_health_alerts(&self, app: &App, session: &Session) -> BotResult<Outcome> {
+        Ok(Outcome::replace(app.health_alerts(session.user_id).await?))
+    }
+
+    async fn handle_maintenance(&self, app: &App, session: &Session) -> BotResult<Outcome> {
+        Ok(Outcome::replace(app.maintenance(session.user_id).await?))
+    }
+
+    async fn handle_colonies(&self, app: &App, session: &Session) -> BotResult<Outcome> {
+        Ok(Outcome::replace(app.colonies(session.user_id).await?))
+    }
+
+    async fn handle_status_overview(&self, app: &App, session: &Session) -> BotResult<Outcome> {
+        Ok(Outcome::replace(
+            app.status_overview(session.user_id).await?,
+        ))
+    }
+
+    async fn handle_record_feeding(&self, app: &App, session: &Session) -> BotResult<Outcome> {
+        Ok(Outcome::replace(
+            app.tarantula_list(session.user_id, ListView::new(ListPurpose::Feed))
+                .await?,
+        ))
+    }
+
+    async fn handle_record_health_check(&self, app: &App, session: &Session) -> BotResult<Outcome> {
+        Ok(Outcome::replace(
+            app.tarantula_list(session.user_id, ListView::new(ListPurpose::HealthCheck))
+                .await?,
+        ))
+    }
+
+    async fn handle_molt_history(&self, app: &App, session: &Session) -> BotResult<Outcome> {
+        Ok(Outcome::replace(app.molt_history(session.user_id).await?))
+    }
+
+    async fn handle_record_molt(&self, app: &App, session: &Session) -> BotResult<Outcome> {
+        Ok(Outcome::replace(
+            app.tarantula_list(session.user_id, ListView::new(ListPurpose::Molt))
+                .await?,
+        ))
+    }
+
+    async fn handle_colony_maintenance(&self, app: &App, session: &Session) -> BotResult<Outcome> {
+        Ok(Outcome::replace(
+            app.colony_maintenance(session.user_id).await?,
+        ))
+    }
+
+    async fn handle_view_records(&self, app: &App, _session: &Session) -> BotResult<Outcome> {
+        Ok(Outcome::replace(app.view_records().await?))
+    }
+
+    async fn handle_view_feeding_records(
+        &self,
+        app: &App,
+        session: &Session,
+    ) -> BotResult<Outcome> {
+        Ok(Outcome::replace(
+            app.view_feeding_records(session.user_id).await?,
+        ))
+    }
+
+    async fn handle_view_health_records(&self, app: &App, session: &Session) -> BotResult<Outcome> {
+        Ok(Outcome::replace(
+            app.view_health_records(session.user_id).await?,
+        ))
+    }
+
+    async fn handle_view_molt_records(&self, app: &App, session: &Session) -> BotResult<Outcome> {
+        Ok(Outcome::replace(
+            app.view_molt_records(session.user_id).await?,
+        ))
+    }
+
+    async fn handle_health_check(
+        &self,
+        app: &App,
+        session: &Session,
+        tarantula_id: &i64,
+    ) -> BotResult<Outcome> {
+        Ok(Outcome::replace(
+            app.health_check_command(*tarantula_id, session.user_id, None)
+                .await?,
+        ))
+    }
+
+    async fn handle_health_status(
+        &self,
+        app: &App,
+        session: &Session,
+        tarantula_id: &i64,
+        health_status_id: &i64,
+    ) -> BotResult<Outcome> {
+        Ok(Outcome::replace(
+            app.health_status_command(
+                *tarantula_id,
+                HealthStatus::from_id(*health_status_id),
+                session.user_id,
+                None,
+            )
+            .await?,
+        ))
+    }
+
+    async fn handle_health_earlier(
+        &self,
+        app: &App,
+        _session: &Session,
+        tarantula_id: &i64,
+    ) -> BotResult<Outcome> {
+        Ok(app.when_prompt(Backdate::HealthCheck {
+            tarantula_id: *tarantula_id,
+        }))
+    }
+
+    async fn handle_backdated_health_status(
+        &self,
+        app: &App,
+        session: &Session,
+        tarantula_id: &i64,
+        health_status_id: &i64,
+        at: &i64,
+    ) -> BotResult<Outcome> {
+        Ok(Outcome::replace(
+            app.health_status_command(
+                *tarantula_id,
+                HealthStatus::from_id(*health_status_id),
+                session.user_id,
+                from_stamp(*at),
+            )
+            .await?,
+        ))
+    }
+
+    async fn handle_molt_simple(
+        &self,
+        app: &App,
+        _session: &Session,
+        tarantula_id: &i64,
+    ) -> BotResult<Outcome> {
+        Ok(app.molt_prompt(*tarantula_id, None))
+    }
+
+    async fn handle_colony_maintenance_menu(
+        &self,
+        app: &App,
+        session: &Session,
+        colony_id: &i64,
+    ) -> BotResult<Outcome> {
+        Ok(Outcome::replace(
+            app.colony_maintenance_menu(*colony_id, session.user_id)
+                .await?,
+        ))
+    }
+
+    async fn handle_feed_select_colony(
+        &self,
+        app: &App,
+        session: &Session,
+        tarantula_id: &i64,
+        colony_id: &i64,
+    ) -> BotResult<Outcome> {
+        Ok(Outcome::replace(
+            app.feed_colony_selection(*tarantula_id, *colony_id, session.user_id, None)
+                .await?,
+        ))
+    }
+
+    async fn handle_feed_confirm(
+        &self,
+        app: &App,
+        session: &Session,
+        tarantula_id: &i64,
+        colony_id: &i64,
+        count: &i32,
+    ) -> BotResult<Outcome> {
+        Ok(Outcome::replace(
+            app.feed_confirmation(*tarantula_id, *colony_id, *count, session.user_id, None)
+                .await?,
+        ))
+    }
+
+    async fn handle_feed_earlier(
+        &self,
+        app: &App,
+        _session: &Session,
+        tarantula_id: &i64,
+        colony_id: &i64,
+    ) -> BotResult<Outcome> {
+        Ok(app.when_prompt(Backdate::Feeding {
+            tarantula_id: *tarantula_id,
+            colony_id: *colony_id,
+        }))
+    }
+
+    async fn handle_backdated_feed(
+        &self,
+        app: &App,
+        session: &Session,
+        tarantula_id: &i64,
+        colony_id: &i64,
+        count: &i32,
+        at: &i64,
+    ) -> BotResult<Outcome> {
+        Ok(Outcome::replace(
+            app.feed_confirmation(
+                *tarantula_id,
+                *colony_id,
+                *count,
+                session.user_id,
+                from_stamp(*at),
+            )
+            .await?,
+        ))
+    }
+
+    async fn handle_colony_get_count(
+        &self,
+        app: &App,
+        session: &Session,
+        colony_id: &i64,
+    ) -> BotResult<Outcome> {
+        Ok(Outcome::replace(
+            app.colony_count(*colony_id, session.user_id).await?,
+        ))
+    }
+
+    async fn handle_colony_count_update(
+        &self,
+        app: &App,
+        session: &Session,
+        colony_id: &i64,
+        adjustment: &i32,
+    ) -> BotResult<Outcome> {
+        Ok(Outcome::replace(
+            app.colony_count_update(*colony_id, *adjustment, session.user_id)
+                .await?,
+        ))
+    }
+
+    async fn handle_view_feeding_schedule(
+        &self,
+        app: &App,
+        session: &Session,
+        tarantula_id: &i64,
+    ) -> BotResult<Outcome> {
+        Ok(Outcome::replace(
+            app.view_feeding_schedule(*tarantula_id, session.user_id)
+                .await?,
+        ))
+    }
+
+    async fn handle_breeding_menu(&self, app: &App, session: &Session) -> BotResult<Outcome> {
+        Ok(Outcome::replace(app.breeding_menu(session.user_id).await?))
+    }
+
+    async fn handle_pairing_details(
+        &self,
+        app: &App,
+        session: &Session,
+        pairing_id: &i64,
+    ) -> BotResult<Outcome> {
+        Ok(Outcome::replace(
+            app.pairing_details(*pairing_id, session.user_id).await?,
+        ))
+    }
+
+    async fn handle_set_pairing_outcome(
+        &self,
+        app: &App,
+        session: &Session,
+        pairing_id: &i64,
+        outcome_id: &i64,
+    ) -> BotResult<Outcome> {
+        Ok(Outcome::replace(
+            app.set_pairing_outcome(
+                *pairing_id,
+                PairingOutcome::from_id(*outcome_id),
+                session.user_id,
+            )
+            .await?,
+        ))
+    }
+
+    async fn handle_egg_sac_details(
+        &self,
+        app: &App,
+        session: &Session,
+        egg_sac_id: &i64,
+    ) -> BotResult<Outcome> {
+        Ok(Outcome::replace(
+            app.egg_sac_details(*egg_sac_id, session.user_id).await?,
+        ))
+    }
+
+    async fn handle_pull_egg_sac(
+        &self,
+        app: &App,
+        session: &Session,
+        egg_sac_id: &i64,
+    ) -> BotResult<Outcome> {
+        Ok(Outcome::replace(
+            app.set_egg_sac_status(*egg_sac_id, EggSacStatus::Pulled, session.user_id)
+                .await?,
+        ))
+    }
+
+    async fn handle_fail_egg_sac(
+        &self,
+        app: &App,
+        session: &Session,
+        egg_sac_id: &i64,
+    ) -> BotResult<Outcome> {
+        Ok(Outcome::replace(
+            app.set_egg_sac_status(*egg_sac_id, EggSacStatus::Failed, session.user_id)
+                .await?,
+        ))
+    }
+
+    async fn handle_egg_sac_counts(
+        &self,
+        app: &App,
+        _session: &Session,
+        egg_sac_id: &i64,
+    ) -> BotResult<Outcome> {
+        Ok(app.egg_sac_counts_prompt(*egg_sac_id))
+    }
+
+    async fn handle_add_slings(
+        &self,
+        app: &App,
+        _session: &Session,
+        egg_sac_id: &i64,
+    ) -> BotResult<Outcome> {
+        Ok(app.add_slings_prompt(*egg_sac_id))
+    }
+
+    async fn handle_confirm_pairing(&self, app: &App, session: &Session) -> BotResult<Outcome> {
+        app.confirm_pairing(session).await
+    }
+
+    async fn handle_cancel_pairing(&self, app: &App, session: &Session) -> BotResult<Outcome> {
+        app.cancel_pairing(session.user_id).await
+    }
+
+    async fn handle_confirm_import(&self, app: &App, session: &Session) -> BotResult<Outcome> {
+        app.confirm_import(session).await
+    }
+
+    async fn handle_cancel_import(&self, app: &App, _session: &Session) -> BotResult<Outcome> {
+        Ok(app.cancel_import())
+    }
+
+    async fn handle_pedigree(
+        &self,
+        app: &App,
+        session: &Session,
+        tarantula_id: &i64,
+    ) -> BotResult<Outcome> {
+        Ok(Outcome::replace(
+            app.pedigree(*tarantula_id, session.user_id).await?,
+        ))
+    }
+
+    async fn handle_groups_menu(&self, app: &App, session: &Session) -> BotResult<Outcome> {
+        Ok(Outcome::replace(app.groups_menu(session.user_id).await?))
+    }
+
+    async fn handle_group_details(
+        &self,
+        app: &App,
+        session: &Session,
+        group_id: &i64,
+    ) -> BotResult<Outcome> {
+        Ok(Outcome::replace(
+            app.group_details(*group_id, session.user_id).await?,
+        ))
+    }
+
+    async fn handle_group_feed_menu(
+        &self,
+        app: &App,
+        session: &Session,
+        group_id: &i64,
+    ) -> BotResult<Outcome> {
+        Ok(Outcome::replace(
+            app.group_feed_menu(*group_id, session.user_id).await?,
+        ))
+    }
+
+    async fn handle_group_feed_colony(
+        &self,
+        app: &App,
+        session: &Session,
+        group_id: &i64,
+        colony_id: &i64,
+    ) -> BotResult<Outcome> {
+        Ok(Outcome::replace(
+            app.group_feed_colony(*group_id, *colony_id, session.user_id, None)
+                .await?,
+        ))
+    }
+
+    async fn handle_group_feed_confirm(
+        &self,
+        app: &App,
+        session: &Session,
+        group_id: &i64,
+        colony_id: &i64,
+        per_member: &i32,
+    ) -> BotResult<Outcome> {
+        Ok(Outcome::replace(
+            app.group_feed_confirm(*group_id, *colony_id, *per_member, session.user_id, None)
+                .await?,
+        ))
+    }
+
+    async fn handle_group_feed_earlier(
+        &self,
+        app: &App,
+        _session: &Session,
+        group_id: &i64,
+        colony_id: &i64,
+    ) -> BotResult<Outcome> {
+        Ok(app.when_prompt(Backdate::GroupFeeding {
+            group_id: *group_id,
+            colony_id: *colony_id,
+        }))
+    }
+
+    async fn handle_backdated_group_feed(
+        &self,
+        app: &App,
+        session: &Session,
+        group_id: &i64,
+        colony_id: &i64,
+        per_member: &i32,
+        at: &i64,
+    ) -> BotResult<Outcome> {
+        Ok(Outcome::replace(
+            app.group_feed_confirm(
+                *group_id,
+                *colony_id,
+                *per_member,
+                session.user_id,
+                from_stamp(*at),
+            )
+            .await?,
+        ))
+    }
+
+    async fn handle_group_health_check(
+        &self,
+        app: &App,
+        session: &Session,
+        group_id: &i64,
+    ) -> BotResult<Outcome> {
+        Ok(Outcome::replace(
+            app.group_health_check(*group_id, session.user_id, None)
+                .await?,
+        ))
+    }
+
+    async fn handle_group_health_status(
+        &self,
+        app: &App,
+        session: &Session,
+        group_id: &i64,
+        status_id: &i64,
+    ) -> BotResult<Outcome> {
+        Ok(Outcome::replace(
+            app.group_health_status(
+                *group_id,
+                HealthStatus::from_id(*status_id),
+                session.user_id,
+                None,
+            )
+            .await?,
+        ))
+    }
+
+    async fn handle_group_health_earlier(
+        &self,
+        app: &App,
+        _session: &Session,
+        group_id: &i64,
+    ) -> BotResult<Outcome> {
+        Ok(app.when_prompt(Backdate::GroupHealthCheck {
+            group_id: *group_id,
+        }))
+    }
+
+    async fn handle_backdated_group_health_status(
+        &self,
+        app: &App,
+        session: &Session,
+        group_id: &i64,
+        status_id: &i64,
+        at: &i64,
+    ) -> BotResult<Outcome> {
+        Ok(Outcome::replace(
+            app.group_health_status(
+                *group_id,
+                HealthStatus::from_id(*status_id),
+                session.user_id,
+                from_stamp(*at),
+            )
+            .await?,
+        ))
+    }
+
+    async fn handle_group_molt(
+        &self,
+        app: &App,
+        _session: &Session,
+        group_id: &i64,
+    ) -> BotResult<Outcome> {
+        Ok(app.group_molt_prompt(*group_id))
+    }
+
+    async fn handle_group_split_menu(
+        &self,
+        app: &App,
+        session: &Session,
+        group_id: &i64,
+        page: &u32,
+    ) -> BotResult<Outcome> {
+        Ok(Outcome::replace(
+            app.group_split_menu(*group_id, *page, session.user_id)
+                .await?,
+        ))
+    }
+
+    async fn handle_split_from_group(
+        &self,
+        app: &App,
+        _session: &Session,
+        tarantula_id: &i64,
+    ) -> BotResult<Outcome> {
+        Ok(app.split_from_group_prompt(*tarantula_id))
+    }
+
+    async fn handle_override_schedule(
+        &self,
+        app: &App,
+        session: &Session,
+        tarantula_id: &i64,
+    ) -> BotResult<Outcome> {
+        Ok(Outcome::replace(
+            app.override_schedule(*tarantula_id, session.user_id)
+                .await?,
+        ))
+    }
+
+    async fn handle_clear_override(
+        &self,
+        app: &App,
+        session: &Session,
+        tarantula_id: &i64,
+    ) -> BotResult<Outcome> {
+        Ok(Outcome::replace(
+            app.clear_override(*tarantula_id, session.user_id).await?,
+        ))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn handle_record_page(
+        &self,
+        app: &App,
+        session: &Session,
+        kind: &u8,
+        tarantula_id: &i64,
+        status_id: &i64,
+        from: &i32,
+        to: &i32,
+        page: &u32,
+    ) -> BotResult<Outcome> {
+        let view = RecordView::decode(*kind, *tarantula_id, *status_id, *from, *to, *page)?;
+        Ok(Outcome::replace(app.records(session.user_id, view).await?))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn handle_record_filters(
+        &self,
+        app: &App,
+        session: &Session,
+        kind: &u8,
+        tarantula_id: &i64,
+        status_id: &i64,
+        from: &i32,
+        to: &i32,
+    ) -> BotResult<Outcome> {
+        let view = RecordView::decode(*kind, *tarantula_id, *status_id, *from, *to, 0)?;
+        Ok(Outcome::replace(
+            app.record_filters(session.user_id, view).await?,
+        ))
+    }
+
+    async fn handle_record_dates(
+        &self,
+        app: &App,
+        _session: &Session,
+        kind: &u8,
+        tarantula_id: &i64,
+        status_id: &i64,
+    ) -> BotResult<Outcome> {
+        let view = RecordView::decode(*kind, *tarantula_id, *status_id, 0, 0, 0)?;
+        Ok(app.record_dates_prompt(view))
+    }
+
+    async fn handle_timeline(
+        &self,
+        app: &App,
+        session: &Session,
+        tarantula_id: &i64,
+        page: &u32,
+    ) -> BotResult<Outcome> {
+        Ok(Outcome::replace(
+            app.timeline(session.user_id, *tarantula_id, *page).await?,
+        ))
+    }
+
+    async fn handle_tarantula_profile(
+        &self,
+        app: &App,
+        session: &Session,
+        tarantula_id: &i64,
+    ) -> BotResult<Outcome> {
+        app.profile(session.user_id, *tarantula_id).await
+    }
+
+    async fn handle_edit_tarantula(
+        &self,
+        app: &App,
+        session: &Session,
+        tarantula_id: &i64,
+    ) -> BotResult<Outcome> {
+        Ok(Outcome::replace(
+            app.edit_tarantula_menu(session.user_id, *tarantula_id)
+                .await?,
+        ))
+    }
+
+    async fn handle_edit_field(
+        &self,
+        app: &App,
+        session: &Session,
+        tarantula_id: &i64,
+        field: &u8,
+    ) -> BotResult<Outcome> {
+        app.edit_field_prompt(session.user_id, *tarantula_id, *field)
+            .await
+    }
+
+    async fn handle_tarantula_photos(
+        &self,
+        app: &App,
+        session: &Session,
+        tarantula_id: &i64,
+        index: &u32,
+    ) -> BotResult<Outcome> {
+        app.photos(session.user_id, *tarantula_id, *index).await
+    }
+
+    async fn handle_add_photo(
+        &self,
+        app: &App,
+        session: &Session,
+        tarantula_id: &i64,
+    ) -> BotResult<Outcome> {
+        app.add_photo_prompt(session.user_id, *tarantula_id).await
+    }
+
+    async fn handle_delete_photo(
+        &self,
+        app: &App,
+        session: &Session,
+        tarantula_id: &i64,
+        photo_id: &i64,
+    ) -> BotResult<Outcome> {
+        app.delete_photo(session.user_id, *tarantula_id, *photo_id)
+            .await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn handle_tarantula_list(
+        &self,
+        app: &App,
+        session: &Session,
+        purpose: &u8,
+        sort: &u8,
+        filter: &u8,
+        page: &u32,
+    ) -> BotResult<Outcome> {
+        let view = ListView::decode(*purpose, *sort, *filter, *page)?;
+        Ok(Outcome::replace(
+            app.tarantula_list(session.user_id, view).await?,
+        ))
+    }
+
+    async fn handle_list_search(
+        &self,
+        app: &App,
+        _session: &Session,
+        purpose: &u8,
+    ) -> BotResult<Outcome> {
+        Ok(app.search_prompt(ListPurpose::from_id(*purpose)?))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn handle_quick_feed(
+        &self,
+        app: &App,
+        session: &Session,
+        tarantula_id: &i64,
+        colony_id: &i64,
+        count: &i32,
+        size: &u8,
+        at: &i64,
+    ) -> BotResult<Outcome> {
+        let size = PREY_SIZES.into_iter().find(|s| *s as u8 == *size);
+        app.quick_feed(
+            session.user_id,
+            *tarantula_id,
+            Some(*colony_id).filter(|id| *id > 0),
+            Some(*count).filter(|n| *n > 0),
+            size,
+            from_stamp(*at),
+        )
+        .await
+    }
+
+    async fn handle_quick_refused(
+        &self,
+        app: &App,
+        session: &Session,
+        tarantula_id: &i64,
+        at: &i64,
+    ) -> BotResult<Outcome> {
+        app.quick_refusal(session.user_id, *tarantula_id, from_stamp(*at))
+            .await
+    }
+
+    async fn handle_quick_molt(
+        &self,
+        app: &App,
+        session: &Session,
+        tarantula_id: &i64,
+        length_mm: &i32,
+        at: &i64,
+    ) -> BotResult<Outcome> {
+        if *length_mm <= 0 {
+            return Ok(app.molt_prompt(*tarantula_id, from_stamp(*at)));
+        }
+        app.quick_molt(
+            session.user_id,
+            *tarantula_id,
+            *length_mm as f32 / 10.0,
+            from_stamp(*at),
+        )
+        .await
+    }
+
+    async fn handle_quick_colony(
+        &self,
+        app: &App,
+        session: &Session,
+        colony_id: &i64,
+        adjustment: &i32,
+    ) -> BotResult<Outcome> {
+        app.quick_colony(session.user_id, *colony_id, *adjustment)
+            .await
+    }
+
+    async fn handle_undo(
+        &self,
+        app: &App,
+        session: &Session,
+        change_id: &i64,
+    ) -> BotResult<Outcome> {
+        app.undo(session.user_id, *change_id).await
+    }
+
+    async fn handle_change_history(
+        &self,
+        app: &App,
+        session: &Session,
+        page: &u32,
+    ) -> BotResult<Outcome> {
+        Ok(Outcome::replace(
+            app.change_history(session.user_id, *page).await?,
+        ))
+    }
+}
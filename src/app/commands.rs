@@ -0,0 +1,292 @@
+use crate::app::callbacks::{BotCallback, CallbackCommand};
+use crate::app::inline::deep_link;
+use crate::app::keyboards::back_to_menu_keyboard;
+use crate::app::lineage::parse_parent;
+use crate::app::listing::ListPurpose;
+use crate::app::overrides::{parse_override_expiry, parse_override_field};
+use crate::app::screen::{Button, Outcome, Screen};
+use crate::app::{App, Session};
+use crate::db::db::{
+    AddColonyParams, AddEggSacParams, AddPairingParams, AddTarantulaParams, CreateGroupParams,
+    SetFeedingOverrideParams,
+};
+use crate::error::BotError;
+use crate::models::user::TelegramUser;
+use crate::BotResult;
+use chrono::NaiveDate;
+use teloxide::macros::BotCommands;
+use teloxide::utils::command::BotCommands as _;
+
+#[derive(BotCommands, Clone)]
+#[command(rename_rule = "lowercase", description = "Available commands:")]
+pub(crate) enum Command {
+    #[command(description = "show this message.")]
+    Help,
+    #[command(description = "start bot interaction.")]
+    Start(String),
+    #[command(
+        description = "add a new tarantula. use /addtarantula name species_id acqusition_date age_months notes ",
+        parse_with = "split"
+    )]
+    AddTarantula(String, i64, String, i64, String),
+    #[command(
+        description = "add a new cricket colony. use /addcolony name size_type_id current_count last_count_date notes",
+        parse_with = "split"
+    )]
+    AddColony(String, i64, i32, String, String),
+    #[command(
+        description = "log a breeding pairing. use /addpairing female_id male_id pairing_date notes",
+        parse_with = "split"
+    )]
+    AddPairing(i64, i64, String, String),
+    #[command(
+        description = "log an egg sac. use /addeggsac pairing_id laid_date notes",
+        parse_with = "split"
+    )]
+    AddEggSac(i64, String, String),
+    #[command(
+        description = "record parents. use /setparents tarantula_id mother father (id, breeder_name or -)",
+        parse_with = "split"
+    )]
+    SetParents(i64, String, String),
+    #[command(
+        description = "customize a feeding schedule. use /feedoverride tarantula_id frequency_id prey_count prey_size_id expires_on (- keeps the species default)",
+        parse_with = "split"
+    )]
+    FeedOverride(i64, String, String, String, String),
+    #[command(
+        description = "add a group of slings. use /addgroup name species_id count acquisition_date",
+        parse_with = "split"
+    )]
+    AddGroup(String, i64, i32, String),
+    #[command(description = "download your data as CSV and JSON. use /export xlsx to add a spreadsheet")]
+    Export(String),
+    #[command(description = "how to import history from a CSV or spreadsheet")]
+    Import,
+    #[command(description = "find a tarantula by name, species or enclosure. use /find rosie")]
+    Find(String),
+    #[command(description = "your recent feedings, health checks, molts and colony counts, to undo a mistake")]
+    History,
+    #[command(description = "show or set your timezone. use /timezone Europe/Berlin or /timezone +02:00")]
+    Timezone(String),
+}
+
+impl Command {
+    /// The command as typed, without the slash or arguments.
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            Command::Help => "help",
+            Command::Start(..) => "start",
+            Command::AddTarantula(..) => "addtarantula",
+            Command::AddColony(..) => "addcolony",
+            Command::AddPairing(..) => "addpairing",
+            Command::AddEggSac(..) => "addeggsac",
+            Command::SetParents(..) => "setparents",
+            Command::FeedOverride(..) => "feedoverride",
+            Command::AddGroup(..) => "addgroup",
+            Command::Export(..) => "export",
+            Command::Import => "import",
+            Command::Find(..) => "find",
+            Command::History => "history",
+            Command::Timezone(..) => "timezone",
+        }
+    }
+}
+
+impl App {
+    /// Runs a slash command for `user`, registering them on first contact.
+    /// Failures the keeper caused are shown to them as an error screen, internal
+    /// ones are returned so the transport can report them.
+    pub(crate) async fn command(&self, user: &TelegramUser, cmd: Command) -> BotResult<Outcome> {
+        self.db.ensure_user_exists(user).await?;
+        let user_id = user.telegram_id;
+
+        let result = match cmd {
+            Command::Help => Ok(Outcome::send(Screen::text(
+                Command::descriptions().to_string(),
+            ))),
+            Command::Start(payload) => match deep_link(&payload) {
+                Some(callback) => {
+                    let session = Session {
+                        user_id,
+                        dialogue: None,
+                    };
+                    callback.callback(self, &session).await
+                }
+                None => self.welcome(user_id).await.map(Outcome::send),
+            },
+            Command::AddTarantula(name, species, date, age_months, notes) => {
+                self.db
+                    .add_tarantula(
+                        user_id,
+                        AddTarantulaParams {
+                            name,
+                            species_id: species,
+                            acquisition_date: date,
+                            estimated_age_months: age_months,
+                            notes: Some(notes),
+                            enclosure_number: None,
+                        },
+                    )
+                    .await?;
+                self.welcome(user_id).await.map(Outcome::send)
+            }
+            Command::AddColony(colony_name, size_type_id, current_count, container_name, notes) => {
+                self.db
+                    .add_colony(
+                        user_id,
+                        AddColonyParams {
+                            colony_name,
+                            size_type_id,
+                            current_count,
+                            container_number: container_name,
+                            notes: Some(notes),
+                        },
+                    )
+                    .await?;
+                self.welcome(user_id).await.map(Outcome::send)
+            }
+            Command::AddPairing(female_id, male_id, date, notes) => {
+                self.add_pairing(
+                    user_id,
+                    AddPairingParams {
+                        female_id,
+                        male_id,
+                        pairing_date: NaiveDate::parse_from_str(&date, "%Y-%m-%d")?,
+                        notes: Some(notes),
+                    },
+                )
+                .await
+            }
+            Command::AddEggSac(pairing_id, date, notes) => {
+                let egg_sac_id = self
+                    .db
+                    .record_egg_sac(
+                        user_id,
+                        AddEggSacParams {
+                            pairing_id,
+                            laid_date: NaiveDate::parse_from_str(&date, "%Y-%m-%d")?,
+                            expected_pull_date: None,
+                            notes: Some(notes),
+                        },
+                    )
+                    .await?;
+                Ok(Outcome::send(Screen::new(
+                    format!("✅ Egg sac #{} logged", egg_sac_id),
+                    back_to_menu_keyboard(),
+                )))
+            }
+            Command::SetParents(tarantula_id, mother, father) => {
+                self.db
+                    .set_tarantula_parents(
+                        user_id,
+                        tarantula_id,
+                        parse_parent(&mother),
+                        parse_parent(&father),
+                    )
+                    .await?;
+                Ok(Outcome::send(Screen::new(
+                    "✅ Parents recorded",
+                    vec![vec![Button::callback(
+                        "🧬 View Pedigree",
+                        BotCallback::Pedigree(tarantula_id),
+                    )]],
+                )))
+            }
+            Command::FeedOverride(
+                tarantula_id,
+                frequency_id,
+                prey_count,
+                prey_size_id,
+                expires,
+            ) => {
+                let params = SetFeedingOverrideParams {
+                    tarantula_id,
+                    frequency_id: parse_override_field(&frequency_id)?,
+                    prey_count: parse_override_field(&prey_count)?,
+                    prey_size_id: parse_override_field(&prey_size_id)?,
+                    expires_on: parse_override_expiry(&expires)?,
+                    notes: None,
+                };
+                self.set_feeding_override(user_id, params)
+                    .await
+                    .map(Outcome::send)
+            }
+            Command::AddGroup(name, species_id, count, date) => {
+                let group_id = self
+                    .db
+                    .create_group(
+                        user_id,
+                        CreateGroupParams {
+                            name,
+                            species_id,
+                            count,
+                            acquisition_date: NaiveDate::parse_from_str(&date, "%Y-%m-%d")?,
+                            notes: None,
+                        },
+                    )
+                    .await?;
+                Ok(Outcome::send(Screen::new(
+                    format!("✅ Group of {} slings created", count),
+                    vec![vec![Button::callback(
+                        "🧺 View Group",
+                        BotCallback::GroupDetails(group_id),
+                    )]],
+                )))
+            }
+            Command::Export(format) => self.export(user_id, &format).await,
+            Command::Import => Ok(Outcome::send(self.import_help())),
+            Command::Find(query) if query.trim().is_empty() => {
+                Ok(self.search_prompt(ListPurpose::Browse))
+            }
+            Command::Find(query) => {
+                self.search_tarantulas(user_id, ListPurpose::Browse, &query)
+                    .await
+            }
+            Command::History => self.change_history(user_id, 0).await.map(Outcome::send),
+            Command::Timezone(zone) => self.timezone(user_id, &zone).await.map(Outcome::send),
+        };
+
+        match result {
+            Err(e) if e.is_internal() => Err(e),
+            result => Ok(result.unwrap_or_else(|e| Outcome::send(error_screen(&e)))),
+        }
+    }
+}
+
+/// Turns a failed command into something the keeper can act on. Internal
+/// errors are logged and replaced with a generic message.
+pub(crate) fn error_screen(error: &BotError) -> Screen {
+    let error_message = match error {
+        BotError::NotFound(msg) => format!("❌ {}", msg),
+        BotError::ValidationError(msg) => format!("⚠️ {}", msg),
+        BotError::DateParse(e) => format!("⚠️ Dates are written as YYYY-MM-DD ({})", e),
+        BotError::Database(e) => {
+            log::error!("Database error: {:?}", e);
+            "❌ A database error occurred. Please try again later.".to_string()
+        }
+        BotError::Telegram(e) => {
+            log::error!("Telegram error: {:?}", e);
+            "❌ A communication error occurred. Please try again later.".to_string()
+        }
+        _ => {
+            log::error!("Unexpected error: {:?}", error);
+            "❌ An unexpected error occurred. Please try again later.".to_string()
+        }
+    };
+
+    Screen::new(error_message, back_to_menu_keyboard())
+}
+
+/// What the keeper sees when something failed on our side and was reported
+/// under `reference`.
+pub(crate) fn incident_screen(reference: &str) -> Screen {
+    Screen::new(
+        format!(
+            "❌ Something went wrong on our side and the team has been told. \
+             If it keeps happening, mention the reference <code>{}</code>.",
+            reference
+        ),
+        back_to_menu_keyboard(),
+    )
+}
@@ -0,0 +1,252 @@
+use crate::app::breeding::{parse_egg_sac_counts, parse_sling_batch};
+use crate::app::groups::parse_group_molt_size;
+use crate::app::history::{parse_date_range, RecordView};
+use crate::app::listing::ListPurpose;
+use crate::app::profile::parse_field_value;
+use crate::app::screen::{Outcome, Screen};
+use crate::app::when::{parse_when, split_when, Backdate, WHEN_EXAMPLES};
+use crate::app::{App, Session};
+use crate::models::import::ImportPlan;
+use crate::models::tarantula::TarantulaField;
+use crate::BotResult;
+use chrono::{NaiveDate, NaiveDateTime};
+
+#[derive(Clone, Debug, Default)]
+pub enum DialogueState {
+    #[default]
+    Start,
+
+    RecordMolt {
+        tarantula_id: i64,
+        at: Option<NaiveDateTime>,
+    },
+
+    RecordEggSacCounts {
+        egg_sac_id: i64,
+    },
+
+    AddSlings {
+        egg_sac_id: i64,
+    },
+
+    ConfirmPairing {
+        female_id: i64,
+        male_id: i64,
+        pairing_date: NaiveDate,
+        notes: Option<String>,
+    },
+
+    RecordGroupMolt {
+        group_id: i64,
+    },
+
+    SplitFromGroup {
+        tarantula_id: i64,
+    },
+
+    ConfirmImport {
+        plan: ImportPlan,
+    },
+
+    RecordDateRange {
+        kind: u8,
+        tarantula_id: Option<i64>,
+        status_id: Option<i64>,
+    },
+
+    EditTarantula {
+        tarantula_id: i64,
+        field: TarantulaField,
+    },
+
+    AddPhoto {
+        tarantula_id: i64,
+    },
+
+    SearchTarantulas {
+        purpose: ListPurpose,
+    },
+
+    RecordWhen {
+        record: Backdate,
+    },
+}
+
+impl DialogueState {
+    /// The variant without its fields, for metrics.
+    pub fn name(&self) -> &'static str {
+        match self {
+            DialogueState::Start => "start",
+            DialogueState::RecordMolt { .. } => "record_molt",
+            DialogueState::RecordEggSacCounts { .. } => "record_egg_sac_counts",
+            DialogueState::AddSlings { .. } => "add_slings",
+            DialogueState::ConfirmPairing { .. } => "confirm_pairing",
+            DialogueState::RecordGroupMolt { .. } => "record_group_molt",
+            DialogueState::SplitFromGroup { .. } => "split_from_group",
+            DialogueState::ConfirmImport { .. } => "confirm_import",
+            DialogueState::RecordDateRange { .. } => "record_date_range",
+            DialogueState::EditTarantula { .. } => "edit_tarantula",
+            DialogueState::AddPhoto { .. } => "add_photo",
+            DialogueState::SearchTarantulas { .. } => "search_tarantulas",
+            DialogueState::RecordWhen { .. } => "record_when",
+        }
+    }
+}
+
+impl App {
+    /// Handles a free-text message from a keeper in `session.dialogue`. Input
+    /// that does not parse re-asks and keeps the dialogue open. Outside a
+    /// dialogue the message is read as a quick log.
+    pub async fn reply(&self, session: &Session, text: &str) -> BotResult<Outcome> {
+        let user_id = session.user_id;
+        let Some(state) = session.dialogue.clone() else {
+            return self.quick_log(user_id, text).await;
+        };
+
+        match state {
+            DialogueState::Start => Ok(Outcome::default()
+                .exit()
+                .then(self.quick_log(user_id, text).await?)),
+            DialogueState::RecordMolt { tarantula_id, at } => {
+                let (size, said) = split_when(text, self.now(user_id).await?);
+                match size.parse::<f32>() {
+                    Ok(size) => Ok(Outcome::send(Screen::text(format!(
+                        "Recording molt with size: {}cm",
+                        size
+                    )))
+                    .then(Outcome::send(
+                        self.record_molt_command(tarantula_id, size, user_id, said.or(at))
+                            .await?,
+                    ))
+                    .exit()),
+                    Err(_) => Ok(Outcome::send(Screen::text(
+                        "Please send me the size in centimeters (e.g., 12.5)",
+                    ))),
+                }
+            }
+            DialogueState::RecordEggSacCounts { egg_sac_id } => match parse_egg_sac_counts(text) {
+                Some(counts) => Ok(Outcome::send(
+                    self.record_egg_sac_counts(egg_sac_id, counts, user_id)
+                        .await?,
+                )
+                .exit()),
+                None => Ok(Outcome::send(Screen::text(
+                    "Please send the counts as: eggs nymphs slings (e.g., 120 - -)",
+                ))),
+            },
+            DialogueState::AddSlings { egg_sac_id } => match parse_sling_batch(text) {
+                Some((count, prefix)) => Ok(Outcome::send(
+                    self.add_slings(egg_sac_id, count, &prefix, user_id).await?,
+                )
+                .exit()),
+                None => Ok(Outcome::send(Screen::text(
+                    "Please send the number of slings, optionally with a name prefix (e.g., 40 Hamorii)",
+                ))),
+            },
+            DialogueState::ConfirmPairing { .. } => Ok(Outcome::send(Screen::text(
+                "Please confirm or cancel the pending pairing using the buttons above.",
+            ))),
+            DialogueState::RecordGroupMolt { group_id } => {
+                let (size, at) = split_when(text, self.now(user_id).await?);
+                match parse_group_molt_size(&size) {
+                    Some(size) => Ok(Outcome::send(
+                        self.record_group_molt(group_id, size, user_id, at).await?,
+                    )
+                    .exit()),
+                    None => Ok(Outcome::send(Screen::text(
+                        "Please send me the size in centimeters (e.g., 1.5), or - to skip",
+                    ))),
+                }
+            }
+            DialogueState::SplitFromGroup { tarantula_id } => {
+                match Some(text.trim()).filter(|t| !t.is_empty()) {
+                    Some(text) => {
+                        let new_name = (text != "-").then(|| text.to_string());
+                        Ok(Outcome::send(
+                            self.split_from_group(tarantula_id, new_name, user_id)
+                                .await?,
+                        )
+                        .exit())
+                    }
+                    None => Ok(Outcome::send(Screen::text(
+                        "Please send a name for the sling, or - to keep its current name",
+                    ))),
+                }
+            }
+            DialogueState::ConfirmImport { .. } => Ok(Outcome::send(Screen::text(
+                "Please confirm or cancel the import using the buttons above.",
+            ))),
+            DialogueState::RecordDateRange {
+                kind,
+                tarantula_id,
+                status_id,
+            } => match parse_date_range(text) {
+                Some((from, to)) => {
+                    let view = RecordView {
+                        tarantula_id,
+                        status_id,
+                        from,
+                        to,
+                        ..RecordView::decode(kind, 0, 0, 0, 0, 0)?
+                    };
+                    Ok(Outcome::send(self.records(user_id, view).await?).exit())
+                }
+                None => Ok(Outcome::send(Screen::text(
+                    "Please send two days as YYYY-MM-DD YYYY-MM-DD, the first one not after the second",
+                ))),
+            },
+            DialogueState::EditTarantula {
+                tarantula_id,
+                field,
+            } => match parse_field_value(field, text) {
+                Some(value) => Ok(self
+                    .edit_tarantula(user_id, tarantula_id, field, value)
+                    .await?
+                    .exit()),
+                None => Ok(Outcome::send(Screen::text(format!(
+                    "Please send the new {}",
+                    field.label().to_lowercase()
+                )))),
+            },
+            DialogueState::AddPhoto { .. } => Ok(Outcome::send(Screen::text(
+                "Please send a photo, not a file or text",
+            ))),
+            DialogueState::SearchTarantulas { purpose } => {
+                if text.trim().is_empty() {
+                    return Ok(self.search_prompt(purpose));
+                }
+                self.search_tarantulas(user_id, purpose, text).await
+            }
+            DialogueState::RecordWhen { record } => {
+                let now = self.now(user_id).await?;
+                match parse_when(text, now).filter(|at| *at <= now) {
+                    Some(at) => Ok(Outcome::send(
+                        self.backdated_picker(user_id, record, at).await?,
+                    )
+                    .exit()),
+                    None => Ok(Outcome::send(Screen::text(format!(
+                        "Please send when it was, not later than now: {}",
+                        WHEN_EXAMPLES
+                    )))),
+                }
+            }
+        }
+    }
+
+    /// Handles a photo from a keeper. Only a dialogue waiting for one keeps
+    /// it; anywhere else its caption is taken as the reply.
+    pub async fn reply_photo(
+        &self,
+        session: &Session,
+        file_id: &str,
+        caption: Option<&str>,
+    ) -> BotResult<Outcome> {
+        match session.dialogue {
+            Some(DialogueState::AddPhoto { tarantula_id }) => Ok(self
+                .save_photo(session.user_id, tarantula_id, file_id, caption)
+                .await?
+                .exit()),
+            _ => self.reply(session, caption.unwrap_or_default()).await,
+        }
+    }
+}
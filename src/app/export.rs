@@ -0,0 +1,280 @@
+//! `/export`: everything a keeper has recorded, as a zip with a CSV and a JSON
+//! file per table and, on request, an .xlsx workbook with a sheet per table.
+
+use crate::app::screen::{Document, Outcome};
+use crate::app::App;
+use crate::error::BotError;
+use crate::BotResult;
+use chrono::NaiveDate;
+use rust_xlsxwriter::{Format, Workbook};
+use serde::Serialize;
+use std::io::{Cursor, Write};
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+/// Large enough to mean "all of them" for the `get_recent_*` queries.
+const ALL: i32 = i32::MAX;
+
+/// A tarantula with its species and status spelled out, the list item and the
+/// full record together.
+#[derive(Debug, Serialize)]
+struct TarantulaRow {
+    id: i64,
+    name: String,
+    species: String,
+    acquisition_date: NaiveDate,
+    estimated_age_months: Option<i32>,
+    last_molt_date: Option<NaiveDate>,
+    last_health_check_date: Option<NaiveDate>,
+    status: String,
+    enclosure_number: Option<String>,
+    group: Option<String>,
+    mother_id: Option<i64>,
+    father_id: Option<i64>,
+    mother_external: Option<String>,
+    father_external: Option<String>,
+    notes: Option<String>,
+}
+
+/// One table of the export. The CSV is what the workbook sheet is filled
+/// from, so every format has the same columns in the same order.
+struct Table {
+    name: &'static str,
+    rows: usize,
+    csv: Vec<u8>,
+    json: Vec<u8>,
+}
+
+impl Table {
+    fn new<T: Serialize>(name: &'static str, rows: &[T]) -> BotResult<Self> {
+        let mut csv = csv::Writer::from_writer(Vec::new());
+        for row in rows {
+            csv.serialize(row).map_err(export_error)?;
+        }
+        Ok(Self {
+            name,
+            rows: rows.len(),
+            csv: csv.into_inner().map_err(export_error)?,
+            json: serde_json::to_vec_pretty(rows).map_err(export_error)?,
+        })
+    }
+}
+
+impl App {
+    /// Sends the keeper's data as a zip. `format` is empty, or `xlsx` to add a
+    /// workbook.
+    pub(crate) async fn export(&self, user_id: u64, format: &str) -> BotResult<Outcome> {
+        let with_workbook = match format.trim() {
+            "" => false,
+            "xlsx" => true,
+            other => {
+                return Err(BotError::ValidationError(format!(
+                    "There is no export format {}, use /export or /export xlsx",
+                    other
+                )))
+            }
+        };
+
+        let tables = self.export_tables(user_id).await?;
+        let contents = archive(&tables, with_workbook)?;
+        let counts: Vec<String> = tables
+            .iter()
+            .map(|table| format!("{} {}", table.rows, table.name.replace('_', " ")))
+            .collect();
+
+        Ok(Outcome::document(Document {
+            file_name: format!(
+                "spider-bot-export-{}.zip",
+                self.now(user_id).await?.format("%Y-%m-%d")
+            ),
+            contents,
+            caption: format!("📦 Your data: {}", counts.join(", ")),
+        }))
+    }
+
+    async fn export_tables(&self, user_id: u64) -> BotResult<Vec<Table>> {
+        let mut tarantulas = Vec::new();
+        for item in self.db.get_all_tarantulas(user_id).await? {
+            let t = self.db.get_tarantula_by_id(user_id, item.id).await?;
+            tarantulas.push(TarantulaRow {
+                id: t.id,
+                name: t.name,
+                species: item.species_name,
+                acquisition_date: t.acquisition_date,
+                estimated_age_months: t.estimated_age_months,
+                last_molt_date: t.last_molt_date,
+                last_health_check_date: t.last_health_check_date,
+                status: item.current_status,
+                enclosure_number: t.enclosure_number,
+                group: item.group_name,
+                mother_id: t.mother_id,
+                father_id: t.father_id,
+                mother_external: t.mother_external,
+                father_external: t.father_external,
+                notes: t.notes,
+            });
+        }
+        tarantulas.sort_by_key(|t| t.id);
+
+        Ok(vec![
+            Table::new("tarantulas", &tarantulas)?,
+            Table::new(
+                "feedings",
+                &self.db.get_recent_feeding_records(user_id, ALL).await?,
+            )?,
+            Table::new(
+                "health_checks",
+                &self.db.get_recent_health_records(user_id, ALL).await?,
+            )?,
+            Table::new(
+                "molts",
+                &self.db.get_recent_molt_records(user_id, ALL).await?,
+            )?,
+            Table::new("colonies", &self.db.get_colony_status(user_id).await?)?,
+            Table::new("enclosures", &self.db.get_enclosures(user_id).await?)?,
+        ])
+    }
+}
+
+fn archive(tables: &[Table], with_workbook: bool) -> BotResult<Vec<u8>> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let mut add = |name: String, contents: &[u8]| -> BotResult<()> {
+        zip.start_file(name, SimpleFileOptions::default())
+            .map_err(export_error)?;
+        zip.write_all(contents)?;
+        Ok(())
+    };
+
+    for table in tables {
+        add(format!("{}.csv", table.name), &table.csv)?;
+        add(format!("{}.json", table.name), &table.json)?;
+    }
+    if with_workbook {
+        add("spider-bot.xlsx".to_string(), &workbook(tables)?)?;
+    }
+
+    Ok(zip.finish().map_err(export_error)?.into_inner())
+}
+
+fn workbook(tables: &[Table]) -> BotResult<Vec<u8>> {
+    let header = Format::new().set_bold();
+    let mut workbook = Workbook::new();
+    for table in tables {
+        let sheet = workbook
+            .add_worksheet()
+            .set_name(table.name)
+            .map_err(export_error)?;
+        let mut csv = csv::Reader::from_reader(table.csv.as_slice());
+
+        let headers = csv.headers().map_err(export_error)?.clone();
+        for (col, name) in headers.iter().enumerate() {
+            sheet
+                .write_string_with_format(0, col as u16, name, &header)
+                .map_err(export_error)?;
+        }
+        for (row, record) in csv.records().enumerate() {
+            let record = record.map_err(export_error)?;
+            for (col, value) in record.iter().enumerate() {
+                let (row, col) = (row as u32 + 1, col as u16);
+                match as_number(value) {
+                    Some(number) => sheet.write_number(row, col, number),
+                    None if value.is_empty() => continue,
+                    None => sheet.write_string(row, col, value),
+                }
+                .map_err(export_error)?;
+            }
+        }
+        sheet.autofit();
+    }
+    workbook.save_to_buffer().map_err(export_error)
+}
+
+/// Plain decimals become number cells. An enclosure called "007" or a
+/// tarantula called "NaN" stays text.
+fn as_number(value: &str) -> Option<f64> {
+    let digits = value.strip_prefix('-').unwrap_or(value);
+    let leading_zero = digits.len() > 1 && digits.starts_with('0') && !digits.starts_with("0.");
+    if leading_zero || !digits.chars().all(|c| c.is_ascii_digit() || c == '.') {
+        return None;
+    }
+    value.parse().ok()
+}
+
+fn export_error(e: impl std::fmt::Display) -> BotError {
+    BotError::Export(e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[derive(Serialize)]
+    struct Row {
+        name: &'static str,
+        enclosure: &'static str,
+        length_cm: f32,
+    }
+
+    fn file(zip: &[u8], name: &str) -> Vec<u8> {
+        let mut archive = zip::ZipArchive::new(Cursor::new(zip)).unwrap();
+        let mut contents = Vec::new();
+        archive
+            .by_name(name)
+            .unwrap()
+            .read_to_end(&mut contents)
+            .unwrap();
+        contents
+    }
+
+    #[test]
+    fn every_table_is_in_the_zip_as_csv_and_json() {
+        let rows = [
+            Row {
+                name: "Rosie",
+                enclosure: "007",
+                length_cm: 4.5,
+            },
+            Row {
+                name: "Boris, Jr.",
+                enclosure: "A1",
+                length_cm: 2.0,
+            },
+        ];
+        let tables = [Table::new("tarantulas", &rows).unwrap()];
+
+        let zip = archive(&tables, true).unwrap();
+
+        assert_eq!(
+            String::from_utf8(file(&zip, "tarantulas.csv")).unwrap(),
+            "name,enclosure,length_cm\nRosie,007,4.5\n\"Boris, Jr.\",A1,2.0\n"
+        );
+        let json: serde_json::Value =
+            serde_json::from_slice(&file(&zip, "tarantulas.json")).unwrap();
+        assert_eq!(json[1]["name"], "Boris, Jr.");
+        assert!(file(&zip, "spider-bot.xlsx").starts_with(b"PK"));
+    }
+
+    #[test]
+    fn the_workbook_is_only_added_on_request() {
+        let tables = [Table::new::<Row>("molts", &[]).unwrap()];
+
+        let zip = archive(&tables, false).unwrap();
+
+        let archive = zip::ZipArchive::new(Cursor::new(zip)).unwrap();
+        let mut names: Vec<_> = archive.file_names().collect();
+        names.sort();
+        assert_eq!(names, ["molts.csv", "molts.json"]);
+    }
+
+    #[test]
+    fn only_lossless_numbers_become_number_cells() {
+        assert_eq!(as_number("4.5"), Some(4.5));
+        assert_eq!(as_number("2.0"), Some(2.0));
+        assert_eq!(as_number("-12"), Some(-12.0));
+        assert_eq!(as_number("007"), None);
+        assert_eq!(as_number("NaN"), None);
+        assert_eq!(as_number("2024-01-01"), None);
+        assert_eq!(as_number(""), None);
+    }
+}
@@ -0,0 +1,539 @@
+use crate::app::callbacks::BotCallback;
+use crate::app::callbacks::BotCallback::{
+    ColonyCountUpdate, ColonyGetCount, ColonyMaintenanceMenu, MainMenu,
+};
+use crate::app::dialogue::DialogueState;
+use crate::app::keyboards::{
+    back_to_menu_keyboard, feed_command_keyboard, feed_count_selection_keyboard, undo_keyboard,
+    welcome_keyboard,
+};
+use crate::app::screen::{Button, Keyboard, Outcome, Screen};
+use crate::app::when::{earlier_button, to_stamp, when_line, Backdate};
+use crate::app::App;
+use crate::error::BotError;
+use crate::models::cricket::ColonyStatus;
+use crate::models::enums::HealthStatus;
+use crate::models::feeding::FeedingEvent;
+use crate::models::group::collapse_groups;
+use crate::BotResult;
+use chrono::{NaiveDate, NaiveDateTime};
+
+impl App {
+    pub(crate) async fn feed_command(&self, tarantula_id: i64, user_id: u64) -> BotResult<Screen> {
+        let tarantula = self.db.get_tarantula_by_id(user_id, tarantula_id).await?;
+        let colonies = self.db.get_colony_status(user_id).await?;
+        let keyboard = feed_command_keyboard(tarantula_id, colonies);
+        Ok(Screen::new(
+            format!(
+                "Feeding *{}*\nSelect cricket colony to use:",
+                tarantula.name
+            ),
+            keyboard,
+        ))
+    }
+
+    pub(crate) async fn feed_colony_selection(
+        &self,
+        tarantula_id: i64,
+        colony_id: i64,
+        user_id: u64,
+        at: Option<NaiveDateTime>,
+    ) -> BotResult<Screen> {
+        let colony = self.colony_status(colony_id, user_id).await?;
+        let suggested = self
+            .db
+            .get_feeding_override(user_id, tarantula_id)
+            .await?
+            .and_then(|o| o.prey_count)
+            .map_or(String::new(), |c| format!(" (custom schedule: {})", c));
+        let keyboard = feed_count_selection_keyboard(tarantula_id, colony_id, at);
+        Ok(Screen::new(
+            format!(
+                "Selected colony: {} ({})\nCurrent count: {}{}\nHow many crickets?{}",
+                colony.colony_name,
+                colony.size_type.to_db_name(),
+                colony.current_count,
+                when_line(at),
+                suggested
+            ),
+            keyboard,
+        ))
+    }
+
+    pub(crate) async fn colony_status(
+        &self,
+        colony_id: i64,
+        user_id: u64,
+    ) -> Result<ColonyStatus, BotError> {
+        let colony = self
+            .db
+            .get_colony_status(user_id)
+            .await?
+            .into_iter()
+            .find(|c| c.id == colony_id)
+            .ok_or_else(|| BotError::NotFound("Colony not found".to_string()))?;
+        Ok(colony)
+    }
+
+    pub(crate) async fn feed_confirmation(
+        &self,
+        tarantula_id: i64,
+        colony_id: i64,
+        count: i32,
+        user_id: u64,
+        at: Option<NaiveDateTime>,
+    ) -> BotResult<Screen> {
+        let logged_at = self.logged_at(user_id, at).await?;
+        let feeding_event = FeedingEvent {
+            id: None,
+            tarantula_id,
+            feeding_date: logged_at,
+            cricket_colony_id: colony_id,
+            number_of_crickets: count,
+            feeding_status_id: 1,
+            notes: None,
+        };
+
+        let change = self.db.record_feeding(user_id, feeding_event).await?;
+
+        Ok(Screen::new(
+            format!("✅ Feeding recorded: {} crickets{}", count, when_line(at)),
+            undo_keyboard(change.id),
+        ))
+    }
+
+    pub(crate) async fn welcome(&self, user_id: u64) -> BotResult<Screen> {
+        let keyboard = welcome_keyboard();
+        let feeding_due = self.db.get_tarantulas_due_feeding(user_id).await?;
+        let health_alerts = self.db.get_health_alerts(user_id).await?;
+
+        let today = self.now(user_id).await?.date();
+        let recent_molts = self
+            .db
+            .get_recent_molt_records(user_id, 100)
+            .await?
+            .into_iter()
+            .filter(|r| {
+                if let Ok(date) = NaiveDate::parse_from_str(&r.molt_date, "%Y-%m-%d") {
+                    today.signed_duration_since(date).num_days() <= 30
+                } else {
+                    false
+                }
+            })
+            .count();
+
+        let message = format!(
+            "Welcome to your Tarantula Management System! 🕷\n\n\
+        *Quick Stats:*\n\
+        • Feeding Due: {}\n\
+        • Health Alerts: {}\n\
+        • Recent Molts: {} (30 days)",
+            feeding_due.len(),
+            health_alerts.len(),
+            recent_molts
+        );
+        Ok(Screen::new(message, keyboard))
+    }
+
+    pub(crate) async fn feeding_schedule(&self, user_id: u64) -> BotResult<Screen> {
+        let due_feedings = collapse_groups(self.db.get_tarantulas_due_feeding(user_id).await?);
+
+        let mut message = String::from("🍽 *Feeding Schedule*\n\n");
+        for t in &due_feedings {
+            message.push_str(&format!(
+                "*{}* needs feeding\n- Last fed: {} days ago\n\n",
+                t.name,
+                t.days_since_feeding.unwrap_or(0.0)
+            ));
+        }
+
+        if due_feedings.is_empty() {
+            message = String::from("No feedings currently due! 🎉");
+        }
+
+        let keyboard = back_to_menu_keyboard();
+
+        Ok(Screen::new(message, keyboard))
+    }
+
+    pub(crate) async fn health_alerts(&self, user_id: u64) -> BotResult<Screen> {
+        let alerts = self.db.get_health_alerts(user_id).await?;
+
+        let mut message = String::from("🏥 *Health Alerts*\n\n");
+        for alert in &alerts {
+            message.push_str(&format!(
+                "*{}* - {}\n- Days in state: {}\n\n",
+                alert.name, alert.alert_type, alert.days_in_state
+            ));
+        }
+
+        if alerts.is_empty() {
+            message = String::from("No health alerts! All tarantulas appear healthy. 🎉");
+        }
+
+        let keyboard = back_to_menu_keyboard();
+
+        Ok(Screen::new(message, keyboard))
+    }
+
+    pub(crate) async fn maintenance(&self, user_id: u64) -> BotResult<Screen> {
+        let tasks = self.db.get_maintenance_tasks(user_id).await?;
+
+        let mut message = String::from("🧹 *Maintenance Tasks*\n\n");
+        for task in &tasks {
+            message.push_str(&format!(
+                "*{}* ({})\n- {}\n- Priority: {}\n\n",
+                task.name, task.enclosure_number, task.required_action, task.priority
+            ));
+        }
+
+        if tasks.is_empty() {
+            message = String::from("No maintenance tasks currently due! 🎉");
+        }
+
+        let keyboard = back_to_menu_keyboard();
+
+        Ok(Screen::new(message, keyboard))
+    }
+
+    pub(crate) async fn colonies(&self, user_id: u64) -> BotResult<Screen> {
+        let colonies = self.db.get_colony_status(user_id).await?;
+
+        let mut message = String::from("🦗 *Cricket Colonies*\n\n");
+        for colony in &colonies {
+            message.push_str(&format!(
+                "*{}* ({}):\n- Current count: {}\n- Used this week: {}\n- Weeks remaining: {:.1}\n\n",
+                colony.colony_name,
+                colony.size_type.to_db_name(),
+                colony.current_count,
+                colony.crickets_used_7_days,
+                colony.weeks_remaining.unwrap_or(0.0)
+            ));
+        }
+
+        if colonies.is_empty() {
+            message = String::from("No cricket colonies found in the database.");
+        }
+
+        let keyboard = back_to_menu_keyboard();
+
+        Ok(Screen::new(message, keyboard))
+    }
+
+    pub(crate) async fn record_molt_command(
+        &self,
+        tarantula_id: i64,
+        size: f32,
+        user_id: u64,
+        at: Option<NaiveDateTime>,
+    ) -> BotResult<Screen> {
+        let logged_at = self.logged_at(user_id, at).await?;
+        let change = self
+            .db
+            .record_molt(tarantula_id, size, None, None, user_id, logged_at)
+            .await?;
+
+        let keyboard = undo_keyboard(change.id);
+        Ok(Screen::new(
+            format!("Molt recorded \nThank you!{}", when_line(at)),
+            keyboard,
+        ))
+    }
+
+    pub(crate) async fn health_check_command(
+        &self,
+        tarantula_id: i64,
+        user_id: u64,
+        at: Option<NaiveDateTime>,
+    ) -> BotResult<Screen> {
+        let tarantula = self.db.get_tarantula_by_id(user_id, tarantula_id).await?;
+        let status = |status_id| match at {
+            None => BotCallback::HealthStatus(tarantula_id, status_id),
+            Some(_) => BotCallback::BackdatedHealthStatus(tarantula_id, status_id, to_stamp(at)),
+        };
+
+        let mut keyboard = vec![
+            vec![Button::callback("✅ Healthy", status(1))],
+            vec![Button::callback("⚠️ Monitor", status(2))],
+            vec![Button::callback("🚨 Critical", status(3))],
+        ];
+        if at.is_none() {
+            keyboard.push(vec![earlier_button(Backdate::HealthCheck { tarantula_id })]);
+        }
+        keyboard.push(vec![Button::callback("« Cancel", MainMenu)]);
+
+        Ok(Screen::new(
+            format!(
+                "Health check for *{}*{}\nSelect current health status:",
+                tarantula.name,
+                when_line(at)
+            ),
+            keyboard,
+        ))
+    }
+
+    pub(crate) async fn health_status_command(
+        &self,
+        tarantula_id: i64,
+        health_status: HealthStatus,
+        user_id: u64,
+        at: Option<NaiveDateTime>,
+    ) -> BotResult<Screen> {
+        let logged_at = self.logged_at(user_id, at).await?;
+        let change = self
+            .db
+            .record_health_check(user_id, tarantula_id, health_status, None, logged_at)
+            .await?;
+        let keyboard = undo_keyboard(change.id);
+
+        Ok(Screen::new(
+            format!("Health status recorded \nThank you!{}", when_line(at)),
+            keyboard,
+        ))
+    }
+    pub(crate) async fn colony_maintenance_menu(
+        &self,
+        colony_id: i64,
+        user_id: u64,
+    ) -> BotResult<Screen> {
+        let colony = self.colony_status(colony_id, user_id).await?;
+        self.colony_maintenance_command(&colony.colony_name, user_id)
+            .await
+    }
+
+    pub(crate) async fn colony_maintenance_command(
+        &self,
+        colony_name: &str,
+        user_id: u64,
+    ) -> BotResult<Screen> {
+        let colonies = self.db.get_colony_status(user_id).await?;
+        let colony = colonies
+            .iter()
+            .find(|c| c.colony_name.eq_ignore_ascii_case(colony_name))
+            .ok_or_else(|| BotError::NotFound(format!("Colony '{}' not found", colony_name)))?;
+
+        let keyboard = vec![
+            vec![Button::callback(
+                "📝 Update Count",
+                ColonyGetCount(colony.id),
+            )],
+            vec![Button::callback("« Cancel", MainMenu)],
+        ];
+
+        Ok(Screen::new(format!(
+                "*Cricket Colony Maintenance*\n\nColony: {}\nCurrent count: {}\nSize: {}\n\nSelect maintenance action:",
+                colony.colony_name, colony.current_count, colony.size_type.to_db_name()
+            ), keyboard))
+    }
+    pub(crate) async fn status_overview(&self, user_id: u64) -> BotResult<Screen> {
+        let due_feedings = self.db.get_tarantulas_due_feeding(user_id).await?;
+        let health_alerts = self.db.get_health_alerts(user_id).await?;
+        let colonies = self.db.get_colony_status(user_id).await?;
+
+        let message = format!(
+            "*System Overview*\n\n\
+            🍽 *Feeding Status*\n\
+            • {} tarantulas due feeding\n\
+            • Longest wait: {} days\n\n\
+            🏥 *Health Status*\n\
+            • {} active health alerts\n\
+            • {} critical cases\n\n\
+            🦗 *Colony Status*\n\
+            • {} active colonies\n\
+            • Total crickets: {}\n\n\
+            🧹 *Maintenance*\n\
+            • {} tasks due",
+            due_feedings.len(),
+            due_feedings
+                .iter()
+                .map(|t| t.days_since_feeding.unwrap_or(0.0))
+                .fold(0.0, f64::max),
+            health_alerts.len(),
+            health_alerts
+                .iter()
+                .filter(|a| a.alert_type == "Critical")
+                .count(),
+            colonies.len(),
+            colonies.iter().map(|c| c.current_count).sum::<i32>(),
+            0 // TODO: Implement maintenance task count
+        );
+
+        let keyboard = back_to_menu_keyboard();
+
+        Ok(Screen::new(message, keyboard))
+    }
+
+    pub(crate) async fn molt_history(&self, user_id: u64) -> BotResult<Screen> {
+        self.view_molt_records(user_id).await
+    }
+
+    pub(crate) async fn view_records(&self) -> BotResult<Screen> {
+        let keyboard = vec![
+            vec![
+                Button::callback("Feeding Records", BotCallback::ViewFeedingRecords),
+                Button::callback("Health Records", BotCallback::ViewHealthRecords),
+            ],
+            vec![
+                Button::callback("Molt Records", BotCallback::ViewMoltRecords),
+                Button::callback("🕰 My Changes", BotCallback::ChangeHistory(0)),
+            ],
+            vec![Button::callback("« Back to Menu", MainMenu)],
+        ];
+
+        let msg = "*View Records*\n\nSelect record type:";
+        Ok(Screen::new(msg, keyboard))
+    }
+    pub(crate) async fn colony_maintenance(&self, user_id: u64) -> BotResult<Screen> {
+        let colonies = self.db.get_colony_status(user_id).await?;
+        let mut keyboard: Keyboard = colonies
+            .chunks(2)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .map(|c| {
+                        Button::callback(
+                            format!("{} ({})", c.colony_name, c.size_type.to_db_name()),
+                            ColonyMaintenanceMenu(c.id),
+                        )
+                    })
+                    .collect()
+            })
+            .collect();
+        keyboard.push(vec![Button::callback("« Back to Menu", MainMenu)]);
+
+        Ok(Screen::new(
+            "*Colony Maintenance*\n\nSelect a colony:",
+            keyboard,
+        ))
+    }
+
+    pub(crate) async fn colony_count(&self, colony_id: i64, user_id: u64) -> BotResult<Screen> {
+        let colony = self.colony_status(colony_id, user_id).await?;
+
+        let keyboard = vec![
+            vec![
+                Button::callback("-10", ColonyCountUpdate(colony_id, -10)),
+                Button::callback("-5", ColonyCountUpdate(colony_id, -5)),
+            ],
+            vec![
+                Button::callback("+1", ColonyCountUpdate(colony_id, 1)),
+                Button::callback("+5", ColonyCountUpdate(colony_id, 5)),
+                Button::callback("+10", ColonyCountUpdate(colony_id, 10)),
+                Button::callback("+50", ColonyCountUpdate(colony_id, 50)),
+            ],
+            vec![Button::callback("« Cancel", MainMenu)],
+        ];
+
+        Ok(Screen::new(
+            format!(
+                "*Update Colony Count*\n\nColony: {}\nCurrent count: {}\nSelect adjustment:",
+                colony.colony_name, colony.current_count
+            ),
+            keyboard,
+        ))
+    }
+
+    pub(crate) async fn colony_count_update(
+        &self,
+        colony_id: i64,
+        adjustment: i32,
+        user_id: u64,
+    ) -> BotResult<Screen> {
+        let change = self
+            .db
+            .update_colony_count(colony_id, adjustment, user_id)
+            .await?;
+
+        let keyboard = undo_keyboard(change.id);
+
+        Ok(Screen::new(
+            format!("✅ Colony count updated by {}", adjustment),
+            keyboard,
+        ))
+    }
+
+    pub(crate) async fn view_feeding_schedule(
+        &self,
+        tarantula_id: i64,
+        user_id: u64,
+    ) -> BotResult<Screen> {
+        let tarantula = self.db.get_tarantula_by_id(user_id, tarantula_id).await?;
+        let plan = self.db.get_feeding_plan(user_id, tarantula_id).await?;
+        let band = plan.band.as_ref();
+
+        let mut message = format!(
+            "*Feeding Schedule for {}*\n\n\
+            🦗 *Current Stage:* {}\n\
+            📏 *Size:* {:.1} cm\n\
+            🍽 *Prey Size:* {}\n\
+            ⏱ *Feeding Frequency:* {}\n\
+            🦗 *Prey Type:* {}\n",
+            tarantula.name,
+            band.map_or("-", |b| b.size_category.as_str()),
+            plan.size_cm,
+            plan.prey_size.as_deref().unwrap_or("-"),
+            plan.frequency_name.as_deref().unwrap_or("-"),
+            band.map_or("-", |b| b.prey_type.as_str()),
+        );
+        if let Some(count) = plan.prey_count {
+            message.push_str(&format!("🔢 *Prey Count:* {}\n", count));
+        }
+        message.push_str(&format!("📌 *Status:* {}\n", plan.status()));
+        if let Some(next_due) = plan.next_due {
+            message.push_str(&format!("📅 *Next Feeding:* {}\n", next_due));
+        }
+        if let Some(notes) = band.and_then(|b| b.notes.as_deref()) {
+            message.push_str(&format!("\nℹ️ {}\n", notes));
+        }
+        if plan.has_override {
+            message.push_str(&format!(
+                "\n⚙️ _Custom schedule active{}_\n",
+                plan.override_expires_on
+                    .map_or(String::new(), |d| format!(" until {}", d))
+            ));
+        }
+        if let Some(size_reason) = plan.reasons.first() {
+            message.push_str(&format!("\n_{}_\n", size_reason));
+        }
+        if let (Some(min_days), Some(max_days)) = (plan.min_days, plan.max_days) {
+            message.push_str(&format!(
+                "\n_Feeding window: Every {} to {} days_",
+                min_days, max_days
+            ));
+        }
+
+        let mut buttons = vec![Button::callback(
+            "⚙️ Customize",
+            BotCallback::OverrideSchedule(tarantula_id),
+        )];
+        if plan.has_override {
+            buttons.push(Button::callback(
+                "♻️ Use Species Schedule",
+                BotCallback::ClearOverride(tarantula_id),
+            ));
+        }
+        let keyboard = vec![
+            buttons,
+            vec![Button::callback(
+                "« Back",
+                BotCallback::TarantulaProfile(tarantula_id),
+            )],
+        ];
+        Ok(Screen::new(message, keyboard))
+    }
+
+    /// Asks for the size after a molt on `at`, or unless the keeper adds
+    /// when it was, now.
+    pub(crate) fn molt_prompt(&self, tarantula_id: i64, at: Option<NaiveDateTime>) -> Outcome {
+        let message = match at {
+            None => "Please enter the molt size in centimeters:\n\
+                     (add when if it wasn't today, e.g., 6.5 yesterday)"
+                .to_string(),
+            Some(_) => format!(
+                "Please enter the molt size in centimeters:{}",
+                when_line(at)
+            ),
+        };
+        Outcome::send(Screen::text(message)).enter(DialogueState::RecordMolt { tarantula_id, at })
+    }
+}
@@ -0,0 +1,367 @@
+use crate::app::callbacks::BotCallback;
+use crate::app::dialogue::DialogueState;
+use crate::app::keyboards::{paging, undo_button};
+use crate::app::screen::{Button, Keyboard, Outcome, Screen};
+use crate::app::when::{earlier_button, to_stamp, when_line, Backdate};
+use crate::app::App;
+use crate::error::BotError;
+use crate::models::enums::HealthStatus;
+use crate::models::group::GroupSummary;
+use crate::BotResult;
+use chrono::NaiveDateTime;
+
+const MAX_MEMBERS_LISTED: usize = 10;
+const SPLIT_PAGE_SIZE: usize = 20;
+
+impl App {
+    pub(crate) async fn groups_menu(&self, user_id: u64) -> BotResult<Screen> {
+        let groups = self.db.get_groups(user_id).await?;
+
+        let mut message = String::from("🧺 *Sling Groups*\n\n");
+        if groups.is_empty() {
+            message.push_str(
+                "No groups yet.\nUse /addgroup name species_id count acquisition_date to create one.",
+            );
+        }
+        for g in &groups {
+            message.push_str(&format!(
+                "*{}* ({})\n▫️ Slings: {}\n▫️ Last fed: {}\n\n",
+                g.name,
+                g.species_name,
+                g.member_count,
+                g.days_since_feeding
+                    .map_or("Never".to_string(), |d| format!("{:.1} days ago", d))
+            ));
+        }
+
+        let mut keyboard: Keyboard = groups
+            .iter()
+            .map(|g| {
+                vec![Button::callback(
+                    format!("🧺 {} ({})", g.name, g.member_count),
+                    BotCallback::GroupDetails(g.id),
+                )]
+            })
+            .collect();
+        keyboard.push(vec![Button::callback(
+            "« Back to Menu",
+            BotCallback::MainMenu,
+        )]);
+
+        Ok(Screen::new(message, keyboard))
+    }
+
+    async fn group(&self, group_id: i64, user_id: u64) -> BotResult<GroupSummary> {
+        self.db
+            .get_groups(user_id)
+            .await?
+            .into_iter()
+            .find(|g| g.id == group_id)
+            .ok_or_else(|| BotError::NotFound("Group not found".to_string()))
+    }
+
+    pub(crate) async fn group_details(&self, group_id: i64, user_id: u64) -> BotResult<Screen> {
+        let group = self.group(group_id, user_id).await?;
+        let members = self.db.get_group_members(user_id, group_id).await?;
+
+        let mut message = format!(
+            "🧺 *{}*\n{}\n\n\
+            • Slings: {}\n\
+            • Acquired: {}\n\
+            • Last fed: {}\n",
+            group.name,
+            group.species_name,
+            group.member_count,
+            group.acquisition_date,
+            group
+                .days_since_feeding
+                .map_or("Never".to_string(), |d| format!("{:.1} days ago", d))
+        );
+        if let Some(notes) = &group.notes {
+            message.push_str(&format!("{}\n", notes));
+        }
+
+        message.push_str("\n*Members*\n");
+        for t in members.iter().take(MAX_MEMBERS_LISTED) {
+            message.push_str(&format!("• {} - {}\n", t.name, t.current_status));
+        }
+        if members.len() > MAX_MEMBERS_LISTED {
+            message.push_str(&format!(
+                "… and {} more\n",
+                members.len() - MAX_MEMBERS_LISTED
+            ));
+        }
+
+        let keyboard = vec![
+            vec![
+                Button::callback("🍽 Feed All", BotCallback::GroupFeedMenu(group_id)),
+                Button::callback("🔍 Health Check", BotCallback::GroupHealthCheck(group_id)),
+            ],
+            vec![
+                Button::callback("🐾 Record Molt", BotCallback::GroupMolt(group_id)),
+                Button::callback("✂️ Split Out", BotCallback::GroupSplitMenu(group_id, 0)),
+            ],
+            vec![Button::callback("« Back", BotCallback::GroupsMenu)],
+        ];
+
+        Ok(Screen::new(message, keyboard))
+    }
+
+    pub(crate) async fn group_feed_menu(&self, group_id: i64, user_id: u64) -> BotResult<Screen> {
+        let group = self.group(group_id, user_id).await?;
+        let colonies = self.db.get_colony_status(user_id).await?;
+
+        let mut keyboard: Keyboard = colonies
+            .iter()
+            .map(|c| {
+                vec![Button::callback(
+                    format!("{} ({} left)", c.colony_name, c.current_count),
+                    BotCallback::GroupFeedColony(group_id, c.id),
+                )]
+            })
+            .collect();
+        keyboard.push(vec![back_to_group_button(group_id)]);
+
+        Ok(Screen::new(
+            format!(
+                "Feeding all {} slings in *{}*\nSelect cricket colony to use:",
+                group.member_count, group.name
+            ),
+            keyboard,
+        ))
+    }
+
+    pub(crate) async fn group_feed_colony(
+        &self,
+        group_id: i64,
+        colony_id: i64,
+        user_id: u64,
+        at: Option<NaiveDateTime>,
+    ) -> BotResult<Screen> {
+        let group = self.group(group_id, user_id).await?;
+
+        let mut keyboard = vec![(1..=3)
+            .map(|n| {
+                Button::callback(
+                    format!("{} each ({} total)", n, n * group.member_count),
+                    match at {
+                        None => BotCallback::GroupFeedConfirm(group_id, colony_id, n),
+                        Some(_) => {
+                            BotCallback::BackdatedGroupFeed(group_id, colony_id, n, to_stamp(at))
+                        }
+                    },
+                )
+            })
+            .collect()];
+        if at.is_none() {
+            keyboard.push(vec![earlier_button(Backdate::GroupFeeding {
+                group_id,
+                colony_id,
+            })]);
+        }
+        keyboard.push(vec![back_to_group_button(group_id)]);
+
+        Ok(Screen::new(
+            format!("How many crickets per sling?{}", when_line(at)),
+            keyboard,
+        ))
+    }
+
+    pub(crate) async fn group_feed_confirm(
+        &self,
+        group_id: i64,
+        colony_id: i64,
+        per_member: i32,
+        user_id: u64,
+        at: Option<NaiveDateTime>,
+    ) -> BotResult<Screen> {
+        let logged_at = self.logged_at(user_id, at).await?;
+        let change = self
+            .db
+            .record_group_feeding(user_id, group_id, colony_id, per_member, logged_at)
+            .await?;
+        let fed = change.after.tarantulas.len() as i32;
+
+        Ok(Screen::new(
+            format!(
+                "✅ Fed {} slings: {} crickets used{}",
+                fed,
+                fed * per_member,
+                when_line(at)
+            ),
+            vec![vec![undo_button(change.id), back_to_group_button(group_id)]],
+        ))
+    }
+
+    pub(crate) async fn group_health_check(
+        &self,
+        group_id: i64,
+        user_id: u64,
+        at: Option<NaiveDateTime>,
+    ) -> BotResult<Screen> {
+        let group = self.group(group_id, user_id).await?;
+        let status = |status: HealthStatus| match at {
+            None => BotCallback::GroupHealthStatus(group_id, status as i64),
+            Some(_) => {
+                BotCallback::BackdatedGroupHealthStatus(group_id, status as i64, to_stamp(at))
+            }
+        };
+
+        let mut keyboard = vec![
+            vec![Button::callback(
+                "✅ Healthy",
+                status(HealthStatus::Healthy),
+            )],
+            vec![Button::callback(
+                "⚠️ Monitor",
+                status(HealthStatus::Monitor),
+            )],
+            vec![Button::callback(
+                "🚨 Critical",
+                status(HealthStatus::Critical),
+            )],
+        ];
+        if at.is_none() {
+            keyboard.push(vec![earlier_button(Backdate::GroupHealthCheck {
+                group_id,
+            })]);
+        }
+        keyboard.push(vec![back_to_group_button(group_id)]);
+
+        Ok(Screen::new(
+            format!(
+                "Health check for all slings in *{}*{}\nSelect current health status:",
+                group.name,
+                when_line(at)
+            ),
+            keyboard,
+        ))
+    }
+
+    pub(crate) async fn group_health_status(
+        &self,
+        group_id: i64,
+        status: HealthStatus,
+        user_id: u64,
+        at: Option<NaiveDateTime>,
+    ) -> BotResult<Screen> {
+        let logged_at = self.logged_at(user_id, at).await?;
+        let change = self
+            .db
+            .record_group_health_check(user_id, group_id, status, logged_at)
+            .await?;
+
+        Ok(Screen::new(
+            format!(
+                "✅ Health status recorded for {} slings{}",
+                change.after.tarantulas.len(),
+                when_line(at)
+            ),
+            vec![vec![undo_button(change.id), back_to_group_button(group_id)]],
+        ))
+    }
+
+    pub(crate) fn group_molt_prompt(&self, group_id: i64) -> Outcome {
+        Outcome::send(Screen::text(
+            "Please enter the typical size after molting in centimeters (e.g., 1.5), or - to skip, followed by when if it wasn't today (e.g., 1.5 yesterday)",
+        ))
+        .enter(DialogueState::RecordGroupMolt { group_id })
+    }
+
+    pub(crate) async fn record_group_molt(
+        &self,
+        group_id: i64,
+        length_cm: Option<f32>,
+        user_id: u64,
+        at: Option<NaiveDateTime>,
+    ) -> BotResult<Screen> {
+        let logged_at = self.logged_at(user_id, at).await?;
+        let change = self
+            .db
+            .record_group_molt(user_id, group_id, length_cm, logged_at)
+            .await?;
+
+        Ok(Screen::new(
+            format!(
+                "✅ Molt recorded for {} slings{}",
+                change.after.tarantulas.len(),
+                when_line(at)
+            ),
+            vec![vec![undo_button(change.id), back_to_group_button(group_id)]],
+        ))
+    }
+
+    pub(crate) async fn group_split_menu(
+        &self,
+        group_id: i64,
+        page: u32,
+        user_id: u64,
+    ) -> BotResult<Screen> {
+        let members = self.db.get_group_members(user_id, group_id).await?;
+        let pages = members.len().div_ceil(SPLIT_PAGE_SIZE).max(1) as u32;
+        let page = page.min(pages - 1);
+
+        let mut keyboard: Keyboard = members
+            .iter()
+            .skip(page as usize * SPLIT_PAGE_SIZE)
+            .take(SPLIT_PAGE_SIZE)
+            .collect::<Vec<_>>()
+            .chunks(2)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .map(|t| Button::callback(t.name.clone(), BotCallback::SplitFromGroup(t.id)))
+                    .collect()
+            })
+            .collect();
+        keyboard.extend(paging(page, page + 1 < pages, |page| {
+            BotCallback::GroupSplitMenu(group_id, page)
+        }));
+        keyboard.push(vec![back_to_group_button(group_id)]);
+
+        let mut message = "✂️ Which sling should become an individual?".to_string();
+        if pages > 1 {
+            message.push_str(&format!("\n\nPage {} of {}", page + 1, pages));
+        }
+        Ok(Screen::new(message, keyboard))
+    }
+
+    pub(crate) fn split_from_group_prompt(&self, tarantula_id: i64) -> Outcome {
+        Outcome::send(Screen::text(
+            "Send a name for the sling, or - to keep its current name",
+        ))
+        .enter(DialogueState::SplitFromGroup { tarantula_id })
+    }
+
+    pub(crate) async fn split_from_group(
+        &self,
+        tarantula_id: i64,
+        new_name: Option<String>,
+        user_id: u64,
+    ) -> BotResult<Screen> {
+        self.db
+            .split_from_group(user_id, tarantula_id, new_name)
+            .await?;
+        let tarantula = self.db.get_tarantula_by_id(user_id, tarantula_id).await?;
+
+        Ok(Screen::new(
+            format!("✅ {} is now tracked individually", tarantula.name),
+            vec![vec![
+                Button::callback("🍽 Feed", BotCallback::FeedTarantula(tarantula_id)),
+                Button::callback("« Back to Groups", BotCallback::GroupsMenu),
+            ]],
+        ))
+    }
+}
+
+fn back_to_group_button(group_id: i64) -> Button {
+    Button::callback("« Back to Group", BotCallback::GroupDetails(group_id))
+}
+
+/// Parses the post-molt size for a group, where `-` skips the measurement.
+pub(crate) fn parse_group_molt_size(text: &str) -> Option<Option<f32>> {
+    match text.trim() {
+        "-" => Some(None),
+        v => v.parse::<f32>().ok().filter(|v| *v > 0.0).map(Some),
+    }
+}
@@ -0,0 +1,633 @@
+//! Feeding, health and molt records page by page, narrowed to a tarantula, a
+//! date range or a status, and a tarantula's timeline of everything recorded
+//! for it. What a screen shows travels in the callback data of its buttons.
+
+use crate::app::callbacks::BotCallback;
+use crate::app::dialogue::DialogueState;
+use crate::app::keyboards::paging;
+use crate::app::screen::{Button, Keyboard, Outcome, Screen};
+use crate::app::App;
+use crate::db::db::RecordFilter;
+use crate::error::BotError;
+use crate::models::enums::{FeedingStatus, HealthStatus, MoltStage};
+use crate::models::feeding::FeedingRecord;
+use crate::models::health::HealthRecord;
+use crate::models::molt::MoltRecord;
+use crate::BotResult;
+use chrono::{Datelike, Duration, NaiveDate};
+
+const PAGE_SIZE: u32 = 10;
+/// Days back offered as one-tap date ranges, 0 for all dates.
+const PERIODS: [i64; 4] = [7, 30, 90, 0];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum RecordKind {
+    Feedings = 1,
+    Health = 2,
+    Molts = 3,
+}
+
+impl RecordKind {
+    fn from_id(id: u8) -> BotResult<Self> {
+        match id {
+            1 => Ok(RecordKind::Feedings),
+            2 => Ok(RecordKind::Health),
+            3 => Ok(RecordKind::Molts),
+            _ => Err(BotError::ValidationError(format!(
+                "There are no records of kind {}",
+                id
+            ))),
+        }
+    }
+
+    fn noun(self) -> &'static str {
+        match self {
+            RecordKind::Feedings => "feeding records",
+            RecordKind::Health => "health check records",
+            RecordKind::Molts => "molt records",
+        }
+    }
+
+    fn title(self) -> &'static str {
+        match self {
+            RecordKind::Feedings => "🍽 *Feeding Records*",
+            RecordKind::Health => "🏥 *Health Check Records*",
+            RecordKind::Molts => "🐾 *Molt Records*",
+        }
+    }
+
+    /// The feeding statuses, health statuses or molt stages records can be
+    /// filtered by, as (id, name).
+    fn statuses(self) -> Vec<(i64, &'static str)> {
+        match self {
+            RecordKind::Feedings => [
+                FeedingStatus::Accepted,
+                FeedingStatus::Rejected,
+                FeedingStatus::Partial,
+                FeedingStatus::PreMolt,
+                FeedingStatus::Dead,
+                FeedingStatus::Overflow,
+            ]
+            .into_iter()
+            .map(|s| (s as i64, s.to_db_name()))
+            .collect(),
+            RecordKind::Health => [
+                HealthStatus::Healthy,
+                HealthStatus::Monitor,
+                HealthStatus::Critical,
+            ]
+            .into_iter()
+            .map(|s| (s as i64, s.to_db_name()))
+            .collect(),
+            RecordKind::Molts => [
+                MoltStage::Normal,
+                MoltStage::PreMolt,
+                MoltStage::Molting,
+                MoltStage::PostMolt,
+                MoltStage::Failed,
+            ]
+            .into_iter()
+            .map(|s| (s as i64, s.to_db_name()))
+            .collect(),
+        }
+    }
+}
+
+/// One page of records and the filters it was read with.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct RecordView {
+    pub(crate) kind: RecordKind,
+    pub(crate) tarantula_id: Option<i64>,
+    pub(crate) status_id: Option<i64>,
+    pub(crate) from: Option<NaiveDate>,
+    pub(crate) to: Option<NaiveDate>,
+    pub(crate) page: u32,
+}
+
+impl RecordView {
+    pub(crate) fn new(kind: RecordKind) -> Self {
+        Self {
+            kind,
+            tarantula_id: None,
+            status_id: None,
+            from: None,
+            to: None,
+            page: 0,
+        }
+    }
+
+    /// Rebuilds a view from callback data, where 0 stands for "not set".
+    pub(crate) fn decode(
+        kind: u8,
+        tarantula_id: i64,
+        status_id: i64,
+        from: i32,
+        to: i32,
+        page: u32,
+    ) -> BotResult<Self> {
+        Ok(Self {
+            kind: RecordKind::from_id(kind)?,
+            tarantula_id: (tarantula_id != 0).then_some(tarantula_id),
+            status_id: (status_id != 0).then_some(status_id),
+            from: decode_day(from),
+            to: decode_day(to),
+            page,
+        })
+    }
+
+    fn page_callback(self) -> BotCallback {
+        BotCallback::RecordPage(
+            self.kind as u8,
+            self.tarantula_id.unwrap_or_default(),
+            self.status_id.unwrap_or_default(),
+            encode_day(self.from),
+            encode_day(self.to),
+            self.page,
+        )
+    }
+
+    fn filters_callback(self) -> BotCallback {
+        BotCallback::RecordFilters(
+            self.kind as u8,
+            self.tarantula_id.unwrap_or_default(),
+            self.status_id.unwrap_or_default(),
+            encode_day(self.from),
+            encode_day(self.to),
+        )
+    }
+
+    /// The same filters from the first page, after changing one of them.
+    fn first_page(self, change: impl FnOnce(&mut Self)) -> Self {
+        let mut view = Self { page: 0, ..self };
+        change(&mut view);
+        view
+    }
+
+    /// One more than a page, to tell whether there is a next one.
+    fn filter(self) -> RecordFilter {
+        RecordFilter {
+            tarantula_id: self.tarantula_id,
+            from: self.from,
+            to: self.to,
+            status_id: self.status_id,
+            offset: self.page * PAGE_SIZE,
+            limit: PAGE_SIZE + 1,
+        }
+    }
+}
+
+fn encode_day(day: Option<NaiveDate>) -> i32 {
+    day.map_or(0, |d| d.num_days_from_ce())
+}
+
+fn decode_day(day: i32) -> Option<NaiveDate> {
+    (day != 0)
+        .then(|| NaiveDate::from_num_days_from_ce_opt(day))
+        .flatten()
+}
+
+/// "2024-01-01 2024-03-31", "2024-01-01" for everything since, or
+/// "- 2024-03-31" for everything until.
+pub(crate) fn parse_date_range(text: &str) -> Option<(Option<NaiveDate>, Option<NaiveDate>)> {
+    let day = |part: &str| match part {
+        "-" => Some(None),
+        part => NaiveDate::parse_from_str(part, "%Y-%m-%d").ok().map(Some),
+    };
+    let parts: Vec<&str> = text.split_whitespace().collect();
+    let (from, to) = match parts.as_slice() {
+        [from] => (day(from)?, None),
+        [from, to] => (day(from)?, day(to)?),
+        _ => return None,
+    };
+    match (from, to) {
+        (None, None) => None,
+        (Some(from), Some(to)) if from > to => None,
+        range => Some(range),
+    }
+}
+
+impl App {
+    pub(crate) async fn view_feeding_records(&self, user_id: u64) -> BotResult<Screen> {
+        self.records(user_id, RecordView::new(RecordKind::Feedings))
+            .await
+    }
+
+    pub(crate) async fn view_health_records(&self, user_id: u64) -> BotResult<Screen> {
+        self.records(user_id, RecordView::new(RecordKind::Health))
+            .await
+    }
+
+    pub(crate) async fn view_molt_records(&self, user_id: u64) -> BotResult<Screen> {
+        self.records(user_id, RecordView::new(RecordKind::Molts))
+            .await
+    }
+
+    pub(crate) async fn records(&self, user_id: u64, view: RecordView) -> BotResult<Screen> {
+        let filter = view.filter();
+        let mut entries = match view.kind {
+            RecordKind::Feedings => self
+                .db
+                .get_feeding_records(user_id, &filter)
+                .await?
+                .into_iter()
+                .map(feeding_entry)
+                .collect(),
+            RecordKind::Health => self
+                .db
+                .get_health_records(user_id, &filter)
+                .await?
+                .into_iter()
+                .map(health_entry)
+                .collect(),
+            RecordKind::Molts => self
+                .db
+                .get_molt_records(user_id, &filter)
+                .await?
+                .into_iter()
+                .map(molt_entry)
+                .collect::<Vec<_>>(),
+        };
+        let has_next = entries.len() > PAGE_SIZE as usize;
+        entries.truncate(PAGE_SIZE as usize);
+
+        let tarantula = match view.tarantula_id {
+            Some(id) => Some(self.db.get_tarantula_by_id(user_id, id).await?.name),
+            None => None,
+        };
+        let mut message = format!("{}\n", view.kind.title());
+        if let Some(scope) = describe(&view, tarantula.as_deref()) {
+            message.push_str(&format!("_{}_\n", scope));
+        }
+        message.push('\n');
+        if entries.is_empty() {
+            message.push_str(&format!("No {} found.", view.kind.noun()));
+        } else {
+            message.push_str(&entries.join("\n\n"));
+        }
+        if view.page > 0 || has_next {
+            message.push_str(&format!("\n\nPage {}", view.page + 1));
+        }
+
+        let mut keyboard = paging(view.page, has_next, |page| {
+            RecordView { page, ..view }.page_callback()
+        });
+        keyboard.push(vec![Button::callback("🔎 Filter", view.filters_callback())]);
+        if let (Some(id), Some(name)) = (view.tarantula_id, &tarantula) {
+            keyboard.push(vec![Button::callback(
+                format!("🕰 {}'s timeline", name),
+                BotCallback::Timeline(id, 0),
+            )]);
+        }
+        keyboard.push(vec![Button::callback(
+            "« Back to Records",
+            BotCallback::ViewRecords,
+        )]);
+        Ok(Screen::new(message, keyboard))
+    }
+
+    pub(crate) async fn record_filters(&self, user_id: u64, view: RecordView) -> BotResult<Screen> {
+        let tarantulas = self.db.get_all_tarantulas(user_id).await?;
+        let name = view
+            .tarantula_id
+            .and_then(|id| tarantulas.iter().find(|t| t.id == id))
+            .map(|t| t.name.as_str());
+        let message = format!(
+            "🔎 *Filter {}*\n\nShowing {}.\n\nPick a tarantula, a status or a period:",
+            view.kind.noun(),
+            describe(&view, name).unwrap_or_else(|| "everything".to_string())
+        );
+
+        let option = |label: &str, selected: bool, view: RecordView| {
+            let label = if selected {
+                format!("✓ {}", label)
+            } else {
+                label.to_string()
+            };
+            Button::callback(label, view.page_callback())
+        };
+
+        let mut buttons = vec![option(
+            "All tarantulas",
+            view.tarantula_id.is_none(),
+            view.first_page(|v| v.tarantula_id = None),
+        )];
+        buttons.extend(tarantulas.iter().map(|t| {
+            option(
+                &t.name,
+                view.tarantula_id == Some(t.id),
+                view.first_page(|v| v.tarantula_id = Some(t.id)),
+            )
+        }));
+        let mut keyboard: Keyboard = buttons.chunks(2).map(<[Button]>::to_vec).collect();
+
+        let mut statuses = vec![option(
+            "Any status",
+            view.status_id.is_none(),
+            view.first_page(|v| v.status_id = None),
+        )];
+        statuses.extend(view.kind.statuses().into_iter().map(|(id, name)| {
+            option(
+                name,
+                view.status_id == Some(id),
+                view.first_page(|v| v.status_id = Some(id)),
+            )
+        }));
+        keyboard.extend(statuses.chunks(3).map(<[Button]>::to_vec));
+
+        let today = self.now(user_id).await?.date();
+        keyboard.push(
+            PERIODS
+                .iter()
+                .map(|&days| {
+                    let from = (days > 0).then(|| today - Duration::days(days));
+                    let label = if days > 0 {
+                        format!("{} days", days)
+                    } else {
+                        "All dates".to_string()
+                    };
+                    option(
+                        &label,
+                        view.from == from && view.to.is_none(),
+                        view.first_page(|v| {
+                            v.from = from;
+                            v.to = None;
+                        }),
+                    )
+                })
+                .collect(),
+        );
+        keyboard.push(vec![Button::callback(
+            "📅 Other dates",
+            BotCallback::RecordDates(
+                view.kind as u8,
+                view.tarantula_id.unwrap_or_default(),
+                view.status_id.unwrap_or_default(),
+            ),
+        )]);
+        keyboard.push(vec![Button::callback("« Back", view.page_callback())]);
+        Ok(Screen::new(message, keyboard))
+    }
+
+    pub(crate) fn record_dates_prompt(&self, view: RecordView) -> Outcome {
+        Outcome::send(Screen::text(format!(
+            "Send the first and last day of the {} to show, e.g. 2024-01-01 2024-03-31. \
+             Send one day for everything since.",
+            view.kind.noun()
+        )))
+        .enter(DialogueState::RecordDateRange {
+            kind: view.kind as u8,
+            tarantula_id: view.tarantula_id,
+            status_id: view.status_id,
+        })
+    }
+
+    /// Feedings, health checks, molts and enclosure maintenance of one
+    /// tarantula, newest first.
+    pub(crate) async fn timeline(
+        &self,
+        user_id: u64,
+        tarantula_id: i64,
+        page: u32,
+    ) -> BotResult<Screen> {
+        let tarantula = self.db.get_tarantula_by_id(user_id, tarantula_id).await?;
+        // Each source can fill the pages up to this one on its own.
+        let filter = RecordFilter {
+            tarantula_id: Some(tarantula_id),
+            limit: (page + 1) * PAGE_SIZE + 1,
+            ..RecordFilter::default()
+        };
+
+        let mut events: Vec<(String, String)> = Vec::new();
+        for f in self.db.get_feeding_records(user_id, &filter).await? {
+            events.push((f.feeding_date.clone(), format!("🍽 {}", feeding_summary(&f))));
+        }
+        for h in self.db.get_health_records(user_id, &filter).await? {
+            events.push((
+                h.check_date.clone(),
+                format!("🏥 Health check: {}", h.status),
+            ));
+        }
+        for m in self.db.get_molt_records(user_id, &filter).await? {
+            let length = m
+                .post_molt_length_cm
+                .map_or(String::new(), |l| format!(", {}cm", l));
+            events.push((
+                m.molt_date.clone(),
+                format!("🐾 Molt: {}{}", m.stage, length),
+            ));
+        }
+        if let Some(enclosure_id) = tarantula.enclosure_id {
+            for m in self
+                .db
+                .get_maintenance_history(enclosure_id, user_id)
+                .await?
+            {
+                let readings = [
+                    m.temperature_celsius.map(|t| format!("{}°C", t)),
+                    m.humidity_percent.map(|h| format!("{}%", h)),
+                ]
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>();
+                let mut line = String::from("🧹 Enclosure maintenance");
+                if !readings.is_empty() {
+                    line.push_str(&format!(": {}", readings.join(", ")));
+                }
+                if let Some(notes) = m.notes.filter(|n| !n.is_empty()) {
+                    line.push_str(&format!(" — {}", notes));
+                }
+                events.push((m.maintenance_date.to_string(), line));
+            }
+        }
+        events.sort_by(|a, b| b.0.cmp(&a.0));
+
+        let skip = (page * PAGE_SIZE) as usize;
+        let has_next = events.len() > skip + PAGE_SIZE as usize;
+        let lines: Vec<String> = events
+            .into_iter()
+            .skip(skip)
+            .take(PAGE_SIZE as usize)
+            .map(|(at, line)| format!("*{}* {}", short_timestamp(&at), line))
+            .collect();
+
+        let mut message = format!("🕰 *{}'s timeline*\n\n", tarantula.name);
+        if lines.is_empty() {
+            message.push_str("Nothing recorded yet.");
+        } else {
+            message.push_str(&lines.join("\n"));
+        }
+        if page > 0 || has_next {
+            message.push_str(&format!("\n\nPage {}", page + 1));
+        }
+
+        let mut keyboard = paging(page, has_next, |page| {
+            BotCallback::Timeline(tarantula_id, page)
+        });
+        let records = |kind| {
+            RecordView {
+                tarantula_id: Some(tarantula_id),
+                ..RecordView::new(kind)
+            }
+            .page_callback()
+        };
+        keyboard.push(vec![
+            Button::callback("🍽 Feedings", records(RecordKind::Feedings)),
+            Button::callback("🏥 Health", records(RecordKind::Health)),
+            Button::callback("🐾 Molts", records(RecordKind::Molts)),
+        ]);
+        keyboard.push(vec![
+            Button::callback("🕷 Profile", BotCallback::TarantulaProfile(tarantula_id)),
+            Button::callback("« Back to Records", BotCallback::ViewRecords),
+        ]);
+        Ok(Screen::new(message, keyboard))
+    }
+}
+
+/// "Rosie · Accepted · 2024-01-01 – 2024-03-31", or None when unfiltered.
+fn describe(view: &RecordView, tarantula: Option<&str>) -> Option<String> {
+    let status = view.status_id.and_then(|id| {
+        view.kind
+            .statuses()
+            .into_iter()
+            .find(|(s, _)| *s == id)
+            .map(|(_, name)| name)
+    });
+    let dates = match (view.from, view.to) {
+        (None, None) => None,
+        (Some(from), None) => Some(format!("since {}", from)),
+        (None, Some(to)) => Some(format!("until {}", to)),
+        (Some(from), Some(to)) => Some(format!("{} – {}", from, to)),
+    };
+    let parts: Vec<String> = [
+        tarantula.map(str::to_string),
+        status.map(str::to_string),
+        dates,
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    (!parts.is_empty()).then(|| parts.join(" · "))
+}
+
+/// "2024-01-05 12:00:00" → "2024-01-05 12:00"
+fn short_timestamp(at: &str) -> &str {
+    at.get(..16).unwrap_or(at)
+}
+
+fn feeding_summary(record: &FeedingRecord) -> String {
+    let source = record
+        .colony_name
+        .as_ref()
+        .map(|colony| format!(" from {}", colony))
+        .unwrap_or_default();
+    format!(
+        "{} crickets{}, {}",
+        record.number_of_crickets, source, record.status
+    )
+}
+
+fn feeding_entry(record: FeedingRecord) -> String {
+    let source = record
+        .colony_name
+        .map(|colony| format!(" from {}", colony))
+        .unwrap_or_default();
+    format!(
+        "*{}* - {}\n• {} crickets{}\n• Status: {}\n{}",
+        record.tarantula_name,
+        record.feeding_date,
+        record.number_of_crickets,
+        source,
+        record.status,
+        record.notes.unwrap_or_default()
+    )
+}
+
+fn health_entry(record: HealthRecord) -> String {
+    let details = [
+        record.weight_grams.map(|w| format!("Weight: {}g", w)),
+        record.humidity_percent.map(|h| format!("Humidity: {}%", h)),
+        record.temperature_celsius.map(|t| format!("Temp: {}°C", t)),
+    ];
+    let details_str = details.into_iter().flatten().collect::<Vec<_>>().join(", ");
+    format!(
+        "*{}* - {}\n• Status: {}\n• {}\n{}",
+        record.tarantula_name,
+        record.check_date,
+        record.status,
+        if details_str.is_empty() {
+            "No measurements taken"
+        } else {
+            &details_str
+        },
+        record.notes.unwrap_or_default()
+    )
+}
+
+fn molt_entry(record: MoltRecord) -> String {
+    format!(
+        "*{}* - {}\n• Stage: {}\n{}{}• {}",
+        record.tarantula_name,
+        record.molt_date,
+        record.stage,
+        record
+            .pre_molt_length_cm
+            .map_or(String::new(), |l| format!("• Length: {}cm\n", l)),
+        record
+            .complications
+            .map_or(String::new(), |c| format!("• Complications: {}\n", c)),
+        record.notes.unwrap_or_default()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn views_survive_the_trip_through_callback_data() {
+        let view = RecordView {
+            kind: RecordKind::Molts,
+            tarantula_id: Some(42),
+            status_id: None,
+            from: Some(day(2024, 1, 1)),
+            to: None,
+            page: 3,
+        };
+
+        let data = view.page_callback().to_string();
+        assert!(data.len() <= 64, "{} is too long for Telegram", data);
+        let BotCallback::RecordPage(kind, tarantula, status, from, to, page) =
+            data.parse().unwrap()
+        else {
+            panic!("{} is not a record page", data);
+        };
+        assert_eq!(
+            RecordView::decode(kind, tarantula, status, from, to, page).unwrap(),
+            view
+        );
+        assert!(RecordView::decode(9, 0, 0, 0, 0, 0).is_err());
+    }
+
+    #[test]
+    fn date_ranges_can_be_open_on_either_side() {
+        assert_eq!(
+            parse_date_range("2024-01-01 2024-03-31"),
+            Some((Some(day(2024, 1, 1)), Some(day(2024, 3, 31))))
+        );
+        assert_eq!(
+            parse_date_range(" 2024-01-01 "),
+            Some((Some(day(2024, 1, 1)), None))
+        );
+        assert_eq!(
+            parse_date_range("- 2024-03-31"),
+            Some((None, Some(day(2024, 3, 31))))
+        );
+        assert_eq!(parse_date_range("2024-03-31 2024-01-01"), None);
+        assert_eq!(parse_date_range("-"), None);
+        assert_eq!(parse_date_range("last week"), None);
+    }
+}
@@ -0,0 +1,1164 @@
+//! Importing history kept elsewhere. A keeper sends a CSV, a spreadsheet or
+//! the zip from `/export`; each sheet is recognised as tarantulas, feedings or
+//! molts by its name and headers, species are matched against the species
+//! table, and a preview lists what would be created and what was skipped.
+//! Nothing is written until the keeper confirms, and then all in one go.
+
+use crate::app::callbacks::BotCallback;
+use crate::app::commands::error_screen;
+use crate::app::dialogue::DialogueState;
+use crate::app::keyboards::back_to_menu_keyboard;
+use crate::app::screen::{Button, Outcome, Screen};
+use crate::app::{App, Session};
+use crate::error::BotError;
+use crate::models::enums::FeedingStatus;
+use crate::models::import::{
+    ImportPlan, ImportTarget, ImportedFeeding, ImportedMolt, ImportedTarantula,
+};
+use crate::models::tarantula::TarantulaSpecies;
+use crate::models::user::TelegramUser;
+use crate::BotResult;
+use calamine::{open_workbook_auto_from_rs, Data, Reader};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use std::collections::{HashMap, HashSet};
+use std::io::{Cursor, Read};
+use std::path::Path;
+use teloxide::utils::html;
+
+/// How alike a name has to be to a species name to count as a match, from 0
+/// to 1.
+const SPECIES_SIMILARITY: f64 = 0.8;
+/// Feedings recorded with a date only are placed at midday.
+const FEEDING_TIME: NaiveTime = match NaiveTime::from_hms_opt(12, 0, 0) {
+    Some(time) => time,
+    None => unreachable!(),
+};
+const MAX_LISTED: usize = 15;
+const DATE_FORMATS: [&str; 3] = ["%Y-%m-%d", "%d.%m.%Y", "%Y/%m/%d"];
+const DATETIME_FORMATS: [&str; 3] = ["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M:%S"];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    Tarantulas,
+    Feedings,
+    Molts,
+}
+
+impl Kind {
+    fn label(self) -> &'static str {
+        match self {
+            Kind::Tarantulas => "tarantulas",
+            Kind::Feedings => "feedings",
+            Kind::Molts => "molts",
+        }
+    }
+
+    /// The headers each field is recognised by, after [`normalize_header`].
+    fn columns(self) -> &'static [(Field, &'static [&'static str])] {
+        const NAME: &[&str] = &["tarantula_name", "tarantula", "name", "spider"];
+        const NOTES: &[&str] = &["notes", "note", "comments", "comment"];
+        match self {
+            Kind::Tarantulas => &[
+                (Field::Name, NAME),
+                (
+                    Field::Species,
+                    &["species", "scientific_name", "species_name"],
+                ),
+                (
+                    Field::Acquired,
+                    &[
+                        "acquisition_date",
+                        "acquired",
+                        "acquired_on",
+                        "date_acquired",
+                        "purchased",
+                    ],
+                ),
+                (Field::Age, &["estimated_age_months", "age_months", "age"]),
+                (Field::Enclosure, &["enclosure_number", "enclosure"]),
+                (Field::Notes, NOTES),
+            ],
+            Kind::Feedings => &[
+                (Field::Name, NAME),
+                (Field::Date, &["feeding_date", "fed_on", "fed", "date"]),
+                (
+                    Field::Crickets,
+                    &[
+                        "number_of_crickets",
+                        "crickets",
+                        "prey_count",
+                        "prey",
+                        "count",
+                    ],
+                ),
+                (Field::Status, &["status", "result"]),
+                (Field::Colony, &["colony_name", "colony"]),
+                (Field::Notes, NOTES),
+            ],
+            Kind::Molts => &[
+                (Field::Name, NAME),
+                (
+                    Field::Date,
+                    &["molt_date", "moult_date", "molted_on", "molted", "date"],
+                ),
+                (
+                    Field::Length,
+                    &[
+                        "post_molt_length_cm",
+                        "length_cm",
+                        "size_cm",
+                        "length",
+                        "size",
+                    ],
+                ),
+                (Field::Complications, &["complications"]),
+                (Field::Notes, NOTES),
+            ],
+        }
+    }
+
+    fn required(self) -> &'static [Field] {
+        match self {
+            Kind::Tarantulas => &[Field::Name, Field::Species],
+            Kind::Feedings | Kind::Molts => &[Field::Name, Field::Date],
+        }
+    }
+
+    /// Works out what a sheet holds, first from its name, then from headers
+    /// only one kind has.
+    fn of(sheet: &Sheet) -> Option<Kind> {
+        let name = normalize_header(&sheet.name);
+        if name.contains("feed") {
+            return Some(Kind::Feedings);
+        }
+        if name.contains("molt") || name.contains("moult") {
+            return Some(Kind::Molts);
+        }
+        if ["tarantula", "spider", "collection"]
+            .iter()
+            .any(|word| name.contains(word))
+        {
+            return Some(Kind::Tarantulas);
+        }
+
+        let headers: Vec<String> = sheet.headers.iter().map(|h| normalize_header(h)).collect();
+        let has = |kind: Kind, field: Field| {
+            kind.columns()
+                .iter()
+                .filter(|(f, _)| *f == field)
+                .flat_map(|(_, aliases)| aliases.iter())
+                .any(|alias| headers.iter().any(|h| h == alias))
+        };
+        if has(Kind::Tarantulas, Field::Species) {
+            Some(Kind::Tarantulas)
+        } else if has(Kind::Feedings, Field::Crickets)
+            || headers.iter().any(|h| h == "feeding_date" || h == "fed")
+        {
+            Some(Kind::Feedings)
+        } else if has(Kind::Molts, Field::Length)
+            || headers.iter().any(|h| h == "molt_date" || h == "molted")
+        {
+            Some(Kind::Molts)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Field {
+    Name,
+    Species,
+    Acquired,
+    Age,
+    Enclosure,
+    Date,
+    Crickets,
+    Status,
+    Colony,
+    Length,
+    Complications,
+    Notes,
+}
+
+/// A table as read from the upload, every cell as text.
+#[derive(Debug)]
+struct Sheet {
+    name: String,
+    headers: Vec<String>,
+    /// Each row with its line number in the file, counting the header as 1.
+    rows: Vec<(usize, Vec<String>)>,
+}
+
+impl Sheet {
+    fn new(name: String, mut rows: Vec<Vec<String>>) -> Self {
+        rows.retain(|row| row.iter().any(|cell| !cell.trim().is_empty()));
+        let mut rows = rows.into_iter();
+        let headers = rows.next().unwrap_or_default();
+        Self {
+            name,
+            headers,
+            rows: rows.enumerate().map(|(i, row)| (i + 2, row)).collect(),
+        }
+    }
+}
+
+/// A sheet with its columns assigned to fields.
+struct Mapped<'a> {
+    sheet: &'a Sheet,
+    kind: Kind,
+    columns: HashMap<Field, usize>,
+}
+
+impl Mapped<'_> {
+    fn get<'r>(&self, row: &'r [String], field: Field) -> Option<&'r str> {
+        let index = *self.columns.get(&field)?;
+        row.get(index)
+            .map(|cell| cell.trim())
+            .filter(|cell| !cell.is_empty())
+    }
+
+    /// "Name → name, Species → species; ignored: Colour"
+    fn describe(&self) -> String {
+        let mut mapped: Vec<(usize, Field)> = self.columns.iter().map(|(f, i)| (*i, *f)).collect();
+        mapped.sort_by_key(|(i, _)| *i);
+        let mut text = mapped
+            .iter()
+            .map(|(i, field)| format!("{} → {}", self.sheet.headers[*i], field_label(*field)))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let ignored: Vec<&str> = (0..self.sheet.headers.len())
+            .filter(|i| !self.columns.values().any(|c| c == i))
+            .map(|i| self.sheet.headers[i].as_str())
+            .filter(|h| !h.trim().is_empty())
+            .collect();
+        if !ignored.is_empty() {
+            text.push_str(&format!("; ignored: {}", ignored.join(", ")));
+        }
+        text
+    }
+}
+
+fn field_label(field: Field) -> &'static str {
+    match field {
+        Field::Name => "name",
+        Field::Species => "species",
+        Field::Acquired => "acquired",
+        Field::Age => "age in months",
+        Field::Enclosure => "enclosure",
+        Field::Date => "date",
+        Field::Crickets => "crickets",
+        Field::Status => "status",
+        Field::Colony => "colony",
+        Field::Length => "length",
+        Field::Complications => "complications",
+        Field::Notes => "notes",
+    }
+}
+
+/// Assigns each field the first header that names it. Each header is used
+/// at most once.
+fn map_columns(sheet: &Sheet, kind: Kind) -> Mapped<'_> {
+    let headers: Vec<String> = sheet.headers.iter().map(|h| normalize_header(h)).collect();
+    let mut columns = HashMap::new();
+    for (field, aliases) in kind.columns() {
+        let found = aliases.iter().find_map(|alias| {
+            headers
+                .iter()
+                .enumerate()
+                .find(|(i, h)| h == alias && !columns.values().any(|c| c == i))
+                .map(|(i, _)| i)
+        });
+        if let Some(index) = found {
+            columns.insert(*field, index);
+        }
+    }
+    Mapped {
+        sheet,
+        kind,
+        columns,
+    }
+}
+
+/// "Length (cm)" → "length_cm"
+fn normalize_header(header: &str) -> String {
+    header
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+/// Reads the tables in an upload, telling the format by the file name.
+fn read_upload(file_name: &str, contents: &[u8]) -> BotResult<Vec<Sheet>> {
+    let path = Path::new(file_name);
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let extension = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    match extension.as_str() {
+        "csv" => Ok(vec![read_csv(stem, contents)?]),
+        "xlsx" | "xlsm" | "xls" | "ods" => read_workbook(contents),
+        "zip" => read_zip(contents),
+        _ => Err(BotError::ValidationError(
+            "Send a .csv or .xlsx file, or the .zip from /export, to import it".to_string(),
+        )),
+    }
+}
+
+fn read_csv(name: String, contents: &[u8]) -> BotResult<Sheet> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(contents.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(contents));
+    let rows = reader
+        .records()
+        .map(|record| {
+            record
+                .map(|r| r.iter().map(str::to_string).collect())
+                .map_err(|e| unreadable(&name, e))
+        })
+        .collect::<BotResult<Vec<Vec<String>>>>()?;
+    Ok(Sheet::new(name, rows))
+}
+
+fn read_workbook(contents: &[u8]) -> BotResult<Vec<Sheet>> {
+    let mut workbook =
+        open_workbook_auto_from_rs(Cursor::new(contents)).map_err(|e| unreadable("workbook", e))?;
+    Ok(workbook
+        .worksheets()
+        .into_iter()
+        .map(|(name, range)| {
+            let rows = range
+                .rows()
+                .map(|row| row.iter().map(cell_text).collect())
+                .collect();
+            Sheet::new(name, rows)
+        })
+        .collect())
+}
+
+/// The CSV files in a zip, such as the one `/export` sends.
+fn read_zip(contents: &[u8]) -> BotResult<Vec<Sheet>> {
+    let mut archive =
+        zip::ZipArchive::new(Cursor::new(contents)).map_err(|e| unreadable("zip", e))?;
+    let mut sheets = Vec::new();
+    for index in 0..archive.len() {
+        let mut file = archive.by_index(index).map_err(|e| unreadable("zip", e))?;
+        let Some(stem) = file.name().strip_suffix(".csv").map(str::to_string) else {
+            continue;
+        };
+        let mut csv = Vec::new();
+        file.read_to_end(&mut csv)?;
+        sheets.push(read_csv(stem, &csv)?);
+    }
+    Ok(sheets)
+}
+
+fn cell_text(cell: &Data) -> String {
+    match cell {
+        Data::Empty | Data::Error(_) => String::new(),
+        Data::String(s) | Data::DateTimeIso(s) | Data::DurationIso(s) => s.clone(),
+        Data::Int(i) => i.to_string(),
+        Data::Float(f) => f.to_string(),
+        Data::Bool(b) => b.to_string(),
+        Data::DateTime(dt) => match dt.as_datetime() {
+            Some(dt) if dt.time() == NaiveTime::MIN => dt.date().to_string(),
+            Some(dt) => dt.format("%Y-%m-%d %H:%M:%S").to_string(),
+            None => String::new(),
+        },
+    }
+}
+
+fn unreadable(what: &str, e: impl std::fmt::Display) -> BotError {
+    BotError::ValidationError(format!("The {} can't be read: {}", what, e))
+}
+
+fn parse_date(text: &str) -> Option<NaiveDate> {
+    DATE_FORMATS
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(text, format).ok())
+        .or_else(|| parse_datetime(text).map(|dt| dt.date()))
+}
+
+fn parse_datetime(text: &str) -> Option<NaiveDateTime> {
+    DATETIME_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+}
+
+/// "4.5", "4,5" or "4.5 cm"
+fn parse_length(text: &str) -> Option<f32> {
+    let number = text.trim_end_matches("cm").trim().replace(',', ".");
+    number.parse().ok().filter(|n: &f32| *n > 0.0)
+}
+
+fn parse_feeding_status(text: &str) -> Option<FeedingStatus> {
+    let wanted = normalize_header(text);
+    [
+        FeedingStatus::Accepted,
+        FeedingStatus::Rejected,
+        FeedingStatus::Partial,
+        FeedingStatus::PreMolt,
+        FeedingStatus::Dead,
+        FeedingStatus::Overflow,
+    ]
+    .into_iter()
+    .find(|status| normalize_header(status.to_db_name()) == wanted)
+}
+
+/// What a species name in the file turned out to be.
+#[derive(Debug)]
+enum SpeciesMatch<'a> {
+    Exact(&'a TarantulaSpecies),
+    Close(&'a TarantulaSpecies),
+    Ambiguous(Vec<&'a TarantulaSpecies>),
+    Unknown,
+}
+
+/// Matches species by scientific name, common name or the abbreviated
+/// genus ("B. hamorii"), allowing for typos.
+struct SpeciesMatcher<'a> {
+    species: Vec<(&'a TarantulaSpecies, Vec<String>)>,
+}
+
+impl<'a> SpeciesMatcher<'a> {
+    fn new(species: &'a [TarantulaSpecies]) -> Self {
+        let species = species
+            .iter()
+            .map(|s| {
+                let scientific = normalize_name(&s.scientific_name);
+                let mut names = vec![scientific.clone()];
+                if let Some((genus, rest)) = scientific.split_once(' ') {
+                    names.push(format!("{} {}", &genus[..1], rest));
+                }
+                names.extend(s.common_name.as_deref().map(normalize_name));
+                (s, names)
+            })
+            .collect();
+        Self { species }
+    }
+
+    fn find(&self, text: &str) -> SpeciesMatch<'a> {
+        let wanted = normalize_name(text);
+        if wanted.is_empty() {
+            return SpeciesMatch::Unknown;
+        }
+
+        let exact: Vec<_> = self
+            .species
+            .iter()
+            .filter(|(_, names)| names.contains(&wanted))
+            .map(|(s, _)| *s)
+            .collect();
+        match exact.len() {
+            0 => {}
+            1 => return SpeciesMatch::Exact(exact[0]),
+            _ => return SpeciesMatch::Ambiguous(exact),
+        }
+
+        let mut scored: Vec<(f64, &TarantulaSpecies)> = self
+            .species
+            .iter()
+            .map(|(s, names)| {
+                let best = names
+                    .iter()
+                    .map(|name| similarity(name, &wanted))
+                    .fold(0.0, f64::max);
+                (best, *s)
+            })
+            .filter(|(score, _)| *score >= SPECIES_SIMILARITY)
+            .collect();
+        scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+        match scored.as_slice() {
+            [] => SpeciesMatch::Unknown,
+            [(best, first), (second, other), ..] if best - second < 0.02 => {
+                SpeciesMatch::Ambiguous(vec![*first, *other])
+            }
+            [(_, best), ..] => SpeciesMatch::Close(best),
+        }
+    }
+}
+
+/// Lowercase words without punctuation: "B. Hamorii " → "b hamorii"
+fn normalize_name(name: &str) -> String {
+    name.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// 1 for equal strings, falling towards 0 with each edit needed.
+fn similarity(a: &str, b: &str) -> f64 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 1.0;
+    }
+
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    1.0 - previous[b.len()] as f64 / longest as f64
+}
+
+/// What the keeper already has, to find the rows an import would repeat.
+#[derive(Default)]
+struct Collection {
+    tarantulas: HashMap<String, i64>,
+    enclosures: HashMap<String, String>,
+    colonies: HashMap<String, i64>,
+    feedings: HashSet<(String, String)>,
+    molts: HashSet<(String, String)>,
+}
+
+/// The outcome of checking an upload, shown before anything is written.
+#[derive(Default)]
+struct Preview {
+    plan: ImportPlan,
+    sheets: Vec<String>,
+    close_species: Vec<String>,
+    /// Tarantulas, feedings and molts already in the collection.
+    existing: [usize; 3],
+    conflicts: Vec<String>,
+    undated: usize,
+}
+
+impl Preview {
+    fn conflict(&mut self, sheet: &Sheet, line: usize, problem: String) {
+        self.conflicts
+            .push(format!("{} row {}: {}", sheet.name, line, problem));
+    }
+}
+
+fn preview(
+    sheets: &[Sheet],
+    species: &[TarantulaSpecies],
+    collection: &Collection,
+    today: NaiveDate,
+) -> Preview {
+    let matcher = SpeciesMatcher::new(species);
+    let mut preview = Preview::default();
+    let mut mapped = Vec::new();
+    for sheet in sheets {
+        let Some(kind) = Kind::of(sheet) else {
+            preview
+                .sheets
+                .push(format!("{}: not recognised, skipped", sheet.name));
+            continue;
+        };
+        let sheet = map_columns(sheet, kind);
+        let missing: Vec<&str> = kind
+            .required()
+            .iter()
+            .filter(|f| !sheet.columns.contains_key(f))
+            .map(|f| field_label(*f))
+            .collect();
+        if missing.is_empty() {
+            preview.sheets.push(format!(
+                "{} ({}): {}",
+                sheet.sheet.name,
+                kind.label(),
+                sheet.describe()
+            ));
+            mapped.push(sheet);
+        } else {
+            preview.sheets.push(format!(
+                "{} ({}): no {} column, skipped",
+                sheet.sheet.name,
+                kind.label(),
+                missing.join(" or ")
+            ));
+        }
+    }
+
+    // Tarantulas first, so history in any sheet can refer to them.
+    let mut new_tarantulas: HashMap<String, usize> = HashMap::new();
+    let mut enclosures = collection.enclosures.clone();
+    for sheet in mapped.iter().filter(|m| m.kind == Kind::Tarantulas) {
+        for (line, row) in &sheet.sheet.rows {
+            let line = *line;
+            let Some(name) = sheet.get(row, Field::Name) else {
+                preview.conflict(sheet.sheet, line, "no name".to_string());
+                continue;
+            };
+            let key = name.to_lowercase();
+            if collection.tarantulas.contains_key(&key) {
+                preview.existing[0] += 1;
+                continue;
+            }
+            if new_tarantulas.contains_key(&key) {
+                preview.conflict(sheet.sheet, line, format!("{} is listed twice", name));
+                continue;
+            }
+
+            let written = sheet.get(row, Field::Species).unwrap_or_default();
+            let species_id = match matcher.find(written) {
+                SpeciesMatch::Exact(s) => s.id,
+                SpeciesMatch::Close(s) => {
+                    preview
+                        .close_species
+                        .push(format!("{} → {}", written, s.scientific_name));
+                    s.id
+                }
+                SpeciesMatch::Ambiguous(candidates) => {
+                    let names: Vec<&str> = candidates
+                        .iter()
+                        .map(|s| s.scientific_name.as_str())
+                        .collect();
+                    preview.conflict(
+                        sheet.sheet,
+                        line,
+                        format!("species {} could be {}", written, names.join(" or ")),
+                    );
+                    continue;
+                }
+                SpeciesMatch::Unknown => {
+                    preview.conflict(
+                        sheet.sheet,
+                        line,
+                        format!("species {} not recognised", written),
+                    );
+                    continue;
+                }
+            };
+
+            let acquisition_date = match sheet.get(row, Field::Acquired) {
+                None => {
+                    preview.undated += 1;
+                    today
+                }
+                Some(text) => match parse_date(text) {
+                    Some(date) => date,
+                    None => {
+                        preview.conflict(sheet.sheet, line, format!("{} is not a date", text));
+                        continue;
+                    }
+                },
+            };
+            let estimated_age_months = match sheet.get(row, Field::Age) {
+                None => None,
+                Some(text) => match text.parse::<f64>() {
+                    Ok(months) if months >= 0.0 => Some(months.round() as i32),
+                    _ => {
+                        preview.conflict(
+                            sheet.sheet,
+                            line,
+                            format!("age {} is not a number of months", text),
+                        );
+                        continue;
+                    }
+                },
+            };
+            let enclosure_number = sheet.get(row, Field::Enclosure).map(str::to_string);
+            if let Some(number) = &enclosure_number {
+                if let Some(holder) = enclosures.get(number) {
+                    preview.conflict(
+                        sheet.sheet,
+                        line,
+                        format!("enclosure {} is already {}'s", number, holder),
+                    );
+                    continue;
+                }
+                enclosures.insert(number.clone(), name.to_string());
+            }
+
+            new_tarantulas.insert(key, preview.plan.tarantulas.len());
+            preview.plan.tarantulas.push(ImportedTarantula {
+                name: name.to_string(),
+                species_id,
+                acquisition_date,
+                estimated_age_months,
+                enclosure_number,
+                notes: sheet.get(row, Field::Notes).map(str::to_string),
+            });
+        }
+    }
+
+    let target = |name: &str| {
+        let key = name.to_lowercase();
+        new_tarantulas
+            .get(&key)
+            .map(|&index| ImportTarget::New(index))
+            .or_else(|| {
+                collection
+                    .tarantulas
+                    .get(&key)
+                    .map(|&id| ImportTarget::Existing(id))
+            })
+    };
+
+    for sheet in mapped.iter().filter(|m| m.kind != Kind::Tarantulas) {
+        let mut seen = HashSet::new();
+        for (line, row) in &sheet.sheet.rows {
+            let line = *line;
+            let name = sheet.get(row, Field::Name).unwrap_or_default();
+            let Some(tarantula) = target(name) else {
+                preview.conflict(sheet.sheet, line, format!("no tarantula called {}", name));
+                continue;
+            };
+            let date_text = sheet.get(row, Field::Date).unwrap_or_default();
+            let Some(date) = parse_datetime(date_text)
+                .or_else(|| parse_date(date_text).map(|d| d.and_time(FEEDING_TIME)))
+            else {
+                preview.conflict(sheet.sheet, line, format!("{} is not a date", date_text));
+                continue;
+            };
+            let key = (name.to_lowercase(), date.date().to_string());
+            let recorded = match sheet.kind {
+                Kind::Feedings => &collection.feedings,
+                _ => &collection.molts,
+            };
+            if !seen.insert(key.clone())
+                || (matches!(tarantula, ImportTarget::Existing(_)) && recorded.contains(&key))
+            {
+                preview.existing[if sheet.kind == Kind::Feedings { 1 } else { 2 }] += 1;
+                continue;
+            }
+
+            let notes = sheet.get(row, Field::Notes).map(str::to_string);
+            if sheet.kind == Kind::Feedings {
+                let number_of_crickets = match sheet.get(row, Field::Crickets) {
+                    None => 1,
+                    Some(text) => match text.parse::<f64>() {
+                        Ok(n) if n >= 0.0 && n.fract() == 0.0 => n as i32,
+                        _ => {
+                            preview.conflict(
+                                sheet.sheet,
+                                line,
+                                format!("{} is not a number of crickets", text),
+                            );
+                            continue;
+                        }
+                    },
+                };
+                let status = match sheet.get(row, Field::Status) {
+                    None => FeedingStatus::Accepted,
+                    Some(text) => match parse_feeding_status(text) {
+                        Some(status) => status,
+                        None => {
+                            preview.conflict(
+                                sheet.sheet,
+                                line,
+                                format!("feeding status {} not recognised", text),
+                            );
+                            continue;
+                        }
+                    },
+                };
+                preview.plan.feedings.push(ImportedFeeding {
+                    tarantula,
+                    fed_at: date,
+                    colony_id: sheet
+                        .get(row, Field::Colony)
+                        .and_then(|c| collection.colonies.get(&c.to_lowercase()).copied()),
+                    number_of_crickets,
+                    status,
+                    notes,
+                });
+            } else {
+                let length_cm = match sheet.get(row, Field::Length) {
+                    None => None,
+                    Some(text) => match parse_length(text) {
+                        Some(length) => Some(length),
+                        None => {
+                            preview.conflict(
+                                sheet.sheet,
+                                line,
+                                format!("{} is not a length in cm", text),
+                            );
+                            continue;
+                        }
+                    },
+                };
+                preview.plan.molts.push(ImportedMolt {
+                    tarantula,
+                    molt_date: date.date(),
+                    length_cm,
+                    complications: sheet.get(row, Field::Complications).map(str::to_string),
+                    notes,
+                });
+            }
+        }
+    }
+    preview
+}
+
+impl App {
+    /// Reads an upload and shows what importing it would do, waiting for the
+    /// keeper to confirm.
+    pub async fn import_preview(
+        &self,
+        user: &TelegramUser,
+        file_name: &str,
+        contents: &[u8],
+    ) -> BotResult<Outcome> {
+        self.db.ensure_user_exists(user).await?;
+        let result = self
+            .build_preview(user.telegram_id, file_name, contents)
+            .await;
+        match result {
+            Err(e) if e.is_internal() => Err(e),
+            result => Ok(result.unwrap_or_else(|e| Outcome::send(error_screen(&e)))),
+        }
+    }
+
+    async fn build_preview(
+        &self,
+        user_id: u64,
+        file_name: &str,
+        contents: &[u8],
+    ) -> BotResult<Outcome> {
+        let sheets = read_upload(file_name, contents)?;
+        let species = self.db.get_species().await?;
+        let collection = self.collection(user_id).await?;
+        let preview = preview(
+            &sheets,
+            &species,
+            &collection,
+            self.now(user_id).await?.date(),
+        );
+
+        let text = render_preview(file_name, &preview);
+        if preview.plan.is_empty() {
+            return Ok(Outcome::send(Screen::new(
+                format!("{}\n\nThere is nothing new to import.", text),
+                back_to_menu_keyboard(),
+            ))
+            .exit());
+        }
+        let keyboard = vec![vec![
+            Button::callback("✅ Import", BotCallback::ConfirmImport),
+            Button::callback("« Cancel", BotCallback::CancelImport),
+        ]];
+        Ok(Outcome::send(Screen::new(
+            format!("{}\n\nNothing is saved until you confirm.", text),
+            keyboard,
+        ))
+        .enter(DialogueState::ConfirmImport { plan: preview.plan }))
+    }
+
+    async fn collection(&self, user_id: u64) -> BotResult<Collection> {
+        let mut collection = Collection::default();
+        for t in self.db.get_all_tarantulas(user_id).await? {
+            let key = t.name.to_lowercase();
+            if let Some(number) = t.enclosure_number {
+                collection.enclosures.insert(number, t.name.clone());
+            }
+            collection.tarantulas.entry(key).or_insert(t.id);
+        }
+        for c in self.db.get_colony_status(user_id).await? {
+            collection
+                .colonies
+                .insert(c.colony_name.to_lowercase(), c.id);
+        }
+        for f in self
+            .db
+            .get_recent_feeding_records(user_id, i32::MAX)
+            .await?
+        {
+            collection
+                .feedings
+                .insert((f.tarantula_name.to_lowercase(), day(&f.feeding_date)));
+        }
+        for m in self.db.get_recent_molt_records(user_id, i32::MAX).await? {
+            collection
+                .molts
+                .insert((m.tarantula_name.to_lowercase(), day(&m.molt_date)));
+        }
+        Ok(collection)
+    }
+
+    pub(crate) async fn confirm_import(&self, session: &Session) -> BotResult<Outcome> {
+        let Some(DialogueState::ConfirmImport { plan }) = &session.dialogue else {
+            return Ok(Outcome::send(Screen::new(
+                "There is no import waiting for confirmation, send the file again.",
+                back_to_menu_keyboard(),
+            ))
+            .exit());
+        };
+        self.db.import_collection(session.user_id, plan).await?;
+        Ok(Outcome::replace(Screen::new(
+            format!(
+                "✅ Imported {} tarantulas, {} feedings and {} molts.",
+                plan.tarantulas.len(),
+                plan.feedings.len(),
+                plan.molts.len()
+            ),
+            back_to_menu_keyboard(),
+        ))
+        .exit())
+    }
+
+    pub(crate) fn cancel_import(&self) -> Outcome {
+        Outcome::replace(Screen::new(
+            "Import cancelled, nothing was saved.",
+            back_to_menu_keyboard(),
+        ))
+        .exit()
+    }
+
+    /// What `/import` says: how to send a file and which headers are read.
+    pub(crate) fn import_help(&self) -> Screen {
+        let mut text = String::from(
+            "📥 Send a .csv or .xlsx file, or the .zip from /export, to import your history. \
+             Sheets are told apart by their name or headers, and these headers are read:\n",
+        );
+        for kind in [Kind::Tarantulas, Kind::Feedings, Kind::Molts] {
+            let headers: Vec<&str> = kind
+                .columns()
+                .iter()
+                .map(|(_, aliases)| aliases[0])
+                .collect();
+            text.push_str(&format!("\n• {}: {}", kind.label(), headers.join(", ")));
+        }
+        text.push_str(
+            "\n\nSpecies can be scientific or common names. You see what would be imported \
+             before anything is saved.",
+        );
+        Screen::new(text, back_to_menu_keyboard())
+    }
+}
+
+/// The date part of a stored timestamp.
+fn day(timestamp: &str) -> String {
+    timestamp.chars().take(10).collect()
+}
+
+fn render_preview(file_name: &str, preview: &Preview) -> String {
+    let plan = &preview.plan;
+    let mut text = format!("📥 Import preview for {}\n", html::escape(file_name));
+
+    text.push_str("\nSheets\n");
+    for sheet in &preview.sheets {
+        text.push_str(&format!("• {}\n", html::escape(sheet)));
+    }
+
+    text.push_str(&format!(
+        "\nWill create\n• {} tarantulas\n• {} feedings\n• {} molts\n",
+        plan.tarantulas.len(),
+        plan.feedings.len(),
+        plan.molts.len()
+    ));
+    if preview.undated > 0 {
+        text.push_str(&format!(
+            "{} tarantulas have no acquisition date and get today's.\n",
+            preview.undated
+        ));
+    }
+
+    let [tarantulas, feedings, molts] = preview.existing;
+    if tarantulas + feedings + molts > 0 {
+        text.push_str(&format!(
+            "\nAlready recorded, skipped\n• {} tarantulas, {} feedings, {} molts\n",
+            tarantulas, feedings, molts
+        ));
+    }
+    push_list(&mut text, "Species matched loosely", &preview.close_species);
+    push_list(&mut text, "Conflicts, skipped", &preview.conflicts);
+    text.trim_end().to_string()
+}
+
+fn push_list(text: &mut String, title: &str, items: &[String]) {
+    if items.is_empty() {
+        return;
+    }
+    text.push_str(&format!("\n{} ({})\n", title, items.len()));
+    for item in items.iter().take(MAX_LISTED) {
+        text.push_str(&format!("• {}\n", html::escape(item)));
+    }
+    if items.len() > MAX_LISTED {
+        text.push_str(&format!("… and {} more\n", items.len() - MAX_LISTED));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn species() -> Vec<TarantulaSpecies> {
+        [
+            (1, "Brachypelma hamorii", "Mexican Red Knee"),
+            (2, "Brachypelma smithi", "Mexican Red Knee"),
+            (3, "Grammostola pulchra", "Brazilian Black"),
+            (4, "Caribena versicolor", "Antilles Pinktoe"),
+        ]
+        .into_iter()
+        .map(|(id, scientific, common)| TarantulaSpecies {
+            id,
+            scientific_name: scientific.to_string(),
+            common_name: Some(common.to_string()),
+        })
+        .collect()
+    }
+
+    fn csv(name: &str, text: &str) -> Sheet {
+        read_csv(name.to_string(), text.as_bytes()).unwrap()
+    }
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 6, 1).unwrap()
+    }
+
+    #[test]
+    fn species_are_matched_by_any_name_and_despite_typos() {
+        let species = species();
+        let matcher = SpeciesMatcher::new(&species);
+        let id = |found: SpeciesMatch| match found {
+            SpeciesMatch::Exact(s) => Some(("exact", s.id)),
+            SpeciesMatch::Close(s) => Some(("close", s.id)),
+            _ => None,
+        };
+
+        assert_eq!(id(matcher.find("grammostola PULCHRA")), Some(("exact", 3)));
+        assert_eq!(id(matcher.find("Antilles pinktoe")), Some(("exact", 4)));
+        assert_eq!(id(matcher.find("G. pulchra")), Some(("exact", 3)));
+        assert_eq!(id(matcher.find("Caribena versicolour")), Some(("close", 4)));
+        assert!(matches!(
+            matcher.find("Mexican red knee"),
+            SpeciesMatch::Ambiguous(candidates) if candidates.len() == 2
+        ));
+        assert!(matches!(
+            matcher.find("Poecilotheria metallica"),
+            SpeciesMatch::Unknown
+        ));
+    }
+
+    #[test]
+    fn sheets_are_recognised_by_name_or_headers() {
+        let by_headers = csv("export", "Name,Scientific Name,Acquired\n");
+        let by_name = csv("Feeding log", "Spider,Date\n");
+        let molts = csv("sheet1", "Tarantula,Molted,Length (cm)\n");
+        let other = csv("prices", "Item,Price\n");
+
+        assert_eq!(Kind::of(&by_headers), Some(Kind::Tarantulas));
+        assert_eq!(Kind::of(&by_name), Some(Kind::Feedings));
+        assert_eq!(Kind::of(&molts), Some(Kind::Molts));
+        assert_eq!(Kind::of(&other), None);
+        assert_eq!(
+            map_columns(&molts, Kind::Molts).describe(),
+            "Tarantula → name, Molted → date, Length (cm) → length"
+        );
+    }
+
+    #[test]
+    fn the_preview_creates_what_is_new_and_lists_the_rest() {
+        let sheets = [
+            csv(
+                "collection",
+                "Name,Species,Acquired,Enclosure,Colour\n\
+                 Rosie,Grammostola pulchra,2019-04-02,A1,black\n\
+                 Pinky,Caribena versicolour,,A2,\n\
+                 Ghost,Poecilotheria metallica,2020-01-01,,\n\
+                 Rosie,Grammostola pulchra,2019-04-02,,\n\
+                 Old Bob,G. pulchra,2015-01-01,,\n",
+            ),
+            csv(
+                "feedings",
+                "Tarantula,Date,Crickets,Status\n\
+                 Rosie,02.05.2019,2,accepted\n\
+                 Pinky,2024-03-01 18:30,1,pre-molt\n\
+                 Old Bob,2024-01-10,3,\n\
+                 Ghost,2024-01-10,1,\n\
+                 Rosie,yesterday,1,\n",
+            ),
+            csv(
+                "molts",
+                "Tarantula,Molt date,Length\n\
+                 Pinky,2024-04-01,4.5 cm\n\
+                 Old Bob,2024-02-01,big\n",
+            ),
+        ];
+        let collection = Collection {
+            tarantulas: HashMap::from([("old bob".to_string(), 7)]),
+            feedings: HashSet::from([("old bob".to_string(), "2024-01-10".to_string())]),
+            ..Collection::default()
+        };
+
+        let preview = preview(&sheets, &species(), &collection, today());
+        let plan = &preview.plan;
+
+        let names: Vec<_> = plan.tarantulas.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["Rosie", "Pinky"]);
+        assert_eq!(plan.tarantulas[1].species_id, 4);
+        assert_eq!(plan.tarantulas[1].acquisition_date, today());
+        assert_eq!(
+            preview.close_species,
+            ["Caribena versicolour → Caribena versicolor"]
+        );
+        assert_eq!(preview.existing, [1, 1, 0]);
+
+        assert_eq!(plan.feedings.len(), 2);
+        assert_eq!(plan.feedings[0].tarantula, ImportTarget::New(0));
+        assert_eq!(plan.feedings[0].fed_at.to_string(), "2019-05-02 12:00:00");
+        assert!(matches!(plan.feedings[1].status, FeedingStatus::PreMolt));
+        assert_eq!(plan.feedings[1].fed_at.to_string(), "2024-03-01 18:30:00");
+        assert_eq!(plan.molts.len(), 1);
+        assert_eq!(plan.molts[0].length_cm, Some(4.5));
+
+        assert_eq!(
+            preview.conflicts,
+            [
+                "collection row 4: species Poecilotheria metallica not recognised",
+                "collection row 5: Rosie is listed twice",
+                "feedings row 5: no tarantula called Ghost",
+                "feedings row 6: yesterday is not a date",
+                "molts row 3: big is not a length in cm",
+            ]
+        );
+        assert!(preview.sheets[0].ends_with("; ignored: Colour"));
+    }
+
+    #[test]
+    fn enclosures_stay_unique() {
+        let sheets = [csv(
+            "tarantulas",
+            "Name,Species,Enclosure\nRosie,Grammostola pulchra,A1\nPinky,Caribena versicolor,B1\n",
+        )];
+        let collection = Collection {
+            enclosures: HashMap::from([("B1".to_string(), "Boris".to_string())]),
+            ..Collection::default()
+        };
+
+        let preview = preview(&sheets, &species(), &collection, today());
+
+        assert_eq!(preview.plan.tarantulas.len(), 1);
+        assert_eq!(
+            preview.conflicts,
+            ["tarantulas row 3: enclosure B1 is already Boris's"]
+        );
+    }
+
+    #[test]
+    fn uploads_are_read_by_their_extension() {
+        assert!(matches!(
+            read_upload("notes.txt", b"hello"),
+            Err(BotError::ValidationError(_))
+        ));
+        let sheets = read_upload(
+            "My Tarantulas.csv",
+            "\u{feff}Name,Species\nRosie,x\n".as_bytes(),
+        )
+        .unwrap();
+        assert_eq!(sheets[0].name, "My Tarantulas");
+        assert_eq!(sheets[0].headers, ["Name", "Species"]);
+        assert_eq!(
+            sheets[0].rows,
+            [(2, vec!["Rosie".to_string(), "x".to_string()])]
+        );
+    }
+}
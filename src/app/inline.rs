@@ -0,0 +1,100 @@
+//! Inline mode: `@bot rosie` typed in any chat offers the keeper's matching
+//! tarantulas as cards, with buttons that open the bot on that tarantula.
+
+use crate::app::callbacks::BotCallback;
+use crate::app::listing::{search, Entry};
+use crate::app::screen::{Button, InlineCard};
+use crate::app::App;
+use crate::BotResult;
+use teloxide::utils::html;
+
+/// Telegram takes up to 50 results; a handful more than fit on screen is enough.
+const MAX_RESULTS: usize = 20;
+
+impl App {
+    /// The keeper's tarantulas matching `query` by name, species or enclosure,
+    /// as inline results. An empty query offers the whole collection by name.
+    pub(crate) async fn inline_cards(
+        &self,
+        user_id: u64,
+        query: &str,
+    ) -> BotResult<Vec<InlineCard>> {
+        let facts = self.db.get_schedule_facts(user_id).await?;
+        Ok(search(&facts, false, query, self.now(user_id).await?)
+            .iter()
+            .take(MAX_RESULTS)
+            .map(|(_, entry)| card(entry))
+            .collect())
+    }
+}
+
+fn card(entry: &Entry) -> InlineCard {
+    let (item, facts) = (&entry.item, entry.facts);
+    let last_fed = match item.days_since_feeding {
+        None => "never fed".to_string(),
+        Some(days) if days < 1.0 => "fed today".to_string(),
+        Some(days) => format!("fed {:.0} days ago", days),
+    };
+    let stage = facts.molt_stage.map_or("Unknown", |s| s.to_db_name());
+
+    // Cards are sent as HTML, so whatever the keeper typed is escaped.
+    let mut text = format!(
+        "🕷 <b>{}</b>\n<i>{}</i>",
+        html::escape(&item.name),
+        html::escape(&facts.scientific_name)
+    );
+    if facts.species_name != facts.scientific_name {
+        text.push_str(&format!(" ({})", html::escape(&facts.species_name)));
+    }
+    text.push_str(&format!(
+        "\n\n🍽 <b>Last Fed:</b> {}\n🐾 <b>Molt Stage:</b> {}\n▫️ <b>Status:</b> {}",
+        facts.last_fed.map_or("Never".to_string(), |at| format!(
+            "{} ({})",
+            at.date(),
+            last_fed
+        )),
+        stage,
+        html::escape(&item.current_status)
+    ));
+
+    InlineCard {
+        id: item.id.to_string(),
+        title: item.name.clone(),
+        description: format!("{} · {} · {}", item.species_name, last_fed, stage),
+        text,
+        buttons: vec![vec![
+            Button::callback("🕷 Profile", BotCallback::TarantulaProfile(item.id)),
+            Button::callback("🍽 Feed", BotCallback::FeedTarantula(item.id)),
+        ]],
+    }
+}
+
+/// The callback a `/start` payload from an inline card's button asks for.
+/// Only the actions cards link to are honoured.
+pub(crate) fn deep_link(payload: &str) -> Option<BotCallback> {
+    match payload.parse::<BotCallback>().ok()? {
+        callback @ (BotCallback::TarantulaProfile(_) | BotCallback::FeedTarantula(_)) => {
+            Some(callback)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_card_buttons_deep_link() {
+        assert!(matches!(
+            deep_link("tarantula_profile_7"),
+            Some(BotCallback::TarantulaProfile(7))
+        ));
+        assert!(matches!(
+            deep_link("feed_tarantula_7"),
+            Some(BotCallback::FeedTarantula(7))
+        ));
+        assert!(deep_link("delete_photo_7_1").is_none());
+        assert!(deep_link("").is_none());
+    }
+}
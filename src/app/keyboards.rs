@@ -0,0 +1,129 @@
+use crate::app::callbacks::BotCallback;
+use crate::app::callbacks::BotCallback::{
+    BreedingMenu, Colonies, ColonyMaintenance, FeedingSchedule, GroupsMenu, HealthAlerts,
+    ListTarantulas, MainMenu, Maintenance, MoltHistory, RecordFeeding, RecordHealthCheck,
+    RecordMolt, StatusOverview, ViewRecords,
+};
+use crate::app::screen::{Button, Keyboard};
+use crate::app::when::{earlier_button, to_stamp, Backdate};
+use crate::models::cricket::ColonyStatus;
+use chrono::NaiveDateTime;
+
+pub(crate) fn welcome_keyboard() -> Keyboard {
+    vec![
+        vec![
+            Button::callback("🕷 List Tarantulas", ListTarantulas),
+            Button::callback("📊 Status Overview", StatusOverview),
+        ],
+        vec![
+            Button::callback("🍽 Due Feedings", FeedingSchedule),
+            Button::callback("📝 Record Feeding", RecordFeeding),
+        ],
+        vec![
+            Button::callback("🏥 Health Alerts", HealthAlerts),
+            Button::callback("🔍 Record Health Check", RecordHealthCheck),
+        ],
+        vec![
+            Button::callback("🐾 Recent Molts", MoltHistory),
+            Button::callback("📝 Record Molt", RecordMolt),
+        ],
+        vec![
+            Button::callback("🦗 Colony Status", Colonies),
+            Button::callback("🧰 Colony Maintenance", ColonyMaintenance),
+        ],
+        vec![
+            Button::callback("🧹 Maintenance Tasks", Maintenance),
+            Button::callback("📋 View Records", ViewRecords),
+        ],
+        vec![
+            Button::callback("🥚 Breeding", BreedingMenu),
+            Button::callback("🧺 Sling Groups", GroupsMenu),
+        ],
+    ]
+}
+
+/// Counts to feed, logged for `at` or, without it, now with an offer to
+/// pick an earlier moment.
+pub(crate) fn feed_count_selection_keyboard(
+    tarantula_id: i64,
+    colony_id: i64,
+    at: Option<NaiveDateTime>,
+) -> Keyboard {
+    let count = |label: &str, count| {
+        let callback = match at {
+            None => BotCallback::FeedConfirm(tarantula_id, colony_id, count),
+            Some(_) => BotCallback::BackdatedFeed(tarantula_id, colony_id, count, to_stamp(at)),
+        };
+        Button::callback(label, callback)
+    };
+    let mut keyboard = vec![
+        vec![count("1 cricket", 1), count("2 crickets", 2)],
+        vec![count("3 crickets", 3), count("5 crickets", 5)],
+    ];
+    if at.is_none() {
+        keyboard.push(vec![earlier_button(Backdate::Feeding {
+            tarantula_id,
+            colony_id,
+        })]);
+    }
+    keyboard.push(vec![Button::callback("« Cancel", MainMenu)]);
+    keyboard
+}
+
+pub(crate) fn feed_command_keyboard(tarantula_id: i64, colonies: Vec<ColonyStatus>) -> Keyboard {
+    let mut keyboard: Keyboard = colonies
+        .chunks(2)
+        .map(|chunk| {
+            chunk
+                .iter()
+                .map(|colony| {
+                    Button::callback(
+                        format!("{} ({})", colony.colony_name, colony.size_type.to_db_name()),
+                        BotCallback::FeedSelectColony(tarantula_id, colony.id),
+                    )
+                })
+                .collect()
+        })
+        .collect();
+    keyboard.push(vec![
+        Button::callback(
+            "📋 View Schedule",
+            BotCallback::ViewFeedingSchedule(tarantula_id),
+        ),
+        Button::callback("🧬 Pedigree", BotCallback::Pedigree(tarantula_id)),
+    ]);
+    keyboard.push(vec![Button::callback("« Cancel", MainMenu)]);
+    keyboard
+}
+
+pub(crate) fn back_to_menu_keyboard() -> Keyboard {
+    vec![vec![Button::callback("« Back to Menu", MainMenu)]]
+}
+
+pub(crate) fn undo_button(change_id: i64) -> Button {
+    Button::callback("↩️ Undo", BotCallback::Undo(change_id))
+}
+
+/// For a message confirming a logged change, so a mis-tap can be taken back.
+pub(crate) fn undo_keyboard(change_id: i64) -> Keyboard {
+    vec![vec![
+        undo_button(change_id),
+        Button::callback("« Back to Menu", MainMenu),
+    ]]
+}
+
+/// Previous and next buttons, as far as there are pages that way.
+pub(crate) fn paging(page: u32, has_next: bool, callback: impl Fn(u32) -> BotCallback) -> Keyboard {
+    let mut row = Vec::new();
+    if page > 0 {
+        row.push(Button::callback("« Previous", callback(page - 1)));
+    }
+    if has_next {
+        row.push(Button::callback("Next »", callback(page + 1)));
+    }
+    if row.is_empty() {
+        Vec::new()
+    } else {
+        vec![row]
+    }
+}
@@ -0,0 +1,229 @@
+use crate::app::callbacks::BotCallback;
+use crate::app::dialogue::DialogueState;
+use crate::app::keyboards::back_to_menu_keyboard;
+use crate::app::screen::{Button, Outcome, Screen};
+use crate::app::{App, Session};
+use crate::db::db::AddPairingParams;
+use crate::error::BotError;
+use crate::models::lineage::{shared_ancestors, LineageNode, Parent, CLOSE_ANCESTRY_GENERATIONS};
+use crate::BotResult;
+
+const PEDIGREE_GENERATIONS: i32 = 3;
+const MAX_CHILDREN_SHOWN: usize = 10;
+
+impl App {
+    pub(crate) async fn pedigree(&self, tarantula_id: i64, user_id: u64) -> BotResult<Screen> {
+        let tarantula = self.db.get_tarantula_by_id(user_id, tarantula_id).await?;
+        let ancestors = self
+            .db
+            .get_ancestors(user_id, tarantula_id, PEDIGREE_GENERATIONS)
+            .await?;
+        let descendants = self
+            .db
+            .get_descendants(user_id, tarantula_id, PEDIGREE_GENERATIONS)
+            .await?;
+
+        let mut message = format!("🧬 *Pedigree of {}*\n\n*Ancestry*\n", tarantula.name);
+        if ancestors.is_empty() {
+            message.push_str("No parents recorded.\n");
+        } else {
+            message.push_str(&render_tree(
+                &tarantula.name,
+                tarantula_id,
+                &ancestors,
+                true,
+            ));
+        }
+
+        message.push_str("\n*Descendants*\n");
+        if descendants.is_empty() {
+            message.push_str("No offspring recorded.\n");
+        } else {
+            message.push_str(&render_tree(
+                &tarantula.name,
+                tarantula_id,
+                &descendants,
+                false,
+            ));
+        }
+        message.push_str(&format!(
+            "\nSet parents with /setparents {} mother father (id, breeder name or -)",
+            tarantula_id
+        ));
+
+        let keyboard = vec![vec![Button::callback(
+            "« Back",
+            BotCallback::FeedTarantula(tarantula_id),
+        )]];
+
+        Ok(Screen::new(message, keyboard))
+    }
+
+    /// Looks for close shared ancestry between the pair. Returns the shared
+    /// tarantula names, empty when the pairing is unrelated.
+    pub(crate) async fn pairing_ancestry_conflicts(
+        &self,
+        user_id: u64,
+        female_id: i64,
+        male_id: i64,
+    ) -> BotResult<Vec<String>> {
+        let female = self
+            .db
+            .get_ancestors(user_id, female_id, CLOSE_ANCESTRY_GENERATIONS)
+            .await?;
+        let male = self
+            .db
+            .get_ancestors(user_id, male_id, CLOSE_ANCESTRY_GENERATIONS)
+            .await?;
+        Ok(shared_ancestors(female_id, &female, male_id, &male))
+    }
+
+    /// Logs the pairing, or asks for confirmation first when the pair share
+    /// close ancestry.
+    pub(crate) async fn add_pairing(
+        &self,
+        user_id: u64,
+        params: AddPairingParams,
+    ) -> BotResult<Outcome> {
+        // Checked before the ancestry, which a tarantula shares with itself.
+        if params.female_id == params.male_id {
+            return Err(BotError::ValidationError(
+                "A tarantula cannot be paired with itself".to_string(),
+            ));
+        }
+
+        let shared = self
+            .pairing_ancestry_conflicts(user_id, params.female_id, params.male_id)
+            .await?;
+
+        if !shared.is_empty() {
+            let message = format!(
+                "⚠️ *Close ancestry detected*\n\n\
+                These tarantulas share: {}\n\n\
+                Log the pairing anyway?",
+                shared.join(", ")
+            );
+            let keyboard = vec![vec![
+                Button::callback("✅ Log anyway", BotCallback::ConfirmPairing),
+                Button::callback("« Cancel", BotCallback::CancelPairing),
+            ]];
+            return Ok(Outcome::send(Screen::new(message, keyboard)).enter(
+                DialogueState::ConfirmPairing {
+                    female_id: params.female_id,
+                    male_id: params.male_id,
+                    pairing_date: params.pairing_date,
+                    notes: params.notes,
+                },
+            ));
+        }
+
+        Ok(Outcome::send(self.create_pairing(user_id, params).await?))
+    }
+
+    async fn create_pairing(&self, user_id: u64, params: AddPairingParams) -> BotResult<Screen> {
+        let pairing_id = self.db.record_pairing(user_id, params).await?;
+        Ok(Screen::new(
+            format!("✅ Pairing #{} logged", pairing_id),
+            vec![vec![Button::callback(
+                "💑 View Pairing",
+                BotCallback::PairingDetails(pairing_id),
+            )]],
+        ))
+    }
+
+    pub(crate) async fn confirm_pairing(&self, session: &Session) -> BotResult<Outcome> {
+        let screen = match session.dialogue.clone() {
+            Some(DialogueState::ConfirmPairing {
+                female_id,
+                male_id,
+                pairing_date,
+                notes,
+            }) => {
+                self.create_pairing(
+                    session.user_id,
+                    AddPairingParams {
+                        female_id,
+                        male_id,
+                        pairing_date,
+                        notes,
+                    },
+                )
+                .await?
+            }
+            _ => Screen::new(
+                "There is no pairing waiting for confirmation.",
+                back_to_menu_keyboard(),
+            ),
+        };
+        Ok(Outcome::send(screen).exit())
+    }
+
+    pub(crate) async fn cancel_pairing(&self, user_id: u64) -> BotResult<Outcome> {
+        Ok(Outcome::replace(self.breeding_menu(user_id).await?).exit())
+    }
+}
+
+/// Renders ancestor or descendant edges as an indented tree below the root.
+/// Parent symbols only make sense for ancestors, so descendants get plain bullets.
+fn render_tree(root_name: &str, root_id: i64, nodes: &[LineageNode], show_roles: bool) -> String {
+    let mut out = format!("{}\n", root_name);
+    render_branch(&mut out, nodes, root_id, 0, "", show_roles);
+    out
+}
+
+fn render_branch(
+    out: &mut String,
+    nodes: &[LineageNode],
+    relative_id: i64,
+    depth: i32,
+    prefix: &str,
+    show_roles: bool,
+) {
+    let children: Vec<&LineageNode> = nodes
+        .iter()
+        .filter(|n| n.relative_id == relative_id && n.depth == depth + 1)
+        .collect();
+    let hidden = children.len().saturating_sub(MAX_CHILDREN_SHOWN);
+    let shown = &children[..children.len().min(MAX_CHILDREN_SHOWN)];
+
+    for (i, node) in shown.iter().enumerate() {
+        let last = i + 1 == shown.len() && hidden == 0;
+        let external = if node.is_external() {
+            " (external)"
+        } else {
+            ""
+        };
+        out.push_str(&format!(
+            "{}{} {} {}{}\n",
+            prefix,
+            if last { "└─" } else { "├─" },
+            if show_roles {
+                node.role.symbol()
+            } else {
+                "•"
+            },
+            node.name,
+            external
+        ));
+
+        if let Some(id) = node.id {
+            let child_prefix = format!("{}{}", prefix, if last { "   " } else { "│  " });
+            render_branch(out, nodes, id, node.depth, &child_prefix, show_roles);
+        }
+    }
+
+    if hidden > 0 {
+        out.push_str(&format!("{}└─ … and {} more\n", prefix, hidden));
+    }
+}
+
+/// Parses a /setparents argument: a tarantula id, `-` for unknown, or an external breeder name.
+pub(crate) fn parse_parent(value: &str) -> Parent {
+    match value.trim() {
+        "" | "-" => Parent::Unknown,
+        v => v
+            .parse::<i64>()
+            .map(Parent::Internal)
+            .unwrap_or_else(|_| Parent::External(v.replace('_', " "))),
+    }
+}
@@ -0,0 +1,554 @@
+//! The collection page by page, sorted and narrowed to what needs attention,
+//! and the tarantula pickers for feedings, health checks and molts built on
+//! it. What a page shows travels in the callback data of its buttons.
+
+use crate::app::callbacks::BotCallback;
+use crate::app::dialogue::DialogueState;
+use crate::app::keyboards::{back_to_menu_keyboard, paging};
+use crate::app::screen::{Button, Keyboard, Outcome, Screen};
+use crate::app::App;
+use crate::error::BotError;
+use crate::models::enums::{HealthStatus, MoltStage};
+use crate::models::group::collapse_groups;
+use crate::models::tarantula::TarantulaListItem;
+use crate::schedule::{self, TarantulaFacts};
+use crate::BotResult;
+use chrono::NaiveDateTime;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+const PAGE_SIZE: usize = 10;
+/// Search results offered as buttons; more than this asks for a longer search.
+const MAX_MATCHES: usize = 10;
+
+/// What tapping a tarantula in the list does.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ListPurpose {
+    Browse = 1,
+    Feed = 2,
+    HealthCheck = 3,
+    Molt = 4,
+}
+
+impl ListPurpose {
+    pub(crate) fn from_id(id: u8) -> BotResult<Self> {
+        match id {
+            1 => Ok(ListPurpose::Browse),
+            2 => Ok(ListPurpose::Feed),
+            3 => Ok(ListPurpose::HealthCheck),
+            4 => Ok(ListPurpose::Molt),
+            _ => Err(BotError::ValidationError(format!(
+                "There is no tarantula list {}",
+                id
+            ))),
+        }
+    }
+
+    fn title(self) -> &'static str {
+        match self {
+            ListPurpose::Browse => "🕷 *Your Tarantulas*",
+            ListPurpose::Feed => "*Record Feeding*",
+            ListPurpose::HealthCheck => "*Health Check*",
+            ListPurpose::Molt => "*Record Molt*",
+        }
+    }
+
+    fn button(self, item: &TarantulaListItem) -> Button {
+        match (self, item.group_id) {
+            (ListPurpose::Browse, Some(group_id)) => Button::callback(
+                format!("🧺 {}", item.name),
+                BotCallback::GroupDetails(group_id),
+            ),
+            (ListPurpose::Browse, None) => Button::callback(
+                format!("🕷 {}", item.name),
+                BotCallback::TarantulaProfile(item.id),
+            ),
+            (ListPurpose::Feed, _) => Button::callback(
+                format!("{} ({})", item.name, item.species_name),
+                BotCallback::FeedTarantula(item.id),
+            ),
+            (ListPurpose::HealthCheck, _) => Button::callback(
+                format!("{} ({})", item.name, item.species_name),
+                BotCallback::HealthCheck(item.id),
+            ),
+            (ListPurpose::Molt, _) => Button::callback(
+                format!("{} ({})", item.name, item.species_name),
+                BotCallback::MoltSimple(item.id),
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum ListSort {
+    Name = 1,
+    Species = 2,
+    LastFed = 3,
+    Enclosure = 4,
+}
+
+impl ListSort {
+    const ALL: [ListSort; 4] = [
+        ListSort::Name,
+        ListSort::Species,
+        ListSort::LastFed,
+        ListSort::Enclosure,
+    ];
+
+    fn from_id(id: u8) -> BotResult<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|s| *s as u8 == id)
+            .ok_or_else(|| BotError::ValidationError(format!("Can't sort by {}", id)))
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            ListSort::Name => "Name",
+            ListSort::Species => "Species",
+            ListSort::LastFed => "Last fed",
+            ListSort::Enclosure => "Enclosure",
+        }
+    }
+
+    /// Last fed puts the never fed and the longest unfed first; tarantulas
+    /// without an enclosure go last. Ties fall back to the name.
+    fn compare(self, a: &TarantulaListItem, b: &TarantulaListItem) -> Ordering {
+        let order = match self {
+            ListSort::Name => Ordering::Equal,
+            ListSort::Species => a.species_name.cmp(&b.species_name),
+            ListSort::LastFed => {
+                let days = |t: &TarantulaListItem| t.days_since_feeding.unwrap_or(f64::MAX);
+                days(b).total_cmp(&days(a))
+            }
+            ListSort::Enclosure => enclosure_key(a).cmp(&enclosure_key(b)),
+        };
+        order.then_with(|| a.name.cmp(&b.name))
+    }
+}
+
+/// Numbered enclosures in numeric order, then the rest by name, then none.
+fn enclosure_key(item: &TarantulaListItem) -> (bool, u64, &str) {
+    let enclosure = item.enclosure_number.as_deref();
+    (
+        enclosure.is_none(),
+        enclosure.and_then(|e| e.parse().ok()).unwrap_or(u64::MAX),
+        enclosure.unwrap_or_default(),
+    )
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum ListFilter {
+    Due = 1,
+    PreMolt = 2,
+    Health = 3,
+}
+
+impl ListFilter {
+    const ALL: [ListFilter; 3] = [ListFilter::Due, ListFilter::PreMolt, ListFilter::Health];
+
+    fn from_id(id: u8) -> BotResult<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|f| *f as u8 == id)
+            .ok_or_else(|| BotError::ValidationError(format!("There is no list filter {}", id)))
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            ListFilter::Due => "Due",
+            ListFilter::PreMolt => "Pre-molt",
+            ListFilter::Health => "Health flag",
+        }
+    }
+
+    fn keeps(self, entry: &Entry) -> bool {
+        match self {
+            ListFilter::Due => entry.due,
+            ListFilter::PreMolt => matches!(
+                entry.facts.molt_stage,
+                Some(MoltStage::PreMolt) | Some(MoltStage::Molting)
+            ),
+            ListFilter::Health => matches!(
+                entry.facts.health_status,
+                Some(HealthStatus::Monitor) | Some(HealthStatus::Critical)
+            ),
+        }
+    }
+}
+
+/// One page of the list and how it is sorted and filtered.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct ListView {
+    pub(crate) purpose: ListPurpose,
+    pub(crate) sort: ListSort,
+    pub(crate) filter: Option<ListFilter>,
+    pub(crate) page: u32,
+}
+
+impl ListView {
+    pub(crate) fn new(purpose: ListPurpose) -> Self {
+        Self {
+            purpose,
+            sort: ListSort::Name,
+            filter: None,
+            page: 0,
+        }
+    }
+
+    /// Rebuilds a view from callback data, where a filter of 0 shows everything.
+    pub(crate) fn decode(purpose: u8, sort: u8, filter: u8, page: u32) -> BotResult<Self> {
+        Ok(Self {
+            purpose: ListPurpose::from_id(purpose)?,
+            sort: ListSort::from_id(sort)?,
+            filter: match filter {
+                0 => None,
+                id => Some(ListFilter::from_id(id)?),
+            },
+            page,
+        })
+    }
+
+    fn callback(self) -> BotCallback {
+        BotCallback::TarantulaList(
+            self.purpose as u8,
+            self.sort as u8,
+            self.filter.map_or(0, |f| f as u8),
+            self.page,
+        )
+    }
+}
+
+/// A list item with the facts behind it, for filtering and searching.
+pub(crate) struct Entry<'a> {
+    pub(crate) item: TarantulaListItem,
+    pub(crate) facts: &'a TarantulaFacts,
+    pub(crate) due: bool,
+}
+
+/// The collection as the list shows it: groups folded into one entry when
+/// browsing, every member on its own when picking one to record for.
+fn entries(facts: &[TarantulaFacts], fold_groups: bool, now: NaiveDateTime) -> Vec<Entry<'_>> {
+    let mut items = schedule::collection_status(facts, now);
+    if fold_groups {
+        items = collapse_groups(items);
+    }
+    let by_id: HashMap<i64, &TarantulaFacts> = facts.iter().map(|f| (f.id, f)).collect();
+    items
+        .into_iter()
+        .filter_map(|item| {
+            let facts = *by_id.get(&item.id)?;
+            Some(Entry {
+                due: schedule::feeding_plan(facts, now).is_due(),
+                item,
+                facts,
+            })
+        })
+        .collect()
+}
+
+/// How well an entry matches a lowercase search: 2 for the exact name, alias
+/// or enclosure, 1 for part of one of those or the species, 0 for no match.
+fn search_rank(entry: &Entry, query: &str) -> u8 {
+    let item = &entry.item;
+    let enclosure = item.enclosure_number.as_deref().unwrap_or_default();
+    let group = entry.facts.group_name.as_deref().unwrap_or_default();
+    let aliases = entry.facts.aliases.iter().map(String::as_str);
+    if [entry.facts.name.as_str(), group, enclosure]
+        .into_iter()
+        .chain(aliases.clone())
+        .any(|s| s.to_lowercase() == query)
+    {
+        return 2;
+    }
+    let fields = [
+        item.name.as_str(),
+        item.species_name.as_str(),
+        entry.facts.scientific_name.as_str(),
+        enclosure,
+    ];
+    u8::from(
+        fields
+            .into_iter()
+            .chain(aliases)
+            .any(|s| s.to_lowercase().contains(query)),
+    )
+}
+
+/// Entries matching a search with their [`search_rank`], exact matches first
+/// and otherwise by name. An empty search matches everything.
+pub(crate) fn search<'a>(
+    facts: &'a [TarantulaFacts],
+    fold_groups: bool,
+    query: &str,
+    now: NaiveDateTime,
+) -> Vec<(u8, Entry<'a>)> {
+    let query = query.trim().to_lowercase();
+    let mut found: Vec<(u8, Entry)> = entries(facts, fold_groups, now)
+        .into_iter()
+        .map(|e| match query.as_str() {
+            "" => (1, e),
+            query => (search_rank(&e, query), e),
+        })
+        .filter(|(rank, _)| *rank > 0)
+        .collect();
+    found.sort_by(|(a, _), (b, _)| b.cmp(a));
+    found
+}
+
+fn entry_details(item: &TarantulaListItem) -> String {
+    let feeding_display = item
+        .days_since_feeding
+        .map_or("Unknown".to_string(), |days| {
+            if days < 1.0 {
+                "Today".to_string()
+            } else {
+                format!("{:.1} days", days)
+            }
+        });
+    let enclosure = item
+        .enclosure_number
+        .as_ref()
+        .map(|e| format!("\n▫️ Enclosure: {}", e))
+        .unwrap_or_default();
+    format!(
+        "*{}* ({})\n▫️ Status: {}\n▫️ Last fed: {}{}",
+        item.name, item.species_name, item.current_status, feeding_display, enclosure
+    )
+}
+
+fn item_rows(purpose: ListPurpose, items: &[&TarantulaListItem]) -> Keyboard {
+    items
+        .chunks(2)
+        .map(|chunk| chunk.iter().map(|t| purpose.button(t)).collect())
+        .collect()
+}
+
+impl App {
+    pub(crate) async fn tarantula_list(&self, user_id: u64, view: ListView) -> BotResult<Screen> {
+        let facts = self.db.get_schedule_facts(user_id).await?;
+        if facts.is_empty() {
+            let message = match view.purpose {
+                ListPurpose::Browse => "No tarantulas found in the database.".to_string(),
+                purpose => format!("{}\n\nNo tarantulas yet.", purpose.title()),
+            };
+            return Ok(Screen::new(message, back_to_menu_keyboard()));
+        }
+
+        let fold_groups = view.purpose == ListPurpose::Browse;
+        let mut entries = entries(&facts, fold_groups, self.now(user_id).await?);
+        if let Some(filter) = view.filter {
+            entries.retain(|e| filter.keeps(e));
+        }
+        entries.sort_by(|a, b| view.sort.compare(&a.item, &b.item));
+        let pages = entries.len().div_ceil(PAGE_SIZE).max(1) as u32;
+        let page = view.page.min(pages - 1);
+        let shown: Vec<&TarantulaListItem> = entries
+            .iter()
+            .skip(page as usize * PAGE_SIZE)
+            .take(PAGE_SIZE)
+            .map(|e| &e.item)
+            .collect();
+
+        let mut message = format!(
+            "{}\n_{} shown, sorted by {}",
+            view.purpose.title(),
+            entries.len(),
+            view.sort.label().to_lowercase()
+        );
+        if let Some(filter) = view.filter {
+            message.push_str(&format!(" · {}", filter.label().to_lowercase()));
+        }
+        message.push_str("_\n\n");
+        if shown.is_empty() {
+            message.push_str("No tarantulas match this filter.");
+        } else if view.purpose == ListPurpose::Browse {
+            let details: Vec<String> = shown.iter().map(|t| entry_details(t)).collect();
+            message.push_str(&details.join("\n\n"));
+        } else {
+            message.push_str("Select a tarantula:");
+        }
+        if pages > 1 {
+            message.push_str(&format!("\n\nPage {} of {}", page + 1, pages));
+        }
+
+        let view = ListView { page, ..view };
+        let option = |label: &str, selected: bool, next: ListView| {
+            let label = if selected {
+                format!("✓ {}", label)
+            } else {
+                label.to_string()
+            };
+            Button::callback(label, ListView { page: 0, ..next }.callback())
+        };
+        let mut keyboard = item_rows(view.purpose, &shown);
+        keyboard.extend(paging(page, page + 1 < pages, |page| {
+            ListView { page, ..view }.callback()
+        }));
+        keyboard.push(
+            ListSort::ALL
+                .into_iter()
+                .map(|sort| option(sort.label(), sort == view.sort, ListView { sort, ..view }))
+                .collect(),
+        );
+        let mut filters = vec![option(
+            "All",
+            view.filter.is_none(),
+            ListView {
+                filter: None,
+                ..view
+            },
+        )];
+        filters.extend(ListFilter::ALL.into_iter().map(|filter| {
+            option(
+                filter.label(),
+                view.filter == Some(filter),
+                ListView {
+                    filter: Some(filter),
+                    ..view
+                },
+            )
+        }));
+        keyboard.push(filters);
+        keyboard.push(vec![
+            Button::callback("🔍 Search", BotCallback::ListSearch(view.purpose as u8)),
+            Button::callback("« Back to Menu", BotCallback::MainMenu),
+        ]);
+        Ok(Screen::new(message, keyboard))
+    }
+
+    pub(crate) fn search_prompt(&self, purpose: ListPurpose) -> Outcome {
+        Outcome::send(Screen::text(
+            "🔍 Send a name, species or enclosure to look for:",
+        ))
+        .enter(DialogueState::SearchTarantulas { purpose })
+    }
+
+    /// Looks for tarantulas by name, species or enclosure. A single match, or
+    /// the one with exactly that name, is opened straight away.
+    pub(crate) async fn search_tarantulas(
+        &self,
+        user_id: u64,
+        purpose: ListPurpose,
+        query: &str,
+    ) -> BotResult<Outcome> {
+        let query = query.trim().to_lowercase();
+        let facts = self.db.get_schedule_facts(user_id).await?;
+        let fold_groups = purpose == ListPurpose::Browse;
+        let found = search(&facts, fold_groups, &query, self.now(user_id).await?);
+        let exact = found.iter().filter(|(rank, _)| *rank == 2).count();
+        if found.len() == 1 || exact == 1 {
+            let item = &found[0].1.item;
+            return Ok(Outcome::default()
+                .exit()
+                .then(self.open(user_id, purpose, item).await?));
+        }
+
+        let back = vec![
+            Button::callback("🔍 Search Again", BotCallback::ListSearch(purpose as u8)),
+            Button::callback("« Back to List", ListView::new(purpose).callback()),
+        ];
+        if found.is_empty() {
+            return Ok(Outcome::send(Screen::new(
+                format!("No tarantula matches \"{}\".", query),
+                vec![back],
+            ))
+            .exit());
+        }
+
+        let mut message = format!("🔍 {} tarantulas match \"{}\":", found.len(), query);
+        if found.len() > MAX_MATCHES {
+            message.push_str(&format!(
+                "\n\nShowing the first {}. Search for more of the name to narrow it down.",
+                MAX_MATCHES
+            ));
+        }
+        let shown: Vec<&TarantulaListItem> = found
+            .iter()
+            .take(MAX_MATCHES)
+            .map(|(_, e)| &e.item)
+            .collect();
+        let mut keyboard = item_rows(purpose, &shown);
+        keyboard.push(back);
+        Ok(Outcome::send(Screen::new(message, keyboard)).exit())
+    }
+
+    /// What tapping the tarantula in the list would have shown.
+    async fn open(
+        &self,
+        user_id: u64,
+        purpose: ListPurpose,
+        item: &TarantulaListItem,
+    ) -> BotResult<Outcome> {
+        Ok(match (purpose, item.group_id) {
+            (ListPurpose::Browse, Some(group_id)) => {
+                Outcome::send(self.group_details(group_id, user_id).await?)
+            }
+            (ListPurpose::Browse, None) => self.profile(user_id, item.id).await?,
+            (ListPurpose::Feed, _) => Outcome::send(self.feed_command(item.id, user_id).await?),
+            (ListPurpose::HealthCheck, _) => {
+                Outcome::send(self.health_check_command(item.id, user_id, None).await?)
+            }
+            (ListPurpose::Molt, _) => self.molt_prompt(item.id, None),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(
+        name: &str,
+        species: &str,
+        enclosure: Option<&str>,
+        days: Option<f64>,
+    ) -> TarantulaListItem {
+        TarantulaListItem {
+            id: 1,
+            name: name.to_string(),
+            species_name: species.to_string(),
+            enclosure_number: enclosure.map(str::to_string),
+            days_since_feeding: days,
+            current_status: "Normal".to_string(),
+            group_id: None,
+            group_name: None,
+        }
+    }
+
+    fn sorted(sort: ListSort) -> Vec<String> {
+        let mut items = vec![
+            item("Boris", "Chilean Rose", Some("10"), Some(3.0)),
+            item("Anna", "Mexican Red Knee", None, Some(12.0)),
+            item("Cleo", "Chilean Rose", Some("9"), None),
+            item("Dora", "Brazilian Black", Some("B2"), Some(3.0)),
+        ];
+        items.sort_by(|a, b| sort.compare(a, b));
+        items.into_iter().map(|t| t.name).collect()
+    }
+
+    #[test]
+    fn sorting_puts_what_needs_attention_first() {
+        assert_eq!(sorted(ListSort::LastFed), ["Cleo", "Anna", "Boris", "Dora"]);
+        assert_eq!(
+            sorted(ListSort::Enclosure),
+            ["Cleo", "Boris", "Dora", "Anna"]
+        );
+        assert_eq!(sorted(ListSort::Species), ["Dora", "Boris", "Cleo", "Anna"]);
+        assert_eq!(sorted(ListSort::Name), ["Anna", "Boris", "Cleo", "Dora"]);
+    }
+
+    #[test]
+    fn list_views_come_back_from_callback_data() {
+        let view = ListView {
+            filter: Some(ListFilter::PreMolt),
+            page: 3,
+            ..ListView::new(ListPurpose::Molt)
+        };
+        let BotCallback::TarantulaList(purpose, sort, filter, page) = view.callback() else {
+            panic!("not a list callback");
+        };
+        assert_eq!(ListView::decode(purpose, sort, filter, page).unwrap(), view);
+        assert!(ListView::decode(9, 1, 0, 0).is_err());
+    }
+}
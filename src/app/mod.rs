@@ -0,0 +1,57 @@
+//! The bot's menus and conversations, independent of any chat transport.
+//! Flows read and write through [`TarantulaOperations`] and describe their
+//! result as an [`Outcome`]; the Telegram bot and the terminal REPL decide how
+//! to show it.
+
+mod audit;
+mod breeding;
+pub mod callbacks;
+pub(crate) mod commands;
+pub mod dialogue;
+mod export;
+mod flows;
+mod groups;
+mod history;
+mod import;
+mod inline;
+mod keyboards;
+mod lineage;
+mod listing;
+mod overrides;
+mod profile;
+mod quicklog;
+mod timezone;
+mod when;
+pub mod screen;
+
+use crate::app::callbacks::{BotCallback, CallbackCommand};
+use crate::app::dialogue::DialogueState;
+use crate::app::screen::Outcome;
+use crate::db::db::TarantulaOperations;
+use crate::BotResult;
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub struct App {
+    pub(crate) db: Arc<dyn TarantulaOperations + Send + Sync>,
+}
+
+/// Who is talking to the bot and where their conversation stands.
+#[derive(Debug, Clone)]
+pub struct Session {
+    pub user_id: u64,
+    pub dialogue: Option<DialogueState>,
+}
+
+impl App {
+    pub fn new(db: Arc<dyn TarantulaOperations + Send + Sync>) -> Self {
+        Self { db }
+    }
+
+    /// Handles a button press carrying `data`, the wire form of a [`BotCallback`].
+    pub async fn tap(&self, session: &Session, data: &str) -> BotResult<Outcome> {
+        log::debug!("Received callback query: {:?}", data);
+        let callback = data.parse::<BotCallback>()?;
+        callback.callback(self, session).await
+    }
+}
@@ -0,0 +1,100 @@
+use crate::app::callbacks::BotCallback;
+use crate::app::screen::{Button, Screen};
+use crate::app::App;
+use crate::db::db::SetFeedingOverrideParams;
+use crate::error::BotError;
+use crate::models::enums::CricketSize;
+use crate::BotResult;
+use chrono::NaiveDate;
+use std::str::FromStr;
+
+pub(crate) const PREY_SIZES: [CricketSize; 5] = [
+    CricketSize::Pinhead,
+    CricketSize::Small,
+    CricketSize::Medium,
+    CricketSize::Large,
+    CricketSize::Adult,
+];
+
+impl App {
+    pub(crate) async fn override_schedule(
+        &self,
+        tarantula_id: i64,
+        user_id: u64,
+    ) -> BotResult<Screen> {
+        let tarantula = self.db.get_tarantula_by_id(user_id, tarantula_id).await?;
+        let frequencies = self.db.get_feeding_frequencies().await?;
+
+        let mut message = format!(
+            "⚙️ *Custom schedule for {}*\n\n\
+            Send:\n/feedoverride {} frequency_id prey_count prey_size_id expires_on\n\
+            Use - for anything that should follow the species schedule, \
+            and - as expiry to keep the override until cleared.\n\n\
+            *Frequencies*\n",
+            tarantula.name, tarantula_id
+        );
+        for f in &frequencies {
+            message.push_str(&format!("{} - {}\n", f.id, f.frequency_name));
+        }
+        message.push_str("\n*Prey sizes*\n");
+        for size in PREY_SIZES {
+            message.push_str(&format!("{} - {}\n", size as i64, size.to_db_name()));
+        }
+        message.push_str(&format!(
+            "\nExample: /feedoverride {} 10 1 - 2026-12-31",
+            tarantula_id
+        ));
+
+        let keyboard = vec![vec![Button::callback(
+            "« Back",
+            BotCallback::ViewFeedingSchedule(tarantula_id),
+        )]];
+
+        Ok(Screen::new(message, keyboard))
+    }
+
+    pub(crate) async fn set_feeding_override(
+        &self,
+        user_id: u64,
+        params: SetFeedingOverrideParams,
+    ) -> BotResult<Screen> {
+        let tarantula_id = params.tarantula_id;
+        self.db.set_feeding_override(user_id, params).await?;
+        Ok(Screen::new(
+            "✅ Custom feeding schedule saved",
+            vec![vec![Button::callback(
+                "📅 View Schedule",
+                BotCallback::ViewFeedingSchedule(tarantula_id),
+            )]],
+        ))
+    }
+
+    pub(crate) async fn clear_override(
+        &self,
+        tarantula_id: i64,
+        user_id: u64,
+    ) -> BotResult<Screen> {
+        self.db
+            .clear_feeding_override(user_id, tarantula_id)
+            .await?;
+        self.view_feeding_schedule(tarantula_id, user_id).await
+    }
+}
+
+/// Parses a /feedoverride argument where `-` means "not overridden".
+pub(crate) fn parse_override_field<T: FromStr>(value: &str) -> BotResult<Option<T>> {
+    match value.trim() {
+        "" | "-" => Ok(None),
+        v => v
+            .parse::<T>()
+            .map(Some)
+            .map_err(|_| BotError::ValidationError(format!("Invalid value '{}'", v))),
+    }
+}
+
+pub(crate) fn parse_override_expiry(value: &str) -> BotResult<Option<NaiveDate>> {
+    match value.trim() {
+        "" | "-" => Ok(None),
+        v => Ok(Some(NaiveDate::parse_from_str(v, "%Y-%m-%d")?)),
+    }
+}
@@ -0,0 +1,399 @@
+//! A tarantula's profile, the hub for everything done for one animal, and the
+//! edits and photos reached from it. With photos on file the profile is shown
+//! as the newest one, captioned with the details.
+
+use crate::app::callbacks::BotCallback;
+use crate::app::dialogue::DialogueState;
+use crate::app::screen::{Button, Keyboard, Outcome, Photo, Screen};
+use crate::app::App;
+use crate::error::BotError;
+use crate::models::tarantula::{split_aliases, TarantulaField};
+use crate::schedule;
+use crate::BotResult;
+
+/// Telegram refuses longer photo captions.
+const MAX_CAPTION_CHARS: usize = 1024;
+const EDITABLE_FIELDS: [TarantulaField; 4] = [
+    TarantulaField::Name,
+    TarantulaField::Enclosure,
+    TarantulaField::Notes,
+    TarantulaField::Aliases,
+];
+
+impl App {
+    pub(crate) async fn profile(&self, user_id: u64, tarantula_id: i64) -> BotResult<Outcome> {
+        let facts = self
+            .db
+            .get_schedule_facts(user_id)
+            .await?
+            .into_iter()
+            .find(|f| f.id == tarantula_id)
+            .ok_or_else(|| {
+                BotError::NotFound(format!("Tarantula with id {} not found", tarantula_id))
+            })?;
+        let tarantula = self.db.get_tarantula_by_id(user_id, tarantula_id).await?;
+        let photos = self.db.get_photos(user_id, tarantula_id).await?;
+        let plan = schedule::feeding_plan(&facts, self.now(user_id).await?);
+
+        let mut message = format!("🕷 *{}*\n_{}_", facts.name, facts.scientific_name);
+        if facts.species_name != facts.scientific_name {
+            message.push_str(&format!(" ({})", facts.species_name));
+        }
+        message.push_str("\n\n");
+
+        let measured = if facts.last_molt_length_cm.is_some() {
+            "measured at the last molt"
+        } else {
+            "estimated"
+        };
+        message.push_str(&format!(
+            "📏 *Size:* {:.1} cm, {}\n",
+            plan.size_cm, measured
+        ));
+        message.push_str(&format!(
+            "🐾 *Molt Stage:* {}{}\n",
+            facts.molt_stage.map_or("Unknown", |s| s.to_db_name()),
+            facts
+                .last_molt_date
+                .map_or(String::new(), |d| format!(", last molt {}", d))
+        ));
+        message.push_str(&format!(
+            "🍽 *Last Fed:* {}\n",
+            match (facts.last_fed, plan.days_since_feeding) {
+                (Some(at), Some(days)) => format!("{} ({:.1} days ago)", at.date(), days),
+                _ => "Never".to_string(),
+            }
+        ));
+        message.push_str(&format!(
+            "📅 *Next Feeding:* {}\n",
+            plan.next_due
+                .map_or(plan.status(), |d| format!("{} ({})", d, plan.status()))
+        ));
+        message.push_str(&format!(
+            "🏥 *Health:* {}{}\n",
+            facts
+                .health_status
+                .map_or("Not checked", |s| s.to_db_name()),
+            facts
+                .last_health_check_date
+                .map_or(String::new(), |d| format!(", checked {}", d))
+        ));
+        if let Some(enclosure) = &facts.enclosure_number {
+            message.push_str(&format!("🏠 *Enclosure:* {}\n", enclosure));
+        }
+        if let Some(group) = &facts.group_name {
+            message.push_str(&format!("🧺 *Group:* {}\n", group));
+        }
+
+        if let Some(band) = plan.band.as_ref() {
+            message.push_str(&format!(
+                "\n*Care ({})*\n• Prey: {} ({})\n• Feeding: {}\n",
+                band.size_category, band.prey_size, band.prey_type, band.frequency_name
+            ));
+            if let Some(notes) = &band.notes {
+                message.push_str(&format!("ℹ️ {}\n", notes));
+            }
+        }
+        if let Some(notes) = &tarantula.notes {
+            message.push_str(&format!("\n📝 {}\n", notes));
+        }
+
+        let keyboard = vec![
+            vec![
+                Button::callback("🍽 Feed", BotCallback::FeedTarantula(tarantula_id)),
+                Button::callback("🏥 Health Check", BotCallback::HealthCheck(tarantula_id)),
+            ],
+            vec![
+                Button::callback("🐾 Record Molt", BotCallback::MoltSimple(tarantula_id)),
+                Button::callback("✏️ Edit", BotCallback::EditTarantula(tarantula_id)),
+            ],
+            vec![
+                Button::callback("🕰 History", BotCallback::Timeline(tarantula_id, 0)),
+                Button::callback(
+                    format!("📷 Photos ({})", photos.len()),
+                    BotCallback::TarantulaPhotos(tarantula_id, 0),
+                ),
+            ],
+            vec![
+                Button::callback(
+                    "📋 Feeding Schedule",
+                    BotCallback::ViewFeedingSchedule(tarantula_id),
+                ),
+                Button::callback("🧬 Pedigree", BotCallback::Pedigree(tarantula_id)),
+            ],
+            vec![Button::callback(
+                "« Back to List",
+                BotCallback::ListTarantulas,
+            )],
+        ];
+
+        Ok(match photos.first() {
+            Some(photo) => Outcome::photo(Photo {
+                file_id: photo.file_id.clone(),
+                caption: caption(message),
+                buttons: keyboard,
+            }),
+            None => Outcome::replace(Screen::new(message, keyboard)),
+        })
+    }
+
+    pub(crate) async fn edit_tarantula_menu(
+        &self,
+        user_id: u64,
+        tarantula_id: i64,
+    ) -> BotResult<Screen> {
+        let tarantula = self.db.get_tarantula_by_id(user_id, tarantula_id).await?;
+        let message = format!(
+            "✏️ *Edit {}*\n\n\
+            Name: {}\n\
+            Enclosure: {}\n\
+            Notes: {}\n\
+            Aliases: {}\n\n\
+            What would you like to change?",
+            tarantula.name,
+            tarantula.name,
+            tarantula.enclosure_number.as_deref().unwrap_or("-"),
+            tarantula.notes.as_deref().unwrap_or("-"),
+            tarantula.aliases.as_deref().unwrap_or("-"),
+        );
+        let keyboard = vec![
+            EDITABLE_FIELDS
+                .iter()
+                .map(|&field| {
+                    Button::callback(
+                        field.label(),
+                        BotCallback::EditField(tarantula_id, field as u8),
+                    )
+                })
+                .collect(),
+            vec![Button::callback(
+                "« Back to Profile",
+                BotCallback::TarantulaProfile(tarantula_id),
+            )],
+        ];
+        Ok(Screen::new(message, keyboard))
+    }
+
+    pub(crate) async fn edit_field_prompt(
+        &self,
+        user_id: u64,
+        tarantula_id: i64,
+        field: u8,
+    ) -> BotResult<Outcome> {
+        let field = TarantulaField::from_id(field)
+            .ok_or_else(|| BotError::ValidationError(format!("Unknown field {}", field)))?;
+        let tarantula = self.db.get_tarantula_by_id(user_id, tarantula_id).await?;
+        Ok(
+            Outcome::send(Screen::text(field_prompt(field, &tarantula.name))).enter(
+                DialogueState::EditTarantula {
+                    tarantula_id,
+                    field,
+                },
+            ),
+        )
+    }
+
+    pub(crate) async fn edit_tarantula(
+        &self,
+        user_id: u64,
+        tarantula_id: i64,
+        field: TarantulaField,
+        value: Option<String>,
+    ) -> BotResult<Outcome> {
+        self.db
+            .update_tarantula(user_id, tarantula_id, field, value)
+            .await?;
+        Ok(
+            Outcome::send(Screen::text(format!("✅ {} updated", field.label())))
+                .then(self.profile(user_id, tarantula_id).await?),
+        )
+    }
+
+    /// One photo at a time, newest first; `index` counts back from the newest.
+    pub(crate) async fn photos(
+        &self,
+        user_id: u64,
+        tarantula_id: i64,
+        index: u32,
+    ) -> BotResult<Outcome> {
+        let tarantula = self.db.get_tarantula_by_id(user_id, tarantula_id).await?;
+        let photos = self.db.get_photos(user_id, tarantula_id).await?;
+        let back = vec![Button::callback(
+            "« Back to Profile",
+            BotCallback::TarantulaProfile(tarantula_id),
+        )];
+
+        let Some(last) = photos.len().checked_sub(1) else {
+            return Ok(Outcome::replace(Screen::new(
+                format!(
+                    "📷 No photos of {} yet.\n\nTap Add Photo and send one, a caption on it is kept too.",
+                    tarantula.name
+                ),
+                vec![
+                    vec![Button::callback(
+                        "➕ Add Photo",
+                        BotCallback::AddPhoto(tarantula_id),
+                    )],
+                    back,
+                ],
+            )));
+        };
+        let index = (index as usize).min(last);
+        let photo = &photos[index];
+
+        let mut message = format!(
+            "📷 *{}*, {} of {}\n{}",
+            tarantula.name,
+            index + 1,
+            photos.len(),
+            photo.taken_at.get(..16).unwrap_or(&photo.taken_at)
+        );
+        if let Some(text) = &photo.caption {
+            message.push_str(&format!("\n\n{}", text));
+        }
+
+        let mut keyboard: Keyboard = Vec::new();
+        let mut paging = Vec::new();
+        if index > 0 {
+            paging.push(Button::callback(
+                "« Newer",
+                BotCallback::TarantulaPhotos(tarantula_id, index as u32 - 1),
+            ));
+        }
+        if index < last {
+            paging.push(Button::callback(
+                "Older »",
+                BotCallback::TarantulaPhotos(tarantula_id, index as u32 + 1),
+            ));
+        }
+        if !paging.is_empty() {
+            keyboard.push(paging);
+        }
+        keyboard.push(vec![
+            Button::callback("➕ Add Photo", BotCallback::AddPhoto(tarantula_id)),
+            Button::callback("🗑 Delete", BotCallback::DeletePhoto(tarantula_id, photo.id)),
+        ]);
+        keyboard.push(back);
+
+        Ok(Outcome::photo(Photo {
+            file_id: photo.file_id.clone(),
+            caption: caption(message),
+            buttons: keyboard,
+        }))
+    }
+
+    pub(crate) async fn add_photo_prompt(
+        &self,
+        user_id: u64,
+        tarantula_id: i64,
+    ) -> BotResult<Outcome> {
+        let tarantula = self.db.get_tarantula_by_id(user_id, tarantula_id).await?;
+        Ok(Outcome::send(Screen::text(format!(
+            "Send a photo of {}. A caption on it is kept with the photo.",
+            tarantula.name
+        )))
+        .enter(DialogueState::AddPhoto { tarantula_id }))
+    }
+
+    pub(crate) async fn save_photo(
+        &self,
+        user_id: u64,
+        tarantula_id: i64,
+        file_id: &str,
+        caption: Option<&str>,
+    ) -> BotResult<Outcome> {
+        let caption = caption
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+            .map(str::to_string);
+        self.db
+            .add_photo(user_id, tarantula_id, file_id, caption)
+            .await?;
+        Ok(Outcome::send(Screen::text("✅ Photo saved"))
+            .then(self.photos(user_id, tarantula_id, 0).await?))
+    }
+
+    pub(crate) async fn delete_photo(
+        &self,
+        user_id: u64,
+        tarantula_id: i64,
+        photo_id: i64,
+    ) -> BotResult<Outcome> {
+        self.db.delete_photo(user_id, photo_id).await?;
+        self.photos(user_id, tarantula_id, 0).await
+    }
+}
+
+fn field_prompt(field: TarantulaField, name: &str) -> String {
+    match field {
+        TarantulaField::Name => format!("Please send the new name for {}:", name),
+        TarantulaField::Enclosure => format!(
+            "Please send the enclosure number for {}, or - to clear it:",
+            name
+        ),
+        TarantulaField::Notes => format!("Please send new notes for {}, or - to clear them:", name),
+        TarantulaField::Aliases => format!(
+            "Please send other names for {}, separated by commas, or - to clear them:",
+            name
+        ),
+    }
+}
+
+/// The value to store for a reply to [`field_prompt`], or `None` to ask again.
+pub(crate) fn parse_field_value(field: TarantulaField, text: &str) -> Option<Option<String>> {
+    match text.trim() {
+        "" => None,
+        "-" if field == TarantulaField::Name => None,
+        "-" => Some(None),
+        value if field == TarantulaField::Aliases => {
+            let aliases = split_aliases(Some(value));
+            (!aliases.is_empty()).then(|| Some(aliases.join(", ")))
+        }
+        value => Some(Some(value.to_string())),
+    }
+}
+
+fn caption(text: String) -> String {
+    if text.chars().count() <= MAX_CAPTION_CHARS {
+        return text;
+    }
+    let mut cut: String = text.chars().take(MAX_CAPTION_CHARS - 1).collect();
+    cut.push('…');
+    cut
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn field_values_clear_with_a_dash_except_names() {
+        assert_eq!(
+            parse_field_value(TarantulaField::Notes, " Calm "),
+            Some(Some("Calm".to_string()))
+        );
+        assert_eq!(
+            parse_field_value(TarantulaField::Enclosure, "-"),
+            Some(None)
+        );
+        assert_eq!(parse_field_value(TarantulaField::Name, "-"), None);
+        assert_eq!(parse_field_value(TarantulaField::Notes, "  "), None);
+    }
+
+    #[test]
+    fn aliases_are_tidied_into_one_list() {
+        assert_eq!(
+            parse_field_value(TarantulaField::Aliases, " Rosa ,, Pinky,"),
+            Some(Some("Rosa, Pinky".to_string()))
+        );
+        assert_eq!(parse_field_value(TarantulaField::Aliases, " , "), None);
+    }
+
+    #[test]
+    fn long_captions_are_cut_to_what_telegram_takes() {
+        let cut = caption("🕷".repeat(2000));
+        assert_eq!(cut.chars().count(), MAX_CAPTION_CHARS);
+        assert!(cut.ends_with('…'));
+        assert_eq!(caption("short".to_string()), "short");
+    }
+}
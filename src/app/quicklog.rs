@@ -0,0 +1,640 @@
+//! Logging from plain messages such as "fed rosie 2 small", "rosie refused",
+//! "molt boris 6.5cm" or "colony A +200", for keepers feeding a whole rack at
+//! once. Tarantulas are found by name or alias; a message that could mean
+//! more than one asks which, and everything written comes with an undo
+//! button. Messages that don't read as a log get no answer, so chatter in a
+//! shared chat is left alone.
+
+use crate::app::callbacks::BotCallback::MainMenu;
+use crate::app::callbacks::{BotCallback, CallbackCommand};
+use crate::app::keyboards::{back_to_menu_keyboard, undo_keyboard};
+use crate::app::overrides::PREY_SIZES;
+use crate::app::screen::{Button, Keyboard, Outcome, Screen};
+use crate::app::when::{strip_when, to_stamp, when_line};
+use crate::app::{App, Session};
+use crate::error::BotError;
+use crate::models::cricket::ColonyStatus;
+use crate::models::enums::{CricketSize, FeedingStatus};
+use crate::models::feeding::FeedingEvent;
+use crate::schedule::{self, TarantulaFacts};
+use crate::BotResult;
+use chrono::NaiveDateTime;
+
+/// How many tarantulas or colonies a "which one" question offers.
+const MAX_CHOICES: usize = 6;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum QuickLog {
+    Fed {
+        who: String,
+        count: Option<i32>,
+        size: Option<CricketSize>,
+    },
+    Refused {
+        who: String,
+    },
+    Molted {
+        who: String,
+        length_cm: Option<f32>,
+    },
+    Colony {
+        name: String,
+        change: ColonyChange,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum ColonyChange {
+    By(i32),
+    To(i32),
+}
+
+impl QuickLog {
+    fn who(&self) -> &str {
+        match self {
+            QuickLog::Fed { who, .. }
+            | QuickLog::Refused { who }
+            | QuickLog::Molted { who, .. } => who,
+            QuickLog::Colony { name, .. } => name,
+        }
+    }
+
+    /// The button logging this for one tarantula at `at`; 0 leaves a detail
+    /// for the handler to work out or ask.
+    fn callback(&self, tarantula_id: i64, at: Option<NaiveDateTime>) -> BotCallback {
+        match self {
+            QuickLog::Fed { count, size, .. } => BotCallback::QuickFeed(
+                tarantula_id,
+                0,
+                count.unwrap_or(0),
+                size.map_or(0, |s| s as u8),
+                to_stamp(at),
+            ),
+            QuickLog::Refused { .. } => BotCallback::QuickRefused(tarantula_id, to_stamp(at)),
+            QuickLog::Molted { length_cm, .. } => BotCallback::QuickMolt(
+                tarantula_id,
+                length_cm.map_or(0, |cm| (cm * 10.0).round() as i32),
+                to_stamp(at),
+            ),
+            QuickLog::Colony { .. } => unreachable!("colony logs name a colony"),
+        }
+    }
+}
+
+impl ColonyChange {
+    fn adjustment(self, current: i32) -> i32 {
+        match self {
+            ColonyChange::By(n) => n,
+            ColonyChange::To(n) => n - current,
+        }
+    }
+}
+
+/// Reads a quick log, or `None` when the message is something else.
+pub(crate) fn parse_quick_log(text: &str) -> Option<QuickLog> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let (first, rest) = words.split_first()?;
+    let log = match first.to_lowercase().as_str() {
+        "colony" => {
+            let (last, name) = rest.split_last()?;
+            QuickLog::Colony {
+                name: name.join(" "),
+                change: parse_colony_change(last)?,
+            }
+        }
+        "fed" | "feed" => {
+            let (who, count, size) = strip_feeding_details(rest);
+            QuickLog::Fed {
+                who: who.join(" "),
+                count,
+                size,
+            }
+        }
+        "refused" | "rejected" => QuickLog::Refused {
+            who: rest.join(" "),
+        },
+        "molt" | "molted" | "moult" | "moulted" => {
+            let (who, length_cm) = strip_length(rest);
+            QuickLog::Molted {
+                who: who.join(" "),
+                length_cm,
+            }
+        }
+        _ => {
+            let verb = words.iter().position(|w| {
+                matches!(
+                    w.to_lowercase().as_str(),
+                    "ate" | "refused" | "rejected" | "molted" | "moulted"
+                )
+            })?;
+            let (who, tail) = (words[..verb].join(" "), &words[verb + 1..]);
+            match words[verb].to_lowercase().as_str() {
+                "ate" => match strip_feeding_details(tail) {
+                    ([], count, size) => QuickLog::Fed { who, count, size },
+                    _ => return None,
+                },
+                "refused" | "rejected" if tail.is_empty() => QuickLog::Refused { who },
+                "molted" | "moulted" => match strip_length(tail) {
+                    ([], length_cm) => QuickLog::Molted { who, length_cm },
+                    _ => return None,
+                },
+                _ => return None,
+            }
+        }
+    };
+    (!log.who().is_empty()).then_some(log)
+}
+
+/// "+200" and "-50" change a colony by that much, "200" sets it.
+fn parse_colony_change(word: &str) -> Option<ColonyChange> {
+    match word.strip_prefix('+') {
+        Some(n) => n.parse().ok().map(ColonyChange::By),
+        None if word.starts_with('-') => word.parse().ok().map(ColonyChange::By),
+        None => word.parse().ok().filter(|n| *n >= 0).map(ColonyChange::To),
+    }
+}
+
+/// Takes "2 small crickets", or any end of it, off the end of `words`.
+fn strip_feeding_details<'a>(
+    mut words: &'a [&'a str],
+) -> (&'a [&'a str], Option<i32>, Option<CricketSize>) {
+    if let Some((last, rest)) = words.split_last() {
+        if matches!(last.to_lowercase().as_str(), "cricket" | "crickets") {
+            words = rest;
+        }
+    }
+    let mut size = None;
+    if let Some((last, rest)) = words.split_last() {
+        size = parse_size(last);
+        if size.is_some() {
+            words = rest;
+        }
+    }
+    let mut count = None;
+    if let Some((last, rest)) = words.split_last() {
+        count = last.parse().ok().filter(|n| *n > 0);
+        if count.is_some() {
+            words = rest;
+        }
+    }
+    (words, count, size)
+}
+
+fn parse_size(word: &str) -> Option<CricketSize> {
+    let word = word.trim_end_matches('s');
+    PREY_SIZES
+        .into_iter()
+        .find(|s| s.to_db_name().eq_ignore_ascii_case(word))
+}
+
+/// Takes a length like "6.5cm" or "6.5 cm" off the end of `words`.
+fn strip_length<'a>(words: &'a [&'a str]) -> (&'a [&'a str], Option<f32>) {
+    let cm = |word: &str| {
+        word.trim_end_matches("cm")
+            .parse::<f32>()
+            .ok()
+            .filter(|cm| *cm > 0.0)
+    };
+    match words {
+        [rest @ .., n, unit] if unit.eq_ignore_ascii_case("cm") && cm(n).is_some() => (rest, cm(n)),
+        [rest @ .., n] if cm(n).is_some() => (rest, cm(n)),
+        _ => (words, None),
+    }
+}
+
+/// Whatever `names` gives for an item is matched against `who`, ignoring
+/// case: the items with an exact match and `true`, or without any, those with
+/// `who` in one of their names and `false`.
+fn find<'a, T>(items: &'a [T], who: &str, names: impl Fn(&T) -> Vec<&str>) -> (bool, Vec<&'a T>) {
+    let who = who.to_lowercase();
+    let matching = |exact: bool| -> Vec<&T> {
+        items
+            .iter()
+            .filter(|item| {
+                names(item).iter().any(|name| {
+                    let name = name.to_lowercase();
+                    if exact {
+                        name == who
+                    } else {
+                        name.contains(&who)
+                    }
+                })
+            })
+            .collect()
+    };
+    let exact = matching(true);
+    if exact.is_empty() {
+        (false, matching(false))
+    } else {
+        (true, exact)
+    }
+}
+
+fn tarantula_names(facts: &TarantulaFacts) -> Vec<&str> {
+    std::iter::once(facts.name.as_str())
+        .chain(facts.aliases.iter().map(String::as_str))
+        .collect()
+}
+
+fn crickets(count: i32) -> &'static str {
+    if count == 1 {
+        "cricket"
+    } else {
+        "crickets"
+    }
+}
+
+/// Asks which of `choices` a message meant, noting any left out.
+fn which_one(who: &str, choices: Vec<Button>) -> Outcome {
+    let mut message = format!("🤔 Which one did you mean by \"{}\"?", who);
+    if choices.len() > MAX_CHOICES {
+        message.push_str(&format!(
+            "\n\n_{} more match, send more of the name to narrow it down._",
+            choices.len() - MAX_CHOICES
+        ));
+    }
+    let mut keyboard: Keyboard = choices
+        .into_iter()
+        .take(MAX_CHOICES)
+        .map(|button| vec![button])
+        .collect();
+    keyboard.push(vec![Button::callback("« Cancel", MainMenu)]);
+    Outcome::send(Screen::new(message, keyboard))
+}
+
+impl App {
+    /// Logs what a plain message describes, asking first when it could mean
+    /// more than one tarantula or colony. A "when" at the end of a feeding,
+    /// refusal or molt logs it for then; colony counts are always as of now.
+    pub(crate) async fn quick_log(&self, user_id: u64, text: &str) -> BotResult<Outcome> {
+        let words: Vec<&str> = text.split_whitespace().collect();
+        let (words, at) = strip_when(&words, self.now(user_id).await?);
+        let Some(log) = parse_quick_log(&words.join(" ")) else {
+            return Ok(Outcome::default());
+        };
+        if let QuickLog::Colony { name, change } = &log {
+            return self.quick_colony_by_name(user_id, name, *change).await;
+        }
+
+        let facts = self.db.get_schedule_facts(user_id).await?;
+        let (exact, found) = find(&facts, log.who(), tarantula_names);
+        match found.as_slice() {
+            [] => Ok(Outcome::send(Screen::new(
+                format!("🤷 No tarantula called \"{}\".", log.who()),
+                back_to_menu_keyboard(),
+            ))),
+            [one] if exact => {
+                let session = Session {
+                    user_id,
+                    dialogue: None,
+                };
+                log.callback(one.id, at).callback(self, &session).await
+            }
+            _ => Ok(which_one(
+                log.who(),
+                found
+                    .iter()
+                    .map(|f| {
+                        Button::callback(
+                            format!("{} ({})", f.name, f.species_name),
+                            log.callback(f.id, at),
+                        )
+                    })
+                    .collect(),
+            )),
+        }
+    }
+
+    /// Feeds from `colony_id`, or without one from a colony with enough
+    /// crickets of `size`, or of the size the schedule suggests. Colonies of
+    /// one size give the fullest; a choice of sizes is asked. `count` falls
+    /// back to the schedule's prey count.
+    pub(crate) async fn quick_feed(
+        &self,
+        user_id: u64,
+        tarantula_id: i64,
+        colony_id: Option<i64>,
+        count: Option<i32>,
+        size: Option<CricketSize>,
+        at: Option<NaiveDateTime>,
+    ) -> BotResult<Outcome> {
+        let facts = self
+            .db
+            .get_schedule_facts(user_id)
+            .await?
+            .into_iter()
+            .find(|f| f.id == tarantula_id)
+            .ok_or_else(|| {
+                BotError::NotFound(format!("Tarantula with id {} not found", tarantula_id))
+            })?;
+        let plan = schedule::feeding_plan(&facts, self.now(user_id).await?);
+        let count = count.or(plan.prey_count).unwrap_or(1);
+
+        let colony = match colony_id {
+            Some(colony_id) => self.colony_status(colony_id, user_id).await?,
+            None => {
+                let stocked: Vec<ColonyStatus> = self
+                    .db
+                    .get_colony_status(user_id)
+                    .await?
+                    .into_iter()
+                    .filter(|c| c.current_count >= count)
+                    .filter(|c| size.is_none_or(|s| c.size_type == s))
+                    .collect();
+                let suggested = plan
+                    .prey_size
+                    .as_deref()
+                    .and_then(|p| p.split_whitespace().find_map(parse_size));
+                let (of_size, other): (Vec<_>, Vec<_>) = stocked
+                    .into_iter()
+                    .partition(|c| suggested.is_none_or(|s| c.size_type == s));
+                let candidates = if of_size.is_empty() { other } else { of_size };
+
+                let Some(first) = candidates.first() else {
+                    return Ok(Outcome::replace(Screen::new(
+                        format!(
+                            "🦗 No colony has {} {}{} to spare for *{}*.",
+                            count,
+                            size.map_or(String::new(), |s| format!("{} ", s.to_db_name())),
+                            crickets(count),
+                            facts.name
+                        ),
+                        back_to_menu_keyboard(),
+                    )));
+                };
+                if candidates.iter().any(|c| c.size_type != first.size_type) {
+                    return Ok(self.quick_feed_colonies(&facts, count, candidates, at));
+                }
+                candidates
+                    .into_iter()
+                    .max_by_key(|c| c.current_count)
+                    .expect("candidates are not empty")
+            }
+        };
+
+        let logged_at = self.logged_at(user_id, at).await?;
+        let change = self
+            .db
+            .record_feeding(
+                user_id,
+                FeedingEvent {
+                    id: None,
+                    tarantula_id,
+                    feeding_date: logged_at,
+                    cricket_colony_id: colony.id,
+                    number_of_crickets: count,
+                    feeding_status_id: FeedingStatus::Accepted as i64,
+                    notes: None,
+                },
+            )
+            .await?;
+        Ok(Outcome::replace(Screen::new(
+            format!(
+                "✅ Fed *{}* {} {} {} from {}, {} left.{}",
+                facts.name,
+                count,
+                colony.size_type.to_db_name(),
+                crickets(count),
+                colony.colony_name,
+                colony.current_count - count,
+                when_line(at)
+            ),
+            undo_keyboard(change.id),
+        )))
+    }
+
+    fn quick_feed_colonies(
+        &self,
+        facts: &TarantulaFacts,
+        count: i32,
+        colonies: Vec<ColonyStatus>,
+        at: Option<NaiveDateTime>,
+    ) -> Outcome {
+        let mut keyboard: Keyboard = colonies
+            .iter()
+            .map(|c| {
+                vec![Button::callback(
+                    format!(
+                        "{} ({}, {} left)",
+                        c.colony_name,
+                        c.size_type.to_db_name(),
+                        c.current_count
+                    ),
+                    BotCallback::QuickFeed(facts.id, c.id, count, 0, to_stamp(at)),
+                )]
+            })
+            .collect();
+        keyboard.push(vec![Button::callback("« Cancel", MainMenu)]);
+        Outcome::replace(Screen::new(
+            format!(
+                "🦗 Which colony are *{}*'s {} {} from?",
+                facts.name,
+                count,
+                crickets(count)
+            ),
+            keyboard,
+        ))
+    }
+
+    pub(crate) async fn quick_refusal(
+        &self,
+        user_id: u64,
+        tarantula_id: i64,
+        at: Option<NaiveDateTime>,
+    ) -> BotResult<Outcome> {
+        let tarantula = self.db.get_tarantula_by_id(user_id, tarantula_id).await?;
+        let logged_at = self.logged_at(user_id, at).await?;
+        let change = self
+            .db
+            .record_refusal(user_id, tarantula_id, logged_at)
+            .await?;
+        Ok(Outcome::replace(Screen::new(
+            format!(
+                "✅ Logged a refused meal for *{}*.{}",
+                tarantula.name,
+                when_line(at)
+            ),
+            undo_keyboard(change.id),
+        )))
+    }
+
+    pub(crate) async fn quick_molt(
+        &self,
+        user_id: u64,
+        tarantula_id: i64,
+        length_cm: f32,
+        at: Option<NaiveDateTime>,
+    ) -> BotResult<Outcome> {
+        let tarantula = self.db.get_tarantula_by_id(user_id, tarantula_id).await?;
+        let logged_at = self.logged_at(user_id, at).await?;
+        let change = self
+            .db
+            .record_molt(tarantula_id, length_cm, None, None, user_id, logged_at)
+            .await?;
+        Ok(Outcome::replace(Screen::new(
+            format!(
+                "✅ Molt recorded for *{}* at {:.1} cm.{}",
+                tarantula.name,
+                length_cm,
+                when_line(at)
+            ),
+            undo_keyboard(change.id),
+        )))
+    }
+
+    async fn quick_colony_by_name(
+        &self,
+        user_id: u64,
+        name: &str,
+        change: ColonyChange,
+    ) -> BotResult<Outcome> {
+        let colonies = self.db.get_colony_status(user_id).await?;
+        let (exact, found) = find(&colonies, name, |c| vec![c.colony_name.as_str()]);
+        match found.as_slice() {
+            [] => Ok(Outcome::send(Screen::new(
+                format!("🤷 No colony called \"{}\".", name),
+                back_to_menu_keyboard(),
+            ))),
+            [one] if exact => {
+                self.quick_colony(user_id, one.id, change.adjustment(one.current_count))
+                    .await
+            }
+            _ => Ok(which_one(
+                name,
+                found
+                    .iter()
+                    .map(|c| {
+                        Button::callback(
+                            format!(
+                                "{} ({}, {})",
+                                c.colony_name,
+                                c.size_type.to_db_name(),
+                                c.current_count
+                            ),
+                            BotCallback::QuickColony(c.id, change.adjustment(c.current_count)),
+                        )
+                    })
+                    .collect(),
+            )),
+        }
+    }
+
+    pub(crate) async fn quick_colony(
+        &self,
+        user_id: u64,
+        colony_id: i64,
+        adjustment: i32,
+    ) -> BotResult<Outcome> {
+        let colony = self.colony_status(colony_id, user_id).await?;
+        let count = colony.current_count + adjustment;
+        if count < 0 {
+            return Ok(Outcome::replace(Screen::new(
+                format!(
+                    "🦗 *{}* only has {} {}.",
+                    colony.colony_name,
+                    colony.current_count,
+                    crickets(colony.current_count)
+                ),
+                back_to_menu_keyboard(),
+            )));
+        }
+        let change = self
+            .db
+            .update_colony_count(colony_id, adjustment, user_id)
+            .await?;
+        Ok(Outcome::replace(Screen::new(
+            format!(
+                "✅ *{}*: {} → {} {}.",
+                colony.colony_name,
+                colony.current_count,
+                count,
+                crickets(count)
+            ),
+            undo_keyboard(change.id),
+        )))
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fed(who: &str, count: Option<i32>, size: Option<CricketSize>) -> Option<QuickLog> {
+        Some(QuickLog::Fed {
+            who: who.to_string(),
+            count,
+            size,
+        })
+    }
+
+    #[test]
+    fn feedings_read_with_or_without_count_and_size() {
+        assert_eq!(
+            parse_quick_log("fed rosie 2 small"),
+            fed("rosie", Some(2), Some(CricketSize::Small))
+        );
+        assert_eq!(
+            parse_quick_log("Feed Big Bertha 3 adults crickets"),
+            fed("Big Bertha", Some(3), Some(CricketSize::Adult))
+        );
+        assert_eq!(parse_quick_log("fed rosie"), fed("rosie", None, None));
+        assert_eq!(
+            parse_quick_log("rosie ate 1 pinhead"),
+            fed("rosie", Some(1), Some(CricketSize::Pinhead))
+        );
+        assert_eq!(parse_quick_log("fed 2 small"), None);
+        assert_eq!(parse_quick_log("rosie ate my homework"), None);
+    }
+
+    #[test]
+    fn refusals_and_molts_read_either_way_round() {
+        let refused = Some(QuickLog::Refused {
+            who: "rosie".to_string(),
+        });
+        assert_eq!(parse_quick_log("rosie refused"), refused);
+        assert_eq!(parse_quick_log("rejected rosie"), refused);
+        assert_eq!(parse_quick_log("rosie refused it"), None);
+
+        let molted = |length_cm| {
+            Some(QuickLog::Molted {
+                who: "boris".to_string(),
+                length_cm,
+            })
+        };
+        assert_eq!(parse_quick_log("molt boris 6.5cm"), molted(Some(6.5)));
+        assert_eq!(parse_quick_log("boris moulted 6.5 cm"), molted(Some(6.5)));
+        assert_eq!(parse_quick_log("molted boris"), molted(None));
+    }
+
+    #[test]
+    fn colony_counts_change_by_or_to_a_number() {
+        let colony = |change| {
+            Some(QuickLog::Colony {
+                name: "A".to_string(),
+                change,
+            })
+        };
+        assert_eq!(
+            parse_quick_log("colony A +200"),
+            colony(ColonyChange::By(200))
+        );
+        assert_eq!(
+            parse_quick_log("colony A -50"),
+            colony(ColonyChange::By(-50))
+        );
+        assert_eq!(
+            parse_quick_log("colony A 120"),
+            colony(ColonyChange::To(120))
+        );
+        assert_eq!(parse_quick_log("colony +200"), None);
+        assert_eq!(ColonyChange::To(120).adjustment(100), 20);
+    }
+
+    #[test]
+    fn chatter_is_not_a_log() {
+        for text in ["", "hello there", "how is rosie doing?", "feed"] {
+            assert_eq!(parse_quick_log(text), None, "{:?}", text);
+        }
+    }
+}
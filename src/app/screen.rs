@@ -0,0 +1,147 @@
+use crate::app::callbacks::BotCallback;
+use crate::app::dialogue::DialogueState;
+
+/// A tappable button. `callback` is the [`BotCallback`] wire format, so any
+/// frontend can hand it straight back to [`crate::app::App::tap`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Button {
+    pub label: String,
+    pub callback: String,
+}
+
+impl Button {
+    pub fn callback(label: impl Into<String>, callback: BotCallback) -> Self {
+        Self {
+            label: label.into(),
+            callback: callback.to_string(),
+        }
+    }
+}
+
+pub type Keyboard = Vec<Vec<Button>>;
+
+/// One message worth of content: text plus rows of buttons.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Screen {
+    pub text: String,
+    pub buttons: Keyboard,
+}
+
+impl Screen {
+    pub fn new(text: impl Into<String>, buttons: Keyboard) -> Self {
+        Self {
+            text: text.into(),
+            buttons,
+        }
+    }
+
+    pub fn text(text: impl Into<String>) -> Self {
+        Self::new(text, Vec::new())
+    }
+}
+
+/// A file for the keeper, with a line about what is in it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Document {
+    pub file_name: String,
+    pub contents: Vec<u8>,
+    pub caption: String,
+}
+
+/// A picture the chat already holds, shown again by its file id.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Photo {
+    pub file_id: String,
+    pub caption: String,
+    pub buttons: Keyboard,
+}
+
+/// A tarantula offered in answer to an inline query from any chat. Chosen, it
+/// posts `text` there; its buttons open a private chat with the bot, passing
+/// their callback as the `/start` payload.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InlineCard {
+    pub id: String,
+    pub title: String,
+    pub description: String,
+    pub text: String,
+    pub buttons: Keyboard,
+}
+
+/// How a screen reaches the keeper: over the message whose button was tapped,
+/// or as a new message. Without a tapped message a `Replace` is sent as new.
+/// A `Photo` replaces a tapped photo and is otherwise sent as new, and chats
+/// cannot turn a photo back into text, so a `Replace` over one is sent as new
+/// too. A `Document` is always sent as a new message.
+#[derive(Debug, Clone, PartialEq)]
+pub enum View {
+    Replace(Screen),
+    Send(Screen),
+    Photo(Photo),
+    Document(Document),
+}
+
+/// What happens to the keeper's dialogue once the views are shown.
+#[derive(Debug, Clone, Default)]
+pub enum Transition {
+    #[default]
+    Stay,
+    Enter(DialogueState),
+    Exit,
+}
+
+/// Everything a tap, command or dialogue reply produces.
+#[derive(Debug, Clone, Default)]
+pub struct Outcome {
+    pub views: Vec<View>,
+    pub dialogue: Transition,
+}
+
+impl Outcome {
+    pub fn replace(screen: Screen) -> Self {
+        Self {
+            views: vec![View::Replace(screen)],
+            ..Self::default()
+        }
+    }
+
+    pub fn send(screen: Screen) -> Self {
+        Self {
+            views: vec![View::Send(screen)],
+            ..Self::default()
+        }
+    }
+
+    pub fn photo(photo: Photo) -> Self {
+        Self {
+            views: vec![View::Photo(photo)],
+            ..Self::default()
+        }
+    }
+
+    pub fn document(document: Document) -> Self {
+        Self {
+            views: vec![View::Document(document)],
+            ..Self::default()
+        }
+    }
+
+    /// Shows `next` after this outcome's views, taking its transition if it has one.
+    pub fn then(mut self, next: Outcome) -> Self {
+        self.views.extend(next.views);
+        if !matches!(next.dialogue, Transition::Stay) {
+            self.dialogue = next.dialogue;
+        }
+        self
+    }
+
+    pub fn enter(mut self, state: DialogueState) -> Self {
+        self.dialogue = Transition::Enter(state);
+        self
+    }
+
+    pub fn exit(mut self) -> Self {
+        self.dialogue = Transition::Exit;
+        self
+    }
+}
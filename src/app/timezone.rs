@@ -0,0 +1,146 @@
+//! The keeper's timezone, a zone such as Europe/Berlin or a fixed offset from
+//! UTC such as +02:00. Times are shown and "yesterday" is counted on their
+//! clock.
+
+use crate::app::keyboards::back_to_menu_keyboard;
+use crate::app::screen::Screen;
+use crate::app::App;
+use crate::error::BotError;
+use crate::models::user::Timezone;
+use crate::BotResult;
+
+const TIMEZONE_EXAMPLES: &str =
+    "/timezone Europe/Berlin, /timezone America/New_York, /timezone +05:30 or /timezone UTC";
+
+/// Reads a zone name like "Europe/Berlin" or "CET", in any case, or an offset
+/// like "+2", "+02:00", "-0530" or "UTC+3". "UTC" and "GMT" on their own are
+/// UTC.
+pub(crate) fn parse_timezone(text: &str) -> Option<Timezone> {
+    if let Some(zone) = Timezone::from_zone_name(text.trim()) {
+        return Some(zone);
+    }
+    let text = text.trim().to_uppercase();
+    let offset = text
+        .strip_prefix("UTC")
+        .or_else(|| text.strip_prefix("GMT"))
+        .unwrap_or(&text);
+    if offset.is_empty() {
+        return Some(Timezone::default());
+    }
+    let (sign, offset) = if let Some(rest) = offset.strip_prefix('+') {
+        (1, rest)
+    } else {
+        (-1, offset.strip_prefix('-')?)
+    };
+    let (hours, minutes) = match offset.split_once(':') {
+        Some((hours, minutes)) if minutes.len() == 2 => (hours, minutes),
+        Some(_) => return None,
+        None if offset.len() == 4 => offset.split_at(2),
+        None => (offset, "0"),
+    };
+    let digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+    if hours.is_empty() || hours.len() > 2 || !digits(hours) || !digits(minutes) {
+        return None;
+    }
+    let hours: i32 = hours.parse().ok()?;
+    let minutes: i32 = minutes.parse().ok().filter(|m| (0..60).contains(m))?;
+    Timezone::from_offset_minutes(sign * (hours * 60 + minutes))
+}
+
+impl App {
+    /// Shows the keeper's timezone, or sets it from `zone` when one is given.
+    pub(crate) async fn timezone(&self, user_id: u64, zone: &str) -> BotResult<Screen> {
+        if zone.trim().is_empty() {
+            let timezone = self.db.get_timezone(user_id).await?;
+            return Ok(Screen::new(
+                format!(
+                    "🕐 Your timezone is {}, where it's {}.\n\nChange it with {}",
+                    timezone.label(),
+                    timezone.now().format("%Y-%m-%d %H:%M"),
+                    TIMEZONE_EXAMPLES
+                ),
+                back_to_menu_keyboard(),
+            ));
+        }
+
+        let timezone = parse_timezone(zone).ok_or_else(|| {
+            BotError::ValidationError(format!(
+                "{} is not a timezone, send {}",
+                zone.trim(),
+                TIMEZONE_EXAMPLES
+            ))
+        })?;
+        self.db.set_timezone(user_id, timezone).await?;
+        Ok(Screen::new(
+            format!(
+                "✅ Timezone set to {}, where it's {}.",
+                timezone.label(),
+                timezone.now().format("%Y-%m-%d %H:%M")
+            ),
+            back_to_menu_keyboard(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn offset(text: &str) -> Option<String> {
+        parse_timezone(text).map(Timezone::name)
+    }
+
+    #[test]
+    fn offsets_are_read_in_the_usual_spellings() {
+        assert_eq!(offset("+2").as_deref(), Some("+02:00"));
+        assert_eq!(offset("+02:00").as_deref(), Some("+02:00"));
+        assert_eq!(offset("-5:30").as_deref(), Some("-05:30"));
+        assert_eq!(offset("-0530").as_deref(), Some("-05:30"));
+        assert_eq!(offset("UTC+3").as_deref(), Some("+03:00"));
+        assert_eq!(offset("gmt-10").as_deref(), Some("-10:00"));
+        assert_eq!(offset(" utc ").as_deref(), Some("UTC"));
+        assert_eq!(offset("+0").as_deref(), Some("UTC"));
+        assert_eq!(offset("+14:00").as_deref(), Some("+14:00"));
+    }
+
+    #[test]
+    fn zones_are_read_by_name_in_any_case() {
+        assert_eq!(offset("Europe/Berlin").as_deref(), Some("Europe/Berlin"));
+        assert_eq!(
+            offset(" america/new_york ").as_deref(),
+            Some("America/New_York")
+        );
+        assert_eq!(offset("CET").as_deref(), Some("CET"));
+        assert_eq!(offset("GMT").as_deref(), Some("GMT"));
+    }
+
+    #[test]
+    fn anything_else_is_not_a_timezone() {
+        for text in [
+            "2",
+            "+",
+            "+2:60",
+            "+15",
+            "-14:30",
+            "+1:5",
+            "+2:+5",
+            "+123",
+            "ü+1",
+            "Europe",
+            "Mars/Olympus_Mons",
+        ] {
+            assert_eq!(offset(text), None, "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn labels_leave_out_zero_minutes() {
+        assert_eq!(parse_timezone("UTC").unwrap().label(), "UTC");
+        assert_eq!(parse_timezone("+02:00").unwrap().label(), "UTC+2");
+        assert_eq!(parse_timezone("-3:30").unwrap().label(), "UTC-3:30");
+        assert_eq!(
+            parse_timezone("europe/berlin").unwrap().label(),
+            "Europe/Berlin"
+        );
+    }
+}
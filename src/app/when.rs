@@ -0,0 +1,244 @@
+//! When something being logged happened, for keepers catching up the next
+//! morning: "yesterday", "2 days ago" or a day like 2026-10-17, each with an
+//! optional time such as 18:30. A day without a time keeps the current time
+//! of day, so "2 days ago" is two days back to the minute. Moments are on
+//! the keeper's clock until they are logged. Buttons carry them as Unix
+//! seconds, 0 for now.
+
+use crate::app::callbacks::BotCallback;
+use crate::app::dialogue::DialogueState;
+use crate::app::screen::{Button, Outcome, Screen};
+use crate::app::App;
+use crate::models::models::DbDateTime;
+use crate::BotResult;
+use chrono::{DateTime, Days, NaiveDate, NaiveDateTime, NaiveTime};
+
+pub(crate) const WHEN_EXAMPLES: &str = "yesterday, 2 days ago or 2026-10-17 18:30";
+
+/// Longest "when" taken off the end of a message: "2 days ago at 18:30".
+const MAX_WHEN_WORDS: usize = 5;
+
+/// A picker shown again for an earlier moment once the keeper says when.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Backdate {
+    Feeding { tarantula_id: i64, colony_id: i64 },
+    HealthCheck { tarantula_id: i64 },
+    GroupFeeding { group_id: i64, colony_id: i64 },
+    GroupHealthCheck { group_id: i64 },
+}
+
+/// Reads `text` as a moment relative to `now`, or `None` when it isn't one.
+pub(crate) fn parse_when(text: &str, now: NaiveDateTime) -> Option<NaiveDateTime> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    parse_when_words(&words, now)
+}
+
+fn parse_when_words(words: &[&str], now: NaiveDateTime) -> Option<NaiveDateTime> {
+    let mut words: Vec<String> = words.iter().map(|w| w.to_lowercase()).collect();
+    if words.first().is_some_and(|w| w == "on") {
+        words.remove(0);
+    }
+    words.retain(|w| w != "at");
+
+    let time = words
+        .last()
+        .and_then(|w| NaiveTime::parse_from_str(w, "%H:%M").ok());
+    if time.is_some() {
+        words.pop();
+    }
+    let today = now.date();
+    let words: Vec<&str> = words.iter().map(String::as_str).collect();
+    let day = match words.as_slice() {
+        [] if time.is_some() => today,
+        ["today"] => today,
+        ["yesterday"] => today.checked_sub_days(Days::new(1))?,
+        [n, "day" | "days", "ago"] => today.checked_sub_days(Days::new(n.parse().ok()?))?,
+        [day] => NaiveDate::parse_from_str(day, "%Y-%m-%d").ok()?,
+        _ => return None,
+    };
+    Some(day.and_time(time.unwrap_or(now.time())))
+}
+
+/// Takes a "when" off the end of `words`, leaving at least one word.
+pub(crate) fn strip_when<'a>(
+    words: &'a [&'a str],
+    now: NaiveDateTime,
+) -> (&'a [&'a str], Option<NaiveDateTime>) {
+    let longest = MAX_WHEN_WORDS.min(words.len().saturating_sub(1));
+    (1..=longest)
+        .rev()
+        .find_map(|n| {
+            let (rest, when) = words.split_at(words.len() - n);
+            parse_when_words(when, now).map(|at| (rest, Some(at)))
+        })
+        .unwrap_or((words, None))
+}
+
+/// Splits a reply like "6.5 yesterday" into what comes before its "when"
+/// and the moment that names.
+pub(crate) fn split_when(text: &str, now: NaiveDateTime) -> (String, Option<NaiveDateTime>) {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let (rest, at) = strip_when(&words, now);
+    (rest.join(" "), at)
+}
+
+/// `at` for a button, 0 for now.
+pub(crate) fn to_stamp(at: Option<NaiveDateTime>) -> i64 {
+    at.map_or(0, |at| at.and_utc().timestamp())
+}
+
+pub(crate) fn from_stamp(stamp: i64) -> Option<NaiveDateTime> {
+    (stamp != 0)
+        .then(|| DateTime::from_timestamp(stamp, 0))
+        .flatten()
+        .map(|at| at.naive_utc())
+}
+
+/// A line under a screen's heading or confirmation saying when it is for.
+pub(crate) fn when_line(at: Option<NaiveDateTime>) -> String {
+    at.map_or(String::new(), |at| {
+        format!("\n📅 {}", at.format("%Y-%m-%d %H:%M"))
+    })
+}
+
+/// Offers to log a picker's choice for an earlier moment.
+pub(crate) fn earlier_button(record: Backdate) -> Button {
+    let callback = match record {
+        Backdate::Feeding {
+            tarantula_id,
+            colony_id,
+        } => BotCallback::FeedEarlier(tarantula_id, colony_id),
+        Backdate::HealthCheck { tarantula_id } => BotCallback::HealthEarlier(tarantula_id),
+        Backdate::GroupFeeding {
+            group_id,
+            colony_id,
+        } => BotCallback::GroupFeedEarlier(group_id, colony_id),
+        Backdate::GroupHealthCheck { group_id } => BotCallback::GroupHealthEarlier(group_id),
+    };
+    Button::callback("📅 Earlier…", callback)
+}
+
+impl App {
+    /// The time on the keeper's clock.
+    pub(crate) async fn now(&self, user_id: u64) -> BotResult<NaiveDateTime> {
+        Ok(self.db.get_timezone(user_id).await?.now())
+    }
+
+    /// When to log something at: `at` on the keeper's clock, or now.
+    pub(crate) async fn logged_at(
+        &self,
+        user_id: u64,
+        at: Option<NaiveDateTime>,
+    ) -> BotResult<DbDateTime> {
+        let Some(at) = at else {
+            return Ok(DbDateTime::default());
+        };
+        let timezone = self.db.get_timezone(user_id).await?;
+        Ok(DbDateTime::from_naive_utc(timezone.utc(at)))
+    }
+
+    pub(crate) fn when_prompt(&self, record: Backdate) -> Outcome {
+        Outcome::send(Screen::text(format!(
+            "📅 When was it? Send {}",
+            WHEN_EXAMPLES
+        )))
+        .enter(DialogueState::RecordWhen { record })
+    }
+
+    /// The picker `record` came from, with its buttons logging for `at`.
+    pub(crate) async fn backdated_picker(
+        &self,
+        user_id: u64,
+        record: Backdate,
+        at: NaiveDateTime,
+    ) -> BotResult<Screen> {
+        match record {
+            Backdate::Feeding {
+                tarantula_id,
+                colony_id,
+            } => {
+                self.feed_colony_selection(tarantula_id, colony_id, user_id, Some(at))
+                    .await
+            }
+            Backdate::HealthCheck { tarantula_id } => {
+                self.health_check_command(tarantula_id, user_id, Some(at))
+                    .await
+            }
+            Backdate::GroupFeeding {
+                group_id,
+                colony_id,
+            } => {
+                self.group_feed_colony(group_id, colony_id, user_id, Some(at))
+                    .await
+            }
+            Backdate::GroupHealthCheck { group_id } => {
+                self.group_health_check(group_id, user_id, Some(at)).await
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(text: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn relative_days_keep_the_time_of_day_unless_one_is_given() {
+        let now = at("2026-10-19 09:15");
+        assert_eq!(parse_when("yesterday", now), Some(at("2026-10-18 09:15")));
+        assert_eq!(parse_when("2 days ago", now), Some(at("2026-10-17 09:15")));
+        assert_eq!(parse_when("1 day ago", now), Some(at("2026-10-18 09:15")));
+        assert_eq!(
+            parse_when("Yesterday at 18:30", now),
+            Some(at("2026-10-18 18:30"))
+        );
+        assert_eq!(parse_when("today", now), Some(now));
+        assert_eq!(parse_when("7:05", now), Some(at("2026-10-19 07:05")));
+        assert_eq!(
+            parse_when("on 2026-10-01 18:30", now),
+            Some(at("2026-10-01 18:30"))
+        );
+        assert_eq!(parse_when("2026-10-01", now), Some(at("2026-10-01 09:15")));
+    }
+
+    #[test]
+    fn anything_else_is_not_a_when() {
+        let now = at("2026-10-19 09:15");
+        for text in [
+            "",
+            "soon",
+            "2",
+            "days ago",
+            "-1 days ago",
+            "2026-13-01",
+            "25:00",
+        ] {
+            assert_eq!(parse_when(text, now), None, "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn a_when_comes_off_the_end_of_a_message() {
+        let now = at("2026-10-19 09:15");
+        let words = ["fed", "rosie", "2", "days", "ago"];
+        assert_eq!(
+            strip_when(&words, now),
+            (&words[..2], Some(at("2026-10-17 09:15")))
+        );
+        let words = ["fed", "rosie", "2", "small"];
+        assert_eq!(strip_when(&words, now), (&words[..], None));
+        let words = ["yesterday"];
+        assert_eq!(strip_when(&words, now), (&words[..], None));
+    }
+
+    #[test]
+    fn moments_survive_the_trip_through_callback_data() {
+        let moment = at("2026-10-17 18:30");
+        assert_eq!(from_stamp(to_stamp(Some(moment))), Some(moment));
+        assert_eq!(from_stamp(to_stamp(None)), None);
+    }
+}
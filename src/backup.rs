@@ -0,0 +1,342 @@
+//! Copies of the database taken while the bot keeps running, on a schedule
+//! and on demand, and restoring from one of them. Each backup is a complete
+//! SQLite file named after the time it was taken, so the newest sort last.
+
+use crate::db::db::TarantulaOperations;
+use crate::error::BotError;
+use crate::metrics::Metrics;
+use crate::BotResult;
+use chrono::Utc;
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::{self, Instant};
+use tracing::{info_span, Instrument};
+
+const PREFIX: &str = "spider-bot-";
+const EXTENSION: &str = ".sqlite";
+
+/// The `[backup]` section of the configuration.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BackupConfig {
+    /// Whether to take a backup every `interval_hours`. `/backup` works
+    /// either way.
+    pub enabled: bool,
+    pub directory: PathBuf,
+    pub interval_hours: u64,
+    /// How many backups to keep in `directory`, older ones are deleted.
+    pub keep: usize,
+}
+
+impl Default for BackupConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            directory: PathBuf::from("backups"),
+            interval_hours: 24,
+            keep: 7,
+        }
+    }
+}
+
+impl BackupConfig {
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval_hours.saturating_mul(3600))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Backup {
+    pub path: PathBuf,
+    pub bytes: u64,
+}
+
+impl Backup {
+    pub fn file_name(&self) -> String {
+        self.path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    }
+}
+
+pub struct Backups {
+    db: Arc<dyn TarantulaOperations + Send + Sync>,
+    config: BackupConfig,
+    metrics: Arc<Metrics>,
+}
+
+impl Backups {
+    pub fn new(
+        db: Arc<dyn TarantulaOperations + Send + Sync>,
+        config: BackupConfig,
+        metrics: Arc<Metrics>,
+    ) -> Self {
+        Self {
+            db,
+            config,
+            metrics,
+        }
+    }
+
+    /// Takes a backup every configured interval, if scheduled backups are on.
+    pub async fn run(self: Arc<Self>) {
+        if !self.config.enabled {
+            return;
+        }
+        log::info!(
+            "Backing up to {} every {} hours",
+            self.config.directory.display(),
+            self.config.interval_hours
+        );
+        let interval = self.config.interval();
+        let mut ticks = time::interval_at(Instant::now() + interval, interval);
+        loop {
+            ticks.tick().await;
+            // Failures are logged and counted by `take`.
+            let _ = self.take().instrument(info_span!("backup")).await;
+        }
+    }
+
+    /// Writes a new backup and deletes the ones beyond the configured number.
+    pub async fn take(&self) -> BotResult<Backup> {
+        let result = self.write().await;
+        self.metrics.backup(result.is_ok());
+        match &result {
+            Ok(backup) => {
+                log::info!(
+                    "Wrote backup {} ({} bytes)",
+                    backup.path.display(),
+                    backup.bytes
+                );
+                if let Err(e) = self.prune() {
+                    log::warn!("Could not delete old backups: {}", e);
+                }
+            }
+            Err(e) => log::error!("Backup failed: {}", e),
+        }
+        result
+    }
+
+    /// Replaces the data with the backup at `upload` once it passes an
+    /// integrity check. The data it replaces is backed up first, and that
+    /// backup is returned.
+    pub async fn restore(&self, upload: &Path) -> BotResult<Backup> {
+        // Not pruned, that could delete the very backup being restored.
+        let previous = self.write().await;
+        self.metrics.backup(previous.is_ok());
+        let previous = previous?;
+        if let Err(e) = self.db.restore_from(upload).await {
+            // A refused upload left the data alone, so its backup is not needed.
+            if let BotError::ValidationError(_) = e {
+                let _ = fs::remove_file(&previous.path);
+            }
+            return Err(e);
+        }
+        log::warn!(
+            "Restored the database from {}, the previous data is in {}",
+            upload.display(),
+            previous.path.display()
+        );
+        Ok(previous)
+    }
+
+    /// Where an uploaded backup can be kept until it is restored.
+    pub fn upload_path(&self) -> BotResult<PathBuf> {
+        fs::create_dir_all(&self.config.directory)?;
+        Ok(self
+            .config
+            .directory
+            .join(format!("upload-{}{}", timestamp(), EXTENSION)))
+    }
+
+    async fn write(&self) -> BotResult<Backup> {
+        fs::create_dir_all(&self.config.directory)?;
+        let path = self
+            .config
+            .directory
+            .join(format!("{}{}{}", PREFIX, timestamp(), EXTENSION));
+        // Written under another name first, so an interrupted backup is never
+        // taken for a complete one.
+        let partial = path.with_extension("partial");
+        if let Err(e) = self.db.backup_to(&partial).await {
+            let _ = fs::remove_file(&partial);
+            return Err(e);
+        }
+        fs::rename(&partial, &path)?;
+        let bytes = fs::metadata(&path)?.len();
+        Ok(Backup { path, bytes })
+    }
+
+    /// Deletes all but the newest backups, returning how many went.
+    fn prune(&self) -> BotResult<usize> {
+        let mut names: Vec<String> = fs::read_dir(&self.config.directory)?
+            .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+            .filter(|name| name.starts_with(PREFIX) && name.ends_with(EXTENSION))
+            .collect();
+        names.sort_unstable_by(|a, b| b.cmp(a));
+
+        let mut deleted = 0;
+        for name in names.iter().skip(self.config.keep) {
+            fs::remove_file(self.config.directory.join(name))?;
+            deleted += 1;
+        }
+        Ok(deleted)
+    }
+}
+
+fn timestamp() -> String {
+    Utc::now().format("%Y%m%d-%H%M%S-%3f").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::db::{AddTarantulaParams, TarantulaDB};
+    use crate::db::memory::InMemoryDB;
+    use crate::db::migrations;
+    use crate::models::user::TelegramUser;
+    use tempfile::TempDir;
+
+    const KEEPER: u64 = 1;
+
+    async fn sqlite_db(dir: &TempDir) -> Arc<TarantulaDB> {
+        let path = dir.path().join("tarantulas.sqlite");
+        migrations::migrate(&mut rusqlite::Connection::open(&path).unwrap()).unwrap();
+        let db = TarantulaDB::new(path.to_str().unwrap()).unwrap();
+        db.ensure_user_exists(&TelegramUser {
+            telegram_id: KEEPER,
+            username: None,
+            first_name: "Keeper".to_string(),
+            last_name: None,
+        })
+        .await
+        .unwrap();
+        Arc::new(db)
+    }
+
+    async fn add_tarantula(db: &TarantulaDB, name: &str) {
+        db.add_tarantula(
+            KEEPER,
+            AddTarantulaParams {
+                name: name.to_string(),
+                species_id: 1,
+                acquisition_date: "2024-01-01".to_string(),
+                estimated_age_months: 12,
+                enclosure_number: None,
+                notes: None,
+            },
+        )
+        .await
+        .unwrap();
+    }
+
+    async fn names(db: &TarantulaDB) -> Vec<String> {
+        let mut names: Vec<_> = db
+            .get_all_tarantulas(KEEPER)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|t| t.name)
+            .collect();
+        names.sort();
+        names
+    }
+
+    fn backups(db: Arc<dyn TarantulaOperations + Send + Sync>, dir: &TempDir) -> Backups {
+        Backups::new(
+            db,
+            BackupConfig {
+                enabled: true,
+                directory: dir.path().join("backups"),
+                interval_hours: 1,
+                keep: 2,
+            },
+            Arc::new(Metrics::new()),
+        )
+    }
+
+    #[tokio::test]
+    async fn only_the_newest_backups_are_kept() {
+        let dir = tempfile::tempdir().unwrap();
+        let backups = backups(sqlite_db(&dir).await, &dir);
+
+        let mut taken = Vec::new();
+        for _ in 0..3 {
+            taken.push(backups.take().await.unwrap());
+            time::sleep(Duration::from_millis(5)).await;
+        }
+
+        assert!(!taken[0].path.exists());
+        assert!(taken[1].path.exists() && taken[2].path.exists());
+        assert!(taken[2].bytes > 0);
+        assert!(backups
+            .metrics
+            .render(None)
+            .contains("spider_bot_backups_total{outcome=\"written\"} 3"));
+    }
+
+    #[tokio::test]
+    async fn restoring_brings_back_the_backup_and_keeps_what_it_replaced() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = sqlite_db(&dir).await;
+        let backups = backups(db.clone(), &dir);
+        add_tarantula(&db, "Rosie").await;
+        let snapshot = backups.take().await.unwrap();
+        add_tarantula(&db, "Boris").await;
+
+        let previous = backups.restore(&snapshot.path).await.unwrap();
+        assert_eq!(names(&db).await, ["Rosie"]);
+
+        backups.restore(&previous.path).await.unwrap();
+        assert_eq!(names(&db).await, ["Boris", "Rosie"]);
+    }
+
+    #[tokio::test]
+    async fn files_that_are_not_sound_backups_are_refused() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = sqlite_db(&dir).await;
+        let backups = backups(db.clone(), &dir);
+        add_tarantula(&db, "Rosie").await;
+
+        let garbage = dir.path().join("garbage.sqlite");
+        fs::write(&garbage, "not a database at all").unwrap();
+        let other = dir.path().join("other.sqlite");
+        rusqlite::Connection::open(&other)
+            .unwrap()
+            .execute_batch("CREATE TABLE notes (text TEXT)")
+            .unwrap();
+
+        for upload in [garbage, other] {
+            let result = backups.restore(&upload).await;
+            assert!(
+                matches!(result, Err(BotError::ValidationError(_))),
+                "{:?}",
+                result
+            );
+        }
+        assert_eq!(names(&db).await, ["Rosie"]);
+        assert!(
+            !dir.path().join("backups").exists()
+                || fs::read_dir(dir.path().join("backups"))
+                    .unwrap()
+                    .next()
+                    .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn the_in_memory_store_cannot_be_backed_up() {
+        let dir = tempfile::tempdir().unwrap();
+        let backups = backups(Arc::new(InMemoryDB::new()), &dir);
+
+        assert!(backups.take().await.is_err());
+        assert!(backups
+            .metrics
+            .render(None)
+            .contains("spider_bot_backups_total{outcome=\"failed\"} 1"));
+    }
+}
@@ -0,0 +1,226 @@
+use clap::{Parser, Subcommand};
+use rusqlite::OpenFlags;
+use spider_bot::config::{Backend, Config};
+use spider_bot::db::db::TarantulaDB;
+use spider_bot::db::maintenance::UserExport;
+use spider_bot::db::migrations;
+use spider_bot::error::BotError;
+use spider_bot::BotResult;
+use std::error::Error;
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+/// Maintenance for the bot's SQLite database. The file comes from the bot's
+/// configuration unless `--database` names one.
+#[derive(Parser)]
+#[command(name = "spider-admin", version)]
+struct Cli {
+    /// The SQLite file to work on.
+    #[arg(long, global = true)]
+    database: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create the database or apply the migrations it is missing.
+    Migrate,
+    #[command(flatten)]
+    Task(Task),
+}
+
+/// Commands that need an up to date schema.
+#[derive(Subcommand)]
+enum Task {
+    /// Rebuild the file to reclaim space.
+    Vacuum,
+    /// Check the file and its foreign keys, failing on any problem.
+    IntegrityCheck,
+    /// List keepers with the size of their collections.
+    Users,
+    /// Restore species and feeding schedules from the bundled data.
+    Reseed,
+    /// Move everything one keeper owns to another and remove the first.
+    MergeUsers {
+        /// Telegram id of the duplicate account.
+        from: u64,
+        /// Telegram id of the account to keep.
+        into: u64,
+    },
+    /// Write one keeper's data as JSON.
+    Export {
+        /// Telegram id of the keeper.
+        user: u64,
+        /// File to write, instead of stdout.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Load a keeper's data written by `export`.
+    Import {
+        file: PathBuf,
+        /// Telegram id to import as, instead of the exported keeper's.
+        #[arg(long)]
+        into: Option<u64>,
+    },
+}
+
+fn main() -> ExitCode {
+    let _telemetry = spider_bot::telemetry::init("spider-admin");
+
+    match run(Cli::parse()) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(e) => {
+            log::error!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Returns whether the command found everything in order.
+fn run(cli: Cli) -> Result<bool, Box<dyn Error>> {
+    let path = database_path(cli.database)?;
+    match cli.command {
+        Command::Migrate => migrate(&path),
+        Command::Task(task) => {
+            let conn =
+                rusqlite::Connection::open_with_flags(&path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+            if migrations::is_pending(&conn)? {
+                return Err(format!(
+                    "{} is missing migrations, run `spider-admin migrate` first",
+                    path
+                )
+                .into());
+            }
+            perform(&TarantulaDB::new(&path)?, &path, task)
+        }
+    }
+}
+
+fn migrate(path: &str) -> Result<bool, Box<dyn Error>> {
+    let applied = migrations::migrate(&mut rusqlite::Connection::open(path)?)?;
+    // Opening seeds the enum tables.
+    TarantulaDB::new(path)?;
+    if applied.is_empty() {
+        println!("Schema is up to date");
+    }
+    for name in applied {
+        println!("Applied {}", name);
+    }
+    Ok(true)
+}
+
+fn perform(db: &TarantulaDB, path: &str, task: Task) -> Result<bool, Box<dyn Error>> {
+    match task {
+        Task::Vacuum => {
+            let report = db.vacuum()?;
+            println!(
+                "Vacuumed {}: {} KiB -> {} KiB",
+                path,
+                report.bytes_before / 1024,
+                report.bytes_after / 1024
+            );
+        }
+        Task::IntegrityCheck => {
+            let problems = db.integrity_check()?;
+            for problem in &problems {
+                println!("{}", problem);
+            }
+            if !problems.is_empty() {
+                return Ok(false);
+            }
+            println!("ok");
+        }
+        Task::Users => {
+            println!(
+                "{:>12}  {:<24} {:>10} {:>6} {:>8} {:>8}  last active",
+                "telegram id", "name", "tarantulas", "groups", "colonies", "crickets"
+            );
+            for user in db.user_summaries()? {
+                let name = match (&user.username, &user.first_name) {
+                    (Some(username), _) => format!("@{}", username),
+                    (None, Some(first_name)) => first_name.clone(),
+                    (None, None) => "-".to_string(),
+                };
+                println!(
+                    "{:>12}  {:<24} {:>10} {:>6} {:>8} {:>8}  {}",
+                    user.telegram_id,
+                    name,
+                    user.tarantulas,
+                    user.groups,
+                    user.colonies,
+                    user.crickets,
+                    user.last_active.as_deref().unwrap_or("-")
+                );
+            }
+        }
+        Task::Reseed => {
+            let report = db.reseed()?;
+            println!(
+                "Reseeded {} species and {} feeding schedules",
+                report.species, report.feeding_schedules
+            );
+        }
+        Task::MergeUsers { from, into } => {
+            let moved = db.merge_users(from, into)?;
+            print_counts(&moved);
+            println!("Merged user {} into {}", from, into);
+        }
+        Task::Export { user, output } => {
+            let export = db.export_user(user)?;
+            let json = serde_json::to_string_pretty(&export)?;
+            match output {
+                Some(file) => {
+                    fs::write(&file, json)?;
+                    eprintln!(
+                        "Exported {} rows of user {} to {}",
+                        export.row_count(),
+                        user,
+                        file.display()
+                    );
+                }
+                None => writeln!(io::stdout(), "{}", json)?,
+            }
+        }
+        Task::Import { file, into } => {
+            let export: UserExport = serde_json::from_slice(&fs::read(&file)?)
+                .map_err(|e| format!("{} is not an export: {}", file.display(), e))?;
+            let imported = db.import_user(&export, into)?;
+            print_counts(&imported);
+            println!(
+                "Imported user {} as {}",
+                export.user.telegram_id,
+                into.unwrap_or(export.user.telegram_id)
+            );
+        }
+    }
+    Ok(true)
+}
+
+fn database_path(flag: Option<PathBuf>) -> BotResult<String> {
+    let path = match flag {
+        Some(path) => path,
+        None => {
+            let config = Config::load()?;
+            if config.database.backend != Backend::Sqlite {
+                return Err(BotError::Config(
+                    "spider-admin works on the sqlite backend, pass --database".to_string(),
+                ));
+            }
+            config.database.path
+        }
+    };
+    path.into_os_string()
+        .into_string()
+        .map_err(|path| BotError::Config(format!("{:?} is not valid UTF-8", path)))
+}
+
+fn print_counts(counts: &[(&str, usize)]) {
+    for (table, rows) in counts.iter().filter(|(_, rows)| *rows > 0) {
+        println!("{:>8}  {}", rows, table);
+    }
+}
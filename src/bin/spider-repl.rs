@@ -0,0 +1,24 @@
+use spider_bot::app::App;
+use spider_bot::config::Config;
+use spider_bot::repl::Repl;
+use std::env;
+use std::io;
+
+/// Drives the bot's menus from the terminal against the configured database,
+/// as the keeper given by `REPL_USER_ID` (default 1). No bot token is needed.
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> io::Result<()> {
+    let _telemetry = spider_bot::telemetry::init("spider-repl");
+
+    let user_id = env::var("REPL_USER_ID")
+        .ok()
+        .and_then(|id| id.parse().ok())
+        .unwrap_or(1);
+    let config = Config::load().map_err(io::Error::other)?;
+    let db = spider_bot::db::open(&config.database, config.alerts).map_err(io::Error::other)?;
+    let app = App::new(db);
+
+    Repl::new(app, user_id, io::stdout())
+        .run(io::stdin().lock())
+        .await
+}
@@ -0,0 +1,219 @@
+//! What the operators' Telegram chats get: reports of failures on our side,
+//! each under a short reference that the keeper is shown as well so a
+//! complaint can be matched to the report and to the log line, and commands
+//! only they may use.
+
+use crate::error::BotError;
+use serde::Deserialize;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use teloxide::macros::BotCommands;
+use teloxide::prelude::{ChatId, Requester};
+use teloxide::Bot;
+
+/// At most this many reports go out per [`REPORT_WINDOW`], so a failing
+/// database can't flood the admin chats. The rest are only logged.
+const MAX_REPORTS: u32 = 5;
+const REPORT_WINDOW: Duration = Duration::from_secs(60);
+
+/// Error text beyond this is cut, a report is a pointer to the logs.
+const MAX_SUMMARY_CHARS: usize = 300;
+
+/// The `[admin]` section of the configuration.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AdminConfig {
+    /// Chats that receive error reports. Without any, errors are only logged.
+    pub chats: Vec<ChatId>,
+}
+
+impl AdminConfig {
+    pub fn is_admin(&self, chat: ChatId) -> bool {
+        self.chats.contains(&chat)
+    }
+}
+
+/// Commands only accepted in admin chats, and left out of `/help`.
+#[derive(BotCommands, Clone)]
+#[command(rename_rule = "lowercase")]
+pub(crate) enum AdminCommand {
+    Backup,
+    Restore,
+}
+
+impl AdminCommand {
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            AdminCommand::Backup => "backup",
+            AdminCommand::Restore => "restore",
+        }
+    }
+}
+
+/// The caption that marks an uploaded file as a backup to restore.
+pub(crate) const RESTORE_CAPTION: &str = "/restore";
+
+pub(crate) struct ErrorReporter {
+    bot: Bot,
+    chats: Vec<ChatId>,
+    limit: Mutex<RateLimit>,
+}
+
+impl ErrorReporter {
+    pub(crate) fn new(bot: Bot, config: AdminConfig) -> Self {
+        Self {
+            bot,
+            chats: config.chats,
+            limit: Mutex::new(RateLimit::default()),
+        }
+    }
+
+    /// Logs `error` under a fresh reference and sends a summary to the admin
+    /// chats, unless too many went out lately. `origin` says which handler
+    /// failed. Returns the reference for the keeper.
+    pub(crate) async fn report(&self, error: &BotError, origin: &str) -> String {
+        let reference = format!("{:08x}", rand::random::<u32>());
+        tracing::error!(reference, origin, error = %error, "Reporting handler error");
+        if self.chats.is_empty() {
+            return reference;
+        }
+
+        let held_back = self
+            .limit
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .admit(Instant::now());
+        let Some(held_back) = held_back else {
+            return reference;
+        };
+
+        let mut text = format!(
+            "🚨 Error {}\nWhere: {}\nKind: {}\n{}",
+            reference,
+            origin,
+            error.kind(),
+            sanitize(&error.to_string())
+        );
+        if held_back > 0 {
+            text.push_str(&format!(
+                "\n({} earlier errors were not reported, see the logs)",
+                held_back
+            ));
+        }
+        for chat in &self.chats {
+            if let Err(e) = self.bot.send_message(*chat, text.clone()).await {
+                log::warn!(
+                    "Could not report error {} to chat {}: {}",
+                    reference,
+                    chat,
+                    e
+                );
+            }
+        }
+        reference
+    }
+}
+
+/// A fixed window of [`MAX_REPORTS`] that counts what it turns away.
+#[derive(Default)]
+struct RateLimit {
+    window_start: Option<Instant>,
+    sent: u32,
+    held_back: u32,
+}
+
+impl RateLimit {
+    /// Admits a report unless the window is used up, returning how many were
+    /// held back since the last admitted one.
+    fn admit(&mut self, now: Instant) -> Option<u32> {
+        let expired = self
+            .window_start
+            .is_none_or(|start| now.duration_since(start) >= REPORT_WINDOW);
+        if expired {
+            self.window_start = Some(now);
+            self.sent = 0;
+        }
+        if self.sent >= MAX_REPORTS {
+            self.held_back += 1;
+            return None;
+        }
+        self.sent += 1;
+        Some(std::mem::take(&mut self.held_back))
+    }
+}
+
+/// Keeps reports short and free of secrets: whitespace collapses to single
+/// spaces, anything shaped like a bot token is masked and long text is cut.
+fn sanitize(message: &str) -> String {
+    let words: Vec<String> = message
+        .split_whitespace()
+        .map(|word| {
+            word.split('/')
+                .map(|part| {
+                    if looks_like_token(part) {
+                        "<redacted>"
+                    } else {
+                        part
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("/")
+        })
+        .collect();
+    let summary = words.join(" ");
+
+    match summary.char_indices().nth(MAX_SUMMARY_CHARS) {
+        Some((end, _)) => format!("{}…", &summary[..end]),
+        None => summary,
+    }
+}
+
+/// Bot tokens are the bot's numeric id, a colon and a 35 character secret.
+fn looks_like_token(part: &str) -> bool {
+    let part = part.strip_prefix("bot").unwrap_or(part);
+    let Some((id, secret)) = part.split_once(':') else {
+        return false;
+    };
+    !id.is_empty()
+        && id.chars().all(|c| c.is_ascii_digit())
+        && secret.len() >= 30
+        && secret
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_are_limited_per_window_and_count_the_rest() {
+        let mut limit = RateLimit::default();
+        let start = Instant::now();
+        for _ in 0..MAX_REPORTS {
+            assert_eq!(limit.admit(start), Some(0));
+        }
+        assert_eq!(limit.admit(start), None);
+        assert_eq!(limit.admit(start + Duration::from_secs(59)), None);
+
+        let next_window = start + REPORT_WINDOW;
+        assert_eq!(limit.admit(next_window), Some(2));
+        assert_eq!(limit.admit(next_window), Some(0));
+    }
+
+    #[test]
+    fn summaries_hide_tokens_and_stay_short() {
+        let token = "123456:AAHdqTcvCH1vGWJxfSeofSAs0K5PALDsaw";
+        assert_eq!(
+            sanitize(&format!(
+                "failed  https://api.telegram.org/bot{}/getMe\n{}",
+                token, token
+            )),
+            "failed https://api.telegram.org/<redacted>/getMe <redacted>"
+        );
+
+        let long = sanitize(&"x".repeat(1000));
+        assert_eq!(long.chars().count(), MAX_SUMMARY_CHARS + 1);
+        assert!(long.ends_with('…'));
+    }
+}
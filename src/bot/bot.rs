@@ -1,88 +1,145 @@
-use crate::bot::callbacks::BotCallback;
-use crate::bot::callbacks::BotCallback::ColonyCountUpdate;
-use crate::bot::callbacks::BotCallback::ColonyGetCount;
-use crate::bot::callbacks::BotCallback::ColonyMaintenanceMenu;
-use crate::bot::callbacks::BotCallback::FeedTarantula;
-use crate::bot::callbacks::BotCallback::MainMenu;
-use crate::bot::callbacks::BotCallback::MoltSimple;
-use crate::bot::commands::Command;
-use crate::bot::dialog::DialogueState;
-use crate::bot::keyboards::{
-    feed_command_keyboard, feed_count_selection_keyboard, welcome_keyboard,
-};
+use crate::app::callbacks::BotCallback;
+use crate::app::commands::{error_screen, incident_screen, Command};
+use crate::app::dialogue::DialogueState;
+use crate::app::screen::{Keyboard, Outcome, Transition, View};
+use crate::app::{App, Session};
+use crate::backup::Backups;
+use crate::bot::admin::{AdminCommand, AdminConfig, ErrorReporter, RESTORE_CAPTION};
 use crate::bot::notifications::NotificationSystem;
-use crate::db::db::{AddColonyParams, AddTarantulaParams, TarantulaDB, TarantulaOperations};
+use crate::bot::server::{self, ServerConfig, UpdateMode};
+use crate::config::Config;
+use crate::db::db::TarantulaOperations;
+use crate::db::metered::MeteredDB;
 use crate::error::BotError;
-use crate::models::cricket::ColonyStatus;
-use crate::models::enums::HealthStatus;
-use crate::models::feeding::FeedingEvent;
-use crate::models::models::DbDateTime;
+use crate::metrics::Metrics;
 use crate::models::user::TelegramUser;
 use crate::BotResult;
-use chrono::{NaiveDateTime, Utc};
-use future::BoxFuture;
-use futures_core::future;
-use std::collections::BTreeMap;
-use std::env;
-use std::fmt::Debug;
+use std::fs;
+use std::future;
 use std::sync::Arc;
-use teloxide::dispatching::dialogue::{InMemStorage, Storage};
-use teloxide::dispatching::{Dispatcher, DpHandlerDescription, UpdateFilterExt};
+use teloxide::dispatching::dialogue::{GetChatId, InMemStorage, Storage};
+use teloxide::dispatching::{
+    DefaultKey, Dispatcher, DispatcherBuilder, DpHandlerDescription, HandlerExt, UpdateFilterExt,
+};
 use teloxide::dptree::Handler;
-use teloxide::error_handlers::ErrorHandler;
-use teloxide::payloads::{EditMessageReplyMarkupSetters, SendMessageSetters};
+use teloxide::error_handlers::{ErrorHandler, IgnoringErrorHandler, LoggingErrorHandler};
+use teloxide::net::Download;
+use teloxide::payloads::{
+    AnswerInlineQuerySetters, EditMessageMediaSetters, EditMessageReplyMarkupSetters,
+    SendDocumentSetters, SendMessageSetters, SendPhotoSetters,
+};
 use teloxide::prelude::{CallbackQuery, ChatId, DependencyMap, Message, Requester, Update};
-use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup, MessageId, ParseMode};
-use teloxide::utils::command::BotCommands;
-use teloxide::{dptree, filter_command, Bot, RequestError};
-use BotCallback::ListTarantulas;
+use teloxide::types::{
+    Document, InlineKeyboardButton, InlineKeyboardMarkup, InlineQuery, InlineQueryResult,
+    InlineQueryResultArticle, InputFile, InputMedia, InputMediaPhoto, InputMessageContent,
+    InputMessageContentText, Me, MessageId, ParseMode,
+};
+use teloxide::utils::html;
+use teloxide::{dptree, filter_command, Bot};
+use tracing::{field, instrument, Span};
+
+/// Larger uploads are refused before they are downloaded.
+const MAX_IMPORT_BYTES: u32 = 5 * 1024 * 1024;
 
 #[derive(Clone)]
 pub struct TarantulaBot {
     pub(crate) bot: Bot,
-    db: Arc<dyn TarantulaOperations>,
+    pub(crate) app: App,
     pub(crate) notification_system: Arc<NotificationSystem>,
-    dialogue: Arc<InMemStorage<DialogueState>>,
-}
-
-pub struct ChanErrHandler {
-    bot: Bot,
-}
-
-impl<E> ErrorHandler<E> for ChanErrHandler
-where
-    E: Debug + Sync + Send + 'static,
-{
-    fn handle_error(self: Arc<Self>, error: E) -> BoxFuture<'static, ()> {
-        let default_chat = env::var("DEFAULT_CHAT_ID").unwrap();
-        Box::pin(async move {
-            let _ = self
-                .bot
-                .send_message(default_chat, format!("{:?}", error))
-                .await
-                .inspect_err(|e| log::error!("error {} while processing {:?}", e, error));
-        })
-    }
+    pub(crate) dialogue: Arc<InMemStorage<DialogueState>>,
+    pub(crate) metrics: Arc<Metrics>,
+    pub(crate) reporter: Arc<ErrorReporter>,
+    pub(crate) admin: AdminConfig,
+    pub(crate) backups: Arc<Backups>,
 }
 
 impl TarantulaBot {
-    pub fn new(token: &str) -> Self {
-        let bot = Bot::new(token);
-        let db_path = env::var("DATABASE_PATH").unwrap_or_else(|_| "tarantulas.sqlite".to_string());
-        let db = Arc::new(TarantulaDB::new(&db_path).expect("Failed to open database"));
-        let notification_system = Arc::new(NotificationSystem::new(bot.clone(), db.clone()));
+    pub fn new(config: &Config) -> BotResult<Self> {
+        let bot = Bot::new(config.token()?);
+        let db = crate::db::open(&config.database, config.alerts)?;
+        Ok(Self::with_db(bot, db, config))
+    }
+
+    pub(crate) fn with_db(
+        bot: Bot,
+        db: Arc<dyn TarantulaOperations + Send + Sync>,
+        config: &Config,
+    ) -> Self {
+        let metrics = Arc::new(Metrics::new());
+        let db: Arc<dyn TarantulaOperations + Send + Sync> =
+            Arc::new(MeteredDB::new(db, metrics.clone()));
+        let notification_system = Arc::new(NotificationSystem::new(
+            bot.clone(),
+            db.clone(),
+            metrics.clone(),
+            config,
+        ));
 
         Self {
+            reporter: Arc::new(ErrorReporter::new(bot.clone(), config.admin.clone())),
+            admin: config.admin.clone(),
+            backups: Arc::new(Backups::new(
+                db.clone(),
+                config.backup.clone(),
+                metrics.clone(),
+            )),
             bot,
-            db,
+            app: App::new(db),
             notification_system,
             dialogue: InMemStorage::<DialogueState>::new(),
+            metrics,
         }
     }
 
-    pub async fn run(self) {
+    /// Long-polls for updates, or takes them through the webhook in webhook
+    /// mode. Either way the probes, and `/metrics` unless switched off, are
+    /// served on `config.addr`.
+    pub async fn run(self, config: ServerConfig) -> BotResult<()> {
         let arc_notif_system = self.notification_system.clone();
         tokio::spawn((*arc_notif_system).clone().start());
+        tokio::spawn(self.backups.clone().run());
+        // Failed handlers are already logged by their span and reported by
+        // `handled`, the dispatcher has nothing left to do with the error.
+        let error_handler = IgnoringErrorHandler::new();
+
+        let bot = self.bot.clone();
+        let mut probes = server::probes(bot.clone(), self.app.db.clone());
+        if config.metrics {
+            probes = probes.merge(server::metrics(self.metrics.clone(), self.app.db.clone()));
+        }
+        let http = server::bind(config.addr).await?;
+        let mut dispatcher = self
+            .dispatcher(error_handler)
+            .enable_ctrlc_handler()
+            .build();
+
+        match config.mode {
+            UpdateMode::Webhook => {
+                let (updates, stopped, route) =
+                    server::webhook(bot, &config.webhook, config.addr).await?;
+                tokio::spawn(server::serve(http, route.merge(probes), stopped));
+                dispatcher
+                    .dispatch_with_listener(
+                        updates,
+                        LoggingErrorHandler::with_custom_text("Webhook listener failed"),
+                    )
+                    .await;
+            }
+            UpdateMode::Polling => {
+                tokio::spawn(server::serve(http, probes, future::pending()));
+                dispatcher.dispatch().await;
+            }
+        }
+        Ok(())
+    }
+
+    /// Wires the command, callback and dialogue handlers together. Notifications
+    /// and the ctrl-c handler are left to [`Self::run`] so tests can drive the
+    /// same dispatcher against a mock Bot API.
+    pub(crate) fn dispatcher(
+        self,
+        error_handler: Arc<dyn ErrorHandler<BotError> + Send + Sync>,
+    ) -> DispatcherBuilder<Bot, BotError, DefaultKey> {
         let handler = Self::build_handler();
 
         let mut container = DependencyMap::new();
@@ -96,33 +153,134 @@ impl TarantulaBot {
                 .branch(TarantulaBot::dialogue_handler()),
         )
         .dependencies(container)
-        .error_handler(Arc::new(ChanErrHandler {
-            bot: (*arc).clone().bot,
-        }))
-        .enable_ctrlc_handler()
-        .build()
-        .dispatch()
-        .await;
+        .error_handler(error_handler)
     }
 
     fn build_handler() -> Handler<'static, DependencyMap, BotResult<()>, DpHandlerDescription> {
-        let handler = dptree::entry()
-            .branch(Update::filter_callback_query().endpoint(
-                move |a: Arc<TarantulaBot>, q: CallbackQuery| async move {
-                    a.handle_callback(&a.clone(), q).await
+        let handler =
+            dptree::entry()
+                .branch(Self::admin_handler())
+                .branch(Update::filter_callback_query().endpoint(
+                    move |a: Arc<TarantulaBot>, q: CallbackQuery| async move {
+                        let origin = format!("callback {}", callback_name(q.data.as_deref()));
+                        let chat_id = q.chat_id();
+                        let result = a.handle_callback(q).await;
+                        a.handled(chat_id, &origin, result).await
+                    },
+                ))
+                .branch(Update::filter_inline_query().endpoint(
+                    move |a: Arc<TarantulaBot>, query: InlineQuery, me: Me| async move {
+                        let result = a.handle_inline_query(query, me).await;
+                        a.handled(None, "inline query", result).await
+                    },
+                ))
+                .branch(
+                    Update::filter_message()
+                        .branch(filter_command::<Command, _>().endpoint(
+                            move |a: Arc<TarantulaBot>, msg: Message, cmd: Command| async move {
+                                let origin = format!("command /{}", cmd.name());
+                                let chat_id = msg.chat.id;
+                                let result = a.handle_command(msg, cmd).await;
+                                a.handled(Some(chat_id), &origin, result).await
+                            },
+                        ))
+                        .branch(dptree::filter_map(|msg: Message| msg.document().cloned()).endpoint(
+                            move |a: Arc<TarantulaBot>, msg: Message, document: Document| async move {
+                                let chat_id = msg.chat.id;
+                                let result = a.handle_import_upload(msg, document).await;
+                                a.handled(Some(chat_id), "import upload", result).await
+                            },
+                        )),
+                );
+        handler
+    }
+
+    /// Admin commands, and backups uploaded to restore. Anything else sent in
+    /// an admin chat is handled like a keeper's message.
+    fn admin_handler() -> Handler<'static, DependencyMap, BotResult<()>, DpHandlerDescription> {
+        Update::filter_message()
+            .filter(|a: Arc<TarantulaBot>, msg: Message| a.admin.is_admin(msg.chat.id))
+            .branch(filter_command::<AdminCommand, _>().endpoint(
+                move |a: Arc<TarantulaBot>, msg: Message, cmd: AdminCommand| async move {
+                    let origin = format!("admin /{}", cmd.name());
+                    let chat_id = msg.chat.id;
+                    let result = a.handle_admin_command(msg, cmd).await;
+                    a.handled(Some(chat_id), &origin, result).await
                 },
             ))
             .branch(
-                Update::filter_message().branch(filter_command::<Command, _>().endpoint(
-                    move |a: Arc<TarantulaBot>, msg: Message, cmd: Command| async move {
-                        a.handle_command(msg, cmd).await
+                dptree::filter_map(|msg: Message| {
+                    msg.document()
+                        .filter(|_| msg.caption() == Some(RESTORE_CAPTION))
+                        .cloned()
+                })
+                .endpoint(
+                    move |a: Arc<TarantulaBot>, msg: Message, document: Document| async move {
+                        let chat_id = msg.chat.id;
+                        let result = a.handle_restore_upload(chat_id, document).await;
+                        a.handled(Some(chat_id), "admin restore upload", result)
+                            .await
                     },
-                )),
-            );
-        handler
+                ),
+            )
+    }
+
+    fn dialogue_handler() -> Handler<'static, DependencyMap, BotResult<()>, DpHandlerDescription> {
+        Update::filter_message()
+            .enter_dialogue::<Message, InMemStorage<DialogueState>, DialogueState>()
+            .endpoint(
+                move |a: Arc<TarantulaBot>, state: DialogueState, msg: Message| async move {
+                    let origin = format!("dialogue {}", state.name());
+                    let chat_id = msg.chat.id;
+                    let result = a.handle_dialogue_message(state, msg).await;
+                    a.handled(Some(chat_id), &origin, result).await
+                },
+            )
     }
 
+    /// Counts a failed handler and tells the keeper in `chat_id`. Internal
+    /// errors are also reported to the admins, under a reference the keeper
+    /// is given. The error is passed on to the dispatcher either way.
+    async fn handled(
+        &self,
+        chat_id: Option<ChatId>,
+        origin: &str,
+        result: BotResult<()>,
+    ) -> BotResult<()> {
+        let Err(e) = &result else {
+            return result;
+        };
+        self.metrics.handler_failed(e);
+
+        let screen = if e.is_internal() {
+            incident_screen(&self.reporter.report(e, origin).await)
+        } else {
+            error_screen(e)
+        };
+        if let Some(chat_id) = chat_id {
+            if let Err(send_error) = self.show(chat_id, None, Outcome::send(screen)).await {
+                log::warn!(
+                    "Could not tell chat {} about an error: {}",
+                    chat_id,
+                    send_error
+                );
+            }
+        }
+        result
+    }
+
+    #[instrument(
+        name = "command",
+        skip_all,
+        fields(
+            command = cmd.name(),
+            user_id = msg.from.as_ref().map(|u| u.id.0),
+            chat_id = msg.chat.id.0,
+        ),
+        err(Display)
+    )]
     async fn handle_command(&self, msg: Message, cmd: Command) -> BotResult<()> {
+        self.metrics.command_handled(cmd.name());
         let user = msg.from.unwrap();
         let user = TelegramUser {
             telegram_id: user.id.0,
@@ -130,253 +288,296 @@ impl TarantulaBot {
             first_name: user.first_name,
             last_name: user.last_name,
         };
-        self.db.ensure_user_exists(&user).await?;
+        if let Command::Start(_) = cmd {
+            self.notification_system
+                .register_chat(user.telegram_id, msg.chat.id)
+                .await;
+        }
 
-        let result = match cmd {
-            Command::Help => {
+        let outcome = self.app.command(&user, cmd).await?;
+        self.show(msg.chat.id, None, outcome).await
+    }
+
+    #[instrument(
+        name = "admin",
+        skip_all,
+        fields(command = cmd.name(), chat_id = msg.chat.id.0),
+        err(Display)
+    )]
+    async fn handle_admin_command(&self, msg: Message, cmd: AdminCommand) -> BotResult<()> {
+        self.metrics.command_handled(cmd.name());
+        match cmd {
+            AdminCommand::Backup => {
+                let backup = self.backups.take().await?;
                 self.bot
-                    .send_message(msg.chat.id, Command::descriptions().to_string())
+                    .send_document(msg.chat.id, InputFile::file(&backup.path))
+                    .caption(format!(
+                        "💾 {} ({} KiB)",
+                        backup.file_name(),
+                        backup.bytes / 1024
+                    ))
                     .await?;
                 Ok(())
             }
-            Command::Start => {
-                self.notification_system
-                    .register_chat(user.telegram_id, msg.chat.id)
-                    .await;
-                self.send_welcome_message(msg.chat.id, user.telegram_id)
-                    .await
-            }
-            Command::AddTarantula(name, species, date, age_months, notes) => {
-                self.db
-                    .add_tarantula(
-                        user.telegram_id,
-                        AddTarantulaParams {
-                            name,
-                            species_id: species,
-                            acquisition_date: date,
-                            estimated_age_months: age_months,
-                            notes: Some(notes),
-                            enclosure_number: None,
-                        },
-                    )
-                    .await?;
-                self.send_welcome_message(msg.chat.id, user.telegram_id)
-                    .await
-            }
-            Command::AddColony(colony_name, size_type_id, current_count, container_name, notes) => {
-                self.db
-                    .add_colony(
-                        user.telegram_id,
-                        AddColonyParams {
-                            colony_name,
-                            size_type_id,
-                            current_count,
-                            container_number: container_name,
-                            notes: Some(notes),
-                        },
-                    )
-                    .await?;
-                self.send_welcome_message(msg.chat.id, user.telegram_id)
-                    .await
+            AdminCommand::Restore => {
+                self.reply_with_send(
+                    msg.chat.id,
+                    format!(
+                        "♻️ Send the backup as a file with {} as its caption. \
+                         The current data is backed up before it is replaced.",
+                        RESTORE_CAPTION
+                    ),
+                    None,
+                )
+                .await
             }
-        };
-
-        if let Err(e) = result {
-            self.handle_command_error(msg.chat.id, e).await?;
         }
-        Ok(())
-    }
-
-    async fn handle_command_error(
-        &self,
-        chat_id: ChatId,
-        error: BotError,
-    ) -> Result<(), RequestError> {
-        let error_message = match error {
-            BotError::NotFound(msg) => format!("❌ {}", msg),
-            BotError::ValidationError(msg) => format!("⚠️ {}", msg),
-            BotError::Database(e) => {
-                log::error!("Database error: {:?}", e);
-                "❌ A database error occurred. Please try again later.".to_string()
-            }
-            BotError::Telegram(e) => {
-                log::error!("Telegram error: {:?}", e);
-                "❌ A communication error occurred. Please try again later.".to_string()
-            }
-            _ => {
-                log::error!("Unexpected error: {:?}", error);
-                "❌ An unexpected error occurred. Please try again later.".to_string()
-            }
-        };
-
-        let keyboard = Self::back_to_menu_keyboard();
-
-        self.reply_with_send(chat_id, error_message, Some(keyboard))
-            .await
-            .inspect_err(|e| log::error!("something went wrong {}", e))
-            .expect("TODO: panic message");
-        Ok(())
-    }
-
-    pub(crate) async fn feed_command(
-        &self,
-        chat_id: ChatId,
-        message_id: MessageId,
-        tarantula_id: i64,
-        user_id: u64,
-    ) -> BotResult<()> {
-        let tarantula = self.db.get_tarantula_by_id(user_id, tarantula_id).await?;
-        let colonies = self.db.get_colony_status(user_id).await?;
-        let keyboard = feed_command_keyboard(tarantula_id, colonies);
-        self.replay_with_edit(
-            chat_id,
-            message_id,
-            format!(
-                "Feeding *{}*\nSelect cricket colony to use:",
-                tarantula.name
-            ),
-            InlineKeyboardMarkup::new(keyboard),
-        )
-        .await
     }
 
-    pub(crate) async fn feed_colony_selection(
-        &self,
-        chat_id: ChatId,
-        message_id: MessageId,
-        tarantula_id: i64,
-        colony_id: i64,
-        user_id: u64,
-    ) -> BotResult<()> {
-        let colony = self.colony_status(colony_id, user_id).await?;
-        let keyboard = feed_count_selection_keyboard(tarantula_id, colony_id);
-        self.replay_with_edit(
+    /// Downloads an uploaded backup and restores it, telling the admin where
+    /// the data it replaced went.
+    #[instrument(
+        name = "restore",
+        skip_all,
+        fields(chat_id = chat_id.0, file = document.file_name.as_deref()),
+        err(Display)
+    )]
+    async fn handle_restore_upload(&self, chat_id: ChatId, document: Document) -> BotResult<()> {
+        let contents = self.download(&document, "the backup").await?;
+        let upload = self.backups.upload_path()?;
+        fs::write(&upload, contents)?;
+
+        let restored = self.backups.restore(&upload).await;
+        if let Err(e) = fs::remove_file(&upload) {
+            log::warn!("Could not delete {}: {}", upload.display(), e);
+        }
+        let previous = restored?;
+        self.reply_with_send(
             chat_id,
-            message_id,
             format!(
-                "Selected colony: {} ({})\nCurrent count: {}\nHow many crickets?",
-                colony.colony_name,
-                colony.size_type.to_db_name(),
-                colony.current_count
+                "♻️ Restored the database from {}. The data it replaced is in {}.",
+                html::escape(document.file_name.as_deref().unwrap_or("the upload")),
+                previous.file_name()
             ),
-            keyboard,
+            None,
         )
         .await
     }
 
-    async fn colony_status(&self, colony_id: i64, user_id: u64) -> Result<ColonyStatus, BotError> {
-        let colony = self
-            .db
-            .get_colony_status(user_id)
-            .await?
-            .into_iter()
-            .find(|c| c.id == colony_id)
-            .ok_or_else(|| BotError::NotFound("Colony not found".to_string()))?;
-        Ok(colony)
-    }
-
-    pub(crate) async fn feed_confirmation(
-        &self,
-        chat_id: ChatId,
-        message_id: MessageId,
-        tarantula_id: i64,
-        colony_id: i64,
-        count: i32,
-        user_id: u64,
-    ) -> BotResult<()> {
-        let feeding_event = FeedingEvent {
-            id: None,
-            tarantula_id,
-            feeding_date: DbDateTime::default(),
-            cricket_colony_id: colony_id,
-            number_of_crickets: count,
-            feeding_status_id: 1,
-            notes: None,
+    /// A keeper's file, previewed as an import of their history.
+    #[instrument(
+        name = "import",
+        skip_all,
+        fields(chat_id = msg.chat.id.0, size = document.file.size),
+        err(Display)
+    )]
+    async fn handle_import_upload(&self, msg: Message, document: Document) -> BotResult<()> {
+        let Some(user) = msg.from else {
+            return Ok(());
+        };
+        if document.file.size > MAX_IMPORT_BYTES {
+            return Err(BotError::ValidationError(format!(
+                "Files to import can be at most {} MB",
+                MAX_IMPORT_BYTES / 1024 / 1024
+            )));
+        }
+        let user = TelegramUser {
+            telegram_id: user.id.0,
+            username: user.username,
+            first_name: user.first_name,
+            last_name: user.last_name,
         };
 
-        self.db.record_feeding(user_id, feeding_event).await?;
-
-        self.replay_with_edit(
-            chat_id,
-            message_id,
-            format!("✅ Feeding recorded: {} crickets", count),
-            Self::back_to_menu_keyboard(),
-        )
-        .await
+        let contents = self.download(&document, "the file").await?;
+        let file_name = document.file_name.as_deref().unwrap_or_default();
+        let outcome = self.app.import_preview(&user, file_name, &contents).await?;
+        self.show(msg.chat.id, None, outcome).await
     }
 
-    pub(crate) async fn send_welcome_message(
-        &self,
-        chat_id: ChatId,
-        user_id: u64,
-    ) -> BotResult<()> {
-        let keyboard = welcome_keyboard();
-        let feeding_due = self.db.get_tarantulas_due_feeding(user_id).await?;
-        let health_alerts = self.db.get_health_alerts(user_id).await?;
-
-        let recent_molts = self
-            .db
-            .get_recent_molt_records(user_id, 100)
-            .await?
+    async fn download(&self, document: &Document, what: &str) -> BotResult<Vec<u8>> {
+        let file = self.bot.get_file(document.file.id.clone()).await?;
+        let mut contents = Vec::new();
+        self.bot
+            .download_file(&file.path, &mut contents)
+            .await
+            .map_err(|e| BotError::OperationError(format!("Could not download {}: {}", what, e)))?;
+        Ok(contents)
+    }
+
+    #[instrument(
+        name = "callback",
+        skip_all,
+        fields(
+            callback = field::Empty,
+            data = query.data.as_deref(),
+            user_id = query.from.id.0,
+            chat_id = field::Empty,
+        ),
+        err(Display)
+    )]
+    async fn handle_callback(&self, query: CallbackQuery) -> BotResult<()> {
+        self.bot.answer_callback_query(query.id.clone()).await?;
+
+        let (Some(data), Some(chat_id)) = (query.data.as_deref(), query.chat_id()) else {
+            return Ok(());
+        };
+        let callback = callback_name(Some(data));
+        Span::current()
+            .record("callback", callback)
+            .record("chat_id", chat_id.0);
+        self.metrics.callback_handled(callback);
+        let session = Session {
+            user_id: query.from.id.0,
+            dialogue: self.dialogue.clone().get_dialogue(chat_id).await?,
+        };
+        let outcome = self.app.tap(&session, data).await?;
+        let tapped = query.message.as_ref().and_then(|m| m.regular_message());
+        self.show(chat_id, tapped, outcome).await
+    }
+
+    /// Answers `@bot query` typed in any chat with the keeper's matching
+    /// tarantulas. Results differ per keeper, so Telegram must not share them.
+    #[instrument(
+        name = "inline",
+        skip_all,
+        fields(user_id = query.from.id.0, query = query.query.as_str()),
+        err(Display)
+    )]
+    async fn handle_inline_query(&self, query: InlineQuery, me: Me) -> BotResult<()> {
+        self.metrics.inline_query_handled();
+        let cards = self.app.inline_cards(query.from.id.0, &query.query).await?;
+        let results: Vec<InlineQueryResult> = cards
             .into_iter()
-            .filter(|r| {
-                if let Ok(date) = NaiveDateTime::parse_from_str(&r.molt_date, "%Y-%m-%d %H:%M:%S") {
-                    let now = Utc::now().naive_utc();
-                    now.signed_duration_since(date).num_days() <= 30
-                } else {
-                    false
-                }
+            .map(|card| {
+                let content = InputMessageContentText::new(card.text).parse_mode(ParseMode::Html);
+                InlineQueryResultArticle::new(
+                    card.id,
+                    card.title,
+                    InputMessageContent::Text(content),
+                )
+                .description(card.description)
+                .reply_markup(start_links(&me, card.buttons))
+                .into()
             })
-            .count();
+            .collect();
+        self.bot
+            .answer_inline_query(query.id, results)
+            .is_personal(true)
+            .cache_time(0)
+            .await?;
+        Ok(())
+    }
 
-        let message = format!(
-            "Welcome to your Tarantula Management System! 🕷\n\n\
-        *Quick Stats:*\n\
-        • Feeding Due: {}\n\
-        • Health Alerts: {}\n\
-        • Recent Molts: {} (30 days)",
-            feeding_due.len(),
-            health_alerts.len(),
-            recent_molts
-        );
-        self.reply_with_send(chat_id, message, Some(keyboard)).await
+    #[instrument(
+        name = "dialogue",
+        skip_all,
+        fields(
+            dialogue = state.name(),
+            user_id = msg.from.as_ref().map(|u| u.id.0),
+            chat_id = msg.chat.id.0,
+        ),
+        err(Display)
+    )]
+    async fn handle_dialogue_message(&self, state: DialogueState, msg: Message) -> BotResult<()> {
+        let Some(user) = msg.from.as_ref() else {
+            return Ok(());
+        };
+        let session = Session {
+            user_id: user.id.0,
+            dialogue: Some(state),
+        };
+        let outcome = match msg.photo().and_then(|sizes| sizes.last()) {
+            Some(photo) => {
+                self.app
+                    .reply_photo(&session, &photo.file.id, msg.caption())
+                    .await?
+            }
+            None => {
+                self.app
+                    .reply(&session, msg.text().unwrap_or_default())
+                    .await?
+            }
+        };
+        self.show(msg.chat.id, None, outcome).await
     }
 
-    pub(crate) async fn list_tarantulas(
+    /// Applies the dialogue transition, then delivers the views in order.
+    /// `Replace` edits the tapped message and falls back to sending when the
+    /// outcome was not triggered by a button, or the button was under a photo.
+    async fn show(
         &self,
         chat_id: ChatId,
-        message_id: MessageId,
-        user_id: u64,
+        tapped: Option<&Message>,
+        outcome: Outcome,
     ) -> BotResult<()> {
-        let tarantulas = self.db.get_all_tarantulas(user_id).await?;
-        let mut message = String::from("🕷 *Your Tarantulas*\n\n");
-
-        if tarantulas.is_empty() {
-            message = String::from("No tarantulas found in the database.");
+        match outcome.dialogue {
+            Transition::Stay => {}
+            Transition::Enter(state) => {
+                self.metrics.dialogue_entered(chat_id.0, state.name());
+                self.dialogue
+                    .clone()
+                    .update_dialogue(chat_id, state)
+                    .await?
+            }
+            // The only storage error is "no dialogue", which is already the goal.
+            Transition::Exit => {
+                self.metrics.dialogue_exited(chat_id.0);
+                let _ = self.dialogue.clone().remove_dialogue(chat_id).await;
+            }
         }
 
-        for t in &tarantulas {
-            let feeding_display = t.days_since_feeding.map_or("Unknown".to_string(), |days| {
-                if days < 1.0 {
-                    "Today".to_string()
-                } else {
-                    format!("{:.1} days", days)
+        let tapped = tapped.map(|m| (m.id, m.photo().is_some()));
+        for view in outcome.views {
+            match (view, tapped) {
+                (View::Replace(screen), Some((message_id, false))) => {
+                    self.replay_with_edit(
+                        chat_id,
+                        message_id,
+                        screen.text,
+                        inline_keyboard(screen.buttons),
+                    )
+                    .await?
                 }
-            });
-
-            message.push_str(&format!(
-                "*{}* ({})\n▫️ Status: {}\n▫️ Last fed: {}\n\n",
-                t.name, t.species_name, t.current_status, feeding_display
-            ));
+                (View::Replace(screen) | View::Send(screen), _) => {
+                    let keyboard =
+                        (!screen.buttons.is_empty()).then(|| inline_keyboard(screen.buttons));
+                    self.reply_with_send(chat_id, screen.text, keyboard).await?
+                }
+                (View::Photo(photo), Some((message_id, true))) => {
+                    let media = InputMediaPhoto::new(InputFile::file_id(photo.file_id))
+                        .caption(photo.caption)
+                        .parse_mode(ParseMode::Html);
+                    self.bot
+                        .edit_message_media(chat_id, message_id, InputMedia::Photo(media))
+                        .reply_markup(inline_keyboard(photo.buttons))
+                        .await?;
+                }
+                (View::Photo(photo), _) => {
+                    let mut request = self
+                        .bot
+                        .send_photo(chat_id, InputFile::file_id(photo.file_id))
+                        .caption(photo.caption)
+                        .parse_mode(ParseMode::Html);
+                    if !photo.buttons.is_empty() {
+                        request = request.reply_markup(inline_keyboard(photo.buttons));
+                    }
+                    request.await?;
+                }
+                (View::Document(document), _) => {
+                    self.bot
+                        .send_document(
+                            chat_id,
+                            InputFile::memory(document.contents).file_name(document.file_name),
+                        )
+                        .caption(document.caption)
+                        .await?;
+                }
+            }
         }
-
-        let keyboard = Self::back_to_menu_keyboard();
-
-        self.replay_with_edit(chat_id, message_id, message, keyboard)
-            .await
+        Ok(())
     }
 
-    async fn replay_with_edit(
+    pub(crate) async fn replay_with_edit(
         &self,
         chat_id: ChatId,
         message_id: MessageId,
@@ -396,694 +597,7 @@ impl TarantulaBot {
             .map_err(|e| e.into())
     }
 
-    pub(crate) async fn feeding_schedule(
-        &self,
-        chat_id: ChatId,
-        message_id: MessageId,
-        user_id: u64,
-    ) -> BotResult<()> {
-        let due_feedings = self.db.get_tarantulas_due_feeding(user_id).await?;
-
-        let mut message = String::from("🍽 *Feeding Schedule*\n\n");
-        for t in &due_feedings {
-            message.push_str(&format!(
-                "*{}* needs feeding\n- Last fed: {} days ago\n\n",
-                t.name,
-                t.days_since_feeding.unwrap_or(0.0)
-            ));
-        }
-
-        if due_feedings.is_empty() {
-            message = String::from("No feedings currently due! 🎉");
-        }
-
-        let keyboard = Self::back_to_menu_keyboard();
-
-        self.replay_with_edit(chat_id, message_id, message, keyboard)
-            .await
-    }
-
-    pub(crate) async fn health_alerts(
-        &self,
-        chat_id: ChatId,
-        message_id: MessageId,
-        user_id: u64,
-    ) -> BotResult<()> {
-        let alerts = self.db.get_health_alerts(user_id).await?;
-
-        let mut message = String::from("🏥 *Health Alerts*\n\n");
-        for alert in &alerts {
-            message.push_str(&format!(
-                "*{}* - {}\n- Days in state: {}\n\n",
-                alert.name, alert.alert_type, alert.days_in_state
-            ));
-        }
-
-        if alerts.is_empty() {
-            message = String::from("No health alerts! All tarantulas appear healthy. 🎉");
-        }
-
-        let keyboard = Self::back_to_menu_keyboard();
-
-        self.replay_with_edit(chat_id, message_id, message, keyboard)
-            .await
-    }
-
-    pub(crate) async fn maintenance(
-        &self,
-        chat_id: ChatId,
-        message_id: MessageId,
-        user_id: u64,
-    ) -> BotResult<()> {
-        let tasks = self.db.get_maintenance_tasks(user_id).await?;
-
-        let mut message = String::from("🧹 *Maintenance Tasks*\n\n");
-        for task in &tasks {
-            message.push_str(&format!(
-                "*{}* ({})\n- {}\n- Priority: {}\n\n",
-                task.name, task.enclosure_number, task.required_action, task.priority
-            ));
-        }
-
-        if tasks.is_empty() {
-            message = String::from("No maintenance tasks currently due! 🎉");
-        }
-
-        let keyboard = Self::back_to_menu_keyboard();
-
-        self.replay_with_edit(chat_id, message_id, message, keyboard)
-            .await
-    }
-
-    pub(crate) async fn colonies(
-        &self,
-        chat_id: ChatId,
-        message_id: MessageId,
-        user_id: u64,
-    ) -> BotResult<()> {
-        let colonies = self.db.get_colony_status(user_id).await?;
-
-        let mut message = String::from("🦗 *Cricket Colonies*\n\n");
-        for colony in &colonies {
-            message.push_str(&format!(
-                "*{}* ({}):\n- Current count: {}\n- Used this week: {}\n- Weeks remaining: {:.1}\n\n",
-                colony.colony_name,
-                colony.size_type.to_db_name(),
-                colony.current_count,
-                colony.crickets_used_7_days,
-                colony.weeks_remaining.unwrap_or(0.0)
-            ));
-        }
-
-        if colonies.is_empty() {
-            message = String::from("No cricket colonies found in the database.");
-        }
-
-        let keyboard = Self::back_to_menu_keyboard();
-
-        self.replay_with_edit(chat_id, message_id, message, keyboard)
-            .await
-    }
-
-    pub(crate) async fn record_molt_command(
-        &self,
-        chat_id: ChatId,
-        message_id: MessageId,
-        tarantula_id: i64,
-        size: f32,
-        user_id: u64,
-    ) -> BotResult<()> {
-        self.db
-            .record_molt(tarantula_id, size, None, None, user_id)
-            .await?;
-
-        let keyboard = Self::back_to_menu_keyboard();
-        self.reply_with_send(
-            chat_id,
-            "Molt recorded \nThank you!".to_string(),
-            Some(keyboard),
-        )
-        .await
-    }
-
-    pub(crate) async fn health_check_command(
-        &self,
-        chat_id: ChatId,
-        message_id: MessageId,
-        tarantula_id: i64,
-        user_id: u64,
-    ) -> BotResult<()> {
-        let tarantula = self.db.get_tarantula_by_id(user_id, tarantula_id).await?;
-
-        let keyboard = InlineKeyboardMarkup::new(vec![
-            vec![InlineKeyboardButton::callback(
-                "✅ Healthy",
-                BotCallback::HealthStatus(tarantula_id, 1).to_string(),
-            )],
-            vec![InlineKeyboardButton::callback(
-                "⚠️ Monitor",
-                BotCallback::HealthStatus(tarantula_id, 2).to_string(),
-            )],
-            vec![InlineKeyboardButton::callback(
-                "🚨 Critical",
-                BotCallback::HealthStatus(tarantula_id, 3).to_string(),
-            )],
-            vec![InlineKeyboardButton::callback(
-                "« Cancel",
-                MainMenu.to_string(),
-            )],
-        ]);
-
-        self.replay_with_edit(
-            chat_id,
-            message_id,
-            format!(
-                "Health check for *{}*\nSelect current health status:",
-                tarantula.name
-            ),
-            keyboard,
-        )
-        .await
-    }
-
-    pub(crate) async fn health_status_command(
-        &self,
-        chat_id: ChatId,
-        message_id: MessageId,
-        tarantula_id: i64,
-        health_status: HealthStatus,
-        user_id: u64,
-    ) -> BotResult<()> {
-        self.db
-            .record_health_check(user_id, tarantula_id, health_status, None)
-            .await?;
-        let keyboard = Self::back_to_menu_keyboard();
-
-        self.replay_with_edit(
-            chat_id,
-            message_id,
-            "Health status recorded \nThank you!".to_string(),
-            keyboard,
-        )
-        .await
-    }
-    pub(crate) async fn colony_maintenance_menu(
-        &self,
-        chat_id: ChatId,
-        message_id: MessageId,
-        colony_id: i64,
-        user_id: u64,
-    ) -> BotResult<()> {
-        let colony = self.colony_status(colony_id, user_id).await?;
-        self.colony_maintenance_command(chat_id, message_id, &colony.colony_name, user_id)
-            .await
-    }
-
-    pub(crate) async fn colony_maintenance_command(
-        &self,
-        chat_id: ChatId,
-        message_id: MessageId,
-        colony_name: &str,
-        user_id: u64,
-    ) -> BotResult<()> {
-        let colonies = self.db.get_colony_status(user_id).await?;
-        let colony = colonies
-            .iter()
-            .find(|c| c.colony_name.eq_ignore_ascii_case(colony_name))
-            .ok_or_else(|| BotError::NotFound(format!("Colony '{}' not found", colony_name)))?;
-
-        let keyboard = InlineKeyboardMarkup::new(vec![
-            vec![InlineKeyboardButton::callback(
-                "📝 Update Count",
-                ColonyGetCount(colony.id).to_string(),
-            )],
-            vec![InlineKeyboardButton::callback(
-                "« Cancel",
-                MainMenu.to_string(),
-            )],
-        ]);
-
-        self.replay_with_edit(
-            chat_id,
-            message_id,
-            format!(
-                "*Cricket Colony Maintenance*\n\nColony: {}\nCurrent count: {}\nSize: {}\n\nSelect maintenance action:",
-                colony.colony_name, colony.current_count, colony.size_type.to_db_name()
-            ), keyboard)
-            .await
-    }
-    pub(crate) async fn status_overview(
-        &self,
-        chat_id: ChatId,
-        message_id: MessageId,
-        user_id: u64,
-    ) -> BotResult<()> {
-        let due_feedings = self.db.get_tarantulas_due_feeding(user_id).await?;
-        let health_alerts = self.db.get_health_alerts(user_id).await?;
-        let colonies = self.db.get_colony_status(user_id).await?;
-
-        let message = format!(
-            "*System Overview*\n\n\
-            🍽 *Feeding Status*\n\
-            • {} tarantulas due feeding\n\
-            • Longest wait: {} days\n\n\
-            🏥 *Health Status*\n\
-            • {} active health alerts\n\
-            • {} critical cases\n\n\
-            🦗 *Colony Status*\n\
-            • {} active colonies\n\
-            • Total crickets: {}\n\n\
-            🧹 *Maintenance*\n\
-            • {} tasks due",
-            due_feedings.len(),
-            due_feedings
-                .iter()
-                .map(|t| t.days_since_feeding.unwrap_or(0.0))
-                .fold(0.0, f64::max),
-            health_alerts.len(),
-            health_alerts
-                .iter()
-                .filter(|a| a.alert_type == "Critical")
-                .count(),
-            colonies.len(),
-            colonies.iter().map(|c| c.current_count).sum::<i32>(),
-            0 // TODO: Implement maintenance task count
-        );
-
-        let keyboard = Self::back_to_menu_keyboard();
-
-        self.replay_with_edit(chat_id, message_id, message, keyboard)
-            .await
-    }
-
-    pub(crate) async fn record_feeding_menu(
-        &self,
-        chat_id: ChatId,
-        message_id: MessageId,
-        user_id: u64,
-    ) -> BotResult<()> {
-        let tarantulas = self.db.get_all_tarantulas(user_id).await?;
-
-        let keyboard: Vec<Vec<InlineKeyboardButton>> = tarantulas
-            .chunks(2)
-            .map(|chunk| {
-                chunk
-                    .iter()
-                    .map(|t| {
-                        InlineKeyboardButton::callback(
-                            format!("{} ({})", t.name, t.species_name),
-                            FeedTarantula(t.id).to_string(),
-                        )
-                    })
-                    .collect()
-            })
-            .collect();
-        let keyboard = Self::with_back_button(keyboard);
-
-        let msg = "*Record Feeding*\n\nSelect a tarantula:";
-        self.replay_with_edit(
-            chat_id,
-            message_id,
-            msg.to_string(),
-            InlineKeyboardMarkup::new(keyboard),
-        )
-        .await
-    }
-
-    pub(crate) async fn record_health_check_menu(
-        &self,
-        chat_id: ChatId,
-        message_id: MessageId,
-        user_id: u64,
-    ) -> BotResult<()> {
-        let tarantulas = self.db.get_all_tarantulas(user_id).await?;
-
-        let keyboard: Vec<Vec<InlineKeyboardButton>> = tarantulas
-            .chunks(2)
-            .map(|chunk| {
-                chunk
-                    .iter()
-                    .map(|t| {
-                        InlineKeyboardButton::callback(
-                            format!("{} ({})", t.name, t.species_name),
-                            BotCallback::HealthCheck(t.id).to_string(),
-                        )
-                    })
-                    .collect()
-            })
-            .collect();
-        let keyboard = Self::with_back_button(keyboard);
-
-        self.replay_with_edit(
-            chat_id,
-            message_id,
-            "*Health Check*\n\nSelect a tarantula:".to_string(),
-            InlineKeyboardMarkup::new(keyboard),
-        )
-        .await
-    }
-
-    pub(crate) async fn molt_history(
-        &self,
-        chat_id: ChatId,
-        message_id: MessageId,
-        user_id: u64,
-    ) -> BotResult<()> {
-        self.view_molt_records(chat_id, message_id, user_id).await?;
-        Ok(())
-    }
-
-    pub(crate) async fn view_records(
-        &self,
-        chat_id: ChatId,
-        message_id: MessageId,
-    ) -> BotResult<()> {
-        let keyboard = InlineKeyboardMarkup::new(vec![
-            vec![
-                InlineKeyboardButton::callback("Feeding Records", "view_feeding_records"),
-                InlineKeyboardButton::callback("Health Records", "view_health_records"),
-            ],
-            vec![InlineKeyboardButton::callback(
-                "Molt Records",
-                "view_molt_records",
-            )],
-            vec![InlineKeyboardButton::callback(
-                "« Back to Menu",
-                MainMenu.to_string(),
-            )],
-        ]);
-
-        let msg = "*View Records*\n\nSelect record type:";
-        self.replay_with_edit(chat_id, message_id, msg.to_string(), keyboard)
-            .await
-    }
-    pub(crate) async fn view_feeding_records(
-        &self,
-        chat_id: ChatId,
-        message_id: MessageId,
-        user_id: u64,
-    ) -> BotResult<()> {
-        let records = self.db.get_recent_feeding_records(user_id, 10).await?;
-
-        let mut message = String::from("🍽 *Recent Feeding Records*\n\n");
-        if records.is_empty() {
-            message.push_str("No feeding records found.");
-        } else {
-            for record in records {
-                message.push_str(&format!(
-                    "*{}* - {}\n• {} crickets from {}\n• Status: {}\n{}\n\n",
-                    record.tarantula_name,
-                    record.feeding_date,
-                    record.number_of_crickets,
-                    record.colony_name,
-                    record.status,
-                    record.notes.unwrap_or_default()
-                ));
-            }
-        }
-
-        let keyboard = InlineKeyboardMarkup::new(vec![vec![InlineKeyboardButton::callback(
-            "« Back to Records",
-            "view_records",
-        )]]);
-
-        self.replay_with_edit(chat_id, message_id, message, keyboard)
-            .await
-    }
-
-    pub(crate) async fn view_health_records(
-        &self,
-        chat_id: ChatId,
-        message_id: MessageId,
-        user_id: u64,
-    ) -> BotResult<()> {
-        let records = self.db.get_recent_health_records(user_id, 10).await?;
-
-        let mut message = String::from("🏥 *Recent Health Check Records*\n\n");
-        if records.is_empty() {
-            message.push_str("No health check records found.");
-        } else {
-            for record in records {
-                let details = vec![
-                    record.weight_grams.map(|w| format!("Weight: {}g", w)),
-                    record.humidity_percent.map(|h| format!("Humidity: {}%", h)),
-                    record.temperature_celsius.map(|t| format!("Temp: {}°C", t)),
-                ];
-                let details_str = details.into_iter().flatten().collect::<Vec<_>>().join(", ");
-
-                message.push_str(&format!(
-                    "*{}* - {}\n• Status: {}\n• {}\n{}\n\n",
-                    record.tarantula_name,
-                    record.check_date,
-                    record.status,
-                    if details_str.is_empty() {
-                        "No measurements taken"
-                    } else {
-                        &details_str
-                    },
-                    record.notes.unwrap_or_default()
-                ));
-            }
-        }
-
-        let keyboard = InlineKeyboardMarkup::new(vec![vec![InlineKeyboardButton::callback(
-            "« Back to Records",
-            "view_records",
-        )]]);
-
-        self.replay_with_edit(chat_id, message_id, message, keyboard)
-            .await
-    }
-
-    pub(crate) async fn view_molt_records(
-        &self,
-        chat_id: ChatId,
-        message_id: MessageId,
-        user_id: u64,
-    ) -> BotResult<()> {
-        let records = self.db.get_recent_molt_records(user_id, 10).await?;
-
-        let mut message = String::from("🐾 *Recent Molt Records*\n\n");
-        if records.is_empty() {
-            message.push_str("No molt records found.");
-        } else {
-            for record in records {
-                message.push_str(&format!(
-                    "*{}* - {}\n• Stage: {}\n{}{}• {}\n\n",
-                    record.tarantula_name,
-                    record.molt_date,
-                    record.stage,
-                    record
-                        .pre_molt_length_cm
-                        .map_or(String::new(), |l| format!("• Length: {}cm\n", l)),
-                    record
-                        .complications
-                        .map_or(String::new(), |c| format!("• Complications: {}\n", c)),
-                    record.notes.unwrap_or_default()
-                ));
-            }
-        }
-
-        let keyboard = InlineKeyboardMarkup::new(vec![vec![InlineKeyboardButton::callback(
-            "« Back to Records",
-            "view_records",
-        )]]);
-
-        self.replay_with_edit(chat_id, message_id, message, keyboard)
-            .await
-    }
-
-    pub(crate) async fn record_molt_menu(
-        &self,
-        chat_id: ChatId,
-        message_id: MessageId,
-        user_id: u64,
-    ) -> BotResult<()> {
-        let tarantulas = self.db.get_all_tarantulas(user_id).await?;
-        let mut keyboard: Vec<Vec<InlineKeyboardButton>> = tarantulas
-            .chunks(2)
-            .map(|chunk| {
-                chunk
-                    .iter()
-                    .map(|t| {
-                        InlineKeyboardButton::callback(
-                            format!("{} ({})", t.name, t.species_name),
-                            //todo add actual size
-                            MoltSimple(t.id).to_string(),
-                        )
-                    })
-                    .collect()
-            })
-            .collect();
-        keyboard.push(vec![InlineKeyboardButton::callback(
-            "« Back to Menu",
-            MainMenu.to_string(),
-        )]);
-
-        let msg = "*Record Molt*\n\nSelect a tarantula:";
-        self.replay_with_edit(
-            chat_id,
-            message_id,
-            msg.to_string(),
-            InlineKeyboardMarkup::new(keyboard),
-        )
-        .await
-        .map(|_| ())
-        .map_err(|e| e.into())
-    }
-
-    pub(crate) async fn colony_maintenance(
-        &self,
-        chat_id: ChatId,
-        message_id: MessageId,
-        user_id: u64,
-    ) -> BotResult<()> {
-        let colonies = self.db.get_colony_status(user_id).await?;
-        let mut keyboard: Vec<Vec<InlineKeyboardButton>> = colonies
-            .chunks(2)
-            .map(|chunk| {
-                chunk
-                    .iter()
-                    .map(|c| {
-                        InlineKeyboardButton::callback(
-                            format!("{} ({})", c.colony_name, c.size_type.to_db_name()),
-                            ColonyMaintenanceMenu(c.id).to_string(),
-                        )
-                    })
-                    .collect()
-            })
-            .collect();
-        keyboard.push(vec![InlineKeyboardButton::callback(
-            "« Back to Menu",
-            MainMenu.to_string(),
-        )]);
-
-        self.replay_with_edit(
-            chat_id,
-            message_id,
-            "*Colony Maintenance*\n\nSelect a colony:".to_string(),
-            InlineKeyboardMarkup::new(keyboard),
-        )
-        .await
-        .map(|_| ())
-        .map_err(|e| e.into())
-    }
-
-    pub(crate) async fn colony_count(
-        &self,
-        chat_id: ChatId,
-        message_id: MessageId,
-        colony_id: i64,
-        user_id: u64,
-    ) -> BotResult<()> {
-        let colony = self.colony_status(colony_id, user_id).await?;
-
-        let keyboard = InlineKeyboardMarkup::new(vec![
-            vec![
-                InlineKeyboardButton::callback(
-                    "-10",
-                    ColonyCountUpdate(colony_id, -10).to_string(),
-                ),
-                InlineKeyboardButton::callback("-5", ColonyCountUpdate(colony_id, -5).to_string()),
-            ],
-            vec![
-                InlineKeyboardButton::callback("+1", ColonyCountUpdate(colony_id, 1).to_string()),
-                InlineKeyboardButton::callback("+5", ColonyCountUpdate(colony_id, 5).to_string()),
-                InlineKeyboardButton::callback("+10", ColonyCountUpdate(colony_id, 10).to_string()),
-                InlineKeyboardButton::callback("+50", ColonyCountUpdate(colony_id, 50).to_string()),
-            ],
-            vec![InlineKeyboardButton::callback(
-                "« Cancel",
-                MainMenu.to_string(),
-            )],
-        ]);
-
-        self.replay_with_edit(
-            chat_id,
-            message_id,
-            format!(
-                "*Update Colony Count*\n\nColony: {}\nCurrent count: {}\nSelect adjustment:",
-                colony.colony_name, colony.current_count
-            ),
-            keyboard,
-        )
-        .await
-    }
-
-    pub(crate) async fn colony_count_update(
-        &self,
-        chat_id: ChatId,
-        message_id: MessageId,
-        colony_id: i64,
-        adjustment: i32,
-        user_id: u64,
-    ) -> BotResult<()> {
-        self.db
-            .update_colony_count(colony_id, adjustment, user_id)
-            .await?;
-
-        let keyboard = Self::back_to_menu_keyboard();
-
-        self.replay_with_edit(
-            chat_id,
-            message_id,
-            format!("✅ Colony count updated by {}", adjustment),
-            keyboard,
-        )
-        .await
-    }
-
-    pub(crate) async fn view_feeding_schedule(
-        &self,
-        chat_id: ChatId,
-        message_id: MessageId,
-        tarantula_id: i64,
-        user_id: u64,
-    ) -> BotResult<()> {
-        let tarantula = self.db.get_tarantula_by_id(user_id, tarantula_id).await?;
-
-        let current_size = self.db.get_current_size(tarantula_id).await?;
-        let schedule = self
-            .db
-            .get_feeding_schedule(tarantula.species_id, current_size)
-            .await?
-            .unwrap();
-
-        let frequency = self
-            .db
-            .get_feeding_frequency(schedule.frequency_id.unwrap_or(1))
-            .await?
-            .unwrap();
-
-        let message = format!(
-            "*Feeding Schedule for {}*\n\n\
-            🦗 *Current Stage:* {}\n\
-            📏 *Size:* {:.1} cm\n\
-            🍽 *Prey Size:* {}\n\
-            ⏱ *Feeding Frequency:* {}\n\
-            🦗 *Prey Type:* {}\n\n\
-            ℹ️ {}\n\n\
-            _Feeding window: Every {} to {} days_",
-            tarantula.name,
-            schedule.size_category,
-            current_size,
-            schedule.prey_size,
-            schedule.feeding_frequency,
-            schedule.prey_type,
-            schedule.notes.unwrap_or_default(),
-            frequency.min_days,
-            frequency.max_days
-        );
-
-        let keyboard = InlineKeyboardMarkup::new(vec![vec![InlineKeyboardButton::callback(
-            "« Back",
-            ListTarantulas.to_string(),
-        )]]);
-        self.replay_with_edit(chat_id, message_id, message, keyboard)
-            .await
-    }
-
-    async fn reply_with_send(
+    pub(crate) async fn reply_with_send(
         &self,
         chat_id: ChatId,
         message: String,
@@ -1100,37 +614,31 @@ impl TarantulaBot {
 
         request.await.map(|_| ()).map_err(|e| e.into())
     }
+}
 
-    fn back_to_menu_keyboard() -> InlineKeyboardMarkup {
-        InlineKeyboardMarkup::new(vec![vec![InlineKeyboardButton::callback(
-            "« Back to Menu",
-            MainMenu.to_string(),
-        )]])
-    }
-
-    fn with_back_button(
-        mut keyboard: Vec<Vec<InlineKeyboardButton>>,
-    ) -> Vec<Vec<InlineKeyboardButton>> {
-        keyboard.push(vec![InlineKeyboardButton::callback(
-            "« Back to Menu",
-            MainMenu.to_string(),
-        )]);
-        keyboard
-    }
+fn callback_name(data: Option<&str>) -> &'static str {
+    data.and_then(|d| d.parse::<BotCallback>().ok())
+        .map_or("unknown", |c| c.name())
+}
 
-    pub(crate) async fn molt_simple_callback(
-        &self,
-        chat_id: ChatId,
-        tarantula_id: i64,
-    ) -> BotResult<()> {
-        self.bot
-            .send_message(chat_id, "Please enter the molt size in centimeters:")
-            .await?;
+/// Buttons that open a private chat with the bot, `/start`ing it with their
+/// callback, for messages posted in chats the bot is not part of.
+fn start_links(me: &Me, buttons: Keyboard) -> InlineKeyboardMarkup {
+    InlineKeyboardMarkup::new(buttons.into_iter().map(|row| {
+        row.into_iter()
+            .map(|b| {
+                let mut url = me.tme_url();
+                url.query_pairs_mut().append_pair("start", &b.callback);
+                InlineKeyboardButton::url(b.label, url)
+            })
+            .collect::<Vec<_>>()
+    }))
+}
 
-        self.dialogue
-            .clone()
-            .update_dialogue(chat_id, DialogueState::RecordMolt { tarantula_id })
-            .await?;
-        Ok(())
-    }
+fn inline_keyboard(buttons: Keyboard) -> InlineKeyboardMarkup {
+    InlineKeyboardMarkup::new(buttons.into_iter().map(|row| {
+        row.into_iter()
+            .map(|b| InlineKeyboardButton::callback(b.label, b.callback))
+            .collect::<Vec<_>>()
+    }))
 }
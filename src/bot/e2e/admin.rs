@@ -0,0 +1,75 @@
+use super::Harness;
+use crate::app::callbacks::BotCallback;
+use crate::bot::admin::AdminConfig;
+use crate::config::Config;
+use teloxide::prelude::ChatId;
+
+const ADMIN: ChatId = ChatId(-100_777);
+
+/// Species 999 doesn't exist, so storing the tarantula breaks a constraint.
+const BROKEN_COMMAND: &str = "/addtarantula Rosie 999 2024-01-01 12 calm";
+
+async fn admin_harness() -> Harness {
+    Harness::with_config(Config {
+        admin: AdminConfig { chats: vec![ADMIN] },
+        ..Config::default()
+    })
+    .await
+}
+
+fn reference(report: &str) -> &str {
+    report
+        .lines()
+        .next()
+        .and_then(|line| line.strip_prefix("🚨 Error "))
+        .unwrap_or_else(|| panic!("no reference in report:\n{}", report))
+}
+
+#[tokio::test]
+async fn internal_errors_are_reported_under_the_reference_the_keeper_sees() {
+    let mut h = admin_harness().await;
+    h.main_menu().await;
+
+    h.send(BROKEN_COMMAND);
+    let report = h.expect_sent_to(ADMIN).await;
+    report.assert_text("Where: command /addtarantula");
+    report.assert_text("Kind: database");
+    report.assert_text("FOREIGN KEY constraint failed");
+    assert!(!report.text.contains("Rosie"), "{}", report.text);
+
+    let apology = h.expect_sent().await;
+    apology.assert_text("Something went wrong on our side");
+    apology.assert_text(&format!("<code>{}</code>", reference(&report.text)));
+    assert!(apology.has_button(&BotCallback::MainMenu));
+    h.expect_error().await;
+
+    h.finish().await;
+}
+
+#[tokio::test]
+async fn keeper_mistakes_are_not_reported() {
+    let mut h = admin_harness().await;
+    let menu = h.main_menu().await;
+
+    h.press(menu.message_id, BotCallback::FeedTarantula(99));
+    h.expect_sent()
+        .await
+        .assert_text("❌ Tarantula with id 99 not found");
+    h.expect_error().await;
+
+    h.finish().await;
+}
+
+#[tokio::test]
+async fn without_admins_the_keeper_still_gets_a_reference() {
+    let mut h = Harness::start().await;
+    h.main_menu().await;
+
+    h.send(BROKEN_COMMAND);
+    h.expect_sent()
+        .await
+        .assert_text("Something went wrong on our side");
+    h.expect_error().await;
+
+    h.finish().await;
+}
@@ -0,0 +1,112 @@
+use super::{Harness, KEEPER};
+use crate::backup::BackupConfig;
+use crate::bot::admin::AdminConfig;
+use crate::config::Config;
+use crate::db::db::TarantulaDB;
+use crate::db::migrations;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use teloxide::prelude::ChatId;
+use tempfile::TempDir;
+
+const ADMIN: ChatId = ChatId(-100_777);
+
+async fn sqlite_harness(dir: &TempDir) -> Harness {
+    let path = dir.path().join("tarantulas.sqlite");
+    migrations::migrate(&mut rusqlite::Connection::open(&path).unwrap()).unwrap();
+    let db = TarantulaDB::new(path.to_str().unwrap()).unwrap();
+    let config = Config {
+        admin: AdminConfig { chats: vec![ADMIN] },
+        backup: BackupConfig {
+            directory: backup_dir(dir),
+            ..BackupConfig::default()
+        },
+        ..Config::default()
+    };
+    Harness::with_db(config, Arc::new(db)).await
+}
+
+fn backup_dir(dir: &TempDir) -> PathBuf {
+    dir.path().join("backups")
+}
+
+async fn tarantula_names(h: &Harness) -> Vec<String> {
+    let mut names: Vec<_> = h
+        .db()
+        .get_all_tarantulas(KEEPER as u64)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|t| t.name)
+        .collect();
+    names.sort();
+    names
+}
+
+#[tokio::test]
+async fn a_backup_sent_to_the_admins_can_be_restored_by_uploading_it() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut h = sqlite_harness(&dir).await;
+    h.main_menu().await;
+    h.run_command("/addtarantula Rosie 1 2024-01-01 12 calm")
+        .await;
+
+    h.send_in(ADMIN, "/backup");
+    let sent = h.expect_document_to(ADMIN).await;
+    assert!(sent.contains("💾 spider-bot-"), "{}", sent);
+    let backup = fs::read_dir(backup_dir(&dir))
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap()
+        .path();
+
+    h.run_command("/addtarantula Boris 1 2024-01-01 12 calm")
+        .await;
+    let upload = h.send_document(
+        ADMIN,
+        "rosie.sqlite",
+        fs::read(&backup).unwrap(),
+        "/restore",
+    );
+    h.expect_download(&upload).await;
+    let done = h.expect_sent_to(ADMIN).await;
+    done.assert_text("Restored the database from rosie.sqlite");
+    done.assert_text("The data it replaced is in spider-bot-");
+    assert_eq!(tarantula_names(&h).await, ["Rosie"]);
+
+    h.finish().await;
+}
+
+#[tokio::test]
+async fn uploads_that_are_not_backups_change_nothing() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut h = sqlite_harness(&dir).await;
+    h.main_menu().await;
+    h.run_command("/addtarantula Rosie 1 2024-01-01 12 calm")
+        .await;
+
+    let upload = h.send_document(ADMIN, "notes.txt", b"not a database".to_vec(), "/restore");
+    h.expect_download(&upload).await;
+    h.expect_sent_to(ADMIN)
+        .await
+        .assert_text("⚠️ The backup can't be read");
+    h.expect_error().await;
+    assert_eq!(tarantula_names(&h).await, ["Rosie"]);
+
+    h.finish().await;
+}
+
+#[tokio::test]
+async fn keepers_cannot_take_backups() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut h = sqlite_harness(&dir).await;
+    h.main_menu().await;
+
+    h.send("/backup");
+    h.expect_silence().await;
+    assert!(!backup_dir(&dir).exists());
+
+    h.finish().await;
+}
@@ -0,0 +1,235 @@
+use super::Harness;
+use crate::app::callbacks::BotCallback;
+use crate::app::dialogue::DialogueState;
+
+const PAPA: i64 = 2;
+const PAIRING: i64 = 1;
+const EGG_SAC: i64 = 1;
+
+/// Registers the keeper with a female and a male that have been paired once.
+async fn keeper_with_pairing() -> Harness {
+    let mut h = Harness::start().await;
+    h.main_menu().await;
+    h.run_command("/addtarantula Mama 8 2023-01-01 36 female")
+        .await;
+    h.run_command("/addtarantula Papa 8 2023-01-01 30 male")
+        .await;
+
+    h.send("/addpairing 1 2 2024-05-01 first");
+    let logged = h.expect_sent().await;
+    assert_eq!(logged.text, "✅ Pairing #1 logged");
+    assert!(logged.has_button(&BotCallback::PairingDetails(PAIRING)));
+    h
+}
+
+async fn keeper_with_egg_sac() -> Harness {
+    let mut h = keeper_with_pairing().await;
+    h.send("/addeggsac 1 2024-06-01 big");
+    assert_eq!(h.expect_sent().await.text, "✅ Egg sac #1 logged");
+    h
+}
+
+#[tokio::test]
+async fn pairing_outcome_is_set_from_its_details() {
+    let mut h = keeper_with_pairing().await;
+    let menu = h.main_menu().await;
+
+    h.tap(&menu, BotCallback::BreedingMenu);
+    let breeding = h.expect_edited().await;
+    breeding.assert_text("• Mama × Papa - 2024-05-01 (Pending)");
+
+    h.tap(&breeding, BotCallback::PairingDetails(PAIRING));
+    let details = h.expect_edited().await;
+    details.assert_text("💑 *Mama × Papa*");
+    details.assert_text("• Outcome: Pending");
+
+    h.tap(&details, BotCallback::SetPairingOutcome(PAIRING, 2));
+    let updated = h.expect_edited().await;
+    updated.assert_text("• Outcome: Successful");
+    assert!(updated.has_button(&BotCallback::BreedingMenu));
+
+    h.finish().await;
+}
+
+#[tokio::test]
+async fn pairing_with_an_unknown_tarantula_is_rejected() {
+    let mut h = keeper_with_pairing().await;
+
+    h.send("/addpairing 1 9 2024-05-01 oops");
+    let reply = h.expect_sent().await;
+    assert!(reply.text.starts_with("❌ "), "{}", reply.text);
+    assert!(reply.has_button(&BotCallback::MainMenu));
+
+    h.finish().await;
+}
+
+#[tokio::test]
+async fn pairing_a_tarantula_with_itself_or_swapped_is_rejected() {
+    let mut h = keeper_with_pairing().await;
+    h.run_command("/addtarantula Daughter 8 2024-01-01 6 sling")
+        .await;
+    h.send("/setparents 3 1 2");
+    assert_eq!(h.expect_sent().await.text, "✅ Parents recorded");
+
+    // Daughter shares every ancestor with herself, which isn't a warning.
+    h.send("/addpairing 3 3 2024-09-01 oops");
+    h.expect_sent()
+        .await
+        .assert_text("⚠️ A tarantula cannot be paired with itself");
+    h.expect_silence().await;
+    assert!(h.dialogue_state().await.is_none());
+
+    h.send("/addpairing 2 1 2024-09-01 swapped");
+    h.expect_sent()
+        .await
+        .assert_text("⚠️ Papa is recorded as a male, pair it the other way round");
+
+    h.finish().await;
+}
+
+#[tokio::test]
+async fn egg_sac_is_counted_pulled_and_turned_into_slings() {
+    let mut h = keeper_with_egg_sac().await;
+    let menu = h.main_menu().await;
+
+    h.tap(&menu, BotCallback::BreedingMenu);
+    let breeding = h.expect_edited().await;
+    breeding.assert_text("• Sac #1 from Mama - Incubating");
+
+    h.tap(&breeding, BotCallback::EggSacDetails(EGG_SAC));
+    let details = h.expect_edited().await;
+    details.assert_text("🥚 *Egg Sac #1*");
+    assert!(details.has_button(&BotCallback::FailEggSac(EGG_SAC)));
+
+    h.tap(&details, BotCallback::EggSacCounts(EGG_SAC));
+    h.expect_sent()
+        .await
+        .assert_text("Please enter the counts as");
+    h.expect_silence().await;
+    assert!(matches!(
+        h.dialogue_state().await,
+        Some(DialogueState::RecordEggSacCounts {
+            egg_sac_id: EGG_SAC
+        })
+    ));
+
+    h.send("lots");
+    h.expect_sent()
+        .await
+        .assert_text("Please send the counts as: eggs nymphs slings");
+    h.send("120 - -");
+    let recorded = h.expect_sent().await;
+    assert_eq!(recorded.text, "✅ Egg sac counts recorded");
+
+    h.tap(&recorded, BotCallback::EggSacDetails(EGG_SAC));
+    let details = h.expect_edited().await;
+    details.assert_text("• Eggs: 120");
+
+    h.tap(&details, BotCallback::PullEggSac(EGG_SAC));
+    let pulled = h.expect_edited().await;
+    pulled.assert_text("• Status: Pulled");
+    assert!(!pulled.has_button(&BotCallback::PullEggSac(EGG_SAC)));
+
+    h.tap(&pulled, BotCallback::AddSlings(EGG_SAC));
+    h.expect_sent()
+        .await
+        .assert_text("How many slings should be added?");
+    h.expect_silence().await;
+    assert!(matches!(
+        h.dialogue_state().await,
+        Some(DialogueState::AddSlings {
+            egg_sac_id: EGG_SAC
+        })
+    ));
+
+    h.send("none");
+    h.expect_sent()
+        .await
+        .assert_text("Please send the number of slings");
+    h.send("3 Hamorii");
+    assert_eq!(
+        h.expect_sent().await.text,
+        "✅ Added 3 slings to your collection"
+    );
+
+    let menu = h.main_menu().await;
+    h.press(menu.message_id, BotCallback::Pedigree(3));
+    let pedigree = h.expect_edited().await;
+    pedigree.assert_text("🧬 *Pedigree of Hamorii #1*");
+    pedigree.assert_text("Mama");
+    pedigree.assert_text("Papa");
+    assert!(pedigree.has_button(&BotCallback::FeedTarantula(3)));
+
+    h.finish().await;
+}
+
+#[tokio::test]
+async fn failed_egg_sac_leaves_the_breeding_menu() {
+    let mut h = keeper_with_egg_sac().await;
+    let menu = h.main_menu().await;
+
+    h.press(menu.message_id, BotCallback::EggSacDetails(EGG_SAC));
+    let details = h.expect_edited().await;
+    h.tap(&details, BotCallback::FailEggSac(EGG_SAC));
+    h.expect_edited().await.assert_text("• Status: Failed");
+
+    h.press(menu.message_id, BotCallback::BreedingMenu);
+    let breeding = h.expect_edited().await;
+    assert!(!breeding.text.contains("Sac #1"));
+    assert!(!breeding.has_button(&BotCallback::EggSacDetails(EGG_SAC)));
+
+    h.finish().await;
+}
+
+#[tokio::test]
+async fn close_ancestry_pairing_waits_for_confirmation() {
+    let mut h = keeper_with_pairing().await;
+    h.run_command("/addtarantula Daughter 8 2024-01-01 6 sling")
+        .await;
+    h.send("/setparents 3 1 2");
+    let parents = h.expect_sent().await;
+    assert_eq!(parents.text, "✅ Parents recorded");
+    assert!(parents.has_button(&BotCallback::Pedigree(3)));
+
+    h.send("/addpairing 3 2 2024-09-01 backcross");
+    let warning = h.expect_sent().await;
+    warning.assert_text("Close ancestry detected");
+    warning.assert_text("These tarantulas share: Papa");
+    h.expect_silence().await;
+    assert!(matches!(
+        h.dialogue_state().await,
+        Some(DialogueState::ConfirmPairing {
+            female_id: 3,
+            male_id: PAPA,
+            ..
+        })
+    ));
+
+    h.send("hmm");
+    assert_eq!(
+        h.expect_sent().await.text,
+        "Please confirm or cancel the pending pairing using the buttons above."
+    );
+
+    h.tap(&warning, BotCallback::CancelPairing);
+    let breeding = h.expect_edited().await;
+    assert_eq!(breeding.message_id, warning.message_id);
+    breeding.assert_text("🥚 *Breeding Projects*");
+    assert!(!breeding.has_button(&BotCallback::PairingDetails(2)));
+
+    // The warning was replaced by the menu, but a stale client can still send it.
+    h.press(warning.message_id, BotCallback::ConfirmPairing);
+    h.expect_sent()
+        .await
+        .assert_text("There is no pairing waiting for confirmation.");
+
+    h.send("/addpairing 3 2 2024-09-01 backcross");
+    let warning = h.expect_sent().await;
+    h.tap(&warning, BotCallback::ConfirmPairing);
+    let logged = h.expect_sent().await;
+    assert_eq!(logged.text, "✅ Pairing #2 logged");
+    h.expect_silence().await;
+    assert!(h.dialogue_state().await.is_none());
+
+    h.finish().await;
+}
@@ -0,0 +1,91 @@
+use super::Harness;
+use crate::app::callbacks::BotCallback;
+
+const ROSIE: i64 = 1;
+const COLONY: i64 = 1;
+
+/// Registers the keeper with one Chilean Rose and a colony of 100 small crickets.
+async fn keeper_with_rosie() -> Harness {
+    let mut h = Harness::start().await;
+    h.main_menu().await;
+    h.run_command("/addtarantula Rosie 8 2024-01-01 12 calm")
+        .await;
+    h.run_command("/addcolony Bin 2 100 box-1 main").await;
+    h
+}
+
+async fn crickets_left(h: &Harness) -> i32 {
+    h.db()
+        .get_colony_status(super::KEEPER as u64)
+        .await
+        .unwrap()[0]
+        .current_count
+}
+
+#[tokio::test]
+async fn a_mis_tapped_feeding_is_undone_from_its_confirmation() {
+    let mut h = keeper_with_rosie().await;
+    let menu = h.main_menu().await;
+
+    h.press(menu.message_id, BotCallback::FeedConfirm(ROSIE, COLONY, 5));
+    let fed = h.expect_edited().await;
+    assert_eq!(fed.text, "✅ Feeding recorded: 5 crickets");
+    assert_eq!(crickets_left(&h).await, 95);
+
+    h.tap(&fed, BotCallback::Undo(1));
+    let undone = h.expect_edited().await;
+    assert_eq!(undone.text, "↩️ Undone: 🍽 Feeding · Rosie · Bin 100 → 95");
+    assert!(undone.has_button(&BotCallback::ChangeHistory(0)));
+    assert_eq!(crickets_left(&h).await, 100);
+    assert!(h
+        .db()
+        .get_recent_feeding_records(super::KEEPER as u64, 10)
+        .await
+        .unwrap()
+        .is_empty());
+
+    h.tap(&fed, BotCallback::Undo(1));
+    h.expect_sent()
+        .await
+        .assert_text("⚠️ That change was already undone");
+    h.expect_error().await;
+    assert_eq!(crickets_left(&h).await, 100);
+
+    h.finish().await;
+}
+
+#[tokio::test]
+async fn history_lists_the_keepers_changes_with_undo_buttons() {
+    let mut h = keeper_with_rosie().await;
+    let menu = h.main_menu().await;
+
+    h.press(menu.message_id, BotCallback::HealthStatus(ROSIE, 2));
+    assert!(h.expect_edited().await.has_button(&BotCallback::Undo(1)));
+    h.press(menu.message_id, BotCallback::ColonyCountUpdate(COLONY, 50));
+    assert!(h.expect_edited().await.has_button(&BotCallback::Undo(2)));
+
+    h.send("/history");
+    let history = h.expect_sent().await;
+    history.assert_text("🕰 *Your Changes*");
+    history.assert_text("🦗 Colony count · Bin 100 → 150");
+    history.assert_text("🏥 Health check · Rosie · Unknown → Monitor");
+    assert!(history.has_button(&BotCallback::Undo(2)));
+
+    h.tap(&history, BotCallback::Undo(1));
+    let undone = h.expect_edited().await;
+    undone.assert_text("↩️ Undone: 🏥 Health check");
+    let rosie = h
+        .db()
+        .get_tarantula_by_id(super::KEEPER as u64, ROSIE)
+        .await
+        .unwrap();
+    assert_eq!(rosie.current_health_status_id, None);
+
+    h.tap(&undone, BotCallback::ChangeHistory(0));
+    let history = h.expect_edited().await;
+    history.assert_text("Rosie · Unknown → Monitor _(undone)_");
+    assert!(!history.has_button(&BotCallback::Undo(1)));
+    assert!(history.has_button(&BotCallback::Undo(2)));
+
+    h.finish().await;
+}
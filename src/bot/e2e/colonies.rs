@@ -0,0 +1,48 @@
+use super::Harness;
+use crate::app::callbacks::BotCallback;
+
+const COLONY: i64 = 1;
+
+async fn keeper_with_colony() -> Harness {
+    let mut h = Harness::start().await;
+    h.main_menu().await;
+    h.run_command("/addcolony Bin 2 100 box-1 main").await;
+    h
+}
+
+#[tokio::test]
+async fn colony_count_is_adjusted_from_the_maintenance_menu() {
+    let mut h = keeper_with_colony().await;
+    let menu = h.main_menu().await;
+
+    h.tap(&menu, BotCallback::ColonyMaintenance);
+    let colonies = h.expect_edited().await;
+    assert_eq!(colonies.keyboard[0][0].0, "Bin (Small)");
+
+    h.tap(&colonies, BotCallback::ColonyMaintenanceMenu(COLONY));
+    let actions = h.expect_edited().await;
+    actions.assert_text("Colony: Bin\nCurrent count: 100");
+
+    h.tap(&actions, BotCallback::ColonyGetCount(COLONY));
+    let adjustments = h.expect_edited().await;
+    adjustments.assert_text("*Update Colony Count*");
+    assert!(adjustments.has_button(&BotCallback::ColonyCountUpdate(COLONY, -10)));
+
+    h.tap(&adjustments, BotCallback::ColonyCountUpdate(COLONY, 50));
+    assert_eq!(
+        h.expect_edited().await.text,
+        "✅ Colony count updated by 50"
+    );
+
+    let menu = h.main_menu().await;
+    h.tap(&menu, BotCallback::Colonies);
+    h.expect_edited()
+        .await
+        .assert_text("*Bin* (Small):\n- Current count: 150");
+
+    let menu = h.main_menu().await;
+    h.tap(&menu, BotCallback::StatusOverview);
+    h.expect_edited().await.assert_text("• Total crickets: 150");
+
+    h.finish().await;
+}
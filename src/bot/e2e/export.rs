@@ -0,0 +1,41 @@
+use super::Harness;
+use teloxide::prelude::ChatId;
+
+#[tokio::test]
+async fn export_sends_a_zip_with_every_table() {
+    let mut h = Harness::start().await;
+    h.main_menu().await;
+    h.run_command("/addtarantula Rosie 1 2024-01-01 12 calm")
+        .await;
+    h.run_command("/addcolony Bin 2 100 box-1 main").await;
+
+    h.send("/export xlsx");
+    let sent = h.expect_document_to(ChatId(super::KEEPER)).await;
+    assert!(sent.contains("filename=\"spider-bot-export-"), "{}", sent);
+    assert!(sent.contains(
+        "📦 Your data: 1 tarantulas, 0 feedings, 0 health checks, 0 molts, 1 colonies, 0 enclosures"
+    ));
+    for file in [
+        "tarantulas.csv",
+        "colonies.json",
+        "enclosures.csv",
+        "spider-bot.xlsx",
+    ] {
+        assert!(sent.contains(file), "{} missing", file);
+    }
+
+    h.finish().await;
+}
+
+#[tokio::test]
+async fn unknown_export_formats_are_explained() {
+    let mut h = Harness::start().await;
+    h.main_menu().await;
+
+    h.send("/export pdf");
+    h.expect_sent()
+        .await
+        .assert_text("⚠️ There is no export format pdf, use /export or /export xlsx");
+
+    h.finish().await;
+}
@@ -0,0 +1,239 @@
+use super::Harness;
+use crate::app::callbacks::BotCallback;
+use crate::app::dialogue::DialogueState;
+use chrono::{Days, Utc};
+
+const ROSIE: i64 = 1;
+const COLONY: i64 = 1;
+
+/// Registers the keeper with one Chilean Rose and a colony of 100 small crickets.
+async fn keeper_with_rosie() -> Harness {
+    let mut h = Harness::start().await;
+    h.main_menu().await;
+    h.run_command("/addtarantula Rosie 8 2024-01-01 12 calm")
+        .await;
+    h.run_command("/addcolony Bin 2 100 box-1 main").await;
+    h
+}
+
+#[tokio::test]
+async fn record_feeding_from_the_main_menu() {
+    let mut h = keeper_with_rosie().await;
+    let menu = h.main_menu().await;
+    menu.assert_text("• Feeding Due: 1");
+
+    h.tap(&menu, BotCallback::RecordFeeding);
+    let tarantulas = h.expect_edited().await;
+    assert_eq!(tarantulas.keyboard[0][0].0, "Rosie (Chilean Rose)");
+
+    h.tap(&tarantulas, BotCallback::FeedTarantula(ROSIE));
+    let colonies = h.expect_edited().await;
+    colonies.assert_text("Feeding *Rosie*");
+    assert!(colonies.has_button(&BotCallback::ViewFeedingSchedule(ROSIE)));
+    assert!(colonies.has_button(&BotCallback::Pedigree(ROSIE)));
+
+    h.tap(&colonies, BotCallback::FeedSelectColony(ROSIE, COLONY));
+    let counts = h.expect_edited().await;
+    counts.assert_text("Selected colony: Bin (Small)");
+    counts.assert_text("Current count: 100");
+
+    h.tap(&counts, BotCallback::FeedConfirm(ROSIE, COLONY, 3));
+    let done = h.expect_edited().await;
+    assert_eq!(done.text, "✅ Feeding recorded: 3 crickets");
+    assert_eq!(
+        done.callbacks(),
+        vec![
+            BotCallback::Undo(1).to_string(),
+            BotCallback::MainMenu.to_string()
+        ]
+    );
+
+    let colony = &h
+        .db()
+        .get_colony_status(super::KEEPER as u64)
+        .await
+        .unwrap()[0];
+    assert_eq!(colony.current_count, 97);
+
+    let menu = h.main_menu().await;
+    menu.assert_text("• Feeding Due: 0");
+    h.tap(&menu, BotCallback::ViewRecords);
+    let records = h.expect_edited().await;
+    h.tap(&records, BotCallback::ViewFeedingRecords);
+    h.expect_edited().await.assert_text("• 3 crickets from Bin");
+
+    h.finish().await;
+}
+
+#[tokio::test]
+async fn a_feeding_can_be_logged_for_an_earlier_time() {
+    let mut h = keeper_with_rosie().await;
+    let menu = h.main_menu().await;
+    let yesterday = Utc::now().date_naive() - Days::new(1);
+    let at = yesterday.and_hms_opt(7, 0, 0).unwrap();
+
+    h.press(
+        menu.message_id,
+        BotCallback::FeedSelectColony(ROSIE, COLONY),
+    );
+    let counts = h.expect_edited().await;
+    h.tap(&counts, BotCallback::FeedEarlier(ROSIE, COLONY));
+    h.expect_sent()
+        .await
+        .assert_text("📅 When was it? Send yesterday, 2 days ago");
+    assert!(matches!(
+        h.dialogue_state().await,
+        Some(DialogueState::RecordWhen { .. })
+    ));
+
+    h.send("tomorrow");
+    h.expect_sent()
+        .await
+        .assert_text("Please send when it was, not later than now");
+    h.send("yesterday at 07:00");
+    let counts = h.expect_sent().await;
+    counts.assert_text(&format!("📅 {} 07:00", yesterday));
+    assert!(!counts.has_button(&BotCallback::FeedEarlier(ROSIE, COLONY)));
+    assert!(h.dialogue_state().await.is_none());
+
+    let stamp = at.and_utc().timestamp();
+    h.tap(&counts, BotCallback::BackdatedFeed(ROSIE, COLONY, 3, stamp));
+    h.expect_edited().await.assert_text(&format!(
+        "✅ Feeding recorded: 3 crickets\n📅 {} 07:00",
+        yesterday
+    ));
+    let records = h
+        .db()
+        .get_recent_feeding_records(super::KEEPER as u64, 10)
+        .await
+        .unwrap();
+    assert!(records[0]
+        .feeding_date
+        .starts_with(&format!("{} 07:00", yesterday)));
+
+    h.finish().await;
+}
+
+#[tokio::test]
+async fn feeding_schedule_can_be_overridden_and_cleared() {
+    let mut h = keeper_with_rosie().await;
+    let menu = h.main_menu().await;
+
+    h.press(menu.message_id, BotCallback::FeedTarantula(ROSIE));
+    let colonies = h.expect_edited().await;
+    h.tap(&colonies, BotCallback::ViewFeedingSchedule(ROSIE));
+    let schedule = h.expect_edited().await;
+    schedule.assert_text("*Feeding Schedule for Rosie*");
+    assert!(!schedule.has_button(&BotCallback::ClearOverride(ROSIE)));
+
+    h.tap(&schedule, BotCallback::OverrideSchedule(ROSIE));
+    let help = h.expect_edited().await;
+    help.assert_text("/feedoverride 1 frequency_id prey_count prey_size_id expires_on");
+    assert!(help.has_button(&BotCallback::ViewFeedingSchedule(ROSIE)));
+
+    h.send("/feedoverride 1 - 4 - -");
+    let saved = h.expect_sent().await;
+    assert_eq!(saved.text, "✅ Custom feeding schedule saved");
+
+    h.tap(&saved, BotCallback::ViewFeedingSchedule(ROSIE));
+    let custom = h.expect_edited().await;
+    custom.assert_text("🔢 *Prey Count:* 4");
+    custom.assert_text("Custom schedule active");
+
+    h.tap(&custom, BotCallback::ClearOverride(ROSIE));
+    let cleared = h.expect_edited().await;
+    assert!(!cleared.text.contains("Custom schedule active"));
+    assert!(!cleared.has_button(&BotCallback::ClearOverride(ROSIE)));
+
+    h.finish().await;
+}
+
+#[tokio::test]
+async fn invalid_feed_override_reaches_the_error_handler() {
+    let mut h = keeper_with_rosie().await;
+
+    h.send("/feedoverride 1 often - - -");
+    h.expect_sent()
+        .await
+        .assert_text("⚠️ Invalid value 'often'");
+    let error = h.expect_error().await;
+    assert_eq!(error, r#"ValidationError("Invalid value 'often'")"#);
+
+    h.finish().await;
+}
+
+#[tokio::test]
+async fn health_check_shows_up_in_the_records() {
+    let mut h = keeper_with_rosie().await;
+    let menu = h.main_menu().await;
+
+    h.tap(&menu, BotCallback::RecordHealthCheck);
+    let tarantulas = h.expect_edited().await;
+    h.tap(&tarantulas, BotCallback::HealthCheck(ROSIE));
+    let statuses = h.expect_edited().await;
+    statuses.assert_text("Health check for *Rosie*");
+
+    h.tap(&statuses, BotCallback::HealthStatus(ROSIE, 3));
+    h.expect_edited()
+        .await
+        .assert_text("Health status recorded");
+
+    let menu = h.main_menu().await;
+    h.tap(&menu, BotCallback::ViewRecords);
+    let records = h.expect_edited().await;
+    h.tap(&records, BotCallback::ViewHealthRecords);
+    h.expect_edited().await.assert_text("• Status: Critical");
+
+    h.finish().await;
+}
+
+#[tokio::test]
+async fn molt_size_is_asked_for_until_it_parses() {
+    let mut h = keeper_with_rosie().await;
+    let menu = h.main_menu().await;
+
+    h.tap(&menu, BotCallback::RecordMolt);
+    let tarantulas = h.expect_edited().await;
+    h.tap(&tarantulas, BotCallback::MoltSimple(ROSIE));
+    assert_eq!(
+        h.expect_sent().await.text,
+        "Please enter the molt size in centimeters:\n\
+         (add when if it wasn't today, e.g., 6.5 yesterday)"
+    );
+    h.expect_silence().await;
+    assert!(matches!(
+        h.dialogue_state().await,
+        Some(DialogueState::RecordMolt {
+            tarantula_id: ROSIE,
+            at: None
+        })
+    ));
+
+    h.send("big");
+    assert_eq!(
+        h.expect_sent().await.text,
+        "Please send me the size in centimeters (e.g., 12.5)"
+    );
+
+    h.send("6.5");
+    assert_eq!(
+        h.expect_sent().await.text,
+        "Recording molt with size: 6.5cm"
+    );
+    h.expect_sent().await.assert_text("Molt recorded");
+    h.expect_silence().await;
+    assert!(h.dialogue_state().await.is_none());
+
+    let menu = h.main_menu().await;
+    menu.assert_text("• Recent Molts: 1 (30 days)");
+    h.tap(&menu, BotCallback::MoltHistory);
+    let history = h.expect_edited().await;
+    history.assert_text("*Rosie* - ");
+
+    h.tap(&history, BotCallback::ViewRecords);
+    let records = h.expect_edited().await;
+    h.tap(&records, BotCallback::ViewMoltRecords);
+    assert_eq!(h.expect_edited().await.text, history.text);
+
+    h.finish().await;
+}
@@ -0,0 +1,160 @@
+use super::Harness;
+use crate::app::callbacks::BotCallback;
+use crate::app::dialogue::DialogueState;
+
+const GROUP: i64 = 1;
+const COLONY: i64 = 1;
+
+/// Registers the keeper with a group of four Chilean Rose slings, which take
+/// tarantula ids 1 to 4, and a colony to feed them from.
+async fn keeper_with_group() -> Harness {
+    let mut h = Harness::start().await;
+    h.main_menu().await;
+    h.run_command("/addcolony Bin 1 100 box-1 pinheads").await;
+
+    h.send("/addgroup Batch 8 4 2024-03-01");
+    let created = h.expect_sent().await;
+    assert_eq!(created.text, "✅ Group of 4 slings created");
+    assert!(created.has_button(&BotCallback::GroupDetails(GROUP)));
+    h
+}
+
+#[tokio::test]
+async fn whole_group_is_fed_and_checked_at_once() {
+    let mut h = keeper_with_group().await;
+    let menu = h.main_menu().await;
+    menu.assert_text("• Feeding Due: 4");
+
+    h.tap(&menu, BotCallback::GroupsMenu);
+    let groups = h.expect_edited().await;
+    groups.assert_text("*Batch* (Chilean Rose)\n▫️ Slings: 4\n▫️ Last fed: Never");
+
+    h.tap(&groups, BotCallback::GroupDetails(GROUP));
+    let details = h.expect_edited().await;
+    details.assert_text("• Batch #4 - ");
+
+    h.tap(&details, BotCallback::GroupFeedMenu(GROUP));
+    let colonies = h.expect_edited().await;
+    colonies.assert_text("Feeding all 4 slings in *Batch*");
+
+    h.tap(&colonies, BotCallback::GroupFeedColony(GROUP, COLONY));
+    let portions = h.expect_edited().await;
+    assert_eq!(portions.keyboard[0][1].0, "2 each (8 total)");
+
+    h.tap(&portions, BotCallback::GroupFeedConfirm(GROUP, COLONY, 2));
+    let fed = h.expect_edited().await;
+    assert_eq!(fed.text, "✅ Fed 4 slings: 8 crickets used");
+
+    h.tap(&fed, BotCallback::GroupDetails(GROUP));
+    let details = h.expect_edited().await;
+    h.tap(&details, BotCallback::GroupHealthCheck(GROUP));
+    let statuses = h.expect_edited().await;
+    statuses.assert_text("Health check for all slings in *Batch*");
+
+    h.tap(&statuses, BotCallback::GroupHealthStatus(GROUP, 1));
+    assert_eq!(
+        h.expect_edited().await.text,
+        "✅ Health status recorded for 4 slings"
+    );
+
+    let menu = h.main_menu().await;
+    menu.assert_text("• Feeding Due: 0");
+    h.tap(&menu, BotCallback::Colonies);
+    h.expect_edited().await.assert_text("- Current count: 92");
+
+    h.finish().await;
+}
+
+#[tokio::test]
+async fn group_molt_size_can_be_skipped() {
+    let mut h = keeper_with_group().await;
+    let menu = h.main_menu().await;
+
+    h.press(menu.message_id, BotCallback::GroupDetails(GROUP));
+    let details = h.expect_edited().await;
+    h.tap(&details, BotCallback::GroupMolt(GROUP));
+    h.expect_sent()
+        .await
+        .assert_text("Please enter the typical size after molting");
+    h.expect_silence().await;
+    assert!(matches!(
+        h.dialogue_state().await,
+        Some(DialogueState::RecordGroupMolt { group_id: GROUP })
+    ));
+
+    h.send("0");
+    h.expect_sent()
+        .await
+        .assert_text("Please send me the size in centimeters (e.g., 1.5), or - to skip");
+    h.send("-");
+    let molted = h.expect_sent().await;
+    assert_eq!(molted.text, "✅ Molt recorded for 4 slings");
+    h.expect_silence().await;
+    assert!(h.dialogue_state().await.is_none());
+
+    let menu = h.main_menu().await;
+    menu.assert_text("• Recent Molts: 4 (30 days)");
+
+    h.finish().await;
+}
+
+#[tokio::test]
+async fn large_group_is_split_from_paged_members() {
+    let mut h = keeper_with_group().await;
+    // Slings 5 to 29, listed by name, so Brood #5 to #9 land on a second page.
+    h.send("/addgroup Brood 8 25 2024-03-01");
+    assert_eq!(h.expect_sent().await.text, "✅ Group of 25 slings created");
+    let menu = h.main_menu().await;
+
+    h.press(menu.message_id, BotCallback::GroupSplitMenu(2, 0));
+    let first = h.expect_edited().await;
+    first.assert_text("Page 1 of 2");
+    assert!(first.has_button(&BotCallback::SplitFromGroup(8)));
+    assert!(!first.has_button(&BotCallback::SplitFromGroup(9)));
+
+    h.tap(&first, BotCallback::GroupSplitMenu(2, 1));
+    let second = h.expect_edited().await;
+    second.assert_text("Page 2 of 2");
+    assert_eq!(second.keyboard[0][0].0, "Brood #5");
+    assert!(second.has_button(&BotCallback::SplitFromGroup(13)));
+    assert!(second.has_button(&BotCallback::GroupSplitMenu(2, 0)));
+    assert!(second.has_button(&BotCallback::GroupDetails(2)));
+
+    h.finish().await;
+}
+
+#[tokio::test]
+async fn sling_is_split_out_under_a_new_name() {
+    let mut h = keeper_with_group().await;
+    let menu = h.main_menu().await;
+
+    h.press(menu.message_id, BotCallback::GroupDetails(GROUP));
+    let details = h.expect_edited().await;
+    h.tap(&details, BotCallback::GroupSplitMenu(GROUP, 0));
+    let members = h.expect_edited().await;
+    members.assert_text("Which sling should become an individual?");
+    assert_eq!(members.keyboard[1][0].0, "Batch #3");
+
+    h.tap(&members, BotCallback::SplitFromGroup(3));
+    assert_eq!(
+        h.expect_sent().await.text,
+        "Send a name for the sling, or - to keep its current name"
+    );
+    h.expect_silence().await;
+    assert!(matches!(
+        h.dialogue_state().await,
+        Some(DialogueState::SplitFromGroup { tarantula_id: 3 })
+    ));
+
+    h.send("Solo");
+    let split = h.expect_sent().await;
+    assert_eq!(split.text, "✅ Solo is now tracked individually");
+    assert!(split.has_button(&BotCallback::FeedTarantula(3)));
+    h.expect_silence().await;
+    assert!(h.dialogue_state().await.is_none());
+
+    h.tap(&split, BotCallback::GroupsMenu);
+    h.expect_edited().await.assert_text("▫️ Slings: 3");
+
+    h.finish().await;
+}
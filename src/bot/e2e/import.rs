@@ -0,0 +1,103 @@
+use super::{Harness, KEEPER};
+use crate::app::callbacks::BotCallback;
+use crate::app::dialogue::DialogueState;
+use teloxide::prelude::ChatId;
+
+const COLLECTION: &str = "Name,Species,Acquired,Enclosure\n\
+                          Rosie,Chilean rose,2019-04-02,A1\n\
+                          Boris,Pterinochilus murinis,,A2\n\
+                          Ghost,Poecilotheria metallica,2020-01-01,\n";
+
+#[tokio::test]
+async fn an_uploaded_spreadsheet_is_previewed_and_imported_on_confirmation() {
+    let mut h = Harness::start().await;
+    h.main_menu().await;
+
+    let upload = h.send_document(
+        ChatId(KEEPER),
+        "my tarantulas.csv",
+        COLLECTION.as_bytes().to_vec(),
+        "",
+    );
+    h.expect_download(&upload).await;
+    let preview = h.expect_sent().await;
+    preview.assert_text("📥 Import preview for my tarantulas.csv");
+    preview.assert_text("• 2 tarantulas");
+    preview.assert_text("Pterinochilus murinis → Pterinochilus murinus");
+    preview.assert_text("species Poecilotheria metallica not recognised");
+    assert!(matches!(
+        h.dialogue_state().await,
+        Some(DialogueState::ConfirmImport { .. })
+    ));
+    assert!(h
+        .db()
+        .get_all_tarantulas(KEEPER as u64)
+        .await
+        .unwrap()
+        .is_empty());
+
+    h.tap(&preview, BotCallback::ConfirmImport);
+    h.expect_edited()
+        .await
+        .assert_text("✅ Imported 2 tarantulas, 0 feedings and 0 molts.");
+    let mut names: Vec<_> = h
+        .db()
+        .get_all_tarantulas(KEEPER as u64)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|t| t.name)
+        .collect();
+    names.sort();
+    assert_eq!(names, ["Boris", "Rosie"]);
+    assert!(h.dialogue_state().await.is_none());
+
+    h.finish().await;
+}
+
+#[tokio::test]
+async fn a_cancelled_import_saves_nothing() {
+    let mut h = Harness::start().await;
+    h.main_menu().await;
+
+    let upload = h.send_document(
+        ChatId(KEEPER),
+        "tarantulas.csv",
+        COLLECTION.as_bytes().to_vec(),
+        "",
+    );
+    h.expect_download(&upload).await;
+    let preview = h.expect_sent().await;
+
+    h.send("ok");
+    h.expect_sent()
+        .await
+        .assert_text("Please confirm or cancel the import using the buttons above.");
+
+    h.tap(&preview, BotCallback::CancelImport);
+    h.expect_edited()
+        .await
+        .assert_text("Import cancelled, nothing was saved.");
+    assert!(h
+        .db()
+        .get_all_tarantulas(KEEPER as u64)
+        .await
+        .unwrap()
+        .is_empty());
+
+    h.finish().await;
+}
+
+#[tokio::test]
+async fn files_that_are_not_tables_are_explained() {
+    let mut h = Harness::start().await;
+    h.main_menu().await;
+
+    let upload = h.send_document(ChatId(KEEPER), "photo.jpg", b"\xFF\xD8".to_vec(), "");
+    h.expect_download(&upload).await;
+    h.expect_sent()
+        .await
+        .assert_text("⚠️ Send a .csv or .xlsx file, or the .zip from /export, to import it");
+
+    h.finish().await;
+}
@@ -0,0 +1,98 @@
+use super::Harness;
+
+/// Registers the keeper with Rosie, Rosalind and Boris, ids 1 to 3.
+async fn keeper_with_three() -> Harness {
+    let mut h = Harness::start().await;
+    h.main_menu().await;
+    for (name, species) in [("Rosie", 8), ("Rosalind", 8), ("Boris", 1)] {
+        h.run_command(&format!(
+            "/addtarantula {} {} 2024-01-01 12 -",
+            name, species
+        ))
+        .await;
+    }
+    h
+}
+
+#[tokio::test]
+async fn inline_queries_offer_matching_tarantulas_as_cards() {
+    let mut h = keeper_with_three().await;
+
+    h.inline_query("ros");
+    let results = h.expect_inline_results().await;
+    let titles: Vec<&str> = results
+        .iter()
+        .map(|r| r["title"].as_str().unwrap())
+        .collect();
+    assert_eq!(titles, ["Rosalind", "Rosie"]);
+
+    let rosie = &results[1];
+    assert_eq!(rosie["type"], "article");
+    assert_eq!(rosie["id"], "1");
+    assert_eq!(rosie["description"], "Chilean Rose · never fed · Unknown");
+    let card = rosie["input_message_content"]["message_text"]
+        .as_str()
+        .unwrap();
+    assert!(card.starts_with("🕷 <b>Rosie</b>\n<i>Grammostola rosea</i> (Chilean Rose)"));
+    assert!(card.contains("🍽 <b>Last Fed:</b> Never"));
+    assert_eq!(rosie["input_message_content"]["parse_mode"], "HTML");
+    let buttons = &rosie["reply_markup"]["inline_keyboard"][0];
+    assert_eq!(
+        buttons[0]["url"],
+        "https://t.me/spider_test_bot?start=tarantula_profile_1"
+    );
+    assert_eq!(
+        buttons[1]["url"],
+        "https://t.me/spider_test_bot?start=feed_tarantula_1"
+    );
+
+    h.inline_query("");
+    assert_eq!(h.expect_inline_results().await.len(), 3);
+    h.inline_query("rosie");
+    assert_eq!(h.expect_inline_results().await.len(), 1);
+    h.inline_query("nobody");
+    assert!(h.expect_inline_results().await.is_empty());
+    assert!(h.metrics().contains("spider_bot_inline_queries_total 4"));
+
+    h.finish().await;
+}
+
+#[tokio::test]
+async fn card_text_escapes_what_the_keeper_typed() {
+    let mut h = keeper_with_three().await;
+    h.run_command("/addtarantula <Rosa>&Co 8 2024-01-01 12 -")
+        .await;
+
+    h.inline_query("co");
+    let results = h.expect_inline_results().await;
+    assert_eq!(results[0]["title"], "<Rosa>&Co");
+    let card = results[0]["input_message_content"]["message_text"]
+        .as_str()
+        .unwrap();
+    assert!(
+        card.starts_with("🕷 <b>&lt;Rosa&gt;&amp;Co</b>\n"),
+        "{}",
+        card
+    );
+
+    h.finish().await;
+}
+
+#[tokio::test]
+async fn card_buttons_open_the_bot_on_that_tarantula() {
+    let mut h = keeper_with_three().await;
+
+    h.send("/start tarantula_profile_1");
+    h.expect_sent().await.assert_text("🕷 *Rosie*");
+
+    h.send("/start feed_tarantula_3");
+    h.expect_sent().await.assert_text("Feeding *Boris*");
+
+    // Only the actions cards link to are taken from a payload.
+    h.send("/start delete_photo_1_1");
+    h.expect_sent()
+        .await
+        .assert_text("Welcome to your Tarantula Management System!");
+
+    h.finish().await;
+}
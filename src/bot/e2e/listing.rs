@@ -0,0 +1,112 @@
+use super::Harness;
+use crate::app::callbacks::BotCallback;
+use crate::app::dialogue::DialogueState;
+
+const BROWSE: u8 = 1;
+const FEED: u8 = 2;
+
+/// Registers the keeper with twelve tarantulas, Spider01 to Spider12 with ids
+/// to match, all Chilean Roses but Spider07, a Mexican Red Knee.
+async fn keeper_with_twelve() -> Harness {
+    let mut h = Harness::start().await;
+    h.main_menu().await;
+    for n in 1..=12 {
+        let species = if n == 7 { 1 } else { 8 };
+        h.run_command(&format!(
+            "/addtarantula Spider{:02} {} 2024-01-01 12 -",
+            n, species
+        ))
+        .await;
+    }
+    h
+}
+
+#[tokio::test]
+async fn large_collections_are_paged_sorted_and_filtered() {
+    let mut h = keeper_with_twelve().await;
+    let menu = h.main_menu().await;
+
+    h.tap(&menu, BotCallback::ListTarantulas);
+    let first = h.expect_edited().await;
+    first.assert_text("_12 shown, sorted by name_");
+    first.assert_text("*Spider10* (Chilean Rose)");
+    assert!(!first.text.contains("Spider11"));
+    first.assert_text("Page 1 of 2");
+    assert!(first.has_button(&BotCallback::TarantulaProfile(10)));
+    assert!(!first.has_button(&BotCallback::TarantulaProfile(11)));
+
+    h.tap(&first, BotCallback::TarantulaList(BROWSE, 1, 0, 1));
+    let second = h.expect_edited().await;
+    second.assert_text("Page 2 of 2");
+    assert_eq!(second.keyboard[0][1].0, "🕷 Spider12");
+    assert!(second.has_button(&BotCallback::TarantulaList(BROWSE, 1, 0, 0)));
+
+    h.tap(&second, BotCallback::TarantulaList(BROWSE, 2, 0, 0));
+    let by_species = h.expect_edited().await;
+    assert_eq!(by_species.keyboard[0][0].0, "🕷 Spider01");
+    assert_eq!(by_species.keyboard[3][0].0, "🕷 Spider08");
+
+    h.press(by_species.message_id, BotCallback::HealthStatus(3, 2));
+    h.expect_edited().await;
+    h.tap(&by_species, BotCallback::TarantulaList(BROWSE, 2, 3, 0));
+    let flagged = h.expect_edited().await;
+    flagged.assert_text("_1 shown, sorted by species · health flag_");
+    flagged.assert_text("*Spider03*");
+    assert!(!flagged.text.contains("Page"));
+
+    h.tap(&flagged, BotCallback::TarantulaList(BROWSE, 2, 2, 0));
+    h.expect_edited()
+        .await
+        .assert_text("No tarantulas match this filter.");
+
+    h.finish().await;
+}
+
+#[tokio::test]
+async fn searching_a_picker_jumps_straight_to_a_single_match() {
+    let mut h = keeper_with_twelve().await;
+    let menu = h.main_menu().await;
+
+    h.tap(&menu, BotCallback::RecordFeeding);
+    let picker = h.expect_edited().await;
+    picker.assert_text("*Record Feeding*");
+    assert_eq!(picker.keyboard[0][0].0, "Spider01 (Chilean Rose)");
+
+    h.tap(&picker, BotCallback::ListSearch(FEED));
+    h.expect_sent()
+        .await
+        .assert_text("Send a name, species or enclosure");
+    assert!(matches!(
+        h.dialogue_state().await,
+        Some(DialogueState::SearchTarantulas { .. })
+    ));
+
+    h.send("red knee");
+    h.expect_sent().await.assert_text("Feeding *Spider07*");
+    assert!(h.dialogue_state().await.is_none());
+
+    h.finish().await;
+}
+
+#[tokio::test]
+async fn find_lists_several_matches_and_says_when_there_are_none() {
+    let mut h = keeper_with_twelve().await;
+    h.main_menu().await;
+
+    h.send("/find spider1");
+    let matches = h.expect_sent().await;
+    matches.assert_text("🔍 3 tarantulas match \"spider1\"");
+    for id in [10, 11, 12] {
+        assert!(matches.has_button(&BotCallback::TarantulaProfile(id)));
+    }
+
+    h.send("/find Spider05");
+    h.expect_sent().await.assert_text("🕷 *Spider05*");
+
+    h.send("/find boris");
+    let none = h.expect_sent().await;
+    none.assert_text("No tarantula matches \"boris\".");
+    assert!(none.has_button(&BotCallback::ListSearch(BROWSE)));
+
+    h.finish().await;
+}
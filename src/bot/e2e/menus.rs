@@ -0,0 +1,209 @@
+use super::Harness;
+use crate::app::callbacks::BotCallback;
+use crate::app::dialogue::DialogueState;
+
+#[tokio::test]
+async fn start_shows_the_main_menu() {
+    let mut h = Harness::start().await;
+
+    let menu = h.main_menu().await;
+    menu.assert_text("• Feeding Due: 0");
+    menu.assert_text("• Health Alerts: 0");
+    for entry in [
+        BotCallback::ListTarantulas,
+        BotCallback::StatusOverview,
+        BotCallback::FeedingSchedule,
+        BotCallback::RecordFeeding,
+        BotCallback::HealthAlerts,
+        BotCallback::RecordHealthCheck,
+        BotCallback::MoltHistory,
+        BotCallback::RecordMolt,
+        BotCallback::Colonies,
+        BotCallback::ColonyMaintenance,
+        BotCallback::Maintenance,
+        BotCallback::ViewRecords,
+        BotCallback::BreedingMenu,
+        BotCallback::GroupsMenu,
+    ] {
+        assert!(menu.has_button(&entry), "main menu lacks {}", entry);
+    }
+
+    h.finish().await;
+}
+
+#[tokio::test]
+async fn help_lists_the_commands() {
+    let mut h = Harness::start().await;
+
+    h.send("/help");
+    let help = h.expect_sent().await;
+    help.assert_text("Available commands:");
+    help.assert_text("/addtarantula");
+    help.assert_text("/feedoverride");
+
+    h.finish().await;
+}
+
+#[tokio::test]
+async fn main_menu_entries_edit_the_menu_in_place() {
+    let mut h = Harness::start().await;
+    let menu = h.main_menu().await;
+
+    for (entry, heading, back) in [
+        (
+            BotCallback::ListTarantulas,
+            "No tarantulas found in the database.",
+            BotCallback::MainMenu,
+        ),
+        (
+            BotCallback::StatusOverview,
+            "*System Overview*",
+            BotCallback::MainMenu,
+        ),
+        (
+            BotCallback::FeedingSchedule,
+            "No feedings currently due!",
+            BotCallback::MainMenu,
+        ),
+        (
+            BotCallback::HealthAlerts,
+            "No health alerts!",
+            BotCallback::MainMenu,
+        ),
+        (
+            BotCallback::Maintenance,
+            "No maintenance tasks currently due!",
+            BotCallback::MainMenu,
+        ),
+        (
+            BotCallback::Colonies,
+            "No cricket colonies found in the database.",
+            BotCallback::MainMenu,
+        ),
+        (
+            BotCallback::MoltHistory,
+            "No molt records found.",
+            BotCallback::ViewRecords,
+        ),
+        (
+            BotCallback::RecordFeeding,
+            "*Record Feeding*",
+            BotCallback::MainMenu,
+        ),
+        (
+            BotCallback::RecordHealthCheck,
+            "*Health Check*",
+            BotCallback::MainMenu,
+        ),
+        (
+            BotCallback::RecordMolt,
+            "*Record Molt*",
+            BotCallback::MainMenu,
+        ),
+        (
+            BotCallback::ColonyMaintenance,
+            "*Colony Maintenance*",
+            BotCallback::MainMenu,
+        ),
+        (
+            BotCallback::BreedingMenu,
+            "No pairings logged yet.",
+            BotCallback::MainMenu,
+        ),
+        (
+            BotCallback::GroupsMenu,
+            "No groups yet.",
+            BotCallback::MainMenu,
+        ),
+    ] {
+        h.tap(&menu, entry);
+        let screen = h.expect_edited().await;
+        assert_eq!(screen.message_id, menu.message_id);
+        screen.assert_text(heading);
+        assert!(screen.has_button(&back), "no way back from {:?}", screen);
+    }
+
+    h.finish().await;
+}
+
+#[tokio::test]
+async fn main_menu_button_sends_a_fresh_menu() {
+    let mut h = Harness::start().await;
+    let menu = h.main_menu().await;
+
+    h.tap(&menu, BotCallback::ListTarantulas);
+    let list = h.expect_edited().await;
+    h.tap(&list, BotCallback::MainMenu);
+    let fresh = h.expect_sent().await;
+    assert_ne!(fresh.message_id, menu.message_id);
+    fresh.assert_text("Welcome to your Tarantula Management System!");
+
+    h.finish().await;
+}
+
+#[tokio::test]
+async fn view_records_switches_between_record_types() {
+    let mut h = Harness::start().await;
+    let menu = h.main_menu().await;
+
+    h.tap(&menu, BotCallback::ViewRecords);
+    let records = h.expect_edited().await;
+    records.assert_text("Select record type:");
+
+    for (entry, empty) in [
+        (BotCallback::ViewFeedingRecords, "No feeding records found."),
+        (
+            BotCallback::ViewHealthRecords,
+            "No health check records found.",
+        ),
+        (BotCallback::ViewMoltRecords, "No molt records found."),
+    ] {
+        h.tap(&records, entry);
+        let screen = h.expect_edited().await;
+        screen.assert_text(empty);
+        assert!(screen.has_button(&BotCallback::ViewRecords));
+
+        h.tap(&screen, BotCallback::ViewRecords);
+        h.expect_edited().await.assert_text("Select record type:");
+    }
+
+    h.finish().await;
+}
+
+#[tokio::test]
+async fn text_outside_a_dialogue_is_ignored() {
+    let mut h = Harness::start().await;
+    h.main_menu().await;
+
+    h.send("hello there");
+    h.finish().await;
+}
+
+#[tokio::test]
+async fn start_state_is_left_after_any_message() {
+    let mut h = Harness::start().await;
+    h.set_dialogue_state(DialogueState::Start).await;
+
+    h.send("hello there");
+    h.expect_silence().await;
+    assert!(h.dialogue_state().await.is_none());
+}
+
+#[tokio::test]
+async fn failing_handlers_reach_the_error_handler() {
+    let mut h = Harness::start().await;
+    let menu = h.main_menu().await;
+
+    h.press(menu.message_id, BotCallback::FeedTarantula(99));
+    h.expect_sent()
+        .await
+        .assert_text("❌ Tarantula with id 99 not found");
+    let error = h.expect_error().await;
+    assert!(
+        error.contains("Tarantula with id 99 not found"),
+        "{}",
+        error
+    );
+
+    h.finish().await;
+}
@@ -0,0 +1,69 @@
+use super::Harness;
+use crate::app::callbacks::BotCallback;
+
+const ROSIE: i64 = 1;
+
+#[track_caller]
+fn assert_metric(metrics: &str, line: &str) {
+    assert!(
+        metrics.lines().any(|l| l == line),
+        "expected {:?} in:\n{}",
+        line,
+        metrics
+    );
+}
+
+#[tokio::test]
+async fn conversation_is_counted() {
+    let mut h = Harness::start().await;
+    let menu = h.main_menu().await;
+    h.run_command("/addtarantula Rosie 8 2024-01-01 12 calm")
+        .await;
+
+    h.tap(&menu, BotCallback::RecordMolt);
+    let tarantulas = h.expect_edited().await;
+    h.tap(&tarantulas, BotCallback::MoltSimple(ROSIE));
+    h.expect_sent().await;
+    h.expect_silence().await;
+
+    let metrics = h.metrics();
+    assert_metric(&metrics, r#"spider_bot_commands_total{command="start"} 1"#);
+    assert_metric(
+        &metrics,
+        r#"spider_bot_commands_total{command="addtarantula"} 1"#,
+    );
+    assert_metric(
+        &metrics,
+        r#"spider_bot_callbacks_total{callback="record_molt"} 1"#,
+    );
+    assert_metric(
+        &metrics,
+        r#"spider_bot_callbacks_total{callback="molt_simple"} 1"#,
+    );
+    assert_metric(
+        &metrics,
+        r#"spider_bot_dialogues_active{state="record_molt"} 1"#,
+    );
+    assert_metric(
+        &metrics,
+        r#"spider_bot_db_query_duration_seconds_count{method="add_tarantula"} 1"#,
+    );
+
+    h.send("6");
+    h.expect_sent().await;
+    h.expect_sent().await;
+    h.press(tarantulas.message_id, BotCallback::FeedTarantula(99));
+    h.expect_sent().await;
+    h.expect_error().await;
+    h.expect_silence().await;
+
+    let metrics = h.metrics();
+    assert_metric(
+        &metrics,
+        r#"spider_bot_dialogues_active{state="record_molt"} 0"#,
+    );
+    assert_metric(
+        &metrics,
+        r#"spider_bot_handler_errors_total{kind="not_found"} 1"#,
+    );
+}
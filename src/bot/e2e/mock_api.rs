@@ -0,0 +1,273 @@
+//! A tiny stand-in for api.telegram.org. Updates pushed with [`MockApi::push_update`]
+//! are handed out through long-polled `getUpdates`, and every other method call is
+//! recorded so scenarios can assert on what the bot sent.
+
+use axum::body::Bytes;
+use axum::extract::{Path, State};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde_json::{json, Value};
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicI32, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio::sync::Notify;
+use tokio::task::JoinHandle;
+
+pub(crate) const BOT_ID: u64 = 4242;
+pub(crate) const BOT_USERNAME: &str = "spider_test_bot";
+
+/// Bot API methods the bot itself uses while starting up or polling. They are
+/// answered but never recorded.
+const PLUMBING: [&str; 4] = ["getMe", "getWebhookInfo", "deleteWebhook", "getUpdates"];
+
+#[derive(Debug, Clone)]
+pub(crate) struct ApiCall {
+    pub method: String,
+    pub body: Value,
+    pub result: Value,
+}
+
+#[derive(Default)]
+struct ApiState {
+    updates: Mutex<Vec<Value>>,
+    update_pushed: Notify,
+    calls: Mutex<VecDeque<ApiCall>>,
+    call_recorded: Notify,
+    last_message_id: AtomicI32,
+    /// `getMe` calls so far, which are plumbing and not recorded.
+    get_me_calls: AtomicUsize,
+    /// Downloadable files by id, which doubles as their path.
+    files: Mutex<HashMap<String, Vec<u8>>>,
+}
+
+pub(crate) struct MockApi {
+    state: Arc<ApiState>,
+    url: String,
+    server: JoinHandle<()>,
+}
+
+impl MockApi {
+    pub(crate) async fn start() -> Self {
+        let state = Arc::new(ApiState {
+            last_message_id: AtomicI32::new(1000),
+            ..Default::default()
+        });
+        let app = Router::new()
+            .route("/:token/:method", post(handle))
+            .route("/file/:token/:path", get(download))
+            .with_state(state.clone());
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        Self { state, url, server }
+    }
+
+    pub(crate) fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub(crate) fn push_update(&self, update: Value) {
+        self.state.updates.lock().unwrap().push(update);
+        self.state.update_pushed.notify_one();
+    }
+
+    pub(crate) fn get_me_calls(&self) -> usize {
+        self.state.get_me_calls.load(Ordering::SeqCst)
+    }
+
+    /// Makes `contents` available through `getFile` and a download.
+    pub(crate) fn host_file(&self, file_id: &str, contents: Vec<u8>) {
+        self.state
+            .files
+            .lock()
+            .unwrap()
+            .insert(file_id.to_string(), contents);
+    }
+
+    /// Waits for the next recorded call, or `None` once `wait` has passed.
+    pub(crate) async fn next_call(&self, wait: Duration) -> Option<ApiCall> {
+        let deadline = tokio::time::Instant::now() + wait;
+        loop {
+            let recorded = self.state.call_recorded.notified();
+            if let Some(call) = self.state.calls.lock().unwrap().pop_front() {
+                return Some(call);
+            }
+            if tokio::time::timeout_at(deadline, recorded).await.is_err() {
+                return self.state.calls.lock().unwrap().pop_front();
+            }
+        }
+    }
+}
+
+impl Drop for MockApi {
+    fn drop(&mut self) {
+        self.server.abort();
+    }
+}
+
+async fn handle(
+    State(state): State<Arc<ApiState>>,
+    Path((_token, method)): Path<(String, String)>,
+    body: Bytes,
+) -> Json<Value> {
+    // teloxide spells methods `SendMessage`, the docs `sendMessage`; Telegram
+    // accepts both, scenarios use the documented spelling.
+    let method = method[..1].to_lowercase() + &method[1..];
+    // Multipart requests such as `setWebhook` are kept as raw text.
+    let body: Value = serde_json::from_slice(&body)
+        .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(&body).into_owned()));
+    let result = match method.as_str() {
+        "getMe" => {
+            state.get_me_calls.fetch_add(1, Ordering::SeqCst);
+            json!({
+            "id": BOT_ID,
+            "is_bot": true,
+            "first_name": "Spider",
+            "username": BOT_USERNAME,
+            "can_join_groups": false,
+            "can_read_all_group_messages": false,
+            "supports_inline_queries": true,
+            })
+        }
+        "getWebhookInfo" => json!({
+            "url": "",
+            "has_custom_certificate": false,
+            "pending_update_count": 0,
+        }),
+        "getUpdates" => Value::Array(poll_updates(&state, &body).await),
+        "sendMessage" => {
+            let message_id = state.last_message_id.fetch_add(1, Ordering::SeqCst) + 1;
+            bot_message(message_id, &body)
+        }
+        "sendDocument" => {
+            let message_id = state.last_message_id.fetch_add(1, Ordering::SeqCst) + 1;
+            bot_message(message_id, &json!({}))
+        }
+        "sendPhoto" => {
+            let message_id = state.last_message_id.fetch_add(1, Ordering::SeqCst) + 1;
+            photo_message(message_id)
+        }
+        "editMessageMedia" => {
+            let body = body.as_str().unwrap_or_default();
+            let message_id = multipart_field(body, "message_id").and_then(|id| id.parse().ok());
+            photo_message(message_id.unwrap_or_default())
+        }
+        "getFile" => {
+            let file_id = body["file_id"].as_str().unwrap_or_default();
+            match state.files.lock().unwrap().get(file_id) {
+                Some(contents) => json!({
+                    "file_id": file_id,
+                    "file_unique_id": file_id,
+                    "file_size": contents.len(),
+                    "file_path": file_id,
+                }),
+                None => Value::Null,
+            }
+        }
+        "editMessageText" | "editMessageReplyMarkup" => bot_message(
+            body["message_id"].as_i64().unwrap_or_default() as i32,
+            &body,
+        ),
+        "setWebhook" | "deleteWebhook" | "answerCallbackQuery" | "answerInlineQuery" => {
+            Value::Bool(true)
+        }
+        _ => Value::Null,
+    };
+
+    if !PLUMBING.contains(&method.as_str()) {
+        state.calls.lock().unwrap().push_back(ApiCall {
+            method: method.clone(),
+            body,
+            result: result.clone(),
+        });
+        state.call_recorded.notify_waiters();
+    }
+
+    if result.is_null() {
+        return Json(json!({
+            "ok": false,
+            "error_code": 404,
+            "description": format!("Not Found: method {} is not mocked", method),
+        }));
+    }
+    Json(json!({ "ok": true, "result": result }))
+}
+
+async fn download(
+    State(state): State<Arc<ApiState>>,
+    Path((_token, path)): Path<(String, String)>,
+) -> Vec<u8> {
+    state
+        .files
+        .lock()
+        .unwrap()
+        .get(&path)
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// Long-polls like Telegram does: returns as soon as there is an update at or
+/// past `offset`, or an empty batch once the requested timeout runs out.
+async fn poll_updates(state: &ApiState, body: &Value) -> Vec<Value> {
+    let offset = body["offset"].as_i64().unwrap_or_default();
+    let timeout = Duration::from_secs(body["timeout"].as_u64().unwrap_or_default());
+    let pending = || -> Vec<Value> {
+        let mut updates = state.updates.lock().unwrap();
+        updates.retain(|u| u["update_id"].as_i64().unwrap_or_default() >= offset);
+        updates.clone()
+    };
+
+    let pending_now = pending();
+    if !pending_now.is_empty() || timeout.is_zero() {
+        return pending_now;
+    }
+    let _ = tokio::time::timeout(timeout, state.update_pushed.notified()).await;
+    pending()
+}
+
+/// The `Message` Telegram would hand back for a send or an edit in a private chat.
+fn bot_message(message_id: i32, body: &Value) -> Value {
+    let chat_id = body["chat_id"].as_i64().unwrap_or_default();
+    let mut message = json!({
+        "message_id": message_id,
+        "date": chrono::Utc::now().timestamp(),
+        "chat": { "id": chat_id, "type": "private", "first_name": "Keeper" },
+        "from": {
+            "id": BOT_ID,
+            "is_bot": true,
+            "first_name": "Spider",
+            "username": BOT_USERNAME,
+        },
+        "text": body["text"].as_str().unwrap_or("…"),
+    });
+    if !body["reply_markup"].is_null() {
+        message["reply_markup"] = body["reply_markup"].clone();
+    }
+    message
+}
+
+/// The value of the form field `name` in a multipart request body.
+pub(crate) fn multipart_field<'a>(body: &'a str, name: &str) -> Option<&'a str> {
+    let field = &body[body.find(&format!("name=\"{}\"", name))?..];
+    let value = &field[field.find("\r\n\r\n")? + 4..];
+    Some(&value[..value.find("\r\n--")?])
+}
+
+/// Photos come back without their caption or keyboard, the bot never reads them.
+fn photo_message(message_id: i32) -> Value {
+    let mut message = bot_message(message_id, &json!({}));
+    message.as_object_mut().unwrap().remove("text");
+    message["photo"] = json!([photo_size("photo")]);
+    message
+}
+
+/// The one size scenarios give a photo, sent or received.
+pub(crate) fn photo_size(file_id: &str) -> Value {
+    json!({ "file_id": file_id, "file_unique_id": file_id, "width": 640, "height": 480 })
+}
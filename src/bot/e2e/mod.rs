@@ -0,0 +1,503 @@
+//! End-to-end conversations: the real dispatcher, handlers and dialogues run
+//! against [`MockApi`] with an [`InMemoryDB`] behind them. Scenarios script
+//! what the keeper sends or taps and assert on the messages, edits and
+//! keyboards the bot produces.
+
+mod admin;
+mod backup;
+mod breeding;
+mod changes;
+mod colonies;
+mod export;
+mod feeding;
+mod groups;
+mod import;
+mod inline;
+mod listing;
+mod menus;
+mod records;
+mod metrics;
+mod mock_api;
+mod profile;
+mod quicklog;
+mod server;
+
+use crate::bot::bot::TarantulaBot;
+use crate::config::Config;
+use crate::app::callbacks::BotCallback;
+use crate::app::dialogue::DialogueState;
+use crate::db::db::TarantulaOperations;
+use crate::db::memory::InMemoryDB;
+use crate::error::BotError;
+use crate::metrics::Metrics;
+use futures_core::future::BoxFuture;
+use mock_api::{multipart_field, ApiCall, MockApi};
+use serde_json::{json, Value};
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+use teloxide::dispatching::dialogue::{InMemStorage, Storage};
+use teloxide::error_handlers::ErrorHandler;
+use teloxide::prelude::ChatId;
+use teloxide::Bot;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+/// The keeper talking to the bot. Private chats share the user's id.
+const KEEPER: i64 = 1001;
+
+/// How long to wait for the bot to react before failing a scenario.
+const REPLY_TIMEOUT: Duration = Duration::from_secs(5);
+/// How long the bot has to stay quiet at the end of a scenario.
+const QUIET_PERIOD: Duration = Duration::from_millis(200);
+
+/// A message as the keeper sees it after a send, or after an edit together
+/// with the keyboard that replaced the old one.
+#[derive(Debug)]
+pub(crate) struct Reply {
+    pub message_id: i32,
+    pub text: String,
+    pub keyboard: Vec<Vec<(String, String)>>,
+    /// The file id of a photo message; `text` is then its caption.
+    pub photo: Option<String>,
+}
+
+impl Reply {
+    fn new(call: &ApiCall) -> Self {
+        Self {
+            message_id: call.result["message_id"].as_i64().unwrap() as i32,
+            text: call.body["text"].as_str().unwrap_or_default().to_string(),
+            keyboard: Self::keyboard(&call.body["reply_markup"]),
+            photo: None,
+        }
+    }
+
+    /// Reads a `sendPhoto` or `editMessageMedia` call, which are multipart
+    /// even for a file id. An edit carries the photo and caption as `media`.
+    fn photo(call: &ApiCall) -> Self {
+        let body = call.body.as_str().unwrap_or_default();
+        let json = |name| {
+            multipart_field(body, name).map_or(Value::Null, |v| serde_json::from_str(v).unwrap())
+        };
+        let media = json("media");
+        let (photo, caption) = if media.is_null() {
+            (
+                multipart_field(body, "photo"),
+                multipart_field(body, "caption"),
+            )
+        } else {
+            (media["media"].as_str(), media["caption"].as_str())
+        };
+        Self {
+            message_id: call.result["message_id"].as_i64().unwrap() as i32,
+            text: caption.unwrap_or_default().to_string(),
+            keyboard: Self::keyboard(&json("reply_markup")),
+            photo: photo.map(str::to_string),
+        }
+    }
+
+    fn keyboard(markup: &Value) -> Vec<Vec<(String, String)>> {
+        markup["inline_keyboard"]
+            .as_array()
+            .map(|rows| {
+                rows.iter()
+                    .map(|row| {
+                        row.as_array()
+                            .unwrap()
+                            .iter()
+                            .map(|b| {
+                                (
+                                    b["text"].as_str().unwrap().to_string(),
+                                    b["callback_data"].as_str().unwrap().to_string(),
+                                )
+                            })
+                            .collect()
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn callbacks(&self) -> Vec<&str> {
+        self.keyboard
+            .iter()
+            .flatten()
+            .map(|(_, data)| data.as_str())
+            .collect()
+    }
+
+    pub(crate) fn has_button(&self, callback: &BotCallback) -> bool {
+        self.callbacks().contains(&callback.to_string().as_str())
+    }
+
+    #[track_caller]
+    pub(crate) fn assert_text(&self, expected: &str) {
+        assert!(
+            self.text.contains(expected),
+            "expected {:?} in message:\n{}",
+            expected,
+            self.text
+        );
+    }
+}
+
+/// Forwards dispatcher errors to the scenario instead of a Telegram chat.
+struct RecordErrors(mpsc::UnboundedSender<String>);
+
+impl ErrorHandler<BotError> for RecordErrors {
+    fn handle_error(self: Arc<Self>, error: BotError) -> BoxFuture<'static, ()> {
+        let _ = self.0.send(format!("{:?}", error));
+        Box::pin(async {})
+    }
+}
+
+pub(crate) struct Harness {
+    api: MockApi,
+    db: Arc<dyn TarantulaOperations + Send + Sync>,
+    dialogue: Arc<InMemStorage<DialogueState>>,
+    metrics: Arc<Metrics>,
+    errors: mpsc::UnboundedReceiver<String>,
+    dispatcher: JoinHandle<()>,
+    last_update_id: i64,
+    last_keeper_message_id: i32,
+    /// Bot messages that are photos, so taps on them say so.
+    photo_messages: HashSet<i32>,
+}
+
+impl Harness {
+    pub(crate) async fn start() -> Self {
+        Self::with_config(Config::default()).await
+    }
+
+    pub(crate) async fn with_config(config: Config) -> Self {
+        Self::with_db(config, Arc::new(InMemoryDB::new())).await
+    }
+
+    pub(crate) async fn with_db(
+        config: Config,
+        db: Arc<dyn TarantulaOperations + Send + Sync>,
+    ) -> Self {
+        let api = MockApi::start().await;
+        let bot = Bot::new("1234:e2e").set_api_url(api.url().parse().unwrap());
+        let tarantula_bot = TarantulaBot::with_db(bot, db.clone(), &config);
+        let dialogue = tarantula_bot.dialogue.clone();
+        let metrics = tarantula_bot.metrics.clone();
+
+        let (errors_tx, errors) = mpsc::unbounded_channel();
+        let mut dispatcher = tarantula_bot
+            .dispatcher(Arc::new(RecordErrors(errors_tx)))
+            .build();
+        let dispatcher = tokio::spawn(async move { dispatcher.dispatch().await });
+
+        Self {
+            api,
+            db,
+            dialogue,
+            metrics,
+            errors,
+            dispatcher,
+            last_update_id: 0,
+            last_keeper_message_id: 0,
+            photo_messages: HashSet::new(),
+        }
+    }
+
+    pub(crate) fn db(&self) -> &(dyn TarantulaOperations + Send + Sync) {
+        self.db.as_ref()
+    }
+
+    pub(crate) fn metrics(&self) -> String {
+        self.metrics.render(None)
+    }
+
+    /// Sends /start and returns the main menu it produces, which registers the
+    /// keeper on the way.
+    pub(crate) async fn main_menu(&mut self) -> Reply {
+        self.send("/start");
+        let menu = self.expect_sent().await;
+        menu.assert_text("Welcome to your Tarantula Management System!");
+        menu
+    }
+
+    /// Sends a command and checks the main menu comes back as confirmation.
+    pub(crate) async fn run_command(&mut self, command: &str) {
+        self.send(command);
+        self.expect_sent()
+            .await
+            .assert_text("Welcome to your Tarantula Management System!");
+    }
+
+    pub(crate) fn send(&mut self, text: &str) {
+        self.send_in(ChatId(KEEPER), text);
+    }
+
+    /// Sends `text` as the keeper in another chat, such as an admin group.
+    pub(crate) fn send_in(&mut self, chat_id: ChatId, text: &str) {
+        let mut message = self.keeper_message(chat_id);
+        message["text"] = json!(text);
+        self.push("message", message);
+    }
+
+    /// Uploads `contents` as a document with `caption`, from `chat_id`, and
+    /// returns its file id.
+    pub(crate) fn send_document(
+        &mut self,
+        chat_id: ChatId,
+        file_name: &str,
+        contents: Vec<u8>,
+        caption: &str,
+    ) -> String {
+        let file_id = format!("file-{}", self.last_update_id + 1);
+        let mut message = self.keeper_message(chat_id);
+        message["document"] = json!({
+            "file_id": file_id,
+            "file_unique_id": file_id,
+            "file_name": file_name,
+            "file_size": contents.len(),
+        });
+        message["caption"] = json!(caption);
+        self.api.host_file(&file_id, contents);
+        self.push("message", message);
+        file_id
+    }
+
+    /// Sends a photo with an optional caption, as the keeper.
+    pub(crate) fn send_photo(&mut self, file_id: &str, caption: Option<&str>) {
+        let mut message = self.keeper_message(ChatId(KEEPER));
+        message["photo"] = json!([mock_api::photo_size(file_id)]);
+        if let Some(caption) = caption {
+            message["caption"] = json!(caption);
+        }
+        self.push("message", message);
+    }
+
+    fn keeper_message(&mut self, chat_id: ChatId) -> Value {
+        self.last_keeper_message_id += 1;
+        let chat_type = if chat_id.is_user() { "private" } else { "group" };
+        json!({
+            "message_id": self.last_keeper_message_id,
+            "date": chrono::Utc::now().timestamp(),
+            "chat": { "id": chat_id.0, "type": chat_type, "first_name": "Keeper", "title": "Keepers" },
+            "from": keeper(),
+        })
+    }
+
+    /// Taps a button on `reply`, failing if the keyboard doesn't offer it.
+    #[track_caller]
+    pub(crate) fn tap(&mut self, reply: &Reply, callback: BotCallback) {
+        assert!(
+            reply.has_button(&callback),
+            "no {} button on {:?}",
+            callback,
+            reply
+        );
+        self.press(reply.message_id, callback);
+    }
+
+    /// Sends a callback query as if a button with `callback` was tapped on the
+    /// given message, whether or not it is shown there.
+    pub(crate) fn press(&mut self, message_id: i32, callback: BotCallback) {
+        let mut update = json!({
+            "id": format!("query-{}", self.last_update_id + 1),
+            "from": keeper(),
+            "chat_instance": "e2e",
+            "data": callback.to_string(),
+            "message": {
+                "message_id": message_id,
+                "date": chrono::Utc::now().timestamp(),
+                "chat": { "id": KEEPER, "type": "private", "first_name": "Keeper" },
+                "from": { "id": mock_api::BOT_ID, "is_bot": true, "first_name": "Spider" },
+                "text": "…",
+            },
+        });
+        if self.photo_messages.contains(&message_id) {
+            let message = update["message"].as_object_mut().unwrap();
+            message.remove("text");
+            message.insert("photo".to_string(), json!([mock_api::photo_size("photo")]));
+        }
+        self.push("callback_query", update);
+    }
+
+    /// Types `@bot query` in some chat, as the keeper.
+    pub(crate) fn inline_query(&mut self, query: &str) {
+        let update = json!({
+            "id": format!("inline-{}", self.last_update_id + 1),
+            "from": keeper(),
+            "query": query,
+            "offset": "",
+        });
+        self.push("inline_query", update);
+    }
+
+    fn push(&mut self, kind: &str, payload: Value) {
+        self.last_update_id += 1;
+        let mut update = json!({ "update_id": self.last_update_id });
+        update[kind] = payload;
+        self.api.push_update(update);
+    }
+
+    async fn expect_call(&mut self, method: &str) -> ApiCall {
+        let call = match self.api.next_call(REPLY_TIMEOUT).await {
+            Some(call) => call,
+            None => panic!(
+                "bot never called {}, errors: {:?}",
+                method,
+                self.drain_errors()
+            ),
+        };
+        assert_eq!(call.method, method, "unexpected call {:?}", call.body);
+        call
+    }
+
+    /// Waits for the answer to an inline query and returns its results.
+    pub(crate) async fn expect_inline_results(&mut self) -> Vec<Value> {
+        let call = self.expect_call("answerInlineQuery").await;
+        assert_eq!(call.body["is_personal"], json!(true));
+        call.body["results"].as_array().cloned().unwrap_or_default()
+    }
+
+    /// Waits for a new message from the bot. Answers to callback queries are
+    /// skipped, every tap gets one.
+    pub(crate) async fn expect_sent(&mut self) -> Reply {
+        self.expect_sent_to(ChatId(KEEPER)).await
+    }
+
+    pub(crate) async fn expect_sent_to(&mut self, chat_id: ChatId) -> Reply {
+        let call = self.expect_call_skipping_answers().await;
+        assert_eq!(
+            call.method, "sendMessage",
+            "unexpected call {:?}",
+            call.body
+        );
+        assert_eq!(call.body["chat_id"].as_i64(), Some(chat_id.0));
+        Reply::new(&call)
+    }
+
+    /// Waits for a file from the bot and returns the multipart request that
+    /// carried it, as text.
+    pub(crate) async fn expect_document_to(&mut self, chat_id: ChatId) -> String {
+        let call = self.expect_call_skipping_answers().await;
+        assert_eq!(
+            call.method, "sendDocument",
+            "unexpected call {:?}",
+            call.body
+        );
+        let body = call.body.as_str().unwrap_or_default().to_string();
+        assert!(
+            body.contains(&format!("\r\n\r\n{}\r\n", chat_id.0)),
+            "document not sent to {}:\n{}",
+            chat_id,
+            body
+        );
+        body
+    }
+
+    /// Waits for the bot to ask where to download a file sent to it.
+    pub(crate) async fn expect_download(&mut self, file_id: &str) {
+        let call = self.expect_call("getFile").await;
+        assert_eq!(call.body["file_id"].as_str(), Some(file_id));
+    }
+
+    /// Waits for the bot to rewrite a message: new text, then new keyboard.
+    pub(crate) async fn expect_edited(&mut self) -> Reply {
+        let text = self.expect_call_skipping_answers().await;
+        assert_eq!(
+            text.method, "editMessageText",
+            "unexpected call {:?}",
+            text.body
+        );
+        let markup = self.expect_call("editMessageReplyMarkup").await;
+        assert_eq!(text.body["message_id"], markup.body["message_id"]);
+
+        let mut reply = Reply::new(&text);
+        reply.keyboard = Reply::new(&markup).keyboard;
+        reply
+    }
+
+    /// Waits for a new photo message from the bot.
+    pub(crate) async fn expect_photo(&mut self) -> Reply {
+        let call = self.expect_call_skipping_answers().await;
+        assert_eq!(call.method, "sendPhoto", "unexpected call {:?}", call.body);
+        let reply = Reply::photo(&call);
+        self.photo_messages.insert(reply.message_id);
+        reply
+    }
+
+    /// Waits for the bot to swap the picture under a photo message.
+    pub(crate) async fn expect_photo_edited(&mut self) -> Reply {
+        let call = self.expect_call_skipping_answers().await;
+        assert_eq!(
+            call.method, "editMessageMedia",
+            "unexpected call {:?}",
+            call.body
+        );
+        Reply::photo(&call)
+    }
+
+    async fn expect_call_skipping_answers(&mut self) -> ApiCall {
+        loop {
+            let call =
+                self.api.next_call(REPLY_TIMEOUT).await.unwrap_or_else(|| {
+                    panic!("bot never replied, errors: {:?}", self.drain_errors())
+                });
+            if call.method != "answerCallbackQuery" {
+                return call;
+            }
+        }
+    }
+
+    /// Waits for a handler error to reach the dispatcher's error handler.
+    pub(crate) async fn expect_error(&mut self) -> String {
+        tokio::time::timeout(REPLY_TIMEOUT, self.errors.recv())
+            .await
+            .expect("no handler error was reported")
+            .unwrap()
+    }
+
+    pub(crate) async fn dialogue_state(&self) -> Option<DialogueState> {
+        self.dialogue
+            .clone()
+            .get_dialogue(ChatId(KEEPER))
+            .await
+            .unwrap()
+    }
+
+    pub(crate) async fn set_dialogue_state(&self, state: DialogueState) {
+        self.dialogue
+            .clone()
+            .update_dialogue(ChatId(KEEPER), state)
+            .await
+            .unwrap();
+    }
+
+    fn drain_errors(&mut self) -> Vec<String> {
+        std::iter::from_fn(|| self.errors.try_recv().ok()).collect()
+    }
+
+    /// Ends a scenario, failing on anything the bot did that wasn't asserted.
+    pub(crate) async fn finish(mut self) {
+        self.expect_silence().await;
+    }
+
+    /// Gives the bot a moment and fails if it sends, edits or errors meanwhile.
+    pub(crate) async fn expect_silence(&mut self) {
+        while let Some(call) = self.api.next_call(QUIET_PERIOD).await {
+            assert_eq!(
+                call.method, "answerCallbackQuery",
+                "unexpected call {:?}",
+                call.body
+            );
+        }
+        assert_eq!(self.drain_errors(), Vec::<String>::new());
+    }
+}
+
+impl Drop for Harness {
+    fn drop(&mut self) {
+        self.dispatcher.abort();
+    }
+}
+
+fn keeper() -> Value {
+    json!({ "id": KEEPER, "is_bot": false, "first_name": "Keeper", "username": "keeper" })
+}
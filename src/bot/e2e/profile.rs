@@ -0,0 +1,132 @@
+use super::Harness;
+use crate::app::callbacks::BotCallback;
+use crate::app::dialogue::DialogueState;
+use crate::models::tarantula::TarantulaField;
+
+const ROSIE: i64 = 1;
+
+#[tokio::test]
+async fn the_list_leads_to_a_profile_with_every_action() {
+    let mut h = Harness::start().await;
+    let menu = h.main_menu().await;
+    h.run_command("/addtarantula Rosie 8 2024-01-01 12 calm")
+        .await;
+
+    h.tap(&menu, BotCallback::ListTarantulas);
+    let list = h.expect_edited().await;
+    h.tap(&list, BotCallback::TarantulaProfile(ROSIE));
+
+    let profile = h.expect_edited().await;
+    profile.assert_text("🕷 *Rosie*");
+    profile.assert_text("_Grammostola rosea_ (Chilean Rose)");
+    profile.assert_text("🍽 *Last Fed:* Never");
+    profile.assert_text("🏥 *Health:* Not checked");
+    profile.assert_text("• Feeding: Every 21-30 days");
+    profile.assert_text("📝 calm");
+    for action in [
+        BotCallback::FeedTarantula(ROSIE),
+        BotCallback::HealthCheck(ROSIE),
+        BotCallback::MoltSimple(ROSIE),
+        BotCallback::EditTarantula(ROSIE),
+        BotCallback::Timeline(ROSIE, 0),
+        BotCallback::TarantulaPhotos(ROSIE, 0),
+    ] {
+        assert!(profile.has_button(&action), "profile lacks {}", action);
+    }
+
+    h.tap(&profile, BotCallback::HealthCheck(ROSIE));
+    h.expect_edited().await.assert_text("Rosie");
+
+    h.finish().await;
+}
+
+#[tokio::test]
+async fn edits_go_through_a_dialogue_and_show_the_profile_again() {
+    let mut h = Harness::start().await;
+    h.main_menu().await;
+    h.run_command("/addtarantula Rosie 8 2024-01-01 12 calm")
+        .await;
+
+    h.press(1, BotCallback::TarantulaProfile(ROSIE));
+    let profile = h.expect_edited().await;
+    h.tap(&profile, BotCallback::EditTarantula(ROSIE));
+    let edit = h.expect_edited().await;
+    edit.assert_text("Enclosure: -");
+
+    h.tap(
+        &edit,
+        BotCallback::EditField(ROSIE, TarantulaField::Name as u8),
+    );
+    h.expect_sent()
+        .await
+        .assert_text("Please send the new name for Rosie:");
+    h.send("-");
+    h.expect_sent()
+        .await
+        .assert_text("Please send the new name");
+    assert!(matches!(
+        h.dialogue_state().await,
+        Some(DialogueState::EditTarantula { .. })
+    ));
+
+    h.send("Rosa");
+    h.expect_sent().await.assert_text("✅ Name updated");
+    h.expect_sent().await.assert_text("🕷 *Rosa*");
+    assert!(h.dialogue_state().await.is_none());
+
+    h.tap(
+        &edit,
+        BotCallback::EditField(ROSIE, TarantulaField::Notes as u8),
+    );
+    h.expect_sent().await;
+    h.send("-");
+    h.expect_sent().await.assert_text("✅ Notes updated");
+    assert!(!h.expect_sent().await.text.contains("📝"));
+
+    h.finish().await;
+}
+
+#[tokio::test]
+async fn photos_are_kept_and_shown_on_the_profile() {
+    let mut h = Harness::start().await;
+    h.main_menu().await;
+    h.run_command("/addtarantula Rosie 8 2024-01-01 12 calm")
+        .await;
+
+    h.press(1, BotCallback::TarantulaPhotos(ROSIE, 0));
+    let empty = h.expect_edited().await;
+    empty.assert_text("No photos of Rosie yet.");
+    h.tap(&empty, BotCallback::AddPhoto(ROSIE));
+    h.expect_sent().await.assert_text("Send a photo of Rosie.");
+    h.send_photo("first-shot", None);
+    h.expect_sent().await.assert_text("✅ Photo saved");
+    h.expect_photo().await;
+
+    h.press(1, BotCallback::AddPhoto(ROSIE));
+    h.expect_sent().await;
+    h.send("not a photo");
+    h.expect_sent().await.assert_text("Please send a photo");
+    h.send_photo("after-molt", Some("Fresh after her molt"));
+    h.expect_sent().await;
+    let newest = h.expect_photo().await;
+    assert_eq!(newest.photo.as_deref(), Some("after-molt"));
+    newest.assert_text("1 of 2");
+    newest.assert_text("Fresh after her molt");
+
+    h.tap(&newest, BotCallback::TarantulaPhotos(ROSIE, 1));
+    let older = h.expect_photo_edited().await;
+    assert_eq!(older.photo.as_deref(), Some("first-shot"));
+    assert_eq!(older.message_id, newest.message_id);
+
+    h.tap(&older, BotCallback::TarantulaProfile(ROSIE));
+    let profile = h.expect_photo_edited().await;
+    assert_eq!(profile.photo.as_deref(), Some("after-molt"));
+    profile.assert_text("🕷 *Rosie*");
+    assert!(profile.has_button(&BotCallback::TarantulaPhotos(ROSIE, 0)));
+
+    // Text can't replace a photo, so the edit menu arrives as a new message.
+    h.tap(&profile, BotCallback::EditTarantula(ROSIE));
+    h.expect_sent().await.assert_text("✏️ *Edit Rosie*");
+
+    h.finish().await;
+}
@@ -0,0 +1,251 @@
+use super::{Harness, KEEPER};
+use crate::app::callbacks::BotCallback;
+use crate::app::dialogue::DialogueState;
+use crate::models::tarantula::TarantulaField;
+use crate::models::user::Timezone;
+use chrono::{Days, Duration, Utc};
+
+const ROSIE: i64 = 1;
+const ROSALIND: i64 = 2;
+const BORIS: i64 = 3;
+const SMALLS: i64 = 1;
+const MEDIUMS: i64 = 2;
+
+/// Registers the keeper with Rosie, Rosalind and Boris, ids 1 to 3, and
+/// colonies of 100 small and 50 medium crickets.
+async fn keeper_with_rack() -> Harness {
+    let mut h = Harness::start().await;
+    h.main_menu().await;
+    for (name, species) in [("Rosie", 8), ("Rosalind", 8), ("Boris", 1)] {
+        h.run_command(&format!(
+            "/addtarantula {} {} 2024-01-01 12 -",
+            name, species
+        ))
+        .await;
+    }
+    h.run_command("/addcolony Smalls 2 100 box-1 -").await;
+    h.run_command("/addcolony Mediums 3 50 box-2 -").await;
+    h
+}
+
+async fn crickets_left(h: &Harness, colony_id: i64) -> i32 {
+    h.db()
+        .get_colony_status(KEEPER as u64)
+        .await
+        .unwrap()
+        .into_iter()
+        .find(|c| c.id == colony_id)
+        .unwrap()
+        .current_count
+}
+
+#[tokio::test]
+async fn feedings_and_refusals_are_logged_from_a_message_and_undone() {
+    let mut h = keeper_with_rack().await;
+
+    h.send("fed rosie 2 small");
+    let fed = h.expect_sent().await;
+    assert_eq!(
+        fed.text,
+        "✅ Fed *Rosie* 2 Small crickets from Smalls, 98 left."
+    );
+    assert!(fed.has_button(&BotCallback::Undo(1)));
+
+    h.tap(&fed, BotCallback::Undo(1));
+    assert_eq!(
+        h.expect_edited().await.text,
+        "↩️ Undone: 🍽 Feeding · Rosie · Smalls 100 → 98"
+    );
+    assert_eq!(crickets_left(&h, SMALLS).await, 100);
+
+    h.send("Rosie refused");
+    let refused = h.expect_sent().await;
+    refused.assert_text("✅ Logged a refused meal for *Rosie*.");
+    assert!(refused.has_button(&BotCallback::Undo(2)));
+
+    h.send("fed ros 1 small");
+    let which = h.expect_sent().await;
+    which.assert_text("🤔 Which one did you mean by \"ros\"?");
+    assert!(which.has_button(&BotCallback::QuickFeed(ROSIE, 0, 1, 2, 0)));
+    h.tap(&which, BotCallback::QuickFeed(ROSALIND, 0, 1, 2, 0));
+    h.expect_edited()
+        .await
+        .assert_text("✅ Fed *Rosalind* 1 Small cricket from Smalls");
+
+    h.send("fed boris 3 large");
+    h.expect_sent()
+        .await
+        .assert_text("🦗 No colony has 3 Large crickets to spare for *Boris*.");
+
+    h.finish().await;
+}
+
+#[tokio::test]
+async fn a_quick_log_can_say_when_it_happened() {
+    let mut h = keeper_with_rack().await;
+    let two_days_ago = Utc::now().date_naive() - Days::new(2);
+
+    h.send("fed rosie 2 small 2 days ago at 19:00");
+    h.expect_sent().await.assert_text(&format!(
+        "✅ Fed *Rosie* 2 Small crickets from Smalls, 98 left.\n📅 {} 19:00",
+        two_days_ago
+    ));
+    let records = h
+        .db()
+        .get_recent_feeding_records(KEEPER as u64, 10)
+        .await
+        .unwrap();
+    assert!(records[0]
+        .feeding_date
+        .starts_with(&format!("{} 19:00", two_days_ago)));
+
+    let at = two_days_ago.and_hms_opt(19, 0, 0).unwrap();
+    h.send("fed ros 1 small 2 days ago at 19:00");
+    let which = h.expect_sent().await;
+    let stamp = at.and_utc().timestamp();
+    assert!(which.has_button(&BotCallback::QuickFeed(ROSIE, 0, 1, 2, stamp)));
+
+    h.send("molt boris 6.5cm on 2023-12-31");
+    h.expect_sent()
+        .await
+        .assert_text("⚠️ Boris was only acquired on 2024-01-01");
+    h.expect_error().await;
+
+    h.finish().await;
+}
+
+#[tokio::test]
+async fn quick_logs_follow_the_keepers_timezone() {
+    let mut h = keeper_with_rack().await;
+
+    h.send("/timezone");
+    h.expect_sent()
+        .await
+        .assert_text("🕐 Your timezone is UTC, where");
+    h.send("/timezone soon");
+    h.expect_sent()
+        .await
+        .assert_text("⚠️ soon is not a timezone");
+    h.send("/timezone +14:00");
+    h.expect_sent()
+        .await
+        .assert_text("✅ Timezone set to UTC+14, where");
+
+    let yesterday = (Utc::now() + Duration::hours(14)).date_naive() - Days::new(1);
+    h.send("fed rosie 2 small yesterday at 07:00");
+    h.expect_sent()
+        .await
+        .assert_text(&format!("📅 {} 07:00", yesterday));
+    let records = h
+        .db()
+        .get_recent_feeding_records(KEEPER as u64, 10)
+        .await
+        .unwrap();
+    assert_eq!(records[0].feeding_date, format!("{} 07:00:00", yesterday));
+
+    // Stored in UTC, so on UTC it was 17:00 the day before.
+    h.db()
+        .set_timezone(KEEPER as u64, Timezone::default())
+        .await
+        .unwrap();
+    let records = h
+        .db()
+        .get_recent_feeding_records(KEEPER as u64, 10)
+        .await
+        .unwrap();
+    let utc_day = yesterday - Days::new(1);
+    assert_eq!(records[0].feeding_date, format!("{} 17:00:00", utc_day));
+
+    h.finish().await;
+}
+
+#[tokio::test]
+async fn molts_and_colony_counts_are_logged_from_a_message_and_undone() {
+    let mut h = keeper_with_rack().await;
+
+    h.send("molt boris 6.5cm");
+    let molted = h.expect_sent().await;
+    molted.assert_text("✅ Molt recorded for *Boris* at 6.5 cm.");
+    h.tap(&molted, BotCallback::Undo(1));
+    h.expect_edited()
+        .await
+        .assert_text("↩️ Undone: 🐚 Molt · Boris");
+    let boris = h
+        .db()
+        .get_tarantula_by_id(KEEPER as u64, BORIS)
+        .await
+        .unwrap();
+    assert_eq!(boris.last_molt_date, None);
+    assert_eq!(boris.current_molt_stage_id, None);
+
+    h.send("boris molted");
+    h.expect_sent()
+        .await
+        .assert_text("Please enter the molt size in centimeters:");
+    assert!(matches!(
+        h.dialogue_state().await,
+        Some(DialogueState::RecordMolt {
+            tarantula_id: BORIS,
+            at: None
+        })
+    ));
+    h.send("7");
+    h.expect_sent().await;
+    h.expect_sent().await.assert_text("Molt recorded");
+
+    h.send("colony smalls +200");
+    let added = h.expect_sent().await;
+    assert_eq!(added.text, "✅ *Smalls*: 100 → 300 crickets.");
+    h.tap(&added, BotCallback::Undo(3));
+    h.expect_edited()
+        .await
+        .assert_text("↩️ Undone: 🦗 Colony count · Smalls 100 → 300");
+    assert_eq!(crickets_left(&h, SMALLS).await, 100);
+
+    h.send("colony s 40");
+    let which = h.expect_sent().await;
+    assert!(which.has_button(&BotCallback::QuickColony(SMALLS, -60)));
+    h.tap(&which, BotCallback::QuickColony(MEDIUMS, -10));
+    h.expect_edited()
+        .await
+        .assert_text("✅ *Mediums*: 50 → 40 crickets.");
+
+    h.send("colony mediums -500");
+    h.expect_sent()
+        .await
+        .assert_text("🦗 *Mediums* only has 40 crickets.");
+    assert_eq!(crickets_left(&h, MEDIUMS).await, 40);
+
+    h.finish().await;
+}
+
+#[tokio::test]
+async fn aliases_find_a_tarantula_and_chatter_gets_no_answer() {
+    let mut h = keeper_with_rack().await;
+
+    h.press(
+        1,
+        BotCallback::EditField(BORIS, TarantulaField::Aliases as u8),
+    );
+    h.expect_sent()
+        .await
+        .assert_text("Please send other names for Boris");
+    h.send("Bob, big b");
+    h.expect_sent().await.assert_text("✅ Aliases updated");
+    h.expect_sent().await;
+
+    h.send("fed Big B 1 medium");
+    h.expect_sent()
+        .await
+        .assert_text("✅ Fed *Boris* 1 Medium cricket from Mediums, 49 left.");
+
+    h.send("fed nobody");
+    h.expect_sent()
+        .await
+        .assert_text("🤷 No tarantula called \"nobody\".");
+
+    h.send("how is rosie doing?");
+    h.expect_silence().await;
+
+    h.finish().await;
+}
@@ -0,0 +1,155 @@
+use super::{Harness, KEEPER};
+use crate::app::callbacks::BotCallback;
+use crate::app::dialogue::DialogueState;
+use crate::models::enums::FeedingStatus;
+use crate::models::import::{ImportPlan, ImportTarget, ImportedFeeding};
+use chrono::NaiveDate;
+
+const ROSIE: i64 = 1;
+const BORIS: i64 = 2;
+
+/// Rosie fed on the first twelve days of January 2024, refusing on the 5th,
+/// and Boris once on the 20th.
+async fn keeper_with_history() -> Harness {
+    let mut h = Harness::start().await;
+    h.main_menu().await;
+    h.run_command("/addtarantula Rosie 8 2024-01-01 12 calm")
+        .await;
+    h.run_command("/addtarantula Boris 1 2024-01-01 12 calm")
+        .await;
+
+    let feeding = |tarantula, day| ImportedFeeding {
+        tarantula: ImportTarget::Existing(tarantula),
+        fed_at: NaiveDate::from_ymd_opt(2024, 1, day)
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap(),
+        colony_id: None,
+        number_of_crickets: 2,
+        status: if day == 5 {
+            FeedingStatus::Rejected
+        } else {
+            FeedingStatus::Accepted
+        },
+        notes: None,
+    };
+    let mut feedings: Vec<_> = (1..=12).map(|day| feeding(ROSIE, day)).collect();
+    feedings.push(feeding(BORIS, 20));
+    let plan = ImportPlan {
+        feedings,
+        ..ImportPlan::default()
+    };
+    h.db()
+        .import_collection(KEEPER as u64, &plan)
+        .await
+        .unwrap();
+    h
+}
+
+#[tokio::test]
+async fn feeding_records_are_paged() {
+    let mut h = keeper_with_history().await;
+    let menu = h.main_menu().await;
+    h.tap(&menu, BotCallback::ViewRecords);
+    let records = h.expect_edited().await;
+
+    h.tap(&records, BotCallback::ViewFeedingRecords);
+    let first = h.expect_edited().await;
+    first.assert_text("*Boris* - 2024-01-20 12:00:00");
+    first.assert_text("Page 1");
+    assert!(!first.text.contains("2024-01-02 "));
+    let next = BotCallback::RecordPage(1, 0, 0, 0, 0, 1);
+    h.tap(&first, next);
+
+    let second = h.expect_edited().await;
+    second.assert_text("Page 2");
+    second.assert_text("*Rosie* - 2024-01-02 12:00:00");
+    second.assert_text("*Rosie* - 2024-01-01 12:00:00");
+    assert!(!second.has_button(&BotCallback::RecordPage(1, 0, 0, 0, 0, 2)));
+    h.tap(&second, BotCallback::RecordPage(1, 0, 0, 0, 0, 0));
+    assert_eq!(h.expect_edited().await.text, first.text);
+
+    h.finish().await;
+}
+
+#[tokio::test]
+async fn records_are_filtered_by_tarantula_status_and_dates() {
+    let mut h = keeper_with_history().await;
+    let menu = h.main_menu().await;
+    h.tap(&menu, BotCallback::ViewRecords);
+    let records = h.expect_edited().await;
+    h.tap(&records, BotCallback::ViewFeedingRecords);
+    let all = h.expect_edited().await;
+
+    h.tap(&all, BotCallback::RecordFilters(1, 0, 0, 0, 0));
+    let filters = h.expect_edited().await;
+    filters.assert_text("Showing everything.");
+    h.tap(&filters, BotCallback::RecordPage(1, BORIS, 0, 0, 0, 0));
+    let boris = h.expect_edited().await;
+    boris.assert_text("_Boris_");
+    assert!(!boris.text.contains("*Rosie*"));
+
+    h.tap(&boris, BotCallback::RecordFilters(1, BORIS, 0, 0, 0));
+    let filters = h.expect_edited().await;
+    h.tap(&filters, BotCallback::RecordPage(1, 0, 0, 0, 0, 0));
+    let everyone = h.expect_edited().await;
+    h.tap(&everyone, BotCallback::RecordFilters(1, 0, 0, 0, 0));
+    let filters = h.expect_edited().await;
+    h.tap(&filters, BotCallback::RecordPage(1, 0, 2, 0, 0, 0));
+    let refused = h.expect_edited().await;
+    refused.assert_text("_Rejected_");
+    refused.assert_text("*Rosie* - 2024-01-05 12:00:00");
+    assert!(!refused.text.contains("Page"));
+
+    h.tap(&refused, BotCallback::RecordFilters(1, 0, 2, 0, 0));
+    let filters = h.expect_edited().await;
+    h.tap(&filters, BotCallback::RecordDates(1, 0, 2));
+    h.expect_sent()
+        .await
+        .assert_text("Send the first and last day of the feeding records to show");
+    assert!(matches!(
+        h.dialogue_state().await,
+        Some(DialogueState::RecordDateRange { kind: 1, .. })
+    ));
+
+    h.send("2024-01-06 2024-01-01");
+    h.expect_sent()
+        .await
+        .assert_text("Please send two days as YYYY-MM-DD YYYY-MM-DD");
+    h.send("2024-01-06 2024-01-20");
+    let range = h.expect_sent().await;
+    range.assert_text("_Rejected · 2024-01-06 – 2024-01-20_");
+    range.assert_text("No feeding records found.");
+    assert!(h.dialogue_state().await.is_none());
+
+    h.finish().await;
+}
+
+#[tokio::test]
+async fn a_tarantulas_timeline_merges_everything_recorded_for_it() {
+    let mut h = keeper_with_history().await;
+    h.run_command("/addcolony Bin 2 100 box-1 main").await;
+    let menu = h.main_menu().await;
+    h.press(menu.message_id, BotCallback::HealthStatus(ROSIE, 2));
+    h.expect_edited().await;
+
+    h.press(
+        menu.message_id,
+        BotCallback::RecordPage(1, ROSIE, 0, 0, 0, 0),
+    );
+    let rosie = h.expect_edited().await;
+    h.tap(&rosie, BotCallback::Timeline(ROSIE, 0));
+    let timeline = h.expect_edited().await;
+    timeline.assert_text("🕰 *Rosie's timeline*");
+    timeline.assert_text("🏥 Health check: Monitor");
+    timeline.assert_text("*2024-01-12 12:00* 🍽 2 crickets, Accepted");
+    timeline.assert_text("*2024-01-05 12:00* 🍽 2 crickets, Rejected");
+    assert!(!timeline.text.contains("Boris"));
+
+    h.tap(&timeline, BotCallback::Timeline(ROSIE, 1));
+    let older = h.expect_edited().await;
+    older.assert_text("Page 2");
+    older.assert_text("*2024-01-01 12:00* 🍽 2 crickets, Accepted");
+
+    h.finish().await;
+}
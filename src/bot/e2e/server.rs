@@ -0,0 +1,192 @@
+//! The HTTP side: probes, and updates arriving through the webhook instead of
+//! `getUpdates`. Requests go straight into the router, no port is bound.
+
+use super::mock_api::MockApi;
+use super::{keeper, KEEPER, QUIET_PERIOD, REPLY_TIMEOUT};
+use crate::bot::bot::TarantulaBot;
+use crate::bot::server::{self, WebhookConfig};
+use crate::config::Config;
+use crate::db::memory::InMemoryDB;
+use crate::metrics::Metrics;
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use axum::Router;
+use serde_json::json;
+use std::sync::Arc;
+use teloxide::error_handlers::LoggingErrorHandler;
+use teloxide::Bot;
+use tokio::net::TcpListener;
+use tower::ServiceExt;
+
+const SECRET: &str = "spider-secret_1";
+const URL: &str = "https://spiders.example/telegram/updates";
+
+fn bot(api_url: &str) -> Bot {
+    Bot::new("1234:e2e").set_api_url(api_url.parse().unwrap())
+}
+
+async fn call(router: &Router, request: Request<Body>) -> (StatusCode, String) {
+    let response = router.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
+
+async fn get(router: &Router, path: &str) -> (StatusCode, String) {
+    call(router, Request::get(path).body(Body::empty()).unwrap()).await
+}
+
+#[tokio::test]
+async fn ready_when_database_and_telegram_answer() {
+    let api = MockApi::start().await;
+    let router = server::probes(bot(api.url()), Arc::new(InMemoryDB::new()));
+
+    assert_eq!(
+        get(&router, "/healthz").await,
+        (StatusCode::OK, "ok\n".to_string())
+    );
+    assert_eq!(
+        get(&router, "/readyz").await,
+        (StatusCode::OK, "database: ok\ntelegram: ok\n".to_string())
+    );
+}
+
+#[tokio::test]
+async fn telegram_is_not_asked_again_on_every_probe() {
+    let api = MockApi::start().await;
+    let router = server::probes(bot(api.url()), Arc::new(InMemoryDB::new()));
+    for _ in 0..3 {
+        assert_eq!(
+            get(&router, "/readyz").await,
+            (StatusCode::OK, "database: ok\ntelegram: ok\n".to_string())
+        );
+    }
+    assert_eq!(api.get_me_calls(), 1);
+}
+
+#[tokio::test]
+async fn metrics_are_served_as_prometheus_text() {
+    let metrics = Arc::new(Metrics::new());
+    metrics.command_handled("start");
+    let router = server::metrics(metrics, Arc::new(InMemoryDB::new()));
+
+    let response = router
+        .oneshot(Request::get("/metrics").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers()["content-type"],
+        "text/plain; version=0.0.4"
+    );
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body = String::from_utf8(body.to_vec()).unwrap();
+    assert!(body.contains("spider_bot_commands_total{command=\"start\"} 1"));
+    assert!(body.contains("spider_bot_db_pool_max_connections 0"));
+}
+
+#[tokio::test]
+async fn not_ready_while_telegram_is_unreachable() {
+    let closed = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/", closed.local_addr().unwrap());
+    drop(closed);
+    let router = server::probes(bot(&url), Arc::new(InMemoryDB::new()));
+
+    assert_eq!(get(&router, "/healthz").await.0, StatusCode::OK);
+    let (status, body) = get(&router, "/readyz").await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert!(body.starts_with("database: ok\ntelegram: "), "{}", body);
+    assert!(!body.contains("telegram: ok"), "{}", body);
+}
+
+#[tokio::test]
+async fn webhook_updates_need_the_secret_token() {
+    let api = MockApi::start().await;
+    let bot = bot(api.url());
+    let config = WebhookConfig {
+        url: Some(URL.to_string()),
+        secret_token: Some(SECRET.to_string()),
+        secret_file: None,
+    };
+    let (updates, _stopped, router) =
+        server::webhook(bot.clone(), &config, "127.0.0.1:0".parse().unwrap())
+            .await
+            .unwrap();
+
+    let registered = api.next_call(REPLY_TIMEOUT).await.unwrap();
+    assert_eq!(registered.method, "setWebhook");
+    let form = registered.body.as_str().unwrap();
+    assert!(form.contains(URL), "{}", form);
+    assert!(form.contains(SECRET), "{}", form);
+
+    let mut dispatcher =
+        TarantulaBot::with_db(bot, Arc::new(InMemoryDB::new()), &Config::default())
+            .dispatcher(LoggingErrorHandler::new())
+            .build();
+    let dispatcher = tokio::spawn(async move {
+        dispatcher
+            .dispatch_with_listener(updates, LoggingErrorHandler::new())
+            .await
+    });
+
+    let update = json!({
+        "update_id": 1,
+        "message": {
+            "message_id": 1,
+            "date": chrono::Utc::now().timestamp(),
+            "chat": { "id": KEEPER, "type": "private", "first_name": "Keeper" },
+            "from": keeper(),
+            "text": "/start",
+        },
+    })
+    .to_string();
+    let post = |secret: Option<&str>| {
+        let mut request =
+            Request::post("/telegram/updates").header("content-type", "application/json");
+        if let Some(secret) = secret {
+            request = request.header("X-Telegram-Bot-Api-Secret-Token", secret);
+        }
+        request.body(Body::from(update.clone())).unwrap()
+    };
+
+    assert_eq!(call(&router, post(None)).await.0, StatusCode::UNAUTHORIZED);
+    assert_eq!(
+        call(&router, post(Some("guess"))).await.0,
+        StatusCode::UNAUTHORIZED
+    );
+    assert!(api.next_call(QUIET_PERIOD).await.is_none());
+
+    assert_eq!(call(&router, post(Some(SECRET))).await.0, StatusCode::OK);
+    let reply = api.next_call(REPLY_TIMEOUT).await.unwrap();
+    assert_eq!(reply.method, "sendMessage");
+    assert!(reply.body["text"]
+        .as_str()
+        .unwrap()
+        .contains("Welcome to your Tarantula Management System!"));
+
+    dispatcher.abort();
+}
+
+#[tokio::test]
+async fn webhook_url_and_secret_are_validated() {
+    let api = MockApi::start().await;
+    let addr = "127.0.0.1:0".parse().unwrap();
+    let config = |url: &str, secret: &str| WebhookConfig {
+        url: Some(url.to_string()),
+        secret_token: Some(secret.to_string()),
+        secret_file: None,
+    };
+
+    for bad in [
+        config("http://spiders.example/hook", SECRET),
+        config("https://spiders.example/hook", "not allowed!"),
+        config("https://spiders.example/hook", ""),
+    ] {
+        assert!(server::webhook(bot(api.url()), &bad, addr).await.is_err());
+    }
+    assert!(api.next_call(QUIET_PERIOD).await.is_none());
+}
@@ -1,6 +1,8 @@
+pub mod admin;
+#[allow(clippy::module_inception)]
 pub mod bot;
-mod commands;
-mod callbacks;
 mod notifications;
-mod keyboards;
-mod dialog;
+pub mod server;
+
+#[cfg(test)]
+mod e2e;
@@ -1,39 +1,64 @@
-use crate::db::db::{TarantulaDB, TarantulaOperations};
+use crate::config::{Config, Features, NotificationConfig};
+use crate::db::db::TarantulaOperations;
+use crate::metrics::Metrics;
+use crate::models::group::collapse_groups;
 use std::collections::HashMap;
 use std::sync::Arc;
-use chrono::Local;
+use chrono::{Duration, NaiveDate};
 use teloxide::payloads::SendMessageSetters;
 use teloxide::prelude::{ChatId, Requester};
 use teloxide::types::ParseMode;
+use teloxide::utils::html;
 use teloxide::Bot;
 use tokio::sync::RwLock;
-use tokio::time::{self, Duration};
-const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(3600);
+use tokio::time;
+use tracing::{info_span, Instrument};
+
+/// How often keepers' clocks are looked at for the daily feeding reminder.
+const FEEDING_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
 #[derive(Clone)]
 pub struct NotificationSystem {
     bot: Bot,
     db: Arc<dyn TarantulaOperations + Send + Sync>,
     user_chats: Arc<RwLock<HashMap<u64, ChatId>>>,
+    metrics: Arc<Metrics>,
+    settings: NotificationConfig,
+    features: Features,
 }
 
 impl NotificationSystem {
-    pub fn new(bot: Bot, db: Arc<dyn TarantulaOperations + Send + Sync>) -> Self {
+    pub fn new(
+        bot: Bot,
+        db: Arc<dyn TarantulaOperations + Send + Sync>,
+        metrics: Arc<Metrics>,
+        config: &Config,
+    ) -> Self {
         Self {
             bot,
             db,
             user_chats: Arc::new(RwLock::new(HashMap::new())),
+            metrics,
+            settings: config.notifications.clone(),
+            features: config.features.clone(),
         }
     }
 
     pub async fn start(self) {
         log::debug!("Starting notification system");
-        let feeding_task = self.clone();
-        let health_task = self.clone();
-        let colony_task = self.clone();
-
-        tokio::spawn(async move { feeding_task.run_feeding_checks().await });
-        tokio::spawn(async move { health_task.run_health_checks().await });
-        tokio::spawn(async move { colony_task.run_colony_checks().await });
+        let features = self.features.clone();
+        if features.feeding_reminders {
+            tokio::spawn(self.clone().run_feeding_checks());
+        }
+        if features.health_alerts {
+            tokio::spawn(self.clone().run_health_checks());
+        }
+        if features.colony_alerts {
+            tokio::spawn(self.clone().run_colony_checks());
+        }
+        if features.breeding_reminders {
+            tokio::spawn(self.run_breeding_checks());
+        }
     }
 
     pub async fn register_chat(&self, user_id: u64, chat_id: ChatId) {
@@ -44,155 +69,253 @@ impl NotificationSystem {
 
     async fn run_feeding_checks(self) {
         log::debug!("Starting feeding checks");
+        let mut interval = time::interval(FEEDING_CHECK_INTERVAL);
         let mut message = String::with_capacity(1024);
+        // The last of each keeper's days they were reminded on, in their
+        // timezone, so the reminder goes out once a day at feeding time on
+        // their clock.
+        let mut reminded: HashMap<u64, NaiveDate> = HashMap::new();
 
-        let mut next_check = Local::now();
         loop {
-            next_check = next_check.date().succ_opt().unwrap().and_hms_opt(9, 0, 0).unwrap();
-            let sleep_duration = next_check - Local::now();
-            time::sleep(sleep_duration.to_std().unwrap()).await;
+            interval.tick().await;
+            async {
+                let user_chats = self.user_chats.read().await.clone();
 
-            let user_chats = self.user_chats.read().await.clone();
+                for (&user_id, &chat_id) in user_chats.iter() {
+                    let Ok(timezone) = self.db.get_timezone(user_id).await else {
+                        continue;
+                    };
+                    let now = timezone.now();
+                    let past_feeding_time = now.time() >= self.settings.feeding_time;
+                    // Keepers first seen after feeding time, such as after a
+                    // restart, wait for tomorrow's rather than get another.
+                    let last = *reminded.entry(user_id).or_insert(if past_feeding_time {
+                        now.date()
+                    } else {
+                        now.date() - Duration::days(1)
+                    });
+                    if !past_feeding_time || last == now.date() {
+                        continue;
+                    }
+                    reminded.insert(user_id, now.date());
 
-            for (&user_id, &chat_id) in user_chats.iter() {
-                if let Ok(due_feedings) = self.db.get_tarantulas_due_feeding(user_id).await {
-                    if !due_feedings.is_empty() {
-                        message.clear();
-                        message.push_str("🍽 *Feeding Due*\n\n");
-
-                        let mut never_fed = Vec::new();
-                        let mut overdue = Vec::new();
-                        let mut due = Vec::new();
-
-                        for t in &due_feedings {
-                            if t.current_status.contains("Never fed") {
-                                never_fed.push(t);
-                            } else if t.current_status.contains("Overdue") {
-                                overdue.push(t);
-                            } else {
-                                due.push(t);
+                    if let Ok(due_feedings) = self.db.get_tarantulas_due_feeding(user_id).await {
+                        let due_feedings = collapse_groups(due_feedings);
+                        if !due_feedings.is_empty() {
+                            message.clear();
+                            message.push_str("🍽 *Feeding Due*\n\n");
+
+                            let mut never_fed = Vec::new();
+                            let mut overdue = Vec::new();
+                            let mut due = Vec::new();
+
+                            for t in &due_feedings {
+                                if t.current_status.contains("Never fed") {
+                                    never_fed.push(t);
+                                } else if t.current_status.contains("Overdue") {
+                                    overdue.push(t);
+                                } else {
+                                    due.push(t);
+                                }
                             }
-                        }
 
-                        if !never_fed.is_empty() {
-                            message.push_str("❗️ *Never Fed*\n");
-                            for t in never_fed {
-                                message.push_str(&format!("• {} ({})\n", t.name, t.species_name));
+                            if !never_fed.is_empty() {
+                                message.push_str("❗️ *Never Fed*\n");
+                                for t in never_fed {
+                                    message
+                                        .push_str(&format!("• {} ({})\n", t.name, t.species_name));
+                                }
+                                message.push('\n');
                             }
-                            message.push('\n');
-                        }
 
-                        if !overdue.is_empty() {
-                            message.push_str("⚠️ *Overdue*\n");
-                            for t in overdue {
-                                message.push_str(&format!(
-                                    "• {} - {} ({} days since last feeding)\n",
-                                    t.name,
-                                    t.current_status,
-                                    t.days_since_feeding.unwrap_or(0.0) as i32
-                                ));
+                            if !overdue.is_empty() {
+                                message.push_str("⚠️ *Overdue*\n");
+                                for t in overdue {
+                                    message.push_str(&format!(
+                                        "• {} - {} ({} days since last feeding)\n",
+                                        t.name,
+                                        t.current_status,
+                                        t.days_since_feeding.unwrap_or(0.0) as i32
+                                    ));
+                                }
+                                message.push('\n');
                             }
-                            message.push('\n');
-                        }
 
-                        if !due.is_empty() {
-                            message.push_str("📅 *Due for Feeding*\n");
-                            for t in due {
-                                message.push_str(&format!(
-                                    "• {} - {} days since last feeding\n",
-                                    t.name,
-                                    t.days_since_feeding.unwrap_or(0.0) as i32
-                                ));
+                            if !due.is_empty() {
+                                message.push_str("📅 *Due for Feeding*\n");
+                                for t in due {
+                                    message.push_str(&format!(
+                                        "• {} - {} days since last feeding\n",
+                                        t.name,
+                                        t.days_since_feeding.unwrap_or(0.0) as i32
+                                    ));
+                                }
                             }
-                        }
 
-                        if let Err(e) = self
-                            .bot
-                            .send_message(chat_id, &message)
-                            .parse_mode(ParseMode::MarkdownV2)
-                            .await
-                        {
-                            log::error!("Error sending feeding notification: {}", e);
+                            let sent = self
+                                .bot
+                                .send_message(chat_id, &message)
+                                .parse_mode(ParseMode::MarkdownV2)
+                                .await;
+                            if let Err(e) = &sent {
+                                log::error!("Error sending feeding notification: {}", e);
+                            }
+                            self.metrics.notification("feeding", sent.is_ok());
                         }
                     }
                 }
             }
+            .instrument(info_span!("notifications", category = "feeding"))
+            .await;
         }
     }
     async fn run_health_checks(self) {
         log::debug!("Starting health checks");
-        let mut interval = time::interval(HEALTH_CHECK_INTERVAL);
+        let mut interval = time::interval(self.settings.health_check_interval());
         let mut message = String::with_capacity(1024);
 
         loop {
             interval.tick().await;
-            let user_chats = self.user_chats.read().await;
+            async {
+                let user_chats = self.user_chats.read().await;
 
-            for (&user_id, &chat_id) in user_chats.iter() {
-                if let Ok(alerts) = self.db.get_health_alerts(user_id).await {
-                    let critical = alerts.iter().filter(|a| a.alert_type == "Critical");
+                for (&user_id, &chat_id) in user_chats.iter() {
+                    if let Ok(alerts) = self.db.get_health_alerts(user_id).await {
+                        let critical = alerts.iter().filter(|a| a.alert_type == "Critical");
 
-                    message.clear();
-                    let mut has_alerts = false;
-                    message.push_str("🚨 *Critical Health Alerts*\n\n");
+                        message.clear();
+                        let mut has_alerts = false;
+                        message.push_str("🚨 *Critical Health Alerts*\n\n");
 
-                    for alert in critical {
-                        has_alerts = true;
-                        use std::fmt::Write;
-                        let _ = writeln!(message, "• {} - {}", alert.name, alert.alert_type);
-                    }
+                        for alert in critical {
+                            has_alerts = true;
+                            use std::fmt::Write;
+                            let _ = writeln!(message, "• {} - {}", alert.name, alert.alert_type);
+                        }
 
-                    if has_alerts {
-                        let _ = self
-                            .bot
-                            .send_message(chat_id, &message)
-                            .parse_mode(ParseMode::Html)
-                            .await;
+                        if has_alerts {
+                            let sent = self
+                                .bot
+                                .send_message(chat_id, &message)
+                                .parse_mode(ParseMode::Html)
+                                .await;
+                            self.metrics.notification("health", sent.is_ok());
+                        }
                     }
                 }
             }
+            .instrument(info_span!("notifications", category = "health"))
+            .await;
         }
     }
 
     async fn run_colony_checks(self) {
         log::debug!("Starting colony checks");
-        let mut interval = time::interval(Duration::from_secs(86400));
+        let mut interval = time::interval(self.settings.colony_check_interval());
         let mut message = String::with_capacity(1024);
 
         loop {
             interval.tick().await;
-            let user_chats = self.user_chats.read().await;
-
-            for (&user_id, &chat_id) in user_chats.iter() {
-                if let Ok(colonies) = self.db.get_colony_status(user_id).await {
-                    let low_colonies = colonies
-                        .iter()
-                        .filter(|c| c.weeks_remaining.unwrap_or(0.0) < 2.0);
-
-                    message.clear();
-                    let mut has_alerts = false;
-                    message.push_str("🦗 *Low Cricket Colony Alert*\n\n");
-
-                    for colony in low_colonies {
-                        has_alerts = true;
-                        use std::fmt::Write;
-                        let _ = writeln!(
-                            message,
-                            "• {} - {:.1} weeks remaining",
-                            colony.colony_name,
-                            colony.weeks_remaining.unwrap_or(0.0)
-                        );
+            async {
+                let user_chats = self.user_chats.read().await;
+
+                for (&user_id, &chat_id) in user_chats.iter() {
+                    if let Ok(colonies) = self.db.get_colony_status(user_id).await {
+                        let low_colonies = colonies.iter().filter(|c| {
+                            c.weeks_remaining.unwrap_or(0.0) < self.settings.low_colony_weeks
+                        });
+
+                        message.clear();
+                        let mut has_alerts = false;
+                        message.push_str("🦗 *Low Cricket Colony Alert*\n\n");
+
+                        for colony in low_colonies {
+                            has_alerts = true;
+                            use std::fmt::Write;
+                            let _ = writeln!(
+                                message,
+                                "• {} - {:.1} weeks remaining",
+                                colony.colony_name,
+                                colony.weeks_remaining.unwrap_or(0.0)
+                            );
+                        }
+
+                        if has_alerts {
+                            let sent = self
+                                .bot
+                                .send_message(chat_id, &message)
+                                .parse_mode(ParseMode::Html)
+                                .await;
+                            self.metrics.notification("colony", sent.is_ok());
+                        }
                     }
+                }
+            }
+            .instrument(info_span!("notifications", category = "colony"))
+            .await;
+        }
+    }
+
+    async fn run_breeding_checks(self) {
+        log::debug!("Starting breeding checks");
+        let mut interval = time::interval(self.settings.breeding_check_interval());
+        let mut message = String::with_capacity(1024);
+
+        loop {
+            interval.tick().await;
+            async {
+                let user_chats = self.user_chats.read().await;
+
+                for (&user_id, &chat_id) in user_chats.iter() {
+                    if let Ok(sacs) = self
+                        .db
+                        .get_egg_sacs_due_pulling(user_id, self.settings.egg_sac_reminder_days)
+                        .await
+                    {
+                        if sacs.is_empty() {
+                            continue;
+                        }
+
+                        let today = match self.db.get_timezone(user_id).await {
+                            Ok(timezone) => timezone.today(),
+                            Err(_) => continue,
+                        };
+                        message.clear();
+                        message.push_str("🥚 <b>Egg Sac Pulling</b>\n\n");
+
+                        for sac in &sacs {
+                            use std::fmt::Write;
+                            let Some(expected) = sac.expected_pull_date else {
+                                continue;
+                            };
+                            let days = (expected - today).num_days();
+                            let when = match days {
+                                0 => "today".to_string(),
+                                d if d > 0 => format!("in {} days", d),
+                                d => format!("{} days overdue", -d),
+                            };
+                            let _ = writeln!(
+                                message,
+                                "• Sac #{} from {} × {} - pull {} ({})",
+                                sac.id,
+                                html::escape(&sac.female_name),
+                                html::escape(&sac.male_name),
+                                when,
+                                expected
+                            );
+                        }
 
-                    if has_alerts {
-                        let _ = self
+                        let sent = self
                             .bot
                             .send_message(chat_id, &message)
                             .parse_mode(ParseMode::Html)
                             .await;
+                        self.metrics.notification("breeding", sent.is_ok());
                     }
                 }
             }
+            .instrument(info_span!("notifications", category = "breeding"))
+            .await;
         }
     }
 }
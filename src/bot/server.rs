@@ -0,0 +1,238 @@
+//! The bot's HTTP side. `/healthz` and `/readyz` are always served for the
+//! Kubernetes probes; in webhook mode the same server also takes the updates
+//! Telegram posts, instead of the bot long-polling for them.
+
+use crate::db::db::TarantulaOperations;
+use crate::error::BotError;
+use crate::metrics::Metrics;
+use crate::BotResult;
+use axum::extract::State;
+use axum::http::{header, StatusCode};
+use axum::routing::get;
+use axum::Router;
+use serde::Deserialize;
+use std::convert::Infallible;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use teloxide::prelude::Requester;
+use teloxide::update_listeners::{webhooks, UpdateListener};
+use teloxide::Bot;
+use tokio::net::TcpListener;
+
+const DEFAULT_ADDR: &str = "0.0.0.0:8080";
+
+/// How long each readiness check may take before the bot counts as unready.
+const CHECK_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// How long a successful `getMe` vouches for Telegram, so probes every few
+/// seconds don't each reach out to the Bot API.
+const TELEGRAM_TTL: Duration = Duration::from_secs(60);
+
+/// The `[server]` section of the configuration.
+#[derive(Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerConfig {
+    pub addr: SocketAddr,
+    pub mode: UpdateMode,
+    pub webhook: WebhookConfig,
+    /// Serve `/metrics` next to the probes.
+    pub metrics: bool,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            addr: DEFAULT_ADDR.parse().expect("the default address is valid"),
+            mode: UpdateMode::default(),
+            webhook: WebhookConfig::default(),
+            metrics: true,
+        }
+    }
+}
+
+/// How updates reach the bot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdateMode {
+    #[default]
+    Polling,
+    Webhook,
+}
+
+#[derive(Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WebhookConfig {
+    /// The public `https://` URL Telegram posts to. Its path is the route
+    /// served here, so an ingress can forward it unchanged.
+    pub url: Option<String>,
+    /// Telegram echoes it in `X-Telegram-Bot-Api-Secret-Token` and requests
+    /// without it are rejected. A random one is used when unset.
+    #[serde(rename = "secret")]
+    pub secret_token: Option<String>,
+    /// Where to read `secret_token` from instead, when the configuration is
+    /// loaded.
+    pub secret_file: Option<PathBuf>,
+}
+
+impl WebhookConfig {
+    pub(crate) fn options(&self, addr: SocketAddr) -> BotResult<webhooks::Options> {
+        let Some(url) = &self.url else {
+            return Err(BotError::Config(
+                "server.webhook.url is required in webhook mode".to_string(),
+            ));
+        };
+        if !url.starts_with("https://") {
+            return Err(BotError::Config(format!(
+                "Telegram only posts to https:// webhooks, not {}",
+                url
+            )));
+        }
+        let parsed = url
+            .parse()
+            .map_err(|e| BotError::Config(format!("Invalid webhook URL {}: {}", url, e)))?;
+
+        let options = webhooks::Options::new(addr, parsed);
+        match &self.secret_token {
+            Some(secret) => {
+                validate_secret(secret)?;
+                Ok(options.secret_token(secret.clone()))
+            }
+            None => Ok(options),
+        }
+    }
+}
+
+/// Telegram takes 1 to 256 characters of `A-Z`, `a-z`, `0-9`, `_` and `-`.
+fn validate_secret(secret: &str) -> BotResult<()> {
+    let allowed = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '-';
+    if (1..=256).contains(&secret.len()) && secret.chars().all(allowed) {
+        Ok(())
+    } else {
+        Err(BotError::Config(
+            "The webhook secret must be 1-256 characters of A-Z, a-z, 0-9, _ and -".to_string(),
+        ))
+    }
+}
+
+#[derive(Clone)]
+struct Probes {
+    bot: Bot,
+    db: Arc<dyn TarantulaOperations + Send + Sync>,
+    /// When Telegram last accepted our token.
+    telegram_ok: Arc<Mutex<Option<Instant>>>,
+}
+
+/// `/healthz` answers while the process serves HTTP at all. `/readyz` also
+/// needs the database to take queries and Telegram to accept our token; the
+/// database is asked on every probe, Telegram once per [`TELEGRAM_TTL`].
+pub(crate) fn probes(bot: Bot, db: Arc<dyn TarantulaOperations + Send + Sync>) -> Router {
+    Router::new()
+        .route("/healthz", get(|| async { "ok\n" }))
+        .route("/readyz", get(readyz))
+        .with_state(Probes {
+            bot,
+            db,
+            telegram_ok: Arc::default(),
+        })
+}
+
+async fn readyz(State(probes): State<Probes>) -> (StatusCode, String) {
+    let (database, telegram) = tokio::join!(
+        check(probes.db.ping()),
+        check(async {
+            let fresh = probes
+                .telegram_ok
+                .lock()
+                .unwrap()
+                .is_some_and(|at| at.elapsed() < TELEGRAM_TTL);
+            if !fresh {
+                probes.bot.get_me().await?;
+                *probes.telegram_ok.lock().unwrap() = Some(Instant::now());
+            }
+            Ok(())
+        }),
+    );
+
+    let status = if database.is_ok() && telegram.is_ok() {
+        StatusCode::OK
+    } else {
+        log::warn!(
+            "Not ready: database {:?}, telegram {:?}",
+            database,
+            telegram
+        );
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    let body = format!(
+        "database: {}\ntelegram: {}\n",
+        database.err().unwrap_or_else(|| "ok".to_string()),
+        telegram.err().unwrap_or_else(|| "ok".to_string()),
+    );
+    (status, body)
+}
+
+async fn check(probe: impl Future<Output = BotResult<()>>) -> Result<(), String> {
+    match tokio::time::timeout(CHECK_TIMEOUT, probe).await {
+        Ok(result) => result.map_err(|e| e.to_string()),
+        Err(_) => Err(format!("no answer within {}s", CHECK_TIMEOUT.as_secs())),
+    }
+}
+
+/// `/metrics` in the Prometheus text format.
+pub(crate) fn metrics(
+    metrics: Arc<Metrics>,
+    db: Arc<dyn TarantulaOperations + Send + Sync>,
+) -> Router {
+    Router::new().route(
+        "/metrics",
+        get(move || async move {
+            (
+                [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+                metrics.render(db.pool_usage()),
+            )
+        }),
+    )
+}
+
+/// Points Telegram at the webhook and returns the listener to dispatch
+/// updates from, a future that resolves once the dispatcher stops it, and the
+/// route to serve.
+pub(crate) async fn webhook(
+    bot: Bot,
+    config: &WebhookConfig,
+    addr: SocketAddr,
+) -> BotResult<(
+    impl UpdateListener<Err = Infallible>,
+    impl Future<Output = ()> + Send,
+    Router,
+)> {
+    let options = config.options(addr)?;
+    log::info!("Receiving updates through the webhook at {}", options.url);
+    webhooks::axum_to_router(bot, options)
+        .await
+        .map_err(Into::into)
+}
+
+pub(crate) async fn bind(addr: SocketAddr) -> BotResult<TcpListener> {
+    let listener = TcpListener::bind(addr)
+        .await
+        .map_err(|e| BotError::OperationError(format!("Cannot listen on {}: {}", addr, e)))?;
+    log::info!("Serving HTTP on {}", addr);
+    Ok(listener)
+}
+
+pub(crate) async fn serve(
+    listener: TcpListener,
+    router: Router,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) {
+    if let Err(e) = axum::serve(listener, router)
+        .with_graceful_shutdown(shutdown)
+        .await
+    {
+        log::error!("HTTP server failed: {}", e);
+    }
+}
@@ -0,0 +1,522 @@
+//! Settings, read from a TOML file and then overridden by environment
+//! variables. The file is the one named by `SPIDER_BOT_CONFIG`, or
+//! `spider-bot.toml` in the working directory when that exists. Every setting
+//! has a default, so the bot also runs from environment variables alone.
+//! `config.example.toml` lists them all.
+
+use crate::backup::BackupConfig;
+use crate::bot::admin::AdminConfig;
+use crate::bot::server::{ServerConfig, UpdateMode};
+use crate::error::BotError;
+use crate::schedule::AlertThresholds;
+use crate::BotResult;
+use chrono::NaiveTime;
+use serde::Deserialize;
+use std::env;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use teloxide::prelude::ChatId;
+
+const CONFIG_VAR: &str = "SPIDER_BOT_CONFIG";
+const DEFAULT_FILE: &str = "spider-bot.toml";
+
+#[derive(Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub telegram: TelegramConfig,
+    pub database: DatabaseConfig,
+    pub admin: AdminConfig,
+    pub server: ServerConfig,
+    pub notifications: NotificationConfig,
+    pub alerts: AlertThresholds,
+    pub features: Features,
+    pub backup: BackupConfig,
+}
+
+#[derive(Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TelegramConfig {
+    /// Better left to `TELEGRAM_BOT_TOKEN` than written into the file.
+    pub token: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DatabaseConfig {
+    pub backend: Backend,
+    /// The SQLite file, created when missing.
+    pub path: PathBuf,
+}
+
+impl Default for DatabaseConfig {
+    fn default() -> Self {
+        Self {
+            backend: Backend::default(),
+            path: PathBuf::from("tarantulas.sqlite"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Backend {
+    #[default]
+    Sqlite,
+    /// Nothing is persisted, for trying out changes locally.
+    Memory,
+}
+
+/// When the reminders go out.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NotificationConfig {
+    /// Time of the daily feeding reminder, on each keeper's clock.
+    pub feeding_time: NaiveTime,
+    pub health_check_minutes: u64,
+    pub colony_check_hours: u64,
+    /// Colonies expected to last fewer weeks than this are reported.
+    pub low_colony_weeks: f64,
+    pub breeding_check_hours: u64,
+    /// Egg sacs due for pulling within this many days are reported.
+    pub egg_sac_reminder_days: i64,
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        Self {
+            feeding_time: NaiveTime::from_hms_opt(9, 0, 0).expect("09:00 is a time"),
+            health_check_minutes: 60,
+            colony_check_hours: 24,
+            low_colony_weeks: 2.0,
+            breeding_check_hours: 24,
+            egg_sac_reminder_days: 3,
+        }
+    }
+}
+
+impl NotificationConfig {
+    pub fn health_check_interval(&self) -> Duration {
+        Duration::from_secs(self.health_check_minutes.saturating_mul(60))
+    }
+
+    pub fn colony_check_interval(&self) -> Duration {
+        Duration::from_secs(self.colony_check_hours.saturating_mul(3600))
+    }
+
+    pub fn breeding_check_interval(&self) -> Duration {
+        Duration::from_secs(self.breeding_check_hours.saturating_mul(3600))
+    }
+}
+
+/// Parts of the bot that can be switched off. Everything is on by default.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Features {
+    pub feeding_reminders: bool,
+    pub health_alerts: bool,
+    pub colony_alerts: bool,
+    pub breeding_reminders: bool,
+}
+
+impl Default for Features {
+    fn default() -> Self {
+        Self {
+            feeding_reminders: true,
+            health_alerts: true,
+            colony_alerts: true,
+            breeding_reminders: true,
+        }
+    }
+}
+
+impl Config {
+    /// Reads the file and the environment and checks the result, naming every
+    /// setting that is wrong rather than only the first.
+    pub fn load() -> BotResult<Self> {
+        let (path, required) = match env::var(CONFIG_VAR) {
+            Ok(path) => (PathBuf::from(path), true),
+            Err(_) => (PathBuf::from(DEFAULT_FILE), false),
+        };
+        let file = match fs::read_to_string(&path) {
+            Ok(text) => Some(text),
+            Err(e) if !required && e.kind() == ErrorKind::NotFound => None,
+            Err(e) => {
+                return Err(BotError::Config(format!(
+                    "cannot read {}: {}",
+                    path.display(),
+                    e
+                )))
+            }
+        };
+        if file.is_some() {
+            log::info!("Reading configuration from {}", path.display());
+        }
+
+        let config =
+            Self::from_sources(file.as_deref().map(|text| (path.as_path(), text)), |name| {
+                env::var(name).ok()
+            })?;
+        if config.admin.chats.is_empty() {
+            log::warn!("No admin chats are configured, errors are only logged");
+        }
+        Ok(config)
+    }
+
+    /// The bot token, which only the bot itself needs.
+    pub fn token(&self) -> BotResult<&str> {
+        self.telegram
+            .token
+            .as_deref()
+            .filter(|token| !token.is_empty())
+            .ok_or_else(|| BotError::Config("set TELEGRAM_BOT_TOKEN or telegram.token".to_string()))
+    }
+
+    fn from_sources(
+        file: Option<(&Path, &str)>,
+        env: impl Fn(&str) -> Option<String>,
+    ) -> BotResult<Self> {
+        let mut config: Config = match file {
+            Some((path, text)) => toml::from_str(text)
+                .map_err(|e| BotError::Config(format!("{}: {}", path.display(), e)))?,
+            None => Config::default(),
+        };
+        config.apply_env(env)?;
+        config.read_secret_file()?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// The variables the bot read before it had a configuration file keep
+    /// working and win over the file.
+    fn apply_env(&mut self, env: impl Fn(&str) -> Option<String>) -> BotResult<()> {
+        if let Some(token) = env("TELEGRAM_BOT_TOKEN") {
+            self.telegram.token = Some(token);
+        }
+        if let Some(backend) = env("DATABASE_BACKEND") {
+            self.database.backend = match backend.as_str() {
+                "sqlite" => Backend::Sqlite,
+                "memory" => Backend::Memory,
+                other => {
+                    return Err(BotError::Config(format!(
+                        "DATABASE_BACKEND must be sqlite or memory, not {}",
+                        other
+                    )))
+                }
+            };
+        }
+        if let Some(path) = env("DATABASE_PATH") {
+            self.database.path = PathBuf::from(path);
+        }
+
+        let admins = env("ADMIN_CHAT_IDS")
+            .map(|ids| ("ADMIN_CHAT_IDS", ids))
+            .or_else(|| env("DEFAULT_CHAT_ID").map(|id| ("DEFAULT_CHAT_ID", id)));
+        if let Some((name, ids)) = admins {
+            self.admin.chats = parse_chat_ids(name, &ids)?;
+        }
+
+        if let Some(addr) = env("HTTP_ADDR") {
+            self.server.addr = addr
+                .parse()
+                .map_err(|_| BotError::Config(format!("HTTP_ADDR is not an address: {}", addr)))?;
+        }
+        if let Some(url) = env("WEBHOOK_URL") {
+            self.server.mode = UpdateMode::Webhook;
+            self.server.webhook.url = Some(url);
+        }
+        if let Some(path) = env("WEBHOOK_SECRET_FILE") {
+            self.server.webhook.secret_token = None;
+            self.server.webhook.secret_file = Some(PathBuf::from(path));
+        }
+        if let Some(secret) = env("WEBHOOK_SECRET") {
+            self.server.webhook.secret_token = Some(secret);
+            self.server.webhook.secret_file = None;
+        }
+        Ok(())
+    }
+
+    fn read_secret_file(&mut self) -> BotResult<()> {
+        let webhook = &mut self.server.webhook;
+        let Some(path) = &webhook.secret_file else {
+            return Ok(());
+        };
+        if webhook.secret_token.is_some() {
+            return Err(BotError::Config(
+                "set server.webhook.secret or server.webhook.secret_file, not both".to_string(),
+            ));
+        }
+        let secret = fs::read_to_string(path).map_err(|e| {
+            BotError::Config(format!(
+                "cannot read the webhook secret from {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        webhook.secret_token = Some(secret.trim().to_string());
+        Ok(())
+    }
+
+    fn validate(&self) -> BotResult<()> {
+        let mut problems = Vec::new();
+        let n = &self.notifications;
+        for (name, value) in [
+            ("notifications.health_check_minutes", n.health_check_minutes),
+            ("notifications.colony_check_hours", n.colony_check_hours),
+            ("notifications.breeding_check_hours", n.breeding_check_hours),
+        ] {
+            if value == 0 {
+                problems.push(format!("{} must be positive, not 0", name));
+            }
+        }
+        let a = &self.alerts;
+        for (name, value) in [
+            ("alerts.health_check_days", a.health_check_days),
+            ("alerts.feeding_strike_days", a.feeding_strike_days),
+            ("alerts.pre_molt_days", a.pre_molt_days),
+        ] {
+            if value <= 0 {
+                problems.push(format!("{} must be positive, not {}", name, value));
+            }
+        }
+        if n.egg_sac_reminder_days < 0 {
+            problems.push(format!(
+                "notifications.egg_sac_reminder_days can't be negative, not {}",
+                n.egg_sac_reminder_days
+            ));
+        }
+        if n.low_colony_weeks.is_nan() || n.low_colony_weeks < 0.0 {
+            problems.push(format!(
+                "notifications.low_colony_weeks can't be negative, not {}",
+                n.low_colony_weeks
+            ));
+        }
+
+        if self.database.backend == Backend::Sqlite && self.database.path.as_os_str().is_empty() {
+            problems.push("database.path is empty".to_string());
+        }
+        let b = &self.backup;
+        if b.directory.as_os_str().is_empty() {
+            problems.push("backup.directory is empty".to_string());
+        }
+        if b.interval_hours == 0 {
+            problems.push("backup.interval_hours must be positive, not 0".to_string());
+        }
+        if b.keep == 0 {
+            problems.push("backup.keep must be at least 1, not 0".to_string());
+        }
+        if self.server.mode == UpdateMode::Webhook {
+            if let Err(e) = self.server.webhook.options(self.server.addr) {
+                problems.push(problem(e));
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(BotError::Config(problems.join("; ")))
+        }
+    }
+}
+
+fn problem(error: BotError) -> String {
+    match error {
+        BotError::Config(message) => message,
+        other => other.to_string(),
+    }
+}
+
+fn parse_chat_ids(name: &str, value: &str) -> BotResult<Vec<ChatId>> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(|id| {
+            id.parse().map(ChatId).map_err(|_| {
+                BotError::Config(format!(
+                    "{} has a chat id that isn't a number: {}",
+                    name, id
+                ))
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::io::Write;
+
+    fn load(file: &str, vars: &[(&str, &str)]) -> BotResult<Config> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        Config::from_sources(Some((Path::new("test.toml"), file)), |name| {
+            vars.get(name).cloned()
+        })
+    }
+
+    #[track_caller]
+    fn assert_error(result: BotResult<Config>, expected: &str) {
+        match result {
+            Ok(_) => panic!("expected an error mentioning {:?}", expected),
+            Err(e) => assert!(e.to_string().contains(expected), "{}", e),
+        }
+    }
+
+    #[test]
+    fn the_example_is_the_default() {
+        let example = load(include_str!("../config.example.toml"), &[]).unwrap();
+        let default = Config::default();
+        assert_eq!(example.database.path, default.database.path);
+        assert_eq!(example.alerts, default.alerts);
+        assert_eq!(
+            example.notifications.feeding_time,
+            default.notifications.feeding_time
+        );
+        assert_eq!(example.server.addr, default.server.addr);
+        assert_eq!(example.server.mode, UpdateMode::Polling);
+        assert!(example.features.feeding_reminders);
+        assert_eq!(example.backup.directory, default.backup.directory);
+        assert_eq!(example.backup.keep, default.backup.keep);
+    }
+
+    #[test]
+    fn file_settings_are_read() {
+        let config = load(
+            r#"
+            [database]
+            backend = "memory"
+
+            [admin]
+            chats = [42, -1001234]
+
+            [notifications]
+            feeding_time = "07:30"
+            health_check_minutes = 15
+
+            [alerts]
+            health_check_days = 14
+
+            [features]
+            colony_alerts = false
+            "#,
+            &[],
+        )
+        .unwrap();
+
+        assert_eq!(config.database.backend, Backend::Memory);
+        assert_eq!(config.admin.chats, vec![ChatId(42), ChatId(-1001234)]);
+        assert_eq!(
+            config.notifications.feeding_time,
+            NaiveTime::from_hms_opt(7, 30, 0).unwrap()
+        );
+        assert_eq!(
+            config.notifications.health_check_interval(),
+            Duration::from_secs(900)
+        );
+        assert_eq!(config.alerts.health_check_days, 14);
+        assert_eq!(config.alerts.feeding_strike_days, 14);
+        assert!(!config.features.colony_alerts);
+        assert!(config.features.health_alerts);
+    }
+
+    #[test]
+    fn environment_wins_over_the_file() {
+        let config = load(
+            r#"
+            [telegram]
+            token = "from-file"
+
+            [database]
+            path = "/data/file.sqlite"
+
+            [admin]
+            chats = [1]
+            "#,
+            &[
+                ("TELEGRAM_BOT_TOKEN", "from-env"),
+                ("DATABASE_PATH", "/data/env.sqlite"),
+                ("ADMIN_CHAT_IDS", " 42, -1001234 ,"),
+                ("WEBHOOK_URL", "https://spiders.example/hook"),
+                ("WEBHOOK_SECRET", "s3cret"),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(config.token().unwrap(), "from-env");
+        assert_eq!(config.database.path, PathBuf::from("/data/env.sqlite"));
+        assert_eq!(config.admin.chats, vec![ChatId(42), ChatId(-1001234)]);
+        assert_eq!(config.server.mode, UpdateMode::Webhook);
+        assert_eq!(
+            config.server.webhook.secret_token.as_deref(),
+            Some("s3cret")
+        );
+    }
+
+    #[test]
+    fn the_old_default_chat_id_still_names_an_admin() {
+        let config = load("", &[("DEFAULT_CHAT_ID", "141671143")]).unwrap();
+        assert_eq!(config.admin.chats, vec![ChatId(141671143)]);
+
+        assert_error(
+            load("", &[("ADMIN_CHAT_IDS", "42,@ops")]),
+            "ADMIN_CHAT_IDS has a chat id that isn't a number: @ops",
+        );
+    }
+
+    #[test]
+    fn the_webhook_secret_can_come_from_a_file() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(file, "from-file").unwrap();
+        let path = file.path().to_str().unwrap();
+
+        let config = load("", &[("WEBHOOK_SECRET_FILE", path)]).unwrap();
+        assert_eq!(
+            config.server.webhook.secret_token.as_deref(),
+            Some("from-file")
+        );
+
+        let both = format!(
+            "[server.webhook]\nsecret = \"inline\"\nsecret_file = {:?}",
+            path
+        );
+        assert_error(load(&both, &[]), "not both");
+    }
+
+    #[test]
+    fn mistakes_are_named() {
+        assert_error(
+            load("[alerts]\nhealth_check_day = 30", &[]),
+            "unknown field `health_check_day`",
+        );
+        assert_error(
+            load("[notifications]\nfeeding_time = \"9am\"", &[]),
+            "feeding_time",
+        );
+        assert_error(
+            load("[server]\nmode = \"webhook\"", &[]),
+            "server.webhook.url is required in webhook mode",
+        );
+        assert_error(load("", &[("HTTP_ADDR", "everywhere")]), "HTTP_ADDR");
+        assert_error(load("[backup]\nkeep = 0", &[]), "backup.keep must be at least 1");
+
+        let result = load(
+            "[alerts]\nhealth_check_days = 0\n[notifications]\ncolony_check_hours = 0",
+            &[],
+        );
+        assert_error(result, "notifications.colony_check_hours must be positive, not 0; alerts.health_check_days must be positive, not 0");
+    }
+
+    #[test]
+    fn the_token_is_required_only_when_asked_for() {
+        let config = load("", &[]).unwrap();
+        assert_eq!(
+            config.token().unwrap_err().to_string(),
+            "Invalid configuration: set TELEGRAM_BOT_TOKEN or telegram.token"
+        );
+    }
+}
@@ -0,0 +1,1318 @@
+//! Behaviour every [`TarantulaOperations`] backend has to share. Each case runs
+//! against [`InMemoryDB`] and a temp-file [`TarantulaDB`] built by the
+//! migrations.
+
+use super::db::{
+    AddColonyParams, AddEggSacParams, AddPairingParams, AddTarantulaParams, CreateGroupParams,
+    RecordFilter, SetFeedingOverrideParams, TarantulaDB, TarantulaOperations,
+};
+use super::memory::InMemoryDB;
+use super::migrations;
+use crate::error::BotError;
+use crate::models::audit::ChangeKind;
+use crate::models::enums::{FeedingStatus, HealthStatus};
+use crate::models::feeding::{FeedingEvent, FeedingRecord};
+use crate::models::import::{
+    ImportPlan, ImportTarget, ImportedFeeding, ImportedMolt, ImportedTarantula,
+};
+use crate::models::lineage::{Parent, ParentRole};
+use crate::models::models::DbDateTime;
+use crate::models::new::Enclosure;
+use crate::models::tarantula::TarantulaField;
+use crate::models::user::{TelegramUser, Timezone};
+use crate::schedule::FeedingState;
+use chrono::{NaiveDate, Utc};
+use tempfile::TempDir;
+
+const ALICE: u64 = 1;
+const BOB: u64 = 2;
+const MEXICAN_RED_KNEE: i64 = 1;
+
+fn sqlite_db() -> (TarantulaDB, TempDir) {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("tarantulas.sqlite");
+    migrations::migrate(&mut rusqlite::Connection::open(&path).unwrap()).unwrap();
+
+    let db = TarantulaDB::new(path.to_str().unwrap()).unwrap();
+    (db, dir)
+}
+
+macro_rules! contract_tests {
+    ($($case:ident),* $(,)?) => {
+        mod memory {
+            $(
+                #[tokio::test]
+                async fn $case() {
+                    let db = super::InMemoryDB::new();
+                    super::register_users(&db).await;
+                    super::$case(&db).await;
+                }
+            )*
+        }
+
+        mod sqlite {
+            $(
+                #[tokio::test]
+                async fn $case() {
+                    let (db, _dir) = super::sqlite_db();
+                    super::register_users(&db).await;
+                    super::$case(&db).await;
+                }
+            )*
+        }
+    };
+}
+
+contract_tests!(
+    rows_need_a_registered_user,
+    tarantulas_are_isolated_per_user,
+    record_feeding_deducts_crickets,
+    record_feeding_rejects_short_or_foreign_colony,
+    refusals_and_undone_feedings_settle_the_colony,
+    unfed_tarantula_is_due,
+    fed_tarantula_is_not_due,
+    refusals_leave_a_tarantula_due,
+    feeding_override_round_trip,
+    group_feeding_needs_crickets_for_every_member,
+    health_and_molt_history_is_per_user,
+    undone_molts_and_health_checks_restore_what_they_replaced,
+    backdated_events_keep_the_latest_status,
+    events_cannot_be_logged_in_the_future_or_before_acquisition,
+    events_land_on_the_keepers_local_day,
+    zoned_times_follow_daylight_saving,
+    colony_counts_and_group_writes_are_logged_and_undone,
+    enclosures_are_listed_per_user,
+    import_writes_everything_or_nothing,
+    record_history_is_filtered_and_paged,
+    profile_edits_and_photos_are_per_user,
+    slings_from_egg_sac_are_linked_to_parents,
+    pairings_follow_the_sexes_already_recorded,
+    feeding_schedule_follows_species_seed,
+    ping_answers,
+);
+
+async fn register_users(db: &dyn TarantulaOperations) {
+    for (telegram_id, first_name) in [(ALICE, "Alice"), (BOB, "Bob")] {
+        db.ensure_user_exists(&TelegramUser {
+            telegram_id,
+            username: None,
+            first_name: first_name.to_string(),
+            last_name: None,
+        })
+        .await
+        .unwrap();
+    }
+}
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+async fn add_tarantula(db: &dyn TarantulaOperations, user_id: u64, name: &str) -> i64 {
+    db.add_tarantula(
+        user_id,
+        AddTarantulaParams {
+            name: name.to_string(),
+            species_id: MEXICAN_RED_KNEE,
+            acquisition_date: "2025-01-01".to_string(),
+            estimated_age_months: 6,
+            enclosure_number: None,
+            notes: None,
+        },
+    )
+    .await
+    .unwrap();
+
+    db.get_all_tarantulas(user_id)
+        .await
+        .unwrap()
+        .into_iter()
+        .find(|t| t.name == name)
+        .unwrap()
+        .id
+}
+
+async fn add_colony(db: &dyn TarantulaOperations, user_id: u64, name: &str, count: i32) -> i64 {
+    db.add_colony(
+        user_id,
+        AddColonyParams {
+            colony_name: name.to_string(),
+            size_type_id: 2,
+            current_count: count,
+            container_number: format!("{}-{}", user_id, name),
+            notes: None,
+        },
+    )
+    .await
+    .unwrap();
+
+    colony_count(db, user_id, name).await.0
+}
+
+/// Returns the colony id and how many crickets are left.
+async fn colony_count(db: &dyn TarantulaOperations, user_id: u64, name: &str) -> (i64, i32) {
+    db.get_colony_status(user_id)
+        .await
+        .unwrap()
+        .into_iter()
+        .find(|c| c.colony_name == name)
+        .map(|c| (c.id, c.current_count))
+        .unwrap()
+}
+
+fn feeding(tarantula_id: i64, colony_id: i64, crickets: i32) -> FeedingEvent {
+    FeedingEvent {
+        id: None,
+        tarantula_id,
+        feeding_date: DbDateTime::default(),
+        cricket_colony_id: colony_id,
+        number_of_crickets: crickets,
+        feeding_status_id: 1,
+        notes: None,
+    }
+}
+
+async fn rows_need_a_registered_user(db: &dyn TarantulaOperations) {
+    let stranger = 99;
+    let result = db
+        .add_tarantula(
+            stranger,
+            AddTarantulaParams {
+                name: "Stray".to_string(),
+                species_id: MEXICAN_RED_KNEE,
+                acquisition_date: "2025-01-01".to_string(),
+                estimated_age_months: 6,
+                enclosure_number: None,
+                notes: None,
+            },
+        )
+        .await;
+
+    assert!(matches!(result, Err(BotError::Database(_))));
+    assert!(db.get_all_tarantulas(stranger).await.unwrap().is_empty());
+}
+
+async fn tarantulas_are_isolated_per_user(db: &dyn TarantulaOperations) {
+    let id = add_tarantula(db, ALICE, "Rosie").await;
+
+    assert!(db.get_all_tarantulas(BOB).await.unwrap().is_empty());
+    assert!(db.get_tarantulas_due_feeding(BOB).await.unwrap().is_empty());
+    assert!(matches!(
+        db.get_tarantula_by_id(BOB, id).await,
+        Err(BotError::NotFound(_))
+    ));
+    assert!(matches!(
+        db.record_health_check(BOB, id, HealthStatus::Monitor, None, DbDateTime::default())
+            .await,
+        Err(BotError::NotFound(_))
+    ));
+    assert!(matches!(
+        db.get_feeding_plan(BOB, id).await,
+        Err(BotError::NotFound(_))
+    ));
+
+    assert_eq!(
+        db.get_tarantula_by_id(ALICE, id).await.unwrap().name,
+        "Rosie"
+    );
+}
+
+async fn record_feeding_deducts_crickets(db: &dyn TarantulaOperations) {
+    let id = add_tarantula(db, ALICE, "Rosie").await;
+    let colony_id = add_colony(db, ALICE, "Smalls", 10).await;
+
+    db.record_feeding(ALICE, feeding(id, colony_id, 3))
+        .await
+        .unwrap();
+
+    assert_eq!(colony_count(db, ALICE, "Smalls").await.1, 7);
+    let records = db.get_recent_feeding_records(ALICE, 10).await.unwrap();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].tarantula_name, "Rosie");
+    assert_eq!(records[0].colony_name.as_deref(), Some("Smalls"));
+    assert_eq!(records[0].status, "Accepted");
+}
+
+async fn refusals_and_undone_feedings_settle_the_colony(db: &dyn TarantulaOperations) {
+    let rosie = add_tarantula(db, ALICE, "Rosie").await;
+    let colony_id = add_colony(db, ALICE, "Smalls", 10).await;
+
+    let refusal = db
+        .record_refusal(ALICE, rosie, DbDateTime::default())
+        .await
+        .unwrap();
+    assert_eq!(refusal.kind, ChangeKind::Refusal);
+    let records = db.get_recent_feeding_records(ALICE, 10).await.unwrap();
+    assert_eq!(records[0].status, "Rejected");
+    assert_eq!(records[0].number_of_crickets, 0);
+    assert!(matches!(
+        db.record_refusal(BOB, rosie, DbDateTime::default()).await,
+        Err(BotError::NotFound(_))
+    ));
+
+    let fed = db
+        .record_feeding(ALICE, feeding(rosie, colony_id, 3))
+        .await
+        .unwrap();
+    assert_eq!(fed.kind, ChangeKind::Feeding);
+    assert_eq!(fed.before.colonies[&colony_id], 10);
+    assert_eq!(fed.after.colonies[&colony_id], 7);
+    assert_eq!(fed.after.feedings.len(), 1);
+    assert!(matches!(
+        db.undo_change(BOB, fed.id).await,
+        Err(BotError::NotFound(_))
+    ));
+
+    let undone = db.undo_change(ALICE, fed.id).await.unwrap();
+    assert!(undone.undone_at.is_some());
+    assert_eq!(colony_count(db, ALICE, "Smalls").await.1, 10);
+    db.undo_change(ALICE, refusal.id).await.unwrap();
+    assert!(db
+        .get_recent_feeding_records(ALICE, 10)
+        .await
+        .unwrap()
+        .is_empty());
+    assert!(matches!(
+        db.undo_change(ALICE, fed.id).await,
+        Err(BotError::ValidationError(_))
+    ));
+    assert_eq!(colony_count(db, ALICE, "Smalls").await.1, 10);
+
+    let changes = db.get_changes(ALICE, 0, 10).await.unwrap();
+    assert_eq!(
+        changes.iter().map(|c| c.id).collect::<Vec<_>>(),
+        [fed.id, refusal.id]
+    );
+    assert!(changes.iter().all(|c| c.undone_at.is_some()));
+    assert!(db.get_changes(BOB, 0, 10).await.unwrap().is_empty());
+}
+
+async fn record_feeding_rejects_short_or_foreign_colony(db: &dyn TarantulaOperations) {
+    let rosie = add_tarantula(db, ALICE, "Rosie").await;
+    let bobs_spider = add_tarantula(db, BOB, "Boris").await;
+    let alices_colony = add_colony(db, ALICE, "Smalls", 2).await;
+    let bobs_colony = add_colony(db, BOB, "Bigs", 20).await;
+
+    assert!(matches!(
+        db.record_feeding(ALICE, feeding(rosie, alices_colony, 3))
+            .await,
+        Err(BotError::NotFound(_))
+    ));
+    assert!(matches!(
+        db.record_feeding(ALICE, feeding(rosie, bobs_colony, 1))
+            .await,
+        Err(BotError::NotFound(_))
+    ));
+    assert!(matches!(
+        db.record_feeding(BOB, feeding(rosie, bobs_colony, 1)).await,
+        Err(BotError::NotFound(_))
+    ));
+    assert!(matches!(
+        db.record_feeding(ALICE, feeding(bobs_spider, alices_colony, 1))
+            .await,
+        Err(BotError::NotFound(_))
+    ));
+
+    assert_eq!(colony_count(db, ALICE, "Smalls").await.1, 2);
+    assert_eq!(colony_count(db, BOB, "Bigs").await.1, 20);
+    assert!(db
+        .get_recent_feeding_records(ALICE, 10)
+        .await
+        .unwrap()
+        .is_empty());
+    assert!(db
+        .get_recent_feeding_records(BOB, 10)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+async fn unfed_tarantula_is_due(db: &dyn TarantulaOperations) {
+    let id = add_tarantula(db, ALICE, "Rosie").await;
+
+    let due = db.get_tarantulas_due_feeding(ALICE).await.unwrap();
+    assert_eq!(due.len(), 1);
+    assert_eq!(due[0].id, id);
+    assert_eq!(due[0].current_status, "Never fed");
+
+    let plan = db.get_feeding_plan(ALICE, id).await.unwrap();
+    assert_eq!(plan.state, FeedingState::NeverFed);
+    assert!(plan.band.is_some());
+}
+
+async fn fed_tarantula_is_not_due(db: &dyn TarantulaOperations) {
+    let id = add_tarantula(db, ALICE, "Rosie").await;
+    let colony_id = add_colony(db, ALICE, "Smalls", 10).await;
+    db.record_feeding(ALICE, feeding(id, colony_id, 1))
+        .await
+        .unwrap();
+
+    assert!(db
+        .get_tarantulas_due_feeding(ALICE)
+        .await
+        .unwrap()
+        .is_empty());
+    let plan = db.get_feeding_plan(ALICE, id).await.unwrap();
+    assert_eq!(plan.state, FeedingState::NotDue);
+    assert!(plan.days_since_feeding.is_some_and(|d| d < 1.0));
+}
+
+async fn refusals_leave_a_tarantula_due(db: &dyn TarantulaOperations) {
+    let rosie = add_tarantula(db, ALICE, "Rosie").await;
+    let colony_id = add_colony(db, ALICE, "Smalls", 10).await;
+    let weeks_ago = Utc::now().naive_utc() - chrono::Duration::days(20);
+    db.record_feeding(
+        ALICE,
+        FeedingEvent {
+            feeding_date: DbDateTime::from_naive_utc(weeks_ago),
+            ..feeding(rosie, colony_id, 2)
+        },
+    )
+    .await
+    .unwrap();
+    db.record_health_check(
+        ALICE,
+        rosie,
+        HealthStatus::Healthy,
+        None,
+        DbDateTime::default(),
+    )
+    .await
+    .unwrap();
+    db.record_refusal(ALICE, rosie, DbDateTime::default())
+        .await
+        .unwrap();
+
+    let due = db.get_tarantulas_due_feeding(ALICE).await.unwrap();
+    assert_eq!(due.iter().map(|t| t.id).collect::<Vec<_>>(), [rosie]);
+    let alerts = db.get_health_alerts(ALICE).await.unwrap();
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0].alert_type, "Extended Feeding Strike");
+    assert_eq!(alerts[0].days_in_state, 20);
+
+    let group_id = db
+        .create_group(
+            ALICE,
+            CreateGroupParams {
+                name: "Slings".to_string(),
+                species_id: MEXICAN_RED_KNEE,
+                count: 2,
+                acquisition_date: date(2025, 1, 1),
+                notes: None,
+            },
+        )
+        .await
+        .unwrap();
+    let member = db.get_group_members(ALICE, group_id).await.unwrap()[0].id;
+    db.record_refusal(ALICE, member, DbDateTime::default())
+        .await
+        .unwrap();
+    let groups = db.get_groups(ALICE).await.unwrap();
+    assert_eq!(groups[0].days_since_feeding, None);
+}
+
+async fn feeding_override_round_trip(db: &dyn TarantulaOperations) {
+    let id = add_tarantula(db, ALICE, "Rosie").await;
+    let params = |frequency_id, prey_count| SetFeedingOverrideParams {
+        tarantula_id: id,
+        frequency_id,
+        prey_count,
+        prey_size_id: None,
+        expires_on: None,
+        notes: None,
+    };
+
+    assert!(matches!(
+        db.set_feeding_override(ALICE, params(None, None)).await,
+        Err(BotError::ValidationError(_))
+    ));
+    assert!(matches!(
+        db.set_feeding_override(ALICE, params(None, Some(0))).await,
+        Err(BotError::ValidationError(_))
+    ));
+    assert!(matches!(
+        db.set_feeding_override(ALICE, params(Some(999), None))
+            .await,
+        Err(BotError::NotFound(_))
+    ));
+    assert!(matches!(
+        db.set_feeding_override(BOB, params(Some(1), None)).await,
+        Err(BotError::NotFound(_))
+    ));
+    assert!(matches!(
+        db.set_feeding_override(
+            ALICE,
+            SetFeedingOverrideParams {
+                expires_on: Some(Utc::now().date_naive().pred_opt().unwrap()),
+                ..params(Some(1), None)
+            }
+        )
+        .await,
+        Err(BotError::ValidationError(_))
+    ));
+
+    db.set_feeding_override(ALICE, params(Some(1), Some(2)))
+        .await
+        .unwrap();
+    let active = db.get_feeding_override(ALICE, id).await.unwrap().unwrap();
+    assert_eq!(active.frequency_name.as_deref(), Some("3-4 times per week"));
+    assert_eq!(active.min_days, Some(2));
+    assert_eq!(active.prey_count, Some(2));
+    assert!(db.get_feeding_override(BOB, id).await.unwrap().is_none());
+
+    let plan = db.get_feeding_plan(ALICE, id).await.unwrap();
+    assert!(plan.has_override);
+    assert_eq!(plan.prey_count, Some(2));
+
+    assert!(matches!(
+        db.clear_feeding_override(BOB, id).await,
+        Err(BotError::NotFound(_))
+    ));
+    db.clear_feeding_override(ALICE, id).await.unwrap();
+    assert!(db.get_feeding_override(ALICE, id).await.unwrap().is_none());
+    assert!(matches!(
+        db.clear_feeding_override(ALICE, id).await,
+        Err(BotError::NotFound(_))
+    ));
+}
+
+async fn group_feeding_needs_crickets_for_every_member(db: &dyn TarantulaOperations) {
+    let group_id = db
+        .create_group(
+            ALICE,
+            CreateGroupParams {
+                name: "Hamorii".to_string(),
+                species_id: MEXICAN_RED_KNEE,
+                count: 3,
+                acquisition_date: date(2025, 1, 1),
+                notes: None,
+            },
+        )
+        .await
+        .unwrap();
+    let colony_id = add_colony(db, ALICE, "Pinheads", 5).await;
+
+    assert!(matches!(
+        db.record_group_feeding(ALICE, group_id, colony_id, 2, DbDateTime::default())
+            .await,
+        Err(BotError::NotFound(_))
+    ));
+    assert_eq!(colony_count(db, ALICE, "Pinheads").await.1, 5);
+    assert!(matches!(
+        db.record_group_feeding(BOB, group_id, colony_id, 1, DbDateTime::default())
+            .await,
+        Err(BotError::NotFound(_))
+    ));
+
+    assert_eq!(
+        db.record_group_feeding(ALICE, group_id, colony_id, 1, DbDateTime::default())
+            .await
+            .unwrap()
+            .after
+            .feedings
+            .len(),
+        3
+    );
+    assert_eq!(colony_count(db, ALICE, "Pinheads").await.1, 2);
+
+    let groups = db.get_groups(ALICE).await.unwrap();
+    assert_eq!(groups.len(), 1);
+    assert_eq!(groups[0].member_count, 3);
+    assert!(groups[0].days_since_feeding.is_some());
+    assert!(db.get_groups(BOB).await.unwrap().is_empty());
+
+    let members = db.get_group_members(ALICE, group_id).await.unwrap();
+    assert_eq!(members.len(), 3);
+    assert!(db
+        .get_tarantulas_due_feeding(ALICE)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+async fn health_and_molt_history_is_per_user(db: &dyn TarantulaOperations) {
+    let id = add_tarantula(db, ALICE, "Rosie").await;
+    add_tarantula(db, BOB, "Boris").await;
+
+    db.record_health_check(
+        ALICE,
+        id,
+        HealthStatus::Monitor,
+        Some("Lethargic".to_string()),
+        DbDateTime::default(),
+    )
+    .await
+    .unwrap();
+    db.record_molt(id, 4.5, None, None, ALICE, DbDateTime::default())
+        .await
+        .unwrap();
+
+    let health = db.get_recent_health_records(ALICE, 10).await.unwrap();
+    assert_eq!(health.len(), 1);
+    assert_eq!(health[0].status, "Monitor");
+    assert_eq!(health[0].notes.as_deref(), Some("Lethargic"));
+
+    let molts = db.get_recent_molt_records(ALICE, 10).await.unwrap();
+    assert_eq!(molts.len(), 1);
+    assert_eq!(molts[0].stage, "Post-molt");
+    assert_eq!(molts[0].post_molt_length_cm, Some(4.5));
+
+    assert!(db
+        .get_recent_health_records(BOB, 10)
+        .await
+        .unwrap()
+        .is_empty());
+    assert!(db
+        .get_recent_molt_records(BOB, 10)
+        .await
+        .unwrap()
+        .is_empty());
+
+    let tarantula = db.get_tarantula_by_id(ALICE, id).await.unwrap();
+    assert_eq!(
+        tarantula.current_health_status_id,
+        Some(HealthStatus::Monitor as i64)
+    );
+    assert_eq!(tarantula.last_molt_date, Some(Utc::now().date_naive()));
+    let plan = db.get_feeding_plan(ALICE, id).await.unwrap();
+    assert_eq!(plan.size_cm, 4.5);
+}
+
+async fn undone_molts_and_health_checks_restore_what_they_replaced(
+    db: &dyn TarantulaOperations,
+) {
+    let rosie = add_tarantula(db, ALICE, "Rosie").await;
+    let today = Utc::now().date_naive();
+    db.record_health_check(
+        ALICE,
+        rosie,
+        HealthStatus::Monitor,
+        None,
+        DbDateTime::default(),
+    )
+    .await
+    .unwrap();
+    let molt = db
+        .record_molt(rosie, 4.5, None, None, ALICE, DbDateTime::default())
+        .await
+        .unwrap();
+    let check = db
+        .record_health_check(
+            ALICE,
+            rosie,
+            HealthStatus::Critical,
+            None,
+            DbDateTime::default(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        check.before.tarantulas[&rosie].health_status_id,
+        Some(HealthStatus::Monitor as i64)
+    );
+
+    db.undo_change(ALICE, molt.id).await.unwrap();
+    let tarantula = db.get_tarantula_by_id(ALICE, rosie).await.unwrap();
+    assert_eq!(tarantula.last_molt_date, None);
+    assert_eq!(tarantula.current_molt_stage_id, None);
+    assert_eq!(
+        tarantula.current_health_status_id,
+        Some(HealthStatus::Critical as i64)
+    );
+    assert!(db
+        .get_recent_molt_records(ALICE, 10)
+        .await
+        .unwrap()
+        .is_empty());
+
+    db.undo_change(ALICE, check.id).await.unwrap();
+    let tarantula = db.get_tarantula_by_id(ALICE, rosie).await.unwrap();
+    assert_eq!(
+        tarantula.current_health_status_id,
+        Some(HealthStatus::Monitor as i64)
+    );
+    assert_eq!(tarantula.last_health_check_date, Some(today));
+    assert_eq!(db.get_recent_health_records(ALICE, 10).await.unwrap().len(), 1);
+}
+
+fn at(y: i32, m: u32, d: u32) -> DbDateTime {
+    DbDateTime::from_naive_utc(date(y, m, d).and_hms_opt(18, 30, 0).unwrap())
+}
+
+async fn backdated_events_keep_the_latest_status(db: &dyn TarantulaOperations) {
+    let rosie = add_tarantula(db, ALICE, "Rosie").await;
+    let colony_id = add_colony(db, ALICE, "Smalls", 10).await;
+
+    db.record_health_check(ALICE, rosie, HealthStatus::Monitor, None, at(2025, 3, 2))
+        .await
+        .unwrap();
+    let earlier = db
+        .record_health_check(ALICE, rosie, HealthStatus::Critical, None, at(2025, 3, 1))
+        .await
+        .unwrap();
+    assert_eq!(earlier.after.health_checks.len(), 1);
+    db.record_molt(rosie, 4.5, None, None, ALICE, at(2025, 2, 1))
+        .await
+        .unwrap();
+
+    let tarantula = db.get_tarantula_by_id(ALICE, rosie).await.unwrap();
+    assert_eq!(
+        tarantula.current_health_status_id,
+        Some(HealthStatus::Monitor as i64)
+    );
+    assert_eq!(tarantula.last_health_check_date, Some(date(2025, 3, 2)));
+    assert_eq!(tarantula.last_molt_date, Some(date(2025, 2, 1)));
+    let health = db.get_recent_health_records(ALICE, 10).await.unwrap();
+    assert_eq!(health.len(), 2);
+    assert!(health[1].check_date.starts_with("2025-03-01"));
+    let molts = db.get_recent_molt_records(ALICE, 10).await.unwrap();
+    assert!(molts[0].molt_date.starts_with("2025-02-01"));
+
+    db.record_feeding(
+        ALICE,
+        FeedingEvent {
+            feeding_date: at(2025, 2, 3),
+            ..feeding(rosie, colony_id, 2)
+        },
+    )
+    .await
+    .unwrap();
+    db.record_refusal(ALICE, rosie, at(2025, 2, 4))
+        .await
+        .unwrap();
+    let feedings = db.get_recent_feeding_records(ALICE, 10).await.unwrap();
+    assert_eq!(feedings.len(), 2);
+    assert!(feedings
+        .iter()
+        .all(|f| f.feeding_date.starts_with("2025-02-0")));
+}
+
+async fn events_cannot_be_logged_in_the_future_or_before_acquisition(db: &dyn TarantulaOperations) {
+    let rosie = add_tarantula(db, ALICE, "Rosie").await;
+    let colony_id = add_colony(db, ALICE, "Smalls", 10).await;
+    let group_id = db
+        .create_group(
+            ALICE,
+            CreateGroupParams {
+                name: "Slings".to_string(),
+                species_id: MEXICAN_RED_KNEE,
+                count: 2,
+                acquisition_date: date(2025, 6, 1),
+                notes: None,
+            },
+        )
+        .await
+        .unwrap();
+    let tomorrow = DbDateTime::from_naive_utc(Utc::now().naive_utc() + chrono::Days::new(1));
+
+    let Err(BotError::ValidationError(message)) = db
+        .record_health_check(ALICE, rosie, HealthStatus::Monitor, None, tomorrow)
+        .await
+    else {
+        panic!("a health check tomorrow was logged");
+    };
+    assert!(message.ends_with("is in the future"), "{}", message);
+    let Err(BotError::ValidationError(message)) =
+        db.record_refusal(ALICE, rosie, at(2024, 12, 31)).await
+    else {
+        panic!("a refusal before acquisition was logged");
+    };
+    assert_eq!(message, "Rosie was only acquired on 2025-01-01");
+    assert!(matches!(
+        db.record_feeding(
+            ALICE,
+            FeedingEvent {
+                feeding_date: tomorrow,
+                ..feeding(rosie, colony_id, 2)
+            },
+        )
+        .await,
+        Err(BotError::ValidationError(_))
+    ));
+    assert!(matches!(
+        db.record_molt(rosie, 4.5, None, None, ALICE, at(2024, 6, 1))
+            .await,
+        Err(BotError::ValidationError(_))
+    ));
+    assert!(matches!(
+        db.record_group_feeding(ALICE, group_id, colony_id, 1, at(2025, 5, 31))
+            .await,
+        Err(BotError::ValidationError(_))
+    ));
+    assert!(matches!(
+        db.record_group_molt(ALICE, group_id, None, tomorrow).await,
+        Err(BotError::ValidationError(_))
+    ));
+
+    assert_eq!(colony_count(db, ALICE, "Smalls").await.1, 10);
+    assert!(db
+        .get_recent_feeding_records(ALICE, 10)
+        .await
+        .unwrap()
+        .is_empty());
+    assert!(db
+        .get_recent_health_records(ALICE, 10)
+        .await
+        .unwrap()
+        .is_empty());
+    assert!(db
+        .get_recent_molt_records(ALICE, 10)
+        .await
+        .unwrap()
+        .is_empty());
+    assert!(db.get_changes(ALICE, 0, 10).await.unwrap().is_empty());
+}
+
+async fn events_land_on_the_keepers_local_day(db: &dyn TarantulaOperations) {
+    assert_eq!(db.get_timezone(ALICE).await.unwrap(), Timezone::default());
+    let brisbane = Timezone::from_offset_minutes(10 * 60).unwrap();
+    db.set_timezone(ALICE, brisbane).await.unwrap();
+    assert_eq!(db.get_timezone(ALICE).await.unwrap(), brisbane);
+    assert_eq!(db.get_timezone(BOB).await.unwrap(), Timezone::default());
+    assert!(matches!(
+        db.set_timezone(99, brisbane).await,
+        Err(BotError::NotFound(_))
+    ));
+
+    // 18:30 UTC is 04:30 the next morning in Brisbane.
+    let rosie = add_tarantula(db, ALICE, "Rosie").await;
+    let colony_id = add_colony(db, ALICE, "Smalls", 10).await;
+    db.record_health_check(ALICE, rosie, HealthStatus::Monitor, None, at(2025, 3, 1))
+        .await
+        .unwrap();
+    db.record_molt(rosie, 4.5, None, None, ALICE, at(2025, 3, 1))
+        .await
+        .unwrap();
+    let change = db
+        .record_feeding(
+            ALICE,
+            FeedingEvent {
+                feeding_date: at(2025, 3, 1),
+                ..feeding(rosie, colony_id, 2)
+            },
+        )
+        .await
+        .unwrap();
+    let lag = change.changed_at - brisbane.now();
+    assert!(lag.num_minutes().abs() <= 1, "{}", change.changed_at);
+
+    let tarantula = db.get_tarantula_by_id(ALICE, rosie).await.unwrap();
+    assert_eq!(tarantula.last_health_check_date, Some(date(2025, 3, 2)));
+    assert_eq!(tarantula.last_molt_date, Some(date(2025, 3, 2)));
+    let health = db.get_recent_health_records(ALICE, 10).await.unwrap();
+    assert_eq!(health[0].check_date, "2025-03-02");
+    let molts = db.get_recent_molt_records(ALICE, 10).await.unwrap();
+    assert_eq!(molts[0].molt_date, "2025-03-02");
+    let feedings = db.get_recent_feeding_records(ALICE, 10).await.unwrap();
+    assert_eq!(feedings[0].feeding_date, "2025-03-02 04:30:00");
+
+    let on = |day| RecordFilter {
+        from: Some(day),
+        to: Some(day),
+        ..RecordFilter::latest(10)
+    };
+    for (day, count) in [(date(2025, 3, 1), 0), (date(2025, 3, 2), 1)] {
+        assert_eq!(
+            db.get_feeding_records(ALICE, &on(day)).await.unwrap().len(),
+            count
+        );
+        assert_eq!(
+            db.get_health_records(ALICE, &on(day)).await.unwrap().len(),
+            count
+        );
+    }
+}
+
+async fn zoned_times_follow_daylight_saving(db: &dyn TarantulaOperations) {
+    let berlin = Timezone::from_zone_name("Europe/Berlin").unwrap();
+    db.set_timezone(ALICE, berlin).await.unwrap();
+    assert_eq!(db.get_timezone(ALICE).await.unwrap(), berlin);
+
+    // Berlin is an hour ahead of UTC in winter and two in summer, when 22:30
+    // UTC is already the next day.
+    let rosie = add_tarantula(db, ALICE, "Rosie").await;
+    let colony_id = add_colony(db, ALICE, "Smalls", 10).await;
+    for fed_at in [
+        date(2025, 1, 15).and_hms_opt(18, 30, 0),
+        date(2025, 7, 15).and_hms_opt(22, 30, 0),
+    ] {
+        let feeding = FeedingEvent {
+            feeding_date: DbDateTime::from_naive_utc(fed_at.unwrap()),
+            ..feeding(rosie, colony_id, 1)
+        };
+        db.record_feeding(ALICE, feeding).await.unwrap();
+    }
+
+    let feedings = db.get_recent_feeding_records(ALICE, 10).await.unwrap();
+    let fed_at: Vec<_> = feedings.iter().map(|f| f.feeding_date.as_str()).collect();
+    assert_eq!(fed_at, ["2025-07-16 00:30:00", "2025-01-15 19:30:00"]);
+
+    let on = |day| RecordFilter {
+        from: Some(day),
+        to: Some(day),
+        ..RecordFilter::latest(10)
+    };
+    for (day, count) in [(date(2025, 7, 15), 0), (date(2025, 7, 16), 1)] {
+        assert_eq!(
+            db.get_feeding_records(ALICE, &on(day)).await.unwrap().len(),
+            count
+        );
+    }
+}
+
+async fn colony_counts_and_group_writes_are_logged_and_undone(db: &dyn TarantulaOperations) {
+    let colony_id = add_colony(db, ALICE, "Pinheads", 10).await;
+    let bobs_colony = add_colony(db, BOB, "Bigs", 20).await;
+    let group_id = db
+        .create_group(
+            ALICE,
+            CreateGroupParams {
+                name: "Hamorii".to_string(),
+                species_id: MEXICAN_RED_KNEE,
+                count: 3,
+                acquisition_date: date(2025, 1, 1),
+                notes: None,
+            },
+        )
+        .await
+        .unwrap();
+
+    let added = db.update_colony_count(colony_id, 5, ALICE).await.unwrap();
+    assert_eq!(added.kind, ChangeKind::ColonyCount);
+    assert_eq!(added.after.colonies[&colony_id], 15);
+    assert!(matches!(
+        db.update_colony_count(bobs_colony, 5, ALICE).await,
+        Err(BotError::NotFound(_))
+    ));
+    assert_eq!(colony_count(db, BOB, "Bigs").await.1, 20);
+
+    let fed = db
+        .record_group_feeding(ALICE, group_id, colony_id, 4, DbDateTime::default())
+        .await
+        .unwrap();
+    assert_eq!(colony_count(db, ALICE, "Pinheads").await.1, 3);
+    assert!(matches!(
+        db.undo_change(ALICE, added.id).await,
+        Err(BotError::ValidationError(_))
+    ));
+    db.undo_change(ALICE, fed.id).await.unwrap();
+    db.undo_change(ALICE, added.id).await.unwrap();
+    assert_eq!(colony_count(db, ALICE, "Pinheads").await.1, 10);
+    assert!(db
+        .get_recent_feeding_records(ALICE, 10)
+        .await
+        .unwrap()
+        .is_empty());
+
+    let checked = db
+        .record_group_health_check(
+            ALICE,
+            group_id,
+            HealthStatus::Critical,
+            DbDateTime::default(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(checked.after.tarantulas.len(), 3);
+    db.undo_change(ALICE, checked.id).await.unwrap();
+    for member in db.get_group_members(ALICE, group_id).await.unwrap() {
+        let t = db.get_tarantula_by_id(ALICE, member.id).await.unwrap();
+        assert_eq!(t.current_health_status_id, None);
+        assert_eq!(t.last_health_check_date, None);
+    }
+
+    let page = db.get_changes(ALICE, 1, 2).await.unwrap();
+    assert_eq!(
+        page.iter().map(|c| c.id).collect::<Vec<_>>(),
+        [fed.id, added.id]
+    );
+}
+
+async fn enclosures_are_listed_per_user(db: &dyn TarantulaOperations) {
+    for (user_id, name) in [(ALICE, "Tall"), (BOB, "Flat"), (ALICE, "Cube")] {
+        db.create_enclosure(Enclosure {
+            id: None,
+            name: name.to_string(),
+            height_cm: 30,
+            width_cm: 20,
+            length_cm: 20,
+            substrate_depth_cm: 10,
+            notes: None,
+            user_id: user_id as i64,
+        })
+        .await
+        .unwrap();
+    }
+
+    let names: Vec<_> = db
+        .get_enclosures(ALICE)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|e| e.name)
+        .collect();
+    assert_eq!(names, ["Tall", "Cube"]);
+    assert_eq!(db.get_enclosures(BOB).await.unwrap().len(), 1);
+}
+
+fn imported_tarantula(name: &str) -> ImportedTarantula {
+    ImportedTarantula {
+        name: name.to_string(),
+        species_id: MEXICAN_RED_KNEE,
+        acquisition_date: NaiveDate::from_ymd_opt(2020, 5, 1).unwrap(),
+        estimated_age_months: None,
+        enclosure_number: None,
+        notes: None,
+    }
+}
+
+fn imported_feeding(tarantula: ImportTarget) -> ImportedFeeding {
+    ImportedFeeding {
+        tarantula,
+        fed_at: NaiveDate::from_ymd_opt(2021, 1, 1)
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap(),
+        colony_id: None,
+        number_of_crickets: 2,
+        status: FeedingStatus::Accepted,
+        notes: None,
+    }
+}
+
+async fn import_writes_everything_or_nothing(db: &dyn TarantulaOperations) {
+    assert!(db
+        .get_species()
+        .await
+        .unwrap()
+        .iter()
+        .any(|s| s.id == MEXICAN_RED_KNEE));
+    let rosie = add_tarantula(db, ALICE, "Rosie").await;
+    let boris = add_tarantula(db, BOB, "Boris").await;
+
+    let molt_date = NaiveDate::from_ymd_opt(2021, 3, 1).unwrap();
+    let plan = ImportPlan {
+        tarantulas: vec![imported_tarantula("Cleo")],
+        feedings: vec![
+            imported_feeding(ImportTarget::New(0)),
+            imported_feeding(ImportTarget::Existing(rosie)),
+        ],
+        molts: vec![ImportedMolt {
+            tarantula: ImportTarget::New(0),
+            molt_date,
+            length_cm: Some(3.0),
+            complications: None,
+            notes: None,
+        }],
+    };
+    db.import_collection(ALICE, &plan).await.unwrap();
+
+    let tarantulas = db.get_all_tarantulas(ALICE).await.unwrap();
+    let cleo = tarantulas.iter().find(|t| t.name == "Cleo").unwrap();
+    assert_eq!(
+        db.get_tarantula_by_id(ALICE, cleo.id)
+            .await
+            .unwrap()
+            .last_molt_date,
+        Some(molt_date)
+    );
+    let feedings = db.get_recent_feeding_records(ALICE, 10).await.unwrap();
+    assert_eq!(feedings.len(), 2);
+    assert!(feedings.iter().all(|f| f.colony_name.is_none()));
+    let molts = db.get_recent_molt_records(ALICE, 10).await.unwrap();
+    assert_eq!(molts[0].post_molt_length_cm, Some(3.0));
+
+    let foreign = ImportPlan {
+        tarantulas: vec![imported_tarantula("Dora")],
+        feedings: vec![imported_feeding(ImportTarget::Existing(boris))],
+        molts: Vec::new(),
+    };
+    assert!(matches!(
+        db.import_collection(ALICE, &foreign).await,
+        Err(BotError::NotFound(_))
+    ));
+    assert_eq!(db.get_all_tarantulas(ALICE).await.unwrap().len(), 2);
+}
+
+async fn record_history_is_filtered_and_paged(db: &dyn TarantulaOperations) {
+    let rosie = add_tarantula(db, ALICE, "Rosie").await;
+    let cleo = add_tarantula(db, ALICE, "Cleo").await;
+    let feeding = |tarantula: i64, day: u32, status: FeedingStatus| ImportedFeeding {
+        fed_at: date(2024, 1, day).and_hms_opt(12, 0, 0).unwrap(),
+        status,
+        ..imported_feeding(ImportTarget::Existing(tarantula))
+    };
+    let plan = ImportPlan {
+        feedings: vec![
+            feeding(rosie, 1, FeedingStatus::Accepted),
+            feeding(rosie, 2, FeedingStatus::Rejected),
+            feeding(rosie, 3, FeedingStatus::Accepted),
+            feeding(cleo, 4, FeedingStatus::Accepted),
+            feeding(rosie, 5, FeedingStatus::Accepted),
+        ],
+        ..ImportPlan::default()
+    };
+    db.import_collection(ALICE, &plan).await.unwrap();
+    let days = |records: Vec<FeedingRecord>| -> Vec<String> {
+        records
+            .into_iter()
+            .map(|r| r.feeding_date[8..10].to_string())
+            .collect()
+    };
+
+    let page = |offset| RecordFilter {
+        tarantula_id: Some(rosie),
+        offset,
+        limit: 2,
+        ..RecordFilter::default()
+    };
+    let first = db.get_feeding_records(ALICE, &page(0)).await.unwrap();
+    assert_eq!(days(first), ["05", "03"]);
+    let second = db.get_feeding_records(ALICE, &page(2)).await.unwrap();
+    assert_eq!(days(second), ["02", "01"]);
+    assert!(db
+        .get_feeding_records(ALICE, &page(4))
+        .await
+        .unwrap()
+        .is_empty());
+
+    let filter = RecordFilter {
+        from: Some(date(2024, 1, 2)),
+        to: Some(date(2024, 1, 4)),
+        status_id: Some(FeedingStatus::Accepted as i64),
+        limit: 10,
+        ..RecordFilter::default()
+    };
+    let accepted = db.get_feeding_records(ALICE, &filter).await.unwrap();
+    assert_eq!(days(accepted), ["04", "03"]);
+    assert!(db.get_feeding_records(BOB, &filter).await.unwrap().is_empty());
+}
+
+async fn profile_edits_and_photos_are_per_user(db: &dyn TarantulaOperations) {
+    let rosie = add_tarantula(db, ALICE, "Rosie").await;
+    let boris = add_tarantula(db, BOB, "Boris").await;
+
+    db.update_tarantula(ALICE, rosie, TarantulaField::Name, Some("Rosa".to_string()))
+        .await
+        .unwrap();
+    db.update_tarantula(
+        ALICE,
+        rosie,
+        TarantulaField::Enclosure,
+        Some("A1".to_string()),
+    )
+    .await
+    .unwrap();
+    db.update_tarantula(
+        ALICE,
+        rosie,
+        TarantulaField::Aliases,
+        Some("Pinky, Rose".to_string()),
+    )
+    .await
+    .unwrap();
+    let tarantula = db.get_tarantula_by_id(ALICE, rosie).await.unwrap();
+    assert_eq!(tarantula.name, "Rosa");
+    assert_eq!(tarantula.enclosure_number.as_deref(), Some("A1"));
+    assert_eq!(tarantula.aliases.as_deref(), Some("Pinky, Rose"));
+    let facts = db.get_schedule_facts(ALICE).await.unwrap();
+    assert_eq!(facts[0].aliases, ["Pinky", "Rose"]);
+
+    assert!(matches!(
+        db.update_tarantula(ALICE, rosie, TarantulaField::Name, None)
+            .await,
+        Err(BotError::ValidationError(_))
+    ));
+    assert!(matches!(
+        db.update_tarantula(
+            BOB,
+            boris,
+            TarantulaField::Enclosure,
+            Some("A1".to_string())
+        )
+        .await,
+        Err(BotError::ValidationError(_))
+    ));
+    assert!(matches!(
+        db.update_tarantula(BOB, rosie, TarantulaField::Notes, None)
+            .await,
+        Err(BotError::NotFound(_))
+    ));
+
+    let first = db.add_photo(ALICE, rosie, "first", None).await.unwrap();
+    let second = db
+        .add_photo(ALICE, rosie, "second", Some("After the molt".to_string()))
+        .await
+        .unwrap();
+    assert!(matches!(
+        db.add_photo(BOB, rosie, "sneaky", None).await,
+        Err(BotError::NotFound(_))
+    ));
+    let photos = db.get_photos(ALICE, rosie).await.unwrap();
+    assert_eq!(
+        photos.iter().map(|p| p.id).collect::<Vec<_>>(),
+        [second, first]
+    );
+    assert_eq!(photos[0].caption.as_deref(), Some("After the molt"));
+    assert!(db.get_photos(BOB, rosie).await.unwrap().is_empty());
+
+    assert!(matches!(
+        db.delete_photo(BOB, first).await,
+        Err(BotError::NotFound(_))
+    ));
+    db.delete_photo(ALICE, first).await.unwrap();
+    assert_eq!(db.get_photos(ALICE, rosie).await.unwrap().len(), 1);
+}
+
+async fn pairings_follow_the_sexes_already_recorded(db: &dyn TarantulaOperations) {
+    let queenie = add_tarantula(db, ALICE, "Queenie").await;
+    let romeo = add_tarantula(db, ALICE, "Romeo").await;
+    let duke = add_tarantula(db, ALICE, "Duke").await;
+    let sling = add_tarantula(db, ALICE, "Sling").await;
+    let pair = |female_id, male_id| AddPairingParams {
+        female_id,
+        male_id,
+        pairing_date: date(2025, 3, 1),
+        notes: None,
+    };
+    db.record_pairing(ALICE, pair(queenie, romeo))
+        .await
+        .unwrap();
+    db.set_tarantula_parents(ALICE, sling, Parent::Unknown, Parent::Internal(duke))
+        .await
+        .unwrap();
+
+    // Romeo has been paired as a male, Duke fathered Sling and Queenie has
+    // been paired as a female.
+    for (female_id, male_id) in [(romeo, duke), (duke, romeo), (sling, queenie)] {
+        let refused = db.record_pairing(ALICE, pair(female_id, male_id)).await;
+        assert!(
+            matches!(&refused, Err(BotError::ValidationError(_))),
+            "{:?}",
+            refused
+        );
+    }
+    assert_eq!(db.get_pairings(ALICE).await.unwrap().len(), 1);
+
+    db.record_pairing(ALICE, pair(sling, duke)).await.unwrap();
+    db.record_pairing(ALICE, pair(queenie, duke)).await.unwrap();
+    assert_eq!(db.get_pairings(ALICE).await.unwrap().len(), 3);
+}
+
+async fn slings_from_egg_sac_are_linked_to_parents(db: &dyn TarantulaOperations) {
+    let female = add_tarantula(db, ALICE, "Queenie").await;
+    let male = add_tarantula(db, ALICE, "Romeo").await;
+
+    assert!(matches!(
+        db.record_pairing(
+            ALICE,
+            AddPairingParams {
+                female_id: female,
+                male_id: female,
+                pairing_date: date(2025, 3, 1),
+                notes: None,
+            }
+        )
+        .await,
+        Err(BotError::ValidationError(_))
+    ));
+    let pairing_id = db
+        .record_pairing(
+            ALICE,
+            AddPairingParams {
+                female_id: female,
+                male_id: male,
+                pairing_date: date(2025, 3, 1),
+                notes: None,
+            },
+        )
+        .await
+        .unwrap();
+    assert!(db.get_pairings(BOB).await.unwrap().is_empty());
+
+    let egg_sac = |laid_date| AddEggSacParams {
+        pairing_id,
+        laid_date,
+        expected_pull_date: None,
+        notes: None,
+    };
+    assert!(matches!(
+        db.record_egg_sac(ALICE, egg_sac(date(2025, 2, 1))).await,
+        Err(BotError::ValidationError(_))
+    ));
+    assert!(matches!(
+        db.record_egg_sac(BOB, egg_sac(date(2025, 4, 1))).await,
+        Err(BotError::NotFound(_))
+    ));
+    let egg_sac_id = db
+        .record_egg_sac(ALICE, egg_sac(date(2025, 4, 1)))
+        .await
+        .unwrap();
+
+    let pairings = db.get_pairings(ALICE).await.unwrap();
+    assert_eq!(pairings[0].outcome, "Successful");
+    assert_eq!(pairings[0].egg_sac_count, 1);
+
+    let slings = db
+        .create_slings_from_egg_sac(ALICE, egg_sac_id, 3, "Brood")
+        .await
+        .unwrap();
+    assert_eq!(slings.len(), 3);
+    assert_eq!(db.get_egg_sacs(ALICE).await.unwrap()[0].slings_created, 3);
+    assert_eq!(db.get_groups(ALICE).await.unwrap()[0].member_count, 3);
+
+    let descendants = db.get_descendants(ALICE, female, 3).await.unwrap();
+    assert_eq!(descendants.len(), 3);
+    assert!(descendants
+        .iter()
+        .all(|n| n.depth == 1 && n.role == ParentRole::Mother));
+    assert!(db.get_descendants(BOB, female, 3).await.unwrap().is_empty());
+
+    let ancestors = db.get_ancestors(ALICE, slings[0], 3).await.unwrap();
+    let parents: Vec<_> = ancestors.iter().map(|n| (n.id, n.role)).collect();
+    assert_eq!(
+        parents,
+        vec![
+            (Some(female), ParentRole::Mother),
+            (Some(male), ParentRole::Father)
+        ]
+    );
+
+    assert!(matches!(
+        db.set_tarantula_parents(ALICE, female, Parent::Internal(slings[0]), Parent::Unknown)
+            .await,
+        Err(BotError::ValidationError(_))
+    ));
+}
+
+async fn feeding_schedule_follows_species_seed(db: &dyn TarantulaOperations) {
+    let juvenile = db
+        .get_feeding_schedule(MEXICAN_RED_KNEE, 2.0)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(juvenile.size_category, "Juvenile");
+    assert_eq!(juvenile.feeding_frequency, "Every 5-7 days");
+
+    let frequency = db
+        .get_feeding_frequency(juvenile.frequency_id.unwrap())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(frequency.frequency_name, "Every 5-7 days");
+    assert_eq!((frequency.min_days, frequency.max_days), (5, 7));
+
+    assert!(db
+        .get_feeding_schedule(MEXICAN_RED_KNEE, 20.0)
+        .await
+        .unwrap()
+        .is_none());
+
+    let frequencies = db.get_feeding_frequencies().await.unwrap();
+    assert_eq!(frequencies.len(), 11);
+    assert_eq!(frequencies[0].frequency_name, "3-4 times per week");
+}
+
+async fn ping_answers(db: &dyn TarantulaOperations) {
+    db.ping().await.unwrap();
+}
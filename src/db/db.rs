@@ -1,41 +1,135 @@
 use crate::db::init::fill_default_enums;
+use crate::db::maintenance::verify_backup;
+use crate::db::migrations;
 use crate::error::BotError;
+use crate::models::audit::{Change, ChangeKind, ChangeState, TarantulaState};
+use crate::models::breeding::{
+    EggSacCounts, EggSacRecord, PairingRecord, DEFAULT_INCUBATION_DAYS,
+};
 use crate::models::cricket::ColonyStatus;
-use crate::models::enums::{CricketSize, FeedingStatus, HealthStatus, MoltStage};
-use crate::models::feeding::{FeedingEvent, FeedingRecord};
+use crate::models::enums::{
+    CricketSize, EggSacStatus, FeedingStatus, HealthStatus, MoltStage, PairingOutcome,
+};
+use crate::models::feeding::{FeedingEvent, FeedingOverride, FeedingRecord};
+use crate::models::group::{GroupSummary, MAX_GROUP_SIZE};
 use crate::models::health::{HealthAlert, HealthRecord};
+use crate::models::import::{ImportPlan, ImportTarget};
+use crate::models::lineage::{LineageNode, Parent, ParentRole};
+use crate::models::models::DbDateTime;
 use crate::models::molt::MoltRecord;
 use crate::models::new::{Enclosure, FeedingFrequency, FeedingSchedule, MaintenanceRecord};
-use crate::models::tarantula::{MaintenanceTask, Tarantula, TarantulaListItem};
-use crate::models::user::TelegramUser;
+use crate::models::tarantula::{
+    split_aliases, MaintenanceTask, Tarantula, TarantulaField, TarantulaListItem, TarantulaPhoto,
+    TarantulaSpecies,
+};
+use crate::models::user::{TelegramUser, Timezone};
+use crate::schedule::{self, AlertThresholds, FeedingPlan, ScheduleBand, TarantulaFacts};
 use crate::BotResult;
 use async_trait::async_trait;
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime, Utc};
 use r2d2::Pool;
 use r2d2_sqlite::SqliteConnectionManager;
-use rusqlite::{params, OptionalExtension, Row};
-
+use rusqlite::backup::Progress;
+use rusqlite::{params, DatabaseName, OptionalExtension, Row};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Everything the bot keeps, per keeper. Instants go in as UTC
+/// [`DbDateTime`]s; the dates and times read back, and the day an event is
+/// logged on, are on the keeper's clock, see [`Timezone`].
 #[async_trait]
 pub trait TarantulaOperations: Send + Sync {
     async fn add_tarantula(&self, user_id: u64, params: AddTarantulaParams)
         -> Result<(), BotError>;
     async fn get_tarantula_by_id(&self, user_id: u64, id: i64) -> Result<Tarantula, BotError>;
-    async fn get_all_tarantulas(&self, user_id: u64) -> Result<Vec<TarantulaListItem>, BotError>;
+    async fn get_schedule_facts(&self, user_id: u64) -> Result<Vec<TarantulaFacts>, BotError>;
+
+    async fn get_all_tarantulas(&self, user_id: u64) -> Result<Vec<TarantulaListItem>, BotError> {
+        let facts = self.get_schedule_facts(user_id).await?;
+        let now = self.get_timezone(user_id).await?.now();
+        Ok(schedule::collection_status(&facts, now))
+    }
     async fn get_tarantulas_due_feeding(
         &self,
         user_id: u64,
-    ) -> Result<Vec<TarantulaListItem>, BotError>;
+    ) -> Result<Vec<TarantulaListItem>, BotError> {
+        let facts = self.get_schedule_facts(user_id).await?;
+        let now = self.get_timezone(user_id).await?.now();
+        Ok(schedule::due_feedings(&facts, now))
+    }
+    async fn get_feeding_plan(
+        &self,
+        user_id: u64,
+        tarantula_id: i64,
+    ) -> Result<FeedingPlan, BotError> {
+        let now = self.get_timezone(user_id).await?.now();
+        self.get_schedule_facts(user_id)
+            .await?
+            .iter()
+            .find(|f| f.id == tarantula_id)
+            .map(|f| schedule::feeding_plan(f, now))
+            .ok_or_else(|| {
+                BotError::NotFound(format!(
+                    "Tarantula with id {} not found or access denied",
+                    tarantula_id
+                ))
+            })
+    }
     async fn update_tarantula_enclosure(
         &self,
         tarantula_id: i64,
         enclosure_id: Option<i64>,
         user_id: u64,
     ) -> Result<(), BotError>;
+    /// Changes one detail shown on the profile; `None` clears it.
+    async fn update_tarantula(
+        &self,
+        user_id: u64,
+        tarantula_id: i64,
+        field: TarantulaField,
+        value: Option<String>,
+    ) -> Result<(), BotError>;
 
-    async fn record_feeding(&self, user_id: u64, event: FeedingEvent) -> Result<i64, BotError>;
+    async fn add_photo(
+        &self,
+        user_id: u64,
+        tarantula_id: i64,
+        file_id: &str,
+        caption: Option<String>,
+    ) -> Result<i64, BotError>;
+    /// Newest first.
+    async fn get_photos(
+        &self,
+        user_id: u64,
+        tarantula_id: i64,
+    ) -> Result<Vec<TarantulaPhoto>, BotError>;
+    async fn delete_photo(&self, user_id: u64, photo_id: i64) -> Result<(), BotError>;
+
+    /// Refuses a `feeding_date` in the future or before the tarantula was
+    /// acquired, as the other event writes below do with their `at`. A
+    /// backdated health check or molt only moves the current status or
+    /// stage when nothing later is logged.
+    async fn record_feeding(&self, user_id: u64, event: FeedingEvent)
+        -> Result<Change, BotError>;
+    /// Logs a meal the tarantula turned down. No crickets are taken.
+    async fn record_refusal(
+        &self,
+        user_id: u64,
+        tarantula_id: i64,
+        at: DbDateTime,
+    ) -> Result<Change, BotError>;
     async fn get_recent_feeding_records(
         &self,
         user_id: u64,
         limit: i32,
+    ) -> Result<Vec<FeedingRecord>, BotError> {
+        self.get_feeding_records(user_id, &RecordFilter::latest(limit))
+            .await
+    }
+    async fn get_feeding_records(
+        &self,
+        user_id: u64,
+        filter: &RecordFilter,
     ) -> Result<Vec<FeedingRecord>, BotError>;
     async fn get_feeding_schedule(
         &self,
@@ -43,6 +137,20 @@ pub trait TarantulaOperations: Send + Sync {
         body_length_cm: f32,
     ) -> Result<Option<FeedingSchedule>, BotError>;
     async fn get_feeding_frequency(&self, id: i64) -> Result<Option<FeedingFrequency>, BotError>;
+    async fn get_feeding_frequencies(&self) -> Result<Vec<FeedingFrequency>, BotError>;
+
+    async fn set_feeding_override(
+        &self,
+        user_id: u64,
+        params: SetFeedingOverrideParams,
+    ) -> Result<(), BotError>;
+    async fn get_feeding_override(
+        &self,
+        user_id: u64,
+        tarantula_id: i64,
+    ) -> Result<Option<FeedingOverride>, BotError>;
+    async fn clear_feeding_override(&self, user_id: u64, tarantula_id: i64)
+        -> Result<(), BotError>;
 
     async fn record_health_check(
         &self,
@@ -50,13 +158,29 @@ pub trait TarantulaOperations: Send + Sync {
         tarantula_id: i64,
         status: HealthStatus,
         notes: Option<String>,
-    ) -> Result<(), BotError>;
+        at: DbDateTime,
+    ) -> Result<Change, BotError>;
     async fn get_recent_health_records(
         &self,
         user_id: u64,
         limit: i32,
+    ) -> Result<Vec<HealthRecord>, BotError> {
+        self.get_health_records(user_id, &RecordFilter::latest(limit))
+            .await
+    }
+    async fn get_health_records(
+        &self,
+        user_id: u64,
+        filter: &RecordFilter,
     ) -> Result<Vec<HealthRecord>, BotError>;
-    async fn get_health_alerts(&self, user_id: u64) -> Result<Vec<HealthAlert>, BotError>;
+    async fn get_health_alerts(&self, user_id: u64) -> Result<Vec<HealthAlert>, BotError> {
+        let facts = self.get_schedule_facts(user_id).await?;
+        Ok(schedule::health_alerts(
+            &facts,
+            self.get_timezone(user_id).await?.now(),
+            &self.alert_thresholds(),
+        ))
+    }
 
     async fn record_molt(
         &self,
@@ -65,11 +189,20 @@ pub trait TarantulaOperations: Send + Sync {
         complications: Option<String>,
         notes: Option<String>,
         user_id: u64,
-    ) -> Result<(), BotError>;
+        at: DbDateTime,
+    ) -> Result<Change, BotError>;
     async fn get_recent_molt_records(
         &self,
         user_id: u64,
         limit: i32,
+    ) -> Result<Vec<MoltRecord>, BotError> {
+        self.get_molt_records(user_id, &RecordFilter::latest(limit))
+            .await
+    }
+    async fn get_molt_records(
+        &self,
+        user_id: u64,
+        filter: &RecordFilter,
     ) -> Result<Vec<MoltRecord>, BotError>;
 
     async fn add_colony(&self, user_id: u64, params: AddColonyParams) -> Result<(), BotError>;
@@ -79,7 +212,20 @@ pub trait TarantulaOperations: Send + Sync {
         colony_id: i64,
         adjustment: i32,
         user_id: u64,
-    ) -> Result<(), BotError>;
+    ) -> Result<Change, BotError>;
+
+    /// The keeper's audit log, newest first. Every feeding, refusal, health
+    /// check, molt and colony count written above adds the entry it returns.
+    async fn get_changes(
+        &self,
+        user_id: u64,
+        offset: u32,
+        limit: u32,
+    ) -> Result<Vec<Change>, BotError>;
+    /// Takes a logged change back: the records it added go, colonies get the
+    /// crickets back and tarantulas the status it replaced. Each change can
+    /// only be undone once.
+    async fn undo_change(&self, user_id: u64, change_id: i64) -> Result<Change, BotError>;
 
     async fn create_maintenance_record(&self, record: MaintenanceRecord) -> Result<i64, BotError>;
     async fn get_maintenance_history(
@@ -87,20 +233,234 @@ pub trait TarantulaOperations: Send + Sync {
         enclosure_id: i64,
         user_id: u64,
     ) -> Result<Vec<MaintenanceRecord>, BotError>;
-    async fn get_maintenance_tasks(&self, user_id: u64) -> Result<Vec<MaintenanceTask>, BotError>;
+    async fn get_maintenance_tasks(&self, user_id: u64) -> Result<Vec<MaintenanceTask>, BotError> {
+        let facts = self.get_schedule_facts(user_id).await?;
+        Ok(schedule::maintenance_tasks(
+            &facts,
+            self.get_timezone(user_id).await?.now(),
+            &self.alert_thresholds(),
+        ))
+    }
 
     async fn create_enclosure(&self, enclosure: Enclosure) -> Result<i64, BotError>;
     async fn get_enclosure(&self, id: i64, user_id: u64) -> Result<Enclosure, BotError>;
+    async fn get_enclosures(&self, user_id: u64) -> Result<Vec<Enclosure>, BotError>;
 
     async fn ensure_user_exists(&self, user: &TelegramUser) -> Result<(), BotError>;
+    /// UTC until the keeper sets one.
+    async fn get_timezone(&self, user_id: u64) -> Result<Timezone, BotError>;
+    async fn set_timezone(&self, user_id: u64, timezone: Timezone) -> Result<(), BotError>;
+
+    async fn record_pairing(&self, user_id: u64, params: AddPairingParams)
+        -> Result<i64, BotError>;
+    async fn update_pairing_outcome(
+        &self,
+        user_id: u64,
+        pairing_id: i64,
+        outcome: PairingOutcome,
+    ) -> Result<(), BotError>;
+    async fn get_pairings(&self, user_id: u64) -> Result<Vec<PairingRecord>, BotError>;
+
+    async fn record_egg_sac(&self, user_id: u64, params: AddEggSacParams)
+        -> Result<i64, BotError>;
+    async fn update_egg_sac_status(
+        &self,
+        user_id: u64,
+        egg_sac_id: i64,
+        status: EggSacStatus,
+    ) -> Result<(), BotError>;
+    async fn update_egg_sac_counts(
+        &self,
+        user_id: u64,
+        egg_sac_id: i64,
+        counts: EggSacCounts,
+    ) -> Result<(), BotError>;
+    async fn get_egg_sacs(&self, user_id: u64) -> Result<Vec<EggSacRecord>, BotError>;
+    async fn get_egg_sacs_due_pulling(
+        &self,
+        user_id: u64,
+        within_days: i64,
+    ) -> Result<Vec<EggSacRecord>, BotError>;
+    async fn create_slings_from_egg_sac(
+        &self,
+        user_id: u64,
+        egg_sac_id: i64,
+        count: i32,
+        name_prefix: &str,
+    ) -> Result<Vec<i64>, BotError>;
 
-    async fn get_current_size(&self, tarantula_id: i64) -> Result<f32, BotError>;
+    async fn set_tarantula_parents(
+        &self,
+        user_id: u64,
+        tarantula_id: i64,
+        mother: Parent,
+        father: Parent,
+    ) -> Result<(), BotError>;
+    async fn get_ancestors(
+        &self,
+        user_id: u64,
+        tarantula_id: i64,
+        max_depth: i32,
+    ) -> Result<Vec<LineageNode>, BotError>;
+    async fn get_descendants(
+        &self,
+        user_id: u64,
+        tarantula_id: i64,
+        max_depth: i32,
+    ) -> Result<Vec<LineageNode>, BotError>;
+
+    async fn create_group(&self, user_id: u64, params: CreateGroupParams) -> Result<i64, BotError>;
+    async fn get_groups(&self, user_id: u64) -> Result<Vec<GroupSummary>, BotError>;
+    async fn get_group_members(
+        &self,
+        user_id: u64,
+        group_id: i64,
+    ) -> Result<Vec<TarantulaListItem>, BotError>;
+    async fn record_group_feeding(
+        &self,
+        user_id: u64,
+        group_id: i64,
+        colony_id: i64,
+        crickets_per_member: i32,
+        at: DbDateTime,
+    ) -> Result<Change, BotError>;
+    async fn record_group_health_check(
+        &self,
+        user_id: u64,
+        group_id: i64,
+        status: HealthStatus,
+        at: DbDateTime,
+    ) -> Result<Change, BotError>;
+    async fn record_group_molt(
+        &self,
+        user_id: u64,
+        group_id: i64,
+        length_cm: Option<f32>,
+        at: DbDateTime,
+    ) -> Result<Change, BotError>;
+    async fn split_from_group(
+        &self,
+        user_id: u64,
+        tarantula_id: i64,
+        new_name: Option<String>,
+    ) -> Result<(), BotError>;
+
+    async fn get_species(&self) -> Result<Vec<TarantulaSpecies>, BotError>;
+    /// Writes everything in `plan` for `user_id`, or nothing if any of it fails.
+    async fn import_collection(&self, user_id: u64, plan: &ImportPlan) -> Result<(), BotError>;
+
+    /// Checks the store can answer a query right now, for readiness probes.
+    async fn ping(&self) -> Result<(), BotError>;
+
+    /// Copies the whole store to a file at `path` while it stays in use.
+    async fn backup_to(&self, _path: &Path) -> Result<(), BotError> {
+        Err(BotError::OperationError(
+            "This storage backend has no file to back up".to_string(),
+        ))
+    }
+
+    /// Replaces all data with the backup at `path`, once it has been checked.
+    async fn restore_from(&self, _path: &Path) -> Result<(), BotError> {
+        Err(BotError::OperationError(
+            "This storage backend has no file to restore".to_string(),
+        ))
+    }
+
+    /// How busy the connection pool is, for backends that have one.
+    fn pool_usage(&self) -> Option<PoolUsage> {
+        None
+    }
+
+    /// When health alerts and maintenance tasks kick in.
+    fn alert_thresholds(&self) -> AlertThresholds {
+        AlertThresholds::default()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct PoolUsage {
+    pub connections: u32,
+    pub idle: u32,
+    pub max: u32,
 }
 
 trait FromRow: Sized {
     fn from_row(row: &Row) -> rusqlite::Result<Self>;
 }
 
+const PAIRING_SELECT: &str = "
+    SELECT
+        bp.id,
+        bp.female_id,
+        f.name as female_name,
+        bp.male_id,
+        m.name as male_name,
+        bp.pairing_date,
+        po.outcome_name as outcome,
+        (SELECT COUNT(*) FROM egg_sacs es WHERE es.pairing_id = bp.id) as egg_sac_count,
+        bp.notes
+    FROM breeding_pairings bp
+    JOIN tarantulas f ON bp.female_id = f.id
+    JOIN tarantulas m ON bp.male_id = m.id
+    JOIN pairing_outcomes po ON bp.outcome_id = po.id";
+
+impl FromRow for PairingRecord {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            id: row.get("id")?,
+            female_id: row.get("female_id")?,
+            female_name: row.get("female_name")?,
+            male_id: row.get("male_id")?,
+            male_name: row.get("male_name")?,
+            pairing_date: row.get("pairing_date")?,
+            outcome: row.get("outcome")?,
+            egg_sac_count: row.get("egg_sac_count")?,
+            notes: row.get("notes")?,
+        })
+    }
+}
+
+const EGG_SAC_SELECT: &str = "
+    SELECT
+        es.id,
+        es.pairing_id,
+        f.name as female_name,
+        m.name as male_name,
+        es.laid_date,
+        es.expected_pull_date,
+        es.pulled_date,
+        ess.status_name as status,
+        es.egg_count,
+        es.nymph_count,
+        es.sling_count,
+        (SELECT COUNT(*) FROM tarantulas s WHERE s.egg_sac_id = es.id) as slings_created,
+        es.notes
+    FROM egg_sacs es
+    JOIN breeding_pairings bp ON es.pairing_id = bp.id
+    JOIN tarantulas f ON bp.female_id = f.id
+    JOIN tarantulas m ON bp.male_id = m.id
+    JOIN egg_sac_statuses ess ON es.status_id = ess.id";
+
+impl FromRow for EggSacRecord {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            id: row.get("id")?,
+            pairing_id: row.get("pairing_id")?,
+            female_name: row.get("female_name")?,
+            male_name: row.get("male_name")?,
+            laid_date: row.get("laid_date")?,
+            expected_pull_date: row.get("expected_pull_date")?,
+            pulled_date: row.get("pulled_date")?,
+            status: row.get("status")?,
+            egg_count: row.get("egg_count")?,
+            nymph_count: row.get("nymph_count")?,
+            sling_count: row.get("sling_count")?,
+            slings_created: row.get("slings_created")?,
+            notes: row.get("notes")?,
+        })
+    }
+}
+
 impl FromRow for Tarantula {
     fn from_row(row: &Row) -> rusqlite::Result<Self> {
         Ok(Self {
@@ -114,13 +474,20 @@ impl FromRow for Tarantula {
             current_health_status_id: row.get("current_health_status_id")?,
             last_health_check_date: row.get("last_health_check_date")?,
             enclosure_number: row.get("enclosure_number")?,
+            enclosure_id: row.get("enclosure_id")?,
             notes: row.get("notes")?,
+            aliases: row.get("aliases")?,
+            mother_id: row.get("mother_id")?,
+            father_id: row.get("father_id")?,
+            mother_external: row.get("mother_external")?,
+            father_external: row.get("father_external")?,
         })
     }
 }
 
 pub struct TarantulaDB {
-    pool: Pool<SqliteConnectionManager>,
+    pub(super) pool: Pool<SqliteConnectionManager>,
+    thresholds: AlertThresholds,
 }
 
 #[derive(Debug)]
@@ -142,6 +509,68 @@ pub struct AddColonyParams {
     pub notes: Option<String>,
 }
 
+#[derive(Debug)]
+pub struct AddPairingParams {
+    pub female_id: i64,
+    pub male_id: i64,
+    pub pairing_date: NaiveDate,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct AddEggSacParams {
+    pub pairing_id: i64,
+    pub laid_date: NaiveDate,
+    pub expected_pull_date: Option<NaiveDate>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct SetFeedingOverrideParams {
+    pub tarantula_id: i64,
+    pub frequency_id: Option<i64>,
+    pub prey_count: Option<i32>,
+    pub prey_size_id: Option<i64>,
+    pub expires_on: Option<NaiveDate>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct CreateGroupParams {
+    pub name: String,
+    pub species_id: i64,
+    pub count: i32,
+    pub acquisition_date: NaiveDate,
+    pub notes: Option<String>,
+}
+
+/// Which feeding, health or molt records to read, newest first. Unset fields
+/// don't filter.
+#[derive(Debug, Clone, Default)]
+pub struct RecordFilter {
+    pub tarantula_id: Option<i64>,
+    /// First day included.
+    pub from: Option<NaiveDate>,
+    /// Last day included.
+    pub to: Option<NaiveDate>,
+    /// A feeding status, health status or molt stage id, matching the records.
+    pub status_id: Option<i64>,
+    pub offset: u32,
+    pub limit: u32,
+}
+
+impl RecordFilter {
+    pub fn latest(limit: i32) -> Self {
+        Self {
+            limit: limit.max(0) as u32,
+            ..Self::default()
+        }
+    }
+}
+
+/// How long a readiness ping waits for a pooled connection.
+const PING_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(2);
+
 impl TarantulaDB {
     pub fn new(db_path: &str) -> BotResult<Self> {
         let flags =
@@ -151,10 +580,18 @@ impl TarantulaDB {
         let pool = Pool::new(manager)?;
 
         fill_default_enums(pool.clone())?;
-        Ok(Self { pool })
+        Ok(Self {
+            pool,
+            thresholds: AlertThresholds::default(),
+        })
+    }
+
+    pub fn with_thresholds(mut self, thresholds: AlertThresholds) -> Self {
+        self.thresholds = thresholds;
+        self
     }
 
-    fn conn(&self) -> Result<r2d2::PooledConnection<SqliteConnectionManager>, BotError> {
+    pub(super) fn conn(&self) -> Result<r2d2::PooledConnection<SqliteConnectionManager>, BotError> {
         self.pool.get().map_err(Into::into)
     }
 }
@@ -181,10 +618,10 @@ impl TarantulaOperations for TarantulaDB {
         Ok(())
     }
     async fn get_tarantula_by_id(&self, user_id: u64, id: i64) -> BotResult<Tarantula> {
-        const SQL: &str = r#"SELECT id, name, species_id, acquisition_date, last_molt_date, estimated_age_months, current_molt_stage_id, current_health_status_id, last_health_check_date, enclosure_number, notes  FROM tarantulas WHERE id = ? AND user_id = ?"#;
+        const SQL: &str = r#"SELECT id, name, species_id, acquisition_date, last_molt_date, estimated_age_months, current_molt_stage_id, current_health_status_id, last_health_check_date, enclosure_number, enclosure_id, notes, aliases, mother_id, father_id, mother_external, father_external FROM tarantulas WHERE id = ? AND user_id = ?"#;
         let conn = self.conn()?;
         let mut stmt = conn.prepare(SQL)?;
-        stmt.query_row([id, user_id as i64], |row| Tarantula::from_row(row))
+        stmt.query_row([id, user_id as i64], Tarantula::from_row)
             .map_err(|e| match e {
                 rusqlite::Error::QueryReturnedNoRows => {
                     BotError::NotFound(format!("Tarantula with id {} not found", id))
@@ -193,168 +630,147 @@ impl TarantulaOperations for TarantulaDB {
             })
     }
 
-    async fn get_all_tarantulas(&self, user_id: u64) -> BotResult<Vec<TarantulaListItem>> {
-        let sql = "
-        SELECT
-            t.id,
-            t.name,
-            ts.common_name as species_name,
-            t.enclosure_number,
-            julianday('now') - julianday(MAX(f.feeding_date)) as days_since_feeding,
-            CASE
-                WHEN ms.stage_name = ? THEN ?
-                WHEN hs.status_name = ? THEN ?
-                WHEN julianday('now') - julianday(MAX(f.feeding_date)) > 14 THEN 'Needs feeding'
-                ELSE 'Normal'
-            END as current_status
-        FROM tarantulas t
-        JOIN tarantula_species ts ON t.species_id = ts.id
-        LEFT JOIN molt_stages ms ON t.current_molt_stage_id = ms.id
-        LEFT JOIN health_statuses hs ON t.current_health_status_id = hs.id
-        LEFT JOIN feeding_events f ON t.id = f.tarantula_id
-        WHERE t.user_id = ?
-        GROUP BY t.id
-        ORDER BY t.name";
-
+    
+    async fn get_schedule_facts(&self, user_id: u64) -> BotResult<Vec<TarantulaFacts>> {
         let conn = self.conn()?;
-        let mut stmt = conn.prepare(sql)?;
-        let items = stmt
-            .query_map(
-                params![
-                    MoltStage::PreMolt.to_db_name(),
-                    MoltStage::PreMolt.to_db_name(),
-                    HealthStatus::Critical.to_db_name(),
-                    HealthStatus::Critical.to_db_name(),
-                    user_id
-                ],
-                |row| {
-                    Ok(TarantulaListItem {
-                        id: row.get(0)?,
-                        name: row.get(1)?,
-                        species_name: row.get(2)?,
-                        enclosure_number: row.get(3)?,
-                        days_since_feeding: row.get(4)?,
-                        current_status: row.get(5)?,
-                    })
-                },
-            )
-            .map_err(BotError::Database)?;
-
-        items
-            .collect::<rusqlite::Result<Vec<_>, _>>()
-            .map_err(BotError::Database)
-    }
+        let timezone = user_timezone(&conn, user_id)?;
 
-    async fn get_tarantulas_due_feeding(&self, user_id: u64) -> BotResult<Vec<TarantulaListItem>> {
-        let sql = "WITH LastFeeding AS (
-            SELECT
-                tarantula_id,
-                MAX(feeding_date) as last_feeding_date,
-                julianday('now') - julianday(MAX(feeding_date)) as days_since_feeding
-            FROM feeding_events
-            GROUP BY tarantula_id
-        ),
-        CurrentSize AS (
-            -- Get the most recent size measurement from molt records
-            SELECT
-                t.id as tarantula_id,
-                COALESCE(
-                    mr.post_molt_length_cm,
-                    -- If no measurements, use a size category based on species
-                    CASE
-                        WHEN ts.adult_size_cm <= 8 THEN ts.adult_size_cm * 0.3  -- Dwarf species start smaller
-                        ELSE ts.adult_size_cm * 0.4  -- Regular species
-                    END
-                ) as current_size_cm
-            FROM tarantulas t
-            JOIN tarantula_species ts ON t.species_id = ts.id
-            LEFT JOIN (
-                SELECT tarantula_id, post_molt_length_cm
-                FROM molt_records
-                WHERE molt_date = (
-                    SELECT MAX(molt_date)
-                    FROM molt_records mr2
-                    WHERE mr2.tarantula_id = molt_records.tarantula_id
-                    AND mr2.post_molt_length_cm IS NOT NULL
-                )
-            ) mr ON t.id = mr.tarantula_id
-        ),
-        TarantulaSchedule AS (
-            SELECT
-                t.id as tarantula_id,
+        let mut stmt = conn.prepare(
+            "SELECT
+                fs.species_id,
+                fs.size_category,
+                fs.body_length_cm,
+                fs.prey_size,
+                fs.prey_type,
                 fs.feeding_frequency,
                 ff.min_days,
                 ff.max_days,
-                CASE
-                    WHEN ms.stage_name = 'Pre-molt' THEN true
-                    ELSE false
-                END as is_premolt
-            FROM tarantulas t
-            JOIN tarantula_species ts ON t.species_id = ts.id
-            JOIN CurrentSize cs ON t.id = cs.tarantula_id
-            JOIN feeding_schedules fs ON ts.id = fs.species_id
-            JOIN feeding_frequencies ff ON fs.frequency_id = ff.id
-            LEFT JOIN molt_stages ms ON t.current_molt_stage_id = ms.id
-            WHERE
-                fs.size_category = (
-                    SELECT size_category
-                    FROM feeding_schedules fs2
-                    WHERE fs2.species_id = ts.id
-                    AND fs2.body_length_cm >= cs.current_size_cm
-                    ORDER BY fs2.body_length_cm ASC
-                    LIMIT 1
-                )
-        )
-        SELECT
-            t.id,
-            t.name,
-            ts.common_name as species_name,
-            t.enclosure_number,
-            COALESCE(lf.days_since_feeding, 999) as days_since_feeding,
-            CASE
-                WHEN ts2.is_premolt THEN 'In pre-molt'
-                WHEN lf.days_since_feeding IS NULL THEN 'Never fed'
-                WHEN lf.days_since_feeding > ts2.max_days THEN
-                    'Overdue feeding (' || ts2.feeding_frequency || ')'
-                ELSE 'Due for feeding'
-            END as current_status
-        FROM tarantulas t
-        JOIN tarantula_species ts ON t.species_id = ts.id
-        JOIN TarantulaSchedule ts2 ON t.id = ts2.tarantula_id
-        LEFT JOIN LastFeeding lf ON t.id = lf.tarantula_id
-        WHERE
-            t.user_id = ? AND
-            NOT ts2.is_premolt AND
-            (
-                lf.days_since_feeding IS NULL OR
-                lf.days_since_feeding > ts2.max_days
-            )
-        ORDER BY
-            CASE
-                WHEN lf.days_since_feeding IS NULL THEN 999
-                ELSE lf.days_since_feeding
-            END DESC".to_string();
+                fs.notes
+            FROM feeding_schedules fs
+            LEFT JOIN feeding_frequencies ff ON fs.frequency_id = ff.id
+            WHERE fs.species_id IN (SELECT species_id FROM tarantulas WHERE user_id = ?)
+            ORDER BY fs.species_id, fs.body_length_cm",
+        )?;
+        let mut bands: HashMap<i64, Vec<ScheduleBand>> = HashMap::new();
+        for row in stmt.query_map([user_id], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                ScheduleBand {
+                    size_category: row.get(1)?,
+                    body_length_cm: row.get(2)?,
+                    prey_size: row.get(3)?,
+                    prey_type: row.get(4)?,
+                    frequency_name: row.get(5)?,
+                    min_days: row.get(6)?,
+                    max_days: row.get(7)?,
+                    notes: row.get(8)?,
+                },
+            ))
+        })? {
+            let (species_id, band) = row?;
+            bands.entry(species_id).or_default().push(band);
+        }
 
-        let conn = self.conn()?;
-        let mut stmt = conn.prepare(&sql)?;
-        let items = stmt
+        let mut stmt = conn.prepare(
+            "SELECT
+                fo.tarantula_id,
+                fo.frequency_id,
+                ff.frequency_name,
+                ff.min_days,
+                ff.max_days,
+                fo.prey_count,
+                fo.prey_size_id,
+                cst.size_name,
+                fo.expires_on,
+                fo.notes
+            FROM feeding_overrides fo
+            LEFT JOIN feeding_frequencies ff ON fo.frequency_id = ff.id
+            LEFT JOIN cricket_size_types cst ON fo.prey_size_id = cst.id
+            WHERE fo.user_id = ?",
+        )?;
+        let mut overrides: HashMap<i64, FeedingOverride> = stmt
             .query_map([user_id], |row| {
-                Ok(TarantulaListItem {
-                    id: row.get(0)?,
-                    name: row.get(1)?,
-                    species_name: row.get(2)?,
-                    enclosure_number: row.get(3)?,
-                    days_since_feeding: row.get(4)?,
-                    current_status: row.get(5)?,
+                Ok(FeedingOverride {
+                    tarantula_id: row.get(0)?,
+                    frequency_id: row.get(1)?,
+                    frequency_name: row.get(2)?,
+                    min_days: row.get(3)?,
+                    max_days: row.get(4)?,
+                    prey_count: row.get(5)?,
+                    prey_size_id: row.get(6)?,
+                    prey_size: row.get(7)?,
+                    expires_on: row.get(8)?,
+                    notes: row.get(9)?,
                 })
+            })?
+            .map(|o| o.map(|o| (o.tarantula_id, o)))
+            .collect::<Result<_, _>>()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT
+                t.id,
+                t.name,
+                ts.common_name,
+                ts.scientific_name,
+                t.enclosure_number,
+                t.group_id,
+                tg.name,
+                t.acquisition_date,
+                t.estimated_age_months,
+                ts.adult_size_cm,
+                t.current_molt_stage_id,
+                t.current_health_status_id,
+                t.last_molt_date,
+                (SELECT mr.post_molt_length_cm
+                 FROM molt_records mr
+                 WHERE mr.tarantula_id = t.id AND mr.post_molt_length_cm IS NOT NULL
+                 ORDER BY mr.molt_date DESC
+                 LIMIT 1),
+                t.last_health_check_date,
+                (SELECT MAX(fe.feeding_date) FROM feeding_events fe
+                 WHERE fe.tarantula_id = t.id AND fe.feeding_status_id = ?2),
+                t.species_id,
+                t.aliases
+            FROM tarantulas t
+            JOIN tarantula_species ts ON t.species_id = ts.id
+            LEFT JOIN tarantula_groups tg ON t.group_id = tg.id
+            WHERE t.user_id = ?1
+            ORDER BY t.name",
+        )?;
+        let accepted = FeedingStatus::Accepted as i64;
+        let facts = stmt.query_map(params![user_id, accepted], |row| {
+            let id: i64 = row.get(0)?;
+            let species_id: i64 = row.get(16)?;
+            Ok(TarantulaFacts {
+                id,
+                name: row.get(1)?,
+                aliases: split_aliases(row.get::<_, Option<String>>(17)?.as_deref()),
+                species_name: row.get(2)?,
+                scientific_name: row.get(3)?,
+                enclosure_number: row.get(4)?,
+                group_id: row.get(5)?,
+                group_name: row.get(6)?,
+                acquisition_date: row.get(7)?,
+                estimated_age_months: row.get(8)?,
+                adult_size_cm: row.get(9)?,
+                molt_stage: row.get::<_, Option<i64>>(10)?.map(MoltStage::from_id),
+                health_status: row.get::<_, Option<i64>>(11)?.map(HealthStatus::from_id),
+                last_molt_date: row.get(12)?,
+                last_molt_length_cm: row.get(13)?,
+                last_health_check_date: row.get(14)?,
+                last_fed: row
+                    .get::<_, Option<NaiveDateTime>>(15)?
+                    .map(|at| timezone.local(at)),
+                schedule: bands.get(&species_id).cloned().unwrap_or_default(),
+                feeding_override: overrides.remove(&id),
             })
-            .map_err(BotError::Database)?;
+        })?;
 
-        items
-            .collect::<rusqlite::Result<Vec<_>, _>>()
+        facts
+            .collect::<Result<Vec<_>, _>>()
             .map_err(BotError::Database)
     }
-    
+
     async fn update_tarantula_enclosure(
         &self,
         tarantula_id: i64,
@@ -370,7 +786,124 @@ impl TarantulaOperations for TarantulaDB {
         )?;
         Ok(())
     }
-    async fn record_feeding(&self, user_id: u64, event: FeedingEvent) -> BotResult<i64> {
+
+    async fn update_tarantula(
+        &self,
+        user_id: u64,
+        tarantula_id: i64,
+        field: TarantulaField,
+        value: Option<String>,
+    ) -> BotResult<()> {
+        let conn = self.conn()?;
+        let column = match field {
+            TarantulaField::Name => {
+                if value.is_none() {
+                    return Err(BotError::ValidationError(
+                        "A tarantula needs a name".to_string(),
+                    ));
+                }
+                "name"
+            }
+            TarantulaField::Enclosure => {
+                if let Some(number) = &value {
+                    let taken = conn
+                        .query_row(
+                            "SELECT 1 FROM tarantulas WHERE enclosure_number = ? AND id != ?",
+                            params![number, tarantula_id],
+                            |_| Ok(()),
+                        )
+                        .optional()?
+                        .is_some();
+                    if taken {
+                        return Err(BotError::ValidationError(format!(
+                            "Enclosure {} is already taken",
+                            number
+                        )));
+                    }
+                }
+                "enclosure_number"
+            }
+            TarantulaField::Notes => "notes",
+            TarantulaField::Aliases => "aliases",
+        };
+
+        let rows_affected = conn.execute(
+            &format!(
+                "UPDATE tarantulas SET {} = ?, updated_at = CURRENT_TIMESTAMP
+                 WHERE id = ? AND user_id = ?",
+                column
+            ),
+            params![value, tarantula_id, user_id],
+        )?;
+        if rows_affected == 0 {
+            return Err(BotError::NotFound(format!(
+                "Tarantula with id {} not found",
+                tarantula_id
+            )));
+        }
+        Ok(())
+    }
+
+    async fn add_photo(
+        &self,
+        user_id: u64,
+        tarantula_id: i64,
+        file_id: &str,
+        caption: Option<String>,
+    ) -> BotResult<i64> {
+        let conn = self.conn()?;
+        let rows_affected = conn.execute(
+            "INSERT INTO tarantula_photos (tarantula_id, file_id, caption, user_id)
+             SELECT id, ?, ?, user_id FROM tarantulas WHERE id = ? AND user_id = ?",
+            params![file_id, caption, tarantula_id, user_id],
+        )?;
+        if rows_affected == 0 {
+            return Err(BotError::NotFound(format!(
+                "Tarantula with id {} not found",
+                tarantula_id
+            )));
+        }
+        Ok(conn.last_insert_rowid())
+    }
+
+    async fn get_photos(&self, user_id: u64, tarantula_id: i64) -> BotResult<Vec<TarantulaPhoto>> {
+        let conn = self.conn()?;
+        let timezone = user_timezone(&conn, user_id)?;
+        let mut stmt = conn.prepare(
+            "SELECT id, tarantula_id, file_id, caption, taken_at
+             FROM tarantula_photos
+             WHERE tarantula_id = ?1 AND user_id = ?2
+             ORDER BY taken_at DESC, id DESC",
+        )?;
+        let photos = stmt
+            .query_map(params![tarantula_id, user_id], |row| {
+                Ok(TarantulaPhoto {
+                    id: row.get(0)?,
+                    tarantula_id: row.get(1)?,
+                    file_id: row.get(2)?,
+                    caption: row.get(3)?,
+                    taken_at: on_clock(row, 4, timezone)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(photos)
+    }
+
+    async fn delete_photo(&self, user_id: u64, photo_id: i64) -> BotResult<()> {
+        let conn = self.conn()?;
+        let rows_affected = conn.execute(
+            "DELETE FROM tarantula_photos WHERE id = ? AND user_id = ?",
+            params![photo_id, user_id],
+        )?;
+        if rows_affected == 0 {
+            return Err(BotError::NotFound(format!(
+                "Photo with id {} not found",
+                photo_id
+            )));
+        }
+        Ok(())
+    }
+    async fn record_feeding(&self, user_id: u64, event: FeedingEvent) -> BotResult<Change> {
         let mut conn = self.conn()?;
         transactionally(&mut conn, |tx| {
             let tarantula_exists = tx
@@ -387,6 +920,9 @@ impl TarantulaOperations for TarantulaDB {
                     event.tarantula_id
                 )));
             }
+            check_logged_at(tx, user_id, &[event.tarantula_id], event.feeding_date)?;
+            let touched = ([event.tarantula_id], [event.cricket_colony_id]);
+            let before = change_state(tx, user_id, &touched.0, &touched.1)?;
             let rows_affected = tx.execute(
                 "UPDATE cricket_colonies
         SET current_count = current_count - ?
@@ -433,15 +969,51 @@ impl TarantulaOperations for TarantulaDB {
                     event.tarantula_id
                 )));
             }
+            let feeding_id = tx.last_insert_rowid();
 
-            Ok(tx.last_insert_rowid())
+            let mut after = change_state(tx, user_id, &touched.0, &touched.1)?;
+            after.feedings.push(feeding_id);
+            record_change(tx, user_id, ChangeKind::Feeding, &before, &after)
         })
     }
 
-    async fn get_recent_feeding_records(
+    async fn record_refusal(
         &self,
         user_id: u64,
-        limit: i32,
+        tarantula_id: i64,
+        at: DbDateTime,
+    ) -> BotResult<Change> {
+        let mut conn = self.conn()?;
+        transactionally(&mut conn, |tx| {
+            check_logged_at(tx, user_id, &[tarantula_id], at)?;
+            let before = change_state(tx, user_id, &[tarantula_id], &[])?;
+            let rows_affected = tx.execute(
+                "INSERT INTO feeding_events (
+                    tarantula_id, feeding_date, cricket_colony_id,
+                    number_of_crickets, feeding_status_id, user_id
+                )
+                SELECT id, ?, NULL, 0, ?, user_id
+                FROM tarantulas WHERE id = ? AND user_id = ?",
+                params![at, FeedingStatus::Rejected as i64, tarantula_id, user_id],
+            )?;
+            if rows_affected == 0 {
+                return Err(BotError::NotFound(format!(
+                    "Tarantula with id {} not found or access denied",
+                    tarantula_id
+                )));
+            }
+            let feeding_id = tx.last_insert_rowid();
+
+            let mut after = change_state(tx, user_id, &[tarantula_id], &[])?;
+            after.feedings.push(feeding_id);
+            record_change(tx, user_id, ChangeKind::Refusal, &before, &after)
+        })
+    }
+
+    async fn get_feeding_records(
+        &self,
+        user_id: u64,
+        filter: &RecordFilter,
     ) -> BotResult<Vec<FeedingRecord>> {
         let sql = "
             SELECT 
@@ -453,18 +1025,36 @@ impl TarantulaOperations for TarantulaDB {
                 fe.notes
             FROM feeding_events fe
             JOIN tarantulas t ON fe.tarantula_id = t.id
-            JOIN cricket_colonies cc ON fe.cricket_colony_id = cc.id
+            LEFT JOIN cricket_colonies cc ON fe.cricket_colony_id = cc.id
             JOIN feeding_statuses fs ON fe.feeding_status_id = fs.id
-            WHERE t.user_id = ?
-            ORDER BY fe.feeding_date DESC
-            LIMIT ?";
+            WHERE t.user_id = ?1
+            AND (?2 IS NULL OR t.id = ?2)
+            AND (?3 IS NULL OR fe.feeding_date >= ?3)
+            AND (?4 IS NULL OR fe.feeding_date < ?4)
+            AND (?5 IS NULL OR fe.feeding_status_id = ?5)
+            ORDER BY fe.feeding_date DESC, fe.id DESC
+            LIMIT ?6 OFFSET ?7";
 
         let conn = self.conn()?;
+        let timezone = user_timezone(&conn, user_id)?;
+        // The keeper's days start at different UTC times through the year,
+        // so each bound is moved onto UTC by itself.
+        let day_start =
+            |day: NaiveDate| DbDateTime::from_naive_utc(timezone.utc(day.and_time(NaiveTime::MIN)));
         let mut stmt = conn.prepare(sql)?;
-        let records = stmt.query_map([user_id, limit as u64], |row| {
+        let params = params![
+            user_id,
+            filter.tarantula_id,
+            filter.from.map(day_start),
+            filter.to.and_then(|to| to.succ_opt()).map(day_start),
+            filter.status_id,
+            filter.limit,
+            filter.offset
+        ];
+        let records = stmt.query_map(params, |row| {
             Ok(FeedingRecord {
                 tarantula_name: row.get(0)?,
-                feeding_date: row.get(1)?,
+                feeding_date: on_clock(row, 1, timezone)?,
                 colony_name: row.get(2)?,
                 number_of_crickets: row.get(3)?,
                 status: row.get(4)?,
@@ -541,45 +1131,45 @@ impl TarantulaOperations for TarantulaDB {
         tarantula_id: i64,
         status: HealthStatus,
         notes: Option<String>,
-    ) -> BotResult<()> {
+        at: DbDateTime,
+    ) -> BotResult<Change> {
         let mut conn = self.conn()?;
-        let tx = conn.transaction()?;
         let status_id = status as i64;
+        transactionally(&mut conn, |tx| {
+            let day = check_logged_at(tx, user_id, &[tarantula_id], at)?;
+            let before = change_state(tx, user_id, &[tarantula_id], &[])?;
+            let rows_affected = tx.execute(
+                &format!("{} WHERE id = ?3 AND user_id = ?4", HEALTH_CHECK_UPDATE),
+                params![day, status_id, tarantula_id, user_id],
+            )?;
 
-        
-        let rows_affected = tx.execute(
-            "UPDATE tarantulas SET 
-            last_health_check_date = date('now'),
-            current_health_status_id = ?
-        WHERE id = ? AND user_id = ?",
-            params![status_id, tarantula_id, user_id],
-        )?;
-
-        if rows_affected == 0 {
-            return Err(BotError::NotFound(format!(
-                "Tarantula with id {} not found or access denied",
-                tarantula_id
-            )));
-        }
+            if rows_affected == 0 {
+                return Err(BotError::NotFound(format!(
+                    "Tarantula with id {} not found or access denied",
+                    tarantula_id
+                )));
+            }
 
-        
-        tx.execute(
-            "INSERT INTO health_check_records (
+            tx.execute(
+                "INSERT INTO health_check_records (
             tarantula_id, check_date, health_status_id,
             weight_grams, humidity_percent, temperature_celsius,
             notes, user_id
-        ) VALUES (?, datetime('now'), ?, ?, ?, ?, ?, ?)",
-            params![tarantula_id, status_id, 0, 55, 20, notes, user_id],
-        )?;
+        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+                params![tarantula_id, day, status_id, 0, 55, 20, notes, user_id],
+            )?;
+            let check_id = tx.last_insert_rowid();
 
-        tx.commit()?;
-        Ok(())
+            let mut after = change_state(tx, user_id, &[tarantula_id], &[])?;
+            after.health_checks.push(check_id);
+            record_change(tx, user_id, ChangeKind::HealthCheck, &before, &after)
+        })
     }
 
-    async fn get_recent_health_records(
+    async fn get_health_records(
         &self,
         user_id: u64,
-        limit: i32,
+        filter: &RecordFilter,
     ) -> BotResult<Vec<HealthRecord>> {
         let sql = "
             SELECT 
@@ -593,13 +1183,17 @@ impl TarantulaOperations for TarantulaDB {
             FROM health_check_records hcr
             JOIN tarantulas t ON hcr.tarantula_id = t.id
             JOIN health_statuses hs ON hcr.health_status_id = hs.id
-            WHERE t.user_id = ?
-            ORDER BY hcr.check_date DESC
-            LIMIT ?";
+            WHERE t.user_id = ?1
+            AND (?2 IS NULL OR t.id = ?2)
+            AND (?3 IS NULL OR date(hcr.check_date) >= ?3)
+            AND (?4 IS NULL OR date(hcr.check_date) <= ?4)
+            AND (?5 IS NULL OR hcr.health_status_id = ?5)
+            ORDER BY hcr.check_date DESC, hcr.id DESC
+            LIMIT ?6 OFFSET ?7";
 
         let conn = self.conn()?;
         let mut stmt = conn.prepare(sql)?;
-        let records = stmt.query_map([user_id, limit as u64], |row| {
+        let records = stmt.query_map(filter_params(user_id, filter), |row| {
             Ok(HealthRecord {
                 tarantula_name: row.get(0)?,
                 check_date: row.get(1)?,
@@ -616,62 +1210,6 @@ impl TarantulaOperations for TarantulaDB {
             .map_err(BotError::Database)
     }
 
-    async fn get_health_alerts(&self, user_id: u64) -> BotResult<Vec<HealthAlert>> {
-        let sql = format!("SELECT
-        t.id,
-        t.name,
-        ts.scientific_name,
-        CASE
-            WHEN julianday('now') - julianday(t.last_health_check_date) >= 30 THEN 'Overdue Health Check'
-            WHEN julianday('now') - julianday(MAX(f.feeding_date)) >= 14
-                AND ms.stage_name != '{}' THEN 'Extended Feeding Strike'
-            WHEN ms.stage_name = '{}'
-                AND julianday('now') - julianday(t.last_molt_date) >= 180 THEN 'Extended Pre-molt'
-            ELSE 'None'
-        END as alert_type,
-        CAST(
-            CASE
-                WHEN julianday('now') - julianday(t.last_health_check_date) >= 30
-                    THEN julianday('now') - julianday(t.last_health_check_date)
-                WHEN julianday('now') - julianday(MAX(f.feeding_date)) >= 14
-                    THEN julianday('now') - julianday(MAX(f.feeding_date))
-                WHEN ms.stage_name = '{}'
-                    THEN julianday('now') - julianday(t.last_molt_date)
-                ELSE 0
-            END as INTEGER
-        ) as days_in_state
-    FROM tarantulas t
-    JOIN tarantula_species ts ON t.species_id = ts.id
-    LEFT JOIN feeding_events f ON t.id = f.tarantula_id
-    LEFT JOIN molt_stages ms ON t.current_molt_stage_id = ms.id
-    WHERE t.user_id = ?
-    GROUP BY t.id
-    HAVING alert_type != 'None'
-    ORDER BY days_in_state DESC",
-                          MoltStage::PreMolt.to_db_name(),
-                          MoltStage::PreMolt.to_db_name(),
-                          MoltStage::PreMolt.to_db_name()
-        );
-
-        let conn = self.conn()?;
-        let mut stmt = conn.prepare(&sql)?;
-        let alerts = stmt
-            .query_map([user_id], |row| {
-                Ok(HealthAlert {
-                    id: row.get(0)?,
-                    name: row.get(1)?,
-                    scientific_name: row.get(2)?,
-                    alert_type: row.get(3)?,
-                    days_in_state: row.get(4)?,
-                })
-            })
-            .map_err(BotError::Database)?;
-
-        alerts
-            .collect::<Result<Vec<_>, _>>()
-            .map_err(BotError::Database)
-    }
-
     async fn record_molt(
         &self,
         tarantula_id: i64,
@@ -679,51 +1217,52 @@ impl TarantulaOperations for TarantulaDB {
         complications: Option<String>,
         notes: Option<String>,
         user_id: u64,
-    ) -> BotResult<()> {
+        at: DbDateTime,
+    ) -> BotResult<Change> {
         let mut conn = self.conn()?;
-        let tx = conn.transaction()?;
         let post_molt_id = MoltStage::PostMolt as i64;
+        transactionally(&mut conn, |tx| {
+            let day = check_logged_at(tx, user_id, &[tarantula_id], at)?;
+            let before = change_state(tx, user_id, &[tarantula_id], &[])?;
+            let rows_affected = tx.execute(
+                &format!("{} WHERE id = ?3 AND user_id = ?4", MOLT_UPDATE),
+                params![day, post_molt_id, tarantula_id, user_id],
+            )?;
 
-        
-        let rows_affected = tx.execute(
-            "UPDATE tarantulas SET 
-            last_molt_date = date('now'),
-            current_molt_stage_id = ?
-        WHERE id = ? AND user_id = ?",
-            params![post_molt_id, tarantula_id, user_id],
-        )?;
-
-        if rows_affected == 0 {
-            return Err(BotError::NotFound(format!(
-                "Tarantula with id {} not found or access denied",
-                tarantula_id
-            )));
-        }
+            if rows_affected == 0 {
+                return Err(BotError::NotFound(format!(
+                    "Tarantula with id {} not found or access denied",
+                    tarantula_id
+                )));
+            }
 
-        
-        tx.execute(
-            "INSERT INTO molt_records (
+            tx.execute(
+                "INSERT INTO molt_records (
             tarantula_id, molt_date, molt_stage_id,
             post_molt_length_cm, complications, notes, user_id
-        ) VALUES (?, datetime('now'), ?, ?, ?, ?, ?)",
-            params![
-                tarantula_id,
-                post_molt_id,
-                length_cm,
-                complications,
-                notes,
-                user_id
-            ],
-        )?;
+        ) VALUES (?, ?, ?, ?, ?, ?, ?)",
+                params![
+                    tarantula_id,
+                    day,
+                    post_molt_id,
+                    length_cm,
+                    complications,
+                    notes,
+                    user_id
+                ],
+            )?;
+            let molt_id = tx.last_insert_rowid();
 
-        tx.commit()?;
-        Ok(())
+            let mut after = change_state(tx, user_id, &[tarantula_id], &[])?;
+            after.molts.push(molt_id);
+            record_change(tx, user_id, ChangeKind::Molt, &before, &after)
+        })
     }
 
-    async fn get_recent_molt_records(
+    async fn get_molt_records(
         &self,
         user_id: u64,
-        limit: i32,
+        filter: &RecordFilter,
     ) -> BotResult<Vec<MoltRecord>> {
         let sql = "
             SELECT 
@@ -737,13 +1276,17 @@ impl TarantulaOperations for TarantulaDB {
             FROM molt_records mr
             JOIN tarantulas t ON mr.tarantula_id = t.id
             JOIN molt_stages ms ON mr.molt_stage_id = ms.id
-            WHERE t.user_id = ?
-            ORDER BY mr.molt_date DESC
-            LIMIT ?";
+            WHERE t.user_id = ?1
+            AND (?2 IS NULL OR t.id = ?2)
+            AND (?3 IS NULL OR date(mr.molt_date) >= ?3)
+            AND (?4 IS NULL OR date(mr.molt_date) <= ?4)
+            AND (?5 IS NULL OR mr.molt_stage_id = ?5)
+            ORDER BY mr.molt_date DESC, mr.id DESC
+            LIMIT ?6 OFFSET ?7";
 
         let conn = self.conn()?;
         let mut stmt = conn.prepare(sql)?;
-        let records = stmt.query_map([user_id, limit as u64], |row| {
+        let records = stmt.query_map(filter_params(user_id, filter), |row| {
             Ok(MoltRecord {
                 tarantula_name: row.get(0)?,
                 molt_date: row.get(1)?,
@@ -832,15 +1375,108 @@ impl TarantulaOperations for TarantulaDB {
         colony_id: i64,
         adjustment: i32,
         user_id: u64,
-    ) -> BotResult<()> {
-        let conn = self.conn()?;
-        conn.execute(
-            "UPDATE cricket_colonies
+    ) -> BotResult<Change> {
+        let mut conn = self.conn()?;
+        transactionally(&mut conn, |tx| {
+            let before = change_state(tx, user_id, &[], &[colony_id])?;
+            if before.colonies.is_empty() {
+                return Err(BotError::NotFound(format!(
+                    "Colony with id {} not found or access denied",
+                    colony_id
+                )));
+            }
+            tx.execute(
+                "UPDATE cricket_colonies
             SET current_count = current_count + ?
             WHERE id = ? AND user_id = ?",
-            params![adjustment, colony_id, user_id],
-        )?;
-        Ok(())
+                params![adjustment, colony_id, user_id],
+            )?;
+
+            let after = change_state(tx, user_id, &[], &[colony_id])?;
+            record_change(tx, user_id, ChangeKind::ColonyCount, &before, &after)
+        })
+    }
+
+    async fn get_changes(&self, user_id: u64, offset: u32, limit: u32) -> BotResult<Vec<Change>> {
+        let conn = self.conn()?;
+        let timezone = user_timezone(&conn, user_id)?;
+        let mut stmt = conn.prepare(&format!(
+            "{} WHERE user_id = ? ORDER BY id DESC LIMIT ? OFFSET ?",
+            CHANGE_SELECT
+        ))?;
+        let changes = stmt
+            .query_map(params![user_id, limit, offset], Change::from_row)?
+            .map(|change| change.map(|change| change.in_timezone(timezone)))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(changes)
+    }
+
+    async fn undo_change(&self, user_id: u64, change_id: i64) -> BotResult<Change> {
+        let mut conn = self.conn()?;
+        transactionally(&mut conn, |tx| {
+            let change = read_change(tx, user_id, change_id)?;
+            if change.undone_at.is_some() {
+                return Err(BotError::ValidationError(
+                    "That change was already undone".to_string(),
+                ));
+            }
+
+            for (table, ids) in [
+                ("feeding_events", &change.after.feedings),
+                ("health_check_records", &change.after.health_checks),
+                ("molt_records", &change.after.molts),
+            ] {
+                for id in ids {
+                    tx.execute(
+                        &format!("DELETE FROM {} WHERE id = ? AND user_id = ?", table),
+                        params![id, user_id],
+                    )?;
+                }
+            }
+            for (colony_id, refund) in change.colony_refunds() {
+                let rows_affected = tx.execute(
+                    "UPDATE cricket_colonies SET current_count = current_count + ?1
+                     WHERE id = ?2 AND user_id = ?3 AND current_count + ?1 >= 0",
+                    params![refund, colony_id, user_id],
+                )?;
+                if rows_affected == 0 {
+                    return Err(BotError::ValidationError(
+                        "The colony no longer has the crickets to take back".to_string(),
+                    ));
+                }
+            }
+            for (tarantula_id, before) in &change.before.tarantulas {
+                let (Some(after), Some(now)) = (
+                    change.after.tarantulas.get(tarantula_id),
+                    tarantula_state(tx, user_id, *tarantula_id)?,
+                ) else {
+                    continue;
+                };
+                let reverted = now.reverted(before, after);
+                if reverted != now {
+                    tx.execute(
+                        "UPDATE tarantulas SET
+                            current_molt_stage_id = ?, last_molt_date = ?,
+                            current_health_status_id = ?, last_health_check_date = ?
+                         WHERE id = ? AND user_id = ?",
+                        params![
+                            reverted.molt_stage_id,
+                            reverted.last_molt_date,
+                            reverted.health_status_id,
+                            reverted.last_health_check_date,
+                            tarantula_id,
+                            user_id
+                        ],
+                    )?;
+                }
+            }
+
+            tx.execute(
+                "UPDATE audit_log SET undone_at = ? WHERE id = ?",
+                params![DbDateTime::default(), change_id],
+            )?;
+            read_change(tx, user_id, change_id)
+        })
     }
     async fn create_maintenance_record(&self, record: MaintenanceRecord) -> BotResult<i64> {
         let conn = self.conn()?;
@@ -889,56 +1525,6 @@ impl TarantulaOperations for TarantulaDB {
         Ok(records)
     }
 
-    async fn get_maintenance_tasks(&self, user_id: u64) -> BotResult<Vec<MaintenanceTask>> {
-        let sql = format!("SELECT
-        t.id,
-        t.name,
-        COALESCE(t.enclosure_number, 'No enclosure') as enclosure_number,
-        ts.scientific_name,
-        CASE
-            WHEN julianday('now') - julianday(t.last_health_check_date) >= 30 THEN 'Health Check Required'
-            WHEN julianday('now') - julianday(MAX(f.feeding_date)) >= 7
-                AND ms.stage_name != '{}' THEN 'Feeding Due'
-            WHEN ms.stage_name = '{}' THEN 'Monitor for Molt'
-            ELSE 'Regular Check'
-        END as required_action,
-        CASE
-            WHEN julianday('now') - julianday(t.last_health_check_date) >= 30 THEN 1
-            WHEN julianday('now') - julianday(MAX(f.feeding_date)) >= 7 THEN 2
-            WHEN ms.stage_name = '{}' THEN 3
-            ELSE 4
-        END as priority
-    FROM tarantulas t
-    JOIN tarantula_species ts ON t.species_id = ts.id
-    LEFT JOIN feeding_events f ON t.id = f.tarantula_id
-    LEFT JOIN molt_stages ms ON t.current_molt_stage_id = ms.id
-WHERE t.user_id = ?
-    GROUP BY t.id
-    HAVING required_action != 'Regular Check'
-    ORDER BY priority, name",
-                          MoltStage::PreMolt.to_db_name(),
-                          MoltStage::PreMolt.to_db_name(),
-                          MoltStage::PreMolt.to_db_name()
-        );
-
-        let conn = self.conn()?;
-        let mut stmt = conn.prepare(&sql)?;
-        let tasks = stmt.query_map([user_id], |row| {
-            Ok(MaintenanceTask {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                enclosure_number: row.get(2)?,
-                scientific_name: row.get(3)?,
-                required_action: row.get(4)?,
-                priority: row.get(5)?,
-            })
-        })?;
-
-        tasks
-            .collect::<Result<Vec<_>, _>>()
-            .map_err(BotError::Database)
-    }
-
     async fn create_enclosure(&self, enclosure: Enclosure) -> BotResult<i64> {
         let conn = self.conn()?;
         conn.execute(
@@ -981,6 +1567,33 @@ WHERE t.user_id = ?
         Ok(enclosure)
     }
 
+    async fn get_enclosures(&self, user_id: u64) -> BotResult<Vec<Enclosure>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, name, height_cm, width_cm, length_cm, substrate_depth_cm, notes, user_id
+             FROM enclosures
+             WHERE user_id = ?
+             ORDER BY id",
+        )?;
+
+        let enclosures = stmt
+            .query_map(params![user_id], |row| {
+                Ok(Enclosure {
+                    id: Some(row.get(0)?),
+                    name: row.get(1)?,
+                    height_cm: row.get(2)?,
+                    width_cm: row.get(3)?,
+                    length_cm: row.get(4)?,
+                    substrate_depth_cm: row.get(5)?,
+                    notes: row.get(6)?,
+                    user_id: row.get(7)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(enclosures)
+    }
+
     async fn ensure_user_exists(&self, user: &TelegramUser) -> BotResult<()> {
         let conn = self.conn()?;
         conn.execute(
@@ -1001,72 +1614,1435 @@ WHERE t.user_id = ?
         Ok(())
     }
 
+    async fn get_timezone(&self, user_id: u64) -> BotResult<Timezone> {
+        let conn = self.conn()?;
+        user_timezone(&conn, user_id)
+    }
+
+    async fn set_timezone(&self, user_id: u64, timezone: Timezone) -> BotResult<()> {
+        let conn = self.conn()?;
+        let rows_affected = conn.execute(
+            "UPDATE telegram_users SET timezone = ? WHERE telegram_id = ?",
+            params![timezone.name(), user_id],
+        )?;
+        if rows_affected == 0 {
+            return Err(BotError::NotFound(format!("User {} not found", user_id)));
+        }
+        Ok(())
+    }
+
     
-    async fn get_current_size(&self, tarantula_id: i64) -> BotResult<f32> {
+    async fn record_pairing(&self, user_id: u64, params: AddPairingParams) -> BotResult<i64> {
+        if params.female_id == params.male_id {
+            return Err(BotError::ValidationError(
+                "A tarantula cannot be paired with itself".to_string(),
+            ));
+        }
+
+        let mut conn = self.conn()?;
+        transactionally(&mut conn, |tx| {
+            for id in [params.female_id, params.male_id] {
+                tx.query_row(
+                    "SELECT 1 FROM tarantulas WHERE id = ? AND user_id = ?",
+                    params![id, user_id],
+                    |_| Ok(()),
+                )
+                .optional()?
+                .ok_or_else(|| {
+                    BotError::NotFound(format!(
+                        "Tarantula with id {} not found or access denied",
+                        id
+                    ))
+                })?;
+            }
+
+            // Earlier pairings and parent links tell the sexes apart; a pair
+            // going against them has its tarantulas mixed up.
+            for (id, known_as, sql) in [
+                (params.female_id, "male", KNOWN_MALE_SQL),
+                (params.male_id, "female", KNOWN_FEMALE_SQL),
+            ] {
+                let name: Option<String> = tx
+                    .query_row(sql, params![id], |row| row.get(0))
+                    .optional()?;
+                if let Some(name) = name {
+                    return Err(BotError::ValidationError(format!(
+                        "{} is recorded as a {}, pair it the other way round",
+                        name, known_as
+                    )));
+                }
+            }
+
+            tx.execute(
+                "INSERT INTO breeding_pairings (
+                female_id, male_id, pairing_date, outcome_id, notes, user_id
+            ) VALUES (?, ?, ?, ?, ?, ?)",
+                params![
+                    params.female_id,
+                    params.male_id,
+                    params.pairing_date,
+                    PairingOutcome::Pending as i64,
+                    params.notes,
+                    user_id,
+                ],
+            )?;
+            Ok(tx.last_insert_rowid())
+        })
+    }
+
+    async fn update_pairing_outcome(
+        &self,
+        user_id: u64,
+        pairing_id: i64,
+        outcome: PairingOutcome,
+    ) -> BotResult<()> {
         let conn = self.conn()?;
-        let mut stmt = conn.prepare(
-            "WITH LastMoltSize AS (
-    SELECT 
-        tarantula_id,
-        post_molt_length_cm,
-        molt_date
-    FROM molt_records 
-    WHERE molt_date = (
-        SELECT MAX(molt_date)
-        FROM molt_records mr2
-        WHERE mr2.tarantula_id = molt_records.tarantula_id
-        AND mr2.post_molt_length_cm IS NOT NULL
-    )
-),
-EstimatedSize AS (
-    SELECT
-        t.id as tarantula_id,
-        COALESCE(
-            lms.post_molt_length_cm,
-            CASE 
-                -- If we have a molt stage, use feeding schedule size
-                WHEN t.current_molt_stage_id IS NOT NULL THEN
-                    (SELECT fs.body_length_cm
-                     FROM feeding_schedules fs
-                     WHERE fs.species_id = t.species_id
-                     AND CASE 
-                         WHEN ms.stage_name LIKE '%spiderling%' THEN 'Spiderling'
-                         WHEN ms.stage_name LIKE '%juvenile%' THEN 'Juvenile'
-                         WHEN ms.stage_name LIKE '%sub%adult%' THEN 'Sub-Adult'
-                         WHEN ms.stage_name LIKE '%adult%' THEN 'Adult'
-                     END = fs.size_category
-                     LIMIT 1)
-                -- If we have estimated age, make a reasonable guess
-                WHEN t.estimated_age_months IS NOT NULL THEN
-                    CASE 
-                        WHEN t.estimated_age_months < 6 THEN ts.adult_size_cm * 0.2
-                        WHEN t.estimated_age_months < 12 THEN ts.adult_size_cm * 0.4
-                        WHEN t.estimated_age_months < 24 THEN ts.adult_size_cm * 0.6
-                        ELSE ts.adult_size_cm * 0.8
-                    END
-                -- Last resort: use adult size if acquisition date > 2 years
-                ELSE 
-                    CASE 
-                        WHEN julianday('now') - julianday(t.acquisition_date) > 730 THEN ts.adult_size_cm
-                        ELSE ts.adult_size_cm * 0.5
-                    END
-            END
-        ) as current_size_cm
-    FROM tarantulas t
-    JOIN tarantula_species ts ON t.species_id = ts.id
-    LEFT JOIN molt_stages ms ON t.current_molt_stage_id = ms.id
-    LEFT JOIN LastMoltSize lms ON t.id = lms.tarantula_id
-    WHERE t.id = ?
-)
-SELECT current_size_cm FROM EstimatedSize;",
+        let rows_affected = conn.execute(
+            "UPDATE breeding_pairings SET outcome_id = ? WHERE id = ? AND user_id = ?",
+            params![outcome as i64, pairing_id, user_id],
+        )?;
+
+        if rows_affected == 0 {
+            return Err(BotError::NotFound(format!(
+                "Pairing with id {} not found or access denied",
+                pairing_id
+            )));
+        }
+        Ok(())
+    }
+
+    async fn get_pairings(&self, user_id: u64) -> BotResult<Vec<PairingRecord>> {
+        let sql = format!(
+            "{} WHERE bp.user_id = ? ORDER BY bp.pairing_date DESC, bp.id DESC",
+            PAIRING_SELECT
+        );
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(&sql)?;
+        let records = stmt.query_map([user_id], PairingRecord::from_row)?;
+
+        records
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(BotError::Database)
+    }
+
+    async fn record_egg_sac(&self, user_id: u64, params: AddEggSacParams) -> BotResult<i64> {
+        let expected_pull_date = params
+            .expected_pull_date
+            .unwrap_or(params.laid_date + Duration::days(DEFAULT_INCUBATION_DAYS));
+
+        if expected_pull_date < params.laid_date {
+            return Err(BotError::ValidationError(
+                "Expected pulling date cannot be before the sac was laid".to_string(),
+            ));
+        }
+
+        let mut conn = self.conn()?;
+        transactionally(&mut conn, |tx| {
+            let pairing_date: NaiveDate = tx
+                .query_row(
+                    "SELECT pairing_date FROM breeding_pairings WHERE id = ? AND user_id = ?",
+                    params![params.pairing_id, user_id],
+                    |row| row.get(0),
+                )
+                .optional()?
+                .ok_or_else(|| {
+                    BotError::NotFound(format!(
+                        "Pairing with id {} not found or access denied",
+                        params.pairing_id
+                    ))
+                })?;
+
+            if params.laid_date < pairing_date {
+                return Err(BotError::ValidationError(
+                    "Egg sac cannot be laid before the pairing date".to_string(),
+                ));
+            }
+
+            tx.execute(
+                "INSERT INTO egg_sacs (
+                pairing_id, laid_date, expected_pull_date, status_id, notes, user_id
+            ) VALUES (?, ?, ?, ?, ?, ?)",
+                params![
+                    params.pairing_id,
+                    params.laid_date,
+                    expected_pull_date,
+                    EggSacStatus::Incubating as i64,
+                    params.notes,
+                    user_id,
+                ],
+            )?;
+            let egg_sac_id = tx.last_insert_rowid();
+
+            // A sac is the best evidence of a successful pairing.
+            tx.execute(
+                "UPDATE breeding_pairings SET outcome_id = ? WHERE id = ? AND outcome_id = ?",
+                params![
+                    PairingOutcome::Successful as i64,
+                    params.pairing_id,
+                    PairingOutcome::Pending as i64
+                ],
+            )?;
+
+            Ok(egg_sac_id)
+        })
+    }
+
+    async fn update_egg_sac_status(
+        &self,
+        user_id: u64,
+        egg_sac_id: i64,
+        status: EggSacStatus,
+    ) -> BotResult<()> {
+        let conn = self.conn()?;
+        let today = user_timezone(&conn, user_id)?.today();
+        let rows_affected = conn.execute(
+            "UPDATE egg_sacs SET
+            status_id = ?1,
+            pulled_date = CASE WHEN ?1 = ?2 THEN ?5 ELSE pulled_date END
+        WHERE id = ?3 AND user_id = ?4",
+            params![
+                status as i64,
+                EggSacStatus::Pulled as i64,
+                egg_sac_id,
+                user_id,
+                today
+            ],
         )?;
 
-        let size = stmt.query_row(params![tarantula_id], |row| row.get::<_, f32>(0))?;
-        Ok(size)
+        if rows_affected == 0 {
+            return Err(BotError::NotFound(format!(
+                "Egg sac with id {} not found or access denied",
+                egg_sac_id
+            )));
+        }
+        Ok(())
     }
+
+    async fn update_egg_sac_counts(
+        &self,
+        user_id: u64,
+        egg_sac_id: i64,
+        counts: EggSacCounts,
+    ) -> BotResult<()> {
+        let conn = self.conn()?;
+        let rows_affected = conn.execute(
+            "UPDATE egg_sacs SET
+            egg_count = COALESCE(?, egg_count),
+            nymph_count = COALESCE(?, nymph_count),
+            sling_count = COALESCE(?, sling_count)
+        WHERE id = ? AND user_id = ?",
+            params![
+                counts.egg_count,
+                counts.nymph_count,
+                counts.sling_count,
+                egg_sac_id,
+                user_id
+            ],
+        )?;
+
+        if rows_affected == 0 {
+            return Err(BotError::NotFound(format!(
+                "Egg sac with id {} not found or access denied",
+                egg_sac_id
+            )));
+        }
+        Ok(())
+    }
+
+    async fn get_egg_sacs(&self, user_id: u64) -> BotResult<Vec<EggSacRecord>> {
+        let sql = format!(
+            "{} WHERE es.user_id = ? ORDER BY es.laid_date DESC, es.id DESC",
+            EGG_SAC_SELECT
+        );
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(&sql)?;
+        let records = stmt.query_map([user_id], EggSacRecord::from_row)?;
+
+        records
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(BotError::Database)
+    }
+
+    async fn get_egg_sacs_due_pulling(
+        &self,
+        user_id: u64,
+        within_days: i64,
+    ) -> BotResult<Vec<EggSacRecord>> {
+        let sql = format!(
+            "{} WHERE es.user_id = ?
+                AND es.status_id = ?
+                AND es.expected_pull_date <= date(?, '+' || ? || ' days')
+            ORDER BY es.expected_pull_date",
+            EGG_SAC_SELECT
+        );
+        let conn = self.conn()?;
+        let today = user_timezone(&conn, user_id)?.today();
+        let mut stmt = conn.prepare(&sql)?;
+        let records = stmt.query_map(
+            params![user_id, EggSacStatus::Incubating as i64, today, within_days],
+            EggSacRecord::from_row,
+        )?;
+
+        records
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(BotError::Database)
+    }
+
+    async fn create_slings_from_egg_sac(
+        &self,
+        user_id: u64,
+        egg_sac_id: i64,
+        count: i32,
+        name_prefix: &str,
+    ) -> BotResult<Vec<i64>> {
+        if !(1..=MAX_GROUP_SIZE).contains(&count) {
+            return Err(BotError::ValidationError(format!(
+                "Sling count must be between 1 and {}",
+                MAX_GROUP_SIZE
+            )));
+        }
+
+        let mut conn = self.conn()?;
+        transactionally(&mut conn, |tx| {
+            let today = user_timezone(tx, user_id)?.today();
+            let (species_id, hatch_date, female_id, female_name, male_id, male_name, already_created): (
+                i64,
+                String,
+                i64,
+                String,
+                i64,
+                String,
+                i32,
+            ) = tx
+                .query_row(
+                    "SELECT
+                    f.species_id,
+                    COALESCE(es.pulled_date, ?3),
+                    f.id,
+                    f.name,
+                    m.id,
+                    m.name,
+                    (SELECT COUNT(*) FROM tarantulas s WHERE s.egg_sac_id = es.id)
+                FROM egg_sacs es
+                JOIN breeding_pairings bp ON es.pairing_id = bp.id
+                JOIN tarantulas f ON bp.female_id = f.id
+                JOIN tarantulas m ON bp.male_id = m.id
+                WHERE es.id = ?1 AND es.user_id = ?2",
+                    params![egg_sac_id, user_id, today],
+                    |row| {
+                        Ok((
+                            row.get(0)?,
+                            row.get(1)?,
+                            row.get(2)?,
+                            row.get(3)?,
+                            row.get(4)?,
+                            row.get(5)?,
+                            row.get(6)?,
+                        ))
+                    },
+                )
+                .optional()?
+                .ok_or_else(|| {
+                    BotError::NotFound(format!(
+                        "Egg sac with id {} not found or access denied",
+                        egg_sac_id
+                    ))
+                })?;
+
+            let notes = format!("From egg sac #{} ({} x {})", egg_sac_id, female_name, male_name);
+            let group_id = if count > 1 {
+                Some(insert_group(
+                    tx,
+                    user_id,
+                    name_prefix,
+                    species_id,
+                    &hatch_date,
+                    Some(&notes),
+                )?)
+            } else {
+                None
+            };
+
+            let mut ids = Vec::with_capacity(count as usize);
+            for n in 1..=count {
+                tx.execute(
+                    "INSERT INTO tarantulas (
+                    name, species_id, acquisition_date, estimated_age_months,
+                    notes, user_id, egg_sac_id, mother_id, father_id, group_id
+                ) VALUES (?, ?, ?, 0, ?, ?, ?, ?, ?, ?)",
+                    params![
+                        format!("{} #{}", name_prefix, already_created + n),
+                        species_id,
+                        hatch_date,
+                        notes,
+                        user_id,
+                        egg_sac_id,
+                        female_id,
+                        male_id,
+                        group_id,
+                    ],
+                )?;
+                ids.push(tx.last_insert_rowid());
+            }
+
+            tx.execute(
+                "UPDATE egg_sacs SET sling_count = COALESCE(sling_count, ?) WHERE id = ?",
+                params![count, egg_sac_id],
+            )?;
+
+            Ok(ids)
+        })
+    }
+
+    async fn set_tarantula_parents(
+        &self,
+        user_id: u64,
+        tarantula_id: i64,
+        mother: Parent,
+        father: Parent,
+    ) -> BotResult<()> {
+        if let (Parent::Internal(m), Parent::Internal(f)) = (&mother, &father) {
+            if m == f {
+                return Err(BotError::ValidationError(
+                    "Mother and father must be different tarantulas".to_string(),
+                ));
+            }
+        }
+
+        let mut conn = self.conn()?;
+        transactionally(&mut conn, |tx| {
+            tx.query_row(
+                "SELECT 1 FROM tarantulas WHERE id = ? AND user_id = ?",
+                params![tarantula_id, user_id],
+                |_| Ok(()),
+            )
+            .optional()?
+            .ok_or_else(|| {
+                BotError::NotFound(format!(
+                    "Tarantula with id {} not found or access denied",
+                    tarantula_id
+                ))
+            })?;
+
+            for parent in [&mother, &father] {
+                let Parent::Internal(parent_id) = parent else {
+                    continue;
+                };
+                if *parent_id == tarantula_id {
+                    return Err(BotError::ValidationError(
+                        "A tarantula cannot be its own parent".to_string(),
+                    ));
+                }
+                tx.query_row(
+                    "SELECT 1 FROM tarantulas WHERE id = ? AND user_id = ?",
+                    params![parent_id, user_id],
+                    |_| Ok(()),
+                )
+                .optional()?
+                .ok_or_else(|| {
+                    BotError::NotFound(format!(
+                        "Tarantula with id {} not found or access denied",
+                        parent_id
+                    ))
+                })?;
+
+                let is_descendant = tx
+                    .query_row(
+                        "WITH RECURSIVE descendants(id) AS (
+                        SELECT id FROM tarantulas WHERE mother_id = ?1 OR father_id = ?1
+                        UNION
+                        SELECT t.id FROM tarantulas t
+                        JOIN descendants d ON t.mother_id = d.id OR t.father_id = d.id
+                    )
+                    SELECT 1 FROM descendants WHERE id = ?2",
+                        params![tarantula_id, parent_id],
+                        |_| Ok(()),
+                    )
+                    .optional()?
+                    .is_some();
+                if is_descendant {
+                    return Err(BotError::ValidationError(format!(
+                        "Tarantula {} descends from {} and cannot be its parent",
+                        parent_id, tarantula_id
+                    )));
+                }
+            }
+
+            let split = |parent: &Parent| match parent {
+                Parent::Unknown => (None, None),
+                Parent::Internal(id) => (Some(*id), None),
+                Parent::External(breeder) => (None, Some(breeder.clone())),
+            };
+            let (mother_id, mother_external) = split(&mother);
+            let (father_id, father_external) = split(&father);
+
+            tx.execute(
+                "UPDATE tarantulas SET
+                mother_id = ?, mother_external = ?,
+                father_id = ?, father_external = ?
+            WHERE id = ? AND user_id = ?",
+                params![
+                    mother_id,
+                    mother_external,
+                    father_id,
+                    father_external,
+                    tarantula_id,
+                    user_id
+                ],
+            )?;
+            Ok(())
+        })
+    }
+
+    async fn get_ancestors(
+        &self,
+        user_id: u64,
+        tarantula_id: i64,
+        max_depth: i32,
+    ) -> BotResult<Vec<LineageNode>> {
+        let sql = "WITH RECURSIVE lineage(id, depth) AS (
+            SELECT id, 0 FROM tarantulas WHERE id = ?1 AND user_id = ?2
+            UNION
+            SELECT p.id, l.depth + 1
+            FROM lineage l
+            JOIN tarantulas c ON c.id = l.id
+            JOIN tarantulas p ON p.id IN (c.mother_id, c.father_id) AND p.user_id = ?2
+            WHERE l.depth + 1 < ?3
+        )
+        SELECT m.id, COALESCE(m.name, c.mother_external) as name, c.id as relative_id,
+               'Mother' as role, l.depth + 1 as depth
+        FROM lineage l
+        JOIN tarantulas c ON c.id = l.id
+        LEFT JOIN tarantulas m ON m.id = c.mother_id AND m.user_id = ?2
+        WHERE c.mother_id IS NOT NULL OR c.mother_external IS NOT NULL
+        UNION ALL
+        SELECT f.id, COALESCE(f.name, c.father_external) as name, c.id as relative_id,
+               'Father' as role, l.depth + 1 as depth
+        FROM lineage l
+        JOIN tarantulas c ON c.id = l.id
+        LEFT JOIN tarantulas f ON f.id = c.father_id AND f.user_id = ?2
+        WHERE c.father_id IS NOT NULL OR c.father_external IS NOT NULL
+        ORDER BY depth, relative_id, role DESC";
+
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(sql)?;
+        let nodes = stmt.query_map(params![tarantula_id, user_id, max_depth], |row| {
+            lineage_node(row)
+        })?;
+
+        nodes
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(BotError::Database)
+    }
+
+    async fn get_descendants(
+        &self,
+        user_id: u64,
+        tarantula_id: i64,
+        max_depth: i32,
+    ) -> BotResult<Vec<LineageNode>> {
+        let sql = "WITH RECURSIVE descendants(id, relative_id, role, depth) AS (
+            SELECT c.id, p.id, CASE WHEN c.mother_id = p.id THEN 'Mother' ELSE 'Father' END, 1
+            FROM tarantulas p
+            JOIN tarantulas c ON p.id IN (c.mother_id, c.father_id) AND c.user_id = ?2
+            WHERE p.id = ?1 AND p.user_id = ?2
+            UNION
+            SELECT c.id, d.id, CASE WHEN c.mother_id = d.id THEN 'Mother' ELSE 'Father' END, d.depth + 1
+            FROM descendants d
+            JOIN tarantulas c ON d.id IN (c.mother_id, c.father_id) AND c.user_id = ?2
+            WHERE d.depth < ?3
+        )
+        SELECT d.id, t.name, d.relative_id, d.role, d.depth
+        FROM descendants d
+        JOIN tarantulas t ON t.id = d.id
+        ORDER BY d.depth, t.name";
+
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(sql)?;
+        let nodes = stmt.query_map(params![tarantula_id, user_id, max_depth], |row| {
+            lineage_node(row)
+        })?;
+
+        nodes
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(BotError::Database)
+    }
+
+    async fn create_group(&self, user_id: u64, params: CreateGroupParams) -> BotResult<i64> {
+        if !(1..=MAX_GROUP_SIZE).contains(&params.count) {
+            return Err(BotError::ValidationError(format!(
+                "Group size must be between 1 and {}",
+                MAX_GROUP_SIZE
+            )));
+        }
+
+        let mut conn = self.conn()?;
+        transactionally(&mut conn, |tx| {
+            let group_id = insert_group(
+                tx,
+                user_id,
+                &params.name,
+                params.species_id,
+                &params.acquisition_date,
+                params.notes.as_deref(),
+            )?;
+
+            for n in 1..=params.count {
+                tx.execute(
+                    "INSERT INTO tarantulas (
+                    name, species_id, acquisition_date, user_id, group_id
+                ) VALUES (?, ?, ?, ?, ?)",
+                    params![
+                        format!("{} #{}", params.name, n),
+                        params.species_id,
+                        params.acquisition_date,
+                        user_id,
+                        group_id,
+                    ],
+                )?;
+            }
+            Ok(group_id)
+        })
+    }
+
+    async fn get_groups(&self, user_id: u64) -> BotResult<Vec<GroupSummary>> {
+        let sql = "SELECT
+            tg.id,
+            tg.name,
+            tg.species_id,
+            ts.common_name as species_name,
+            tg.acquisition_date,
+            (SELECT COUNT(*) FROM tarantulas m WHERE m.group_id = tg.id) as member_count,
+            (SELECT julianday('now') - julianday(MAX(fe.feeding_date))
+             FROM feeding_events fe
+             JOIN tarantulas m ON fe.tarantula_id = m.id
+             WHERE m.group_id = tg.id AND fe.feeding_status_id = ?2) as days_since_feeding,
+            tg.notes
+        FROM tarantula_groups tg
+        JOIN tarantula_species ts ON tg.species_id = ts.id
+        WHERE tg.user_id = ?1
+        ORDER BY tg.name";
+
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(sql)?;
+        let accepted = FeedingStatus::Accepted as i64;
+        let groups = stmt.query_map(params![user_id, accepted], |row| {
+            Ok(GroupSummary {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                species_id: row.get(2)?,
+                species_name: row.get(3)?,
+                acquisition_date: row.get(4)?,
+                member_count: row.get(5)?,
+                days_since_feeding: row.get(6)?,
+                notes: row.get(7)?,
+            })
+        })?;
+
+        groups
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(BotError::Database)
+    }
+
+    async fn get_group_members(
+        &self,
+        user_id: u64,
+        group_id: i64,
+    ) -> BotResult<Vec<TarantulaListItem>> {
+        Ok(self
+            .get_all_tarantulas(user_id)
+            .await?
+            .into_iter()
+            .filter(|t| t.group_id == Some(group_id))
+            .collect())
+    }
+
+    async fn record_group_feeding(
+        &self,
+        user_id: u64,
+        group_id: i64,
+        colony_id: i64,
+        crickets_per_member: i32,
+        at: DbDateTime,
+    ) -> BotResult<Change> {
+        if crickets_per_member <= 0 {
+            return Err(BotError::ValidationError(
+                "Crickets per sling must be positive".to_string(),
+            ));
+        }
+
+        let mut conn = self.conn()?;
+        transactionally(&mut conn, |tx| {
+            let members = group_member_ids(tx, user_id, group_id)?;
+            check_logged_at(tx, user_id, &members, at)?;
+            let total = members.len() as i32 * crickets_per_member;
+            let before = change_state(tx, user_id, &members, &[colony_id])?;
+
+            let rows_affected = tx.execute(
+                "UPDATE cricket_colonies
+        SET current_count = current_count - ?
+        WHERE id = ? AND user_id = ?
+        AND current_count >= ?",
+                params![total, colony_id, user_id, total],
+            )?;
+
+            if rows_affected == 0 {
+                return Err(BotError::NotFound(format!(
+                    "Colony not found, access denied, or fewer than {} crickets",
+                    total
+                )));
+            }
+
+            let mut feedings = Vec::with_capacity(members.len());
+            for tarantula_id in &members {
+                tx.execute(
+                    "INSERT INTO feeding_events (
+                tarantula_id,
+                feeding_date,
+                cricket_colony_id,
+                number_of_crickets,
+                feeding_status_id,
+                notes,
+                user_id
+            ) VALUES (?, ?, ?, ?, ?, ?, ?)",
+                    params![
+                        tarantula_id,
+                        at,
+                        colony_id,
+                        crickets_per_member,
+                        FeedingStatus::Accepted as i64,
+                        "Group feeding",
+                        user_id,
+                    ],
+                )?;
+                feedings.push(tx.last_insert_rowid());
+            }
+
+            let mut after = change_state(tx, user_id, &members, &[colony_id])?;
+            after.feedings = feedings;
+            record_change(tx, user_id, ChangeKind::GroupFeeding, &before, &after)
+        })
+    }
+
+    async fn record_group_health_check(
+        &self,
+        user_id: u64,
+        group_id: i64,
+        status: HealthStatus,
+        at: DbDateTime,
+    ) -> BotResult<Change> {
+        let mut conn = self.conn()?;
+        transactionally(&mut conn, |tx| {
+            let members = group_member_ids(tx, user_id, group_id)?;
+            let day = check_logged_at(tx, user_id, &members, at)?;
+            let before = change_state(tx, user_id, &members, &[])?;
+            tx.execute(
+                &format!(
+                    "{} WHERE group_id = ?3 AND user_id = ?4",
+                    HEALTH_CHECK_UPDATE
+                ),
+                params![day, status as i64, group_id, user_id],
+            )?;
+            let mut checks = Vec::with_capacity(members.len());
+            for tarantula_id in &members {
+                tx.execute(
+                    "INSERT INTO health_check_records (
+            tarantula_id, check_date, health_status_id, notes, user_id
+        ) VALUES (?, ?, ?, 'Group health check', ?)",
+                    params![tarantula_id, day, status as i64, user_id],
+                )?;
+                checks.push(tx.last_insert_rowid());
+            }
+
+            let mut after = change_state(tx, user_id, &members, &[])?;
+            after.health_checks = checks;
+            record_change(tx, user_id, ChangeKind::GroupHealthCheck, &before, &after)
+        })
+    }
+
+    async fn record_group_molt(
+        &self,
+        user_id: u64,
+        group_id: i64,
+        length_cm: Option<f32>,
+        at: DbDateTime,
+    ) -> BotResult<Change> {
+        let post_molt_id = MoltStage::PostMolt as i64;
+        let mut conn = self.conn()?;
+        transactionally(&mut conn, |tx| {
+            let members = group_member_ids(tx, user_id, group_id)?;
+            let day = check_logged_at(tx, user_id, &members, at)?;
+            let before = change_state(tx, user_id, &members, &[])?;
+            tx.execute(
+                &format!("{} WHERE group_id = ?3 AND user_id = ?4", MOLT_UPDATE),
+                params![day, post_molt_id, group_id, user_id],
+            )?;
+            let mut molts = Vec::with_capacity(members.len());
+            for tarantula_id in &members {
+                tx.execute(
+                    "INSERT INTO molt_records (
+            tarantula_id, molt_date, molt_stage_id, post_molt_length_cm, notes, user_id
+        ) VALUES (?, ?, ?, ?, 'Group molt', ?)",
+                    params![tarantula_id, day, post_molt_id, length_cm, user_id],
+                )?;
+                molts.push(tx.last_insert_rowid());
+            }
+
+            let mut after = change_state(tx, user_id, &members, &[])?;
+            after.molts = molts;
+            record_change(tx, user_id, ChangeKind::GroupMolt, &before, &after)
+        })
+    }
+
+    async fn split_from_group(
+        &self,
+        user_id: u64,
+        tarantula_id: i64,
+        new_name: Option<String>,
+    ) -> BotResult<()> {
+        let conn = self.conn()?;
+        let rows_affected = conn.execute(
+            "UPDATE tarantulas SET
+            group_id = NULL,
+            name = COALESCE(?, name)
+        WHERE id = ? AND user_id = ? AND group_id IS NOT NULL",
+            params![new_name, tarantula_id, user_id],
+        )?;
+
+        if rows_affected == 0 {
+            return Err(BotError::NotFound(format!(
+                "Tarantula with id {} not found or not part of a group",
+                tarantula_id
+            )));
+        }
+        Ok(())
+    }
+
+    async fn get_species(&self) -> BotResult<Vec<TarantulaSpecies>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, scientific_name, common_name FROM tarantula_species ORDER BY id",
+        )?;
+        let species = stmt
+            .query_map([], |row| {
+                Ok(TarantulaSpecies {
+                    id: row.get(0)?,
+                    scientific_name: row.get(1)?,
+                    common_name: row.get(2)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(species)
+    }
+
+    async fn import_collection(&self, user_id: u64, plan: &ImportPlan) -> BotResult<()> {
+        let mut conn = self.conn()?;
+        transactionally(&mut conn, |tx| {
+            let timezone = user_timezone(tx, user_id)?;
+            let mut new_ids = Vec::with_capacity(plan.tarantulas.len());
+            for t in &plan.tarantulas {
+                if let Some(number) = &t.enclosure_number {
+                    let taken = tx
+                        .query_row(
+                            "SELECT 1 FROM tarantulas WHERE enclosure_number = ?",
+                            [number],
+                            |_| Ok(()),
+                        )
+                        .optional()?
+                        .is_some();
+                    if taken {
+                        return Err(BotError::ValidationError(format!(
+                            "Enclosure {} is already taken, change it in the file and send it again",
+                            number
+                        )));
+                    }
+                }
+                tx.execute(
+                    "INSERT INTO tarantulas (
+                        name, species_id, acquisition_date, estimated_age_months,
+                        enclosure_number, notes, user_id
+                    ) VALUES (?, ?, ?, ?, ?, ?, ?)",
+                    params![
+                        t.name,
+                        t.species_id,
+                        t.acquisition_date,
+                        t.estimated_age_months,
+                        t.enclosure_number,
+                        t.notes,
+                        user_id
+                    ],
+                )?;
+                new_ids.push(tx.last_insert_rowid());
+            }
+
+            let tarantula_id = |target: ImportTarget| -> BotResult<i64> {
+                let id = match target {
+                    ImportTarget::New(index) => return Ok(new_ids[index]),
+                    ImportTarget::Existing(id) => id,
+                };
+                tx.query_row(
+                    "SELECT id FROM tarantulas WHERE id = ? AND user_id = ?",
+                    params![id, user_id],
+                    |row| row.get(0),
+                )
+                .optional()?
+                .ok_or_else(|| {
+                    BotError::NotFound(format!(
+                        "Tarantula with id {} not found or access denied",
+                        id
+                    ))
+                })
+            };
+
+            for f in &plan.feedings {
+                if let Some(colony_id) = f.colony_id {
+                    tx.query_row(
+                        "SELECT 1 FROM cricket_colonies WHERE id = ? AND user_id = ?",
+                        params![colony_id, user_id],
+                        |_| Ok(()),
+                    )
+                    .optional()?
+                    .ok_or_else(|| {
+                        BotError::NotFound("Colony not found or access denied".to_string())
+                    })?;
+                }
+                tx.execute(
+                    "INSERT INTO feeding_events (
+                        tarantula_id, feeding_date, cricket_colony_id, number_of_crickets,
+                        feeding_status_id, notes, user_id
+                    ) VALUES (?, ?, ?, ?, ?, ?, ?)",
+                    params![
+                        tarantula_id(f.tarantula)?,
+                        DbDateTime::from_naive_utc(timezone.utc(f.fed_at)),
+                        f.colony_id,
+                        f.number_of_crickets,
+                        f.status as i64,
+                        f.notes,
+                        user_id
+                    ],
+                )?;
+            }
+
+            for m in &plan.molts {
+                let id = tarantula_id(m.tarantula)?;
+                tx.execute(
+                    "INSERT INTO molt_records (
+                        tarantula_id, molt_date, molt_stage_id,
+                        post_molt_length_cm, complications, notes, user_id
+                    ) VALUES (?, ?, ?, ?, ?, ?, ?)",
+                    params![
+                        id,
+                        m.molt_date,
+                        MoltStage::PostMolt as i64,
+                        m.length_cm,
+                        m.complications,
+                        m.notes,
+                        user_id
+                    ],
+                )?;
+                tx.execute(
+                    "UPDATE tarantulas SET last_molt_date = ?1
+                     WHERE id = ?2 AND (last_molt_date IS NULL OR last_molt_date < ?1)",
+                    params![m.molt_date, id],
+                )?;
+            }
+            Ok(())
+        })
+    }
+
+    async fn ping(&self) -> BotResult<()> {
+        let conn = self.pool.get_timeout(PING_TIMEOUT)?;
+        conn.query_row("SELECT 1", [], |_| Ok(()))?;
+        Ok(())
+    }
+
+    async fn backup_to(&self, path: &Path) -> BotResult<()> {
+        self.conn()?.backup(DatabaseName::Main, path, None)?;
+        Ok(())
+    }
+
+    async fn restore_from(&self, path: &Path) -> BotResult<()> {
+        verify_backup(path)?;
+        let mut conn = self.conn()?;
+        conn.restore(DatabaseName::Main, path, None::<fn(Progress)>)?;
+        // Backups taken before a migration get it now.
+        migrations::migrate(&mut conn)?;
+        drop(conn);
+        fill_default_enums(self.pool.clone())
+    }
+
+    fn pool_usage(&self) -> Option<PoolUsage> {
+        let state = self.pool.state();
+        Some(PoolUsage {
+            connections: state.connections,
+            idle: state.idle_connections,
+            max: self.pool.max_size(),
+        })
+    }
+
+    fn alert_thresholds(&self) -> AlertThresholds {
+        self.thresholds
+    }
+
+    async fn get_feeding_frequencies(&self) -> BotResult<Vec<FeedingFrequency>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, frequency_name, min_days, max_days, description
+             FROM feeding_frequencies
+             ORDER BY min_days, max_days",
+        )?;
+
+        let frequencies = stmt.query_map([], |row| {
+            Ok(FeedingFrequency {
+                id: row.get(0)?,
+                frequency_name: row.get(1)?,
+                min_days: row.get(2)?,
+                max_days: row.get(3)?,
+                description: row.get(4)?,
+            })
+        })?;
+
+        frequencies
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(BotError::Database)
+    }
+
+    async fn set_feeding_override(
+        &self,
+        user_id: u64,
+        params: SetFeedingOverrideParams,
+    ) -> BotResult<()> {
+        if params.frequency_id.is_none()
+            && params.prey_count.is_none()
+            && params.prey_size_id.is_none()
+        {
+            return Err(BotError::ValidationError(
+                "An override needs a frequency, prey count or prey size".to_string(),
+            ));
+        }
+        if params.prey_count.is_some_and(|c| c <= 0) {
+            return Err(BotError::ValidationError(
+                "Prey count must be positive".to_string(),
+            ));
+        }
+        let mut conn = self.conn()?;
+        let today = user_timezone(&conn, user_id)?.today();
+        if params.expires_on.is_some_and(|d| d < today) {
+            return Err(BotError::ValidationError(
+                "Expiry date is in the past".to_string(),
+            ));
+        }
+
+        transactionally(&mut conn, |tx| {
+            tx.query_row(
+                "SELECT 1 FROM tarantulas WHERE id = ? AND user_id = ?",
+                params![params.tarantula_id, user_id],
+                |_| Ok(()),
+            )
+            .optional()?
+            .ok_or_else(|| {
+                BotError::NotFound(format!(
+                    "Tarantula with id {} not found or access denied",
+                    params.tarantula_id
+                ))
+            })?;
+
+            if let Some(frequency_id) = params.frequency_id {
+                let exists: bool = tx.query_row(
+                    "SELECT EXISTS(SELECT 1 FROM feeding_frequencies WHERE id = ?)",
+                    [frequency_id],
+                    |row| row.get(0),
+                )?;
+                if !exists {
+                    return Err(BotError::NotFound(format!(
+                        "Feeding frequency {} not found",
+                        frequency_id
+                    )));
+                }
+            }
+            if let Some(prey_size_id) = params.prey_size_id {
+                let exists: bool = tx.query_row(
+                    "SELECT EXISTS(SELECT 1 FROM cricket_size_types WHERE id = ?)",
+                    [prey_size_id],
+                    |row| row.get(0),
+                )?;
+                if !exists {
+                    return Err(BotError::NotFound(format!(
+                        "Prey size {} not found",
+                        prey_size_id
+                    )));
+                }
+            }
+
+            tx.execute(
+                "INSERT INTO feeding_overrides (
+                tarantula_id, frequency_id, prey_count, prey_size_id,
+                expires_on, notes, user_id
+            ) VALUES (?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT (tarantula_id) DO UPDATE SET
+                frequency_id = excluded.frequency_id,
+                prey_count = excluded.prey_count,
+                prey_size_id = excluded.prey_size_id,
+                expires_on = excluded.expires_on,
+                notes = excluded.notes,
+                created_at = CURRENT_TIMESTAMP",
+                params![
+                    params.tarantula_id,
+                    params.frequency_id,
+                    params.prey_count,
+                    params.prey_size_id,
+                    params.expires_on,
+                    params.notes,
+                    user_id,
+                ],
+            )?;
+            Ok(())
+        })
+    }
+
+    async fn get_feeding_override(
+        &self,
+        user_id: u64,
+        tarantula_id: i64,
+    ) -> BotResult<Option<FeedingOverride>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT
+                fo.tarantula_id,
+                fo.frequency_id,
+                ff.frequency_name,
+                ff.min_days,
+                ff.max_days,
+                fo.prey_count,
+                fo.prey_size_id,
+                cst.size_name,
+                fo.expires_on,
+                fo.notes
+            FROM feeding_overrides fo
+            LEFT JOIN feeding_frequencies ff ON fo.frequency_id = ff.id
+            LEFT JOIN cricket_size_types cst ON fo.prey_size_id = cst.id
+            WHERE fo.tarantula_id = ? AND fo.user_id = ?
+            AND (fo.expires_on IS NULL OR fo.expires_on >= ?3)",
+        )?;
+
+        let today = user_timezone(&conn, user_id)?.today();
+        let feeding_override = stmt
+            .query_row(params![tarantula_id, user_id, today], |row| {
+                Ok(FeedingOverride {
+                    tarantula_id: row.get(0)?,
+                    frequency_id: row.get(1)?,
+                    frequency_name: row.get(2)?,
+                    min_days: row.get(3)?,
+                    max_days: row.get(4)?,
+                    prey_count: row.get(5)?,
+                    prey_size_id: row.get(6)?,
+                    prey_size: row.get(7)?,
+                    expires_on: row.get(8)?,
+                    notes: row.get(9)?,
+                })
+            })
+            .optional()?;
+
+        Ok(feeding_override)
+    }
+
+    async fn clear_feeding_override(&self, user_id: u64, tarantula_id: i64) -> BotResult<()> {
+        let conn = self.conn()?;
+        let rows_affected = conn.execute(
+            "DELETE FROM feeding_overrides WHERE tarantula_id = ? AND user_id = ?",
+            params![tarantula_id, user_id],
+        )?;
+
+        if rows_affected == 0 {
+            return Err(BotError::NotFound(format!(
+                "No feeding override for tarantula {}",
+                tarantula_id
+            )));
+        }
+        Ok(())
+    }
+}
+
+fn insert_group(
+    tx: &rusqlite::Transaction,
+    user_id: u64,
+    name: &str,
+    species_id: i64,
+    acquisition_date: &dyn rusqlite::ToSql,
+    notes: Option<&str>,
+) -> Result<i64, BotError> {
+    tx.execute(
+        "INSERT INTO tarantula_groups (name, species_id, acquisition_date, notes, user_id)
+         VALUES (?, ?, ?, ?, ?)",
+        params![name, species_id, acquisition_date, notes, user_id],
+    )?;
+    Ok(tx.last_insert_rowid())
+}
+
+fn group_member_ids(
+    tx: &rusqlite::Transaction,
+    user_id: u64,
+    group_id: i64,
+) -> Result<Vec<i64>, BotError> {
+    let mut stmt = tx.prepare(
+        "SELECT t.id FROM tarantulas t
+         JOIN tarantula_groups tg ON t.group_id = tg.id
+         WHERE tg.id = ? AND tg.user_id = ?",
+    )?;
+    let ids = stmt
+        .query_map(params![group_id, user_id], |row| row.get(0))?
+        .collect::<Result<Vec<i64>, _>>()?;
+
+    if ids.is_empty() {
+        return Err(BotError::NotFound(format!(
+            "Group with id {} not found, access denied, or empty",
+            group_id
+        )));
+    }
+    Ok(ids)
+}
+
+/// Sets a health status checked on day `?1` to `?2`, unless a later check is
+/// already logged. Every right-hand side sees the row as it was.
+const HEALTH_CHECK_UPDATE: &str = "UPDATE tarantulas SET
+    current_health_status_id = CASE
+        WHEN last_health_check_date > ?1 THEN current_health_status_id ELSE ?2 END,
+    last_health_check_date = MAX(COALESCE(last_health_check_date, ?1), ?1)";
+
+/// Sets the molt stage to `?2` for a molt on day `?1`, unless a later molt is
+/// already logged.
+const MOLT_UPDATE: &str = "UPDATE tarantulas SET
+    current_molt_stage_id = CASE
+        WHEN last_molt_date > ?1 THEN current_molt_stage_id ELSE ?2 END,
+    last_molt_date = MAX(COALESCE(last_molt_date, ?1), ?1)";
+
+/// The name of tarantula `?1` when it has been paired as a male or fathered
+/// offspring.
+const KNOWN_MALE_SQL: &str = "
+    SELECT t.name FROM tarantulas t
+    WHERE t.id = ?1
+      AND (EXISTS (SELECT 1 FROM breeding_pairings bp WHERE bp.male_id = t.id)
+           OR EXISTS (SELECT 1 FROM tarantulas c WHERE c.father_id = t.id))";
+
+/// The name of tarantula `?1` when it has been paired as a female or
+/// mothered offspring.
+const KNOWN_FEMALE_SQL: &str = "
+    SELECT t.name FROM tarantulas t
+    WHERE t.id = ?1
+      AND (EXISTS (SELECT 1 FROM breeding_pairings bp WHERE bp.female_id = t.id)
+           OR EXISTS (SELECT 1 FROM tarantulas c WHERE c.mother_id = t.id))";
+
+/// The keeper's timezone, UTC for keepers who never set one.
+fn user_timezone(conn: &rusqlite::Connection, user_id: u64) -> Result<Timezone, BotError> {
+    let name: Option<String> = conn
+        .query_row(
+            "SELECT timezone FROM telegram_users WHERE telegram_id = ?",
+            params![user_id],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(name
+        .and_then(|name| Timezone::from_name(&name))
+        .unwrap_or_default())
+}
+
+/// The UTC instant `row` holds at `idx`, written out on `timezone`'s clock.
+fn on_clock(row: &Row, idx: usize, timezone: Timezone) -> rusqlite::Result<String> {
+    let at: DbDateTime = row.get(idx)?;
+    Ok(timezone
+        .local(at.naive_utc())
+        .format("%Y-%m-%d %H:%M:%S")
+        .to_string())
+}
+
+/// Refuses to log something for `tarantulas` at `at` when that is still to
+/// come or before one of them was acquired, and gives the keeper's day it
+/// falls on. Ids the keeper doesn't own are skipped; the write itself
+/// reports them.
+fn check_logged_at(
+    tx: &rusqlite::Transaction,
+    user_id: u64,
+    tarantulas: &[i64],
+    at: DbDateTime,
+) -> Result<NaiveDate, BotError> {
+    let timezone = user_timezone(tx, user_id)?;
+    let at = at.naive_utc();
+    if at > Utc::now().naive_utc() {
+        return Err(BotError::ValidationError(format!(
+            "{} is in the future",
+            timezone.local(at).format("%Y-%m-%d %H:%M")
+        )));
+    }
+    let day = timezone.day_of(at);
+    for &id in tarantulas {
+        let acquired: Option<(String, NaiveDate)> = tx
+            .query_row(
+                "SELECT name, acquisition_date FROM tarantulas WHERE id = ? AND user_id = ?",
+                params![id, user_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+        if let Some((name, acquired)) = acquired.filter(|(_, acquired)| day < *acquired) {
+            return Err(BotError::ValidationError(format!(
+                "{} was only acquired on {}",
+                name, acquired
+            )));
+        }
+    }
+    Ok(day)
+}
+
+const CHANGE_SELECT: &str =
+    "SELECT id, kind, before, after, changed_at, undone_at FROM audit_log";
+
+impl FromRow for Change {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        let kind: i64 = row.get("kind")?;
+        Ok(Self {
+            id: row.get("id")?,
+            kind: ChangeKind::from_id(kind)
+                .ok_or(rusqlite::Error::IntegralValueOutOfRange(1, kind))?,
+            before: state_from_json(row, "before")?,
+            after: state_from_json(row, "after")?,
+            changed_at: row.get::<_, DbDateTime>("changed_at")?.naive_utc(),
+            undone_at: row
+                .get::<_, Option<DbDateTime>>("undone_at")?
+                .map(|at| at.naive_utc()),
+        })
+    }
+}
+
+fn state_from_json(row: &Row, column: &str) -> rusqlite::Result<ChangeState> {
+    let json: String = row.get(column)?;
+    serde_json::from_str(&json).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e))
+    })
+}
+
+fn tarantula_state(
+    tx: &rusqlite::Transaction,
+    user_id: u64,
+    tarantula_id: i64,
+) -> Result<Option<TarantulaState>, BotError> {
+    let state = tx
+        .query_row(
+            "SELECT current_molt_stage_id, last_molt_date,
+                    current_health_status_id, last_health_check_date
+             FROM tarantulas WHERE id = ? AND user_id = ?",
+            params![tarantula_id, user_id],
+            |row| {
+                Ok(TarantulaState {
+                    molt_stage_id: row.get(0)?,
+                    last_molt_date: row.get(1)?,
+                    health_status_id: row.get(2)?,
+                    last_health_check_date: row.get(3)?,
+                })
+            },
+        )
+        .optional()?;
+    Ok(state)
+}
+
+/// What a write to `tarantulas` and `colonies` can move, for the audit log.
+/// Rows the keeper doesn't own are left out; the write itself reports them.
+fn change_state(
+    tx: &rusqlite::Transaction,
+    user_id: u64,
+    tarantulas: &[i64],
+    colonies: &[i64],
+) -> Result<ChangeState, BotError> {
+    let mut state = ChangeState::default();
+    for &id in tarantulas {
+        if let Some(tarantula) = tarantula_state(tx, user_id, id)? {
+            state.tarantulas.insert(id, tarantula);
+        }
+    }
+    for &id in colonies {
+        let count = tx
+            .query_row(
+                "SELECT current_count FROM cricket_colonies WHERE id = ? AND user_id = ?",
+                params![id, user_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        if let Some(count) = count {
+            state.colonies.insert(id, count);
+        }
+    }
+    Ok(state)
+}
+
+/// Adds the audit log entry for a write made in `tx`.
+fn record_change(
+    tx: &rusqlite::Transaction,
+    user_id: u64,
+    kind: ChangeKind,
+    before: &ChangeState,
+    after: &ChangeState,
+) -> Result<Change, BotError> {
+    let to_json = |state: &ChangeState| {
+        serde_json::to_string(state).map_err(|e| BotError::OperationError(e.to_string()))
+    };
+    tx.execute(
+        "INSERT INTO audit_log (user_id, kind, before, after, changed_at)
+         VALUES (?, ?, ?, ?, ?)",
+        params![
+            user_id,
+            kind as i64,
+            to_json(before)?,
+            to_json(after)?,
+            DbDateTime::default()
+        ],
+    )?;
+    read_change(tx, user_id, tx.last_insert_rowid())
+}
+
+/// A change of the keeper's, on their clock.
+fn read_change(
+    tx: &rusqlite::Transaction,
+    user_id: u64,
+    change_id: i64,
+) -> Result<Change, BotError> {
+    let timezone = user_timezone(tx, user_id)?;
+    tx.query_row(
+        &format!("{} WHERE id = ? AND user_id = ?", CHANGE_SELECT),
+        params![change_id, user_id],
+        Change::from_row,
+    )
+    .optional()?
+    .map(|change| change.in_timezone(timezone))
+    .ok_or_else(|| BotError::NotFound(format!("Change with id {} not found", change_id)))
+}
+
+/// The parameters `?1` to `?7` of the health and molt history queries.
+fn filter_params(user_id: u64, filter: &RecordFilter) -> impl rusqlite::Params {
+    (
+        user_id,
+        filter.tarantula_id,
+        filter.from,
+        filter.to,
+        filter.status_id,
+        filter.limit,
+        filter.offset,
+    )
+}
+
+fn lineage_node(row: &Row) -> rusqlite::Result<LineageNode> {
+    let role: String = row.get(3)?;
+    Ok(LineageNode {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        relative_id: row.get(2)?,
+        role: if role == "Mother" {
+            ParentRole::Mother
+        } else {
+            ParentRole::Father
+        },
+        depth: row.get(4)?,
+    })
 }
 
-fn transactionally<T>(
+pub(super) fn transactionally<T>(
     conn: &mut rusqlite::Connection,
     f: impl FnOnce(&rusqlite::Transaction) -> Result<T, BotError>,
 ) -> Result<T, BotError> {
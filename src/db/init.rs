@@ -1,15 +1,53 @@
-use crate::models::enums::{CricketSize, FeedingStatus, HealthStatus, MoltStage};
+use crate::models::enums::{
+    CricketSize, EggSacStatus, FeedingStatus, HealthStatus, MoltStage, PairingOutcome,
+};
 use crate::BotResult;
 use r2d2::Pool;
 use r2d2_sqlite::SqliteConnectionManager;
 use rusqlite::params;
 
+pub(crate) const CRICKET_SIZES: [CricketSize; 5] = [
+    CricketSize::Pinhead,
+    CricketSize::Small,
+    CricketSize::Medium,
+    CricketSize::Large,
+    CricketSize::Adult,
+];
+
+/// Seeded in this order, so the n-th entry gets id n.
+pub(crate) const FEEDING_FREQUENCIES: [(&str, i32, i32, &str); 11] = [
+    (
+        "3-4 times per week",
+        2,
+        3,
+        "Very frequent feeding for spiderlings",
+    ),
+    (
+        "2-3 times per week",
+        3,
+        4,
+        "Frequent feeding for spiderlings",
+    ),
+    ("Every 4-5 days", 4, 5, "Regular feeding for juveniles"),
+    ("Every 5-7 days", 5, 7, "Standard juvenile feeding"),
+    ("Every 7 days", 7, 7, "Weekly feeding"),
+    ("Every 7-10 days", 7, 10, "Extended weekly feeding"),
+    ("Every 10-14 days", 10, 14, "Bi-weekly feeding"),
+    ("Every 14 days", 14, 14, "Strict bi-weekly feeding"),
+    ("Every 14-21 days", 14, 21, "Extended bi-weekly feeding"),
+    ("Every 21-28 days", 21, 28, "Monthly feeding"),
+    ("Every 21-30 days", 21, 30, "Extended monthly feeding"),
+];
+
 pub fn fill_default_enums(pool: Pool<SqliteConnectionManager>) -> BotResult<()> {
     init_health_statuses(pool.clone())?;
     init_feeding_statuses(pool.clone())?;
     init_molt_stages(pool.clone())?;
     init_cricket_sizes(pool.clone())?;
-    init_feeding_frequencies(pool)?;
+    init_feeding_frequencies(pool.clone())?;
+    link_feeding_schedules(pool.clone())?;
+    init_pairing_outcomes(pool.clone())?;
+    init_egg_sac_statuses(pool)?;
     Ok(())
 }
 
@@ -71,15 +109,7 @@ fn init_molt_stages(conn: Pool<SqliteConnectionManager>) -> BotResult<()> {
 }
 
 fn init_cricket_sizes(conn: Pool<SqliteConnectionManager>) -> BotResult<()> {
-    let sizes = [
-        CricketSize::Pinhead,
-        CricketSize::Small,
-        CricketSize::Medium,
-        CricketSize::Large,
-        CricketSize::Adult,
-    ];
-
-    for size in sizes.iter() {
+    for size in CRICKET_SIZES.iter() {
         conn.get()?.execute(
             "INSERT OR IGNORE INTO cricket_size_types (id, size_name, approximate_length_mm)
                  VALUES (?, ?, ?)",
@@ -90,31 +120,7 @@ fn init_cricket_sizes(conn: Pool<SqliteConnectionManager>) -> BotResult<()> {
 }
 
 fn init_feeding_frequencies(conn: Pool<SqliteConnectionManager>) -> BotResult<()> {
-    let frequencies = [
-        (
-            "3-4 times per week",
-            2,
-            3,
-            "Very frequent feeding for spiderlings",
-        ),
-        (
-            "2-3 times per week",
-            3,
-            4,
-            "Frequent feeding for spiderlings",
-        ),
-        ("Every 4-5 days", 4, 5, "Regular feeding for juveniles"),
-        ("Every 5-7 days", 5, 7, "Standard juvenile feeding"),
-        ("Every 7 days", 7, 7, "Weekly feeding"),
-        ("Every 7-10 days", 7, 10, "Extended weekly feeding"),
-        ("Every 10-14 days", 10, 14, "Bi-weekly feeding"),
-        ("Every 14 days", 14, 14, "Strict bi-weekly feeding"),
-        ("Every 14-21 days", 14, 21, "Extended bi-weekly feeding"),
-        ("Every 21-28 days", 21, 28, "Monthly feeding"),
-        ("Every 21-30 days", 21, 30, "Extended monthly feeding"),
-    ];
-
-    for (name, min, max, desc) in frequencies.iter() {
+    for (name, min, max, desc) in FEEDING_FREQUENCIES.iter() {
         conn.get()?.execute(
             "INSERT OR IGNORE INTO feeding_frequencies (frequency_name, min_days, max_days, description)
              VALUES (?, ?, ?, ?)",
@@ -123,3 +129,53 @@ fn init_feeding_frequencies(conn: Pool<SqliteConnectionManager>) -> BotResult<()
     }
     Ok(())
 }
+
+/// The schema links schedules to frequencies before the frequencies are seeded,
+/// so fill in any links that are still missing.
+fn link_feeding_schedules(conn: Pool<SqliteConnectionManager>) -> BotResult<()> {
+    conn.get()?.execute(
+        "UPDATE feeding_schedules
+         SET frequency_id = (SELECT id
+                             FROM feeding_frequencies
+                             WHERE frequency_name = feeding_frequency)
+         WHERE frequency_id IS NULL",
+        [],
+    )?;
+    Ok(())
+}
+
+fn init_pairing_outcomes(conn: Pool<SqliteConnectionManager>) -> BotResult<()> {
+    let outcomes = [
+        PairingOutcome::Pending,
+        PairingOutcome::Successful,
+        PairingOutcome::NoInterest,
+        PairingOutcome::Aggression,
+        PairingOutcome::MaleKilled,
+    ];
+
+    for outcome in outcomes.iter() {
+        conn.get()?.execute(
+            "INSERT OR IGNORE INTO pairing_outcomes (id, outcome_name, description)
+                 VALUES (?, ?, ?)",
+            params![*outcome as i32, outcome.to_db_name(), outcome.description()],
+        )?;
+    }
+    Ok(())
+}
+
+fn init_egg_sac_statuses(conn: Pool<SqliteConnectionManager>) -> BotResult<()> {
+    let statuses = [
+        EggSacStatus::Incubating,
+        EggSacStatus::Pulled,
+        EggSacStatus::Failed,
+    ];
+
+    for status in statuses.iter() {
+        conn.get()?.execute(
+            "INSERT OR IGNORE INTO egg_sac_statuses (id, status_name, description)
+                 VALUES (?, ?, ?)",
+            params![*status as i32, status.to_db_name(), status.description()],
+        )?;
+    }
+    Ok(())
+}
@@ -0,0 +1,842 @@
+//! Operator tasks on the SQLite database that the bot itself never runs:
+//! housekeeping, reseeding the bundled species data, merging keepers who
+//! ended up with two accounts and moving one keeper's data between files.
+
+use crate::db::db::{transactionally, TarantulaDB};
+use crate::db::init::fill_default_enums;
+use crate::db::migrations::{self, MIGRATIONS, SPECIES, SPECIES_FEEDING};
+use crate::error::BotError;
+use crate::models::user::TelegramUser;
+use crate::BotResult;
+use chrono::Utc;
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{
+    params, params_from_iter, Connection, OpenFlags, OptionalExtension, Transaction,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+
+/// Bumped whenever [`UserExport`] changes in a way older readers can't follow.
+pub const EXPORT_FORMAT: u32 = 1;
+
+/// A table holding keepers' data, the columns that point at other such
+/// tables and the columns whose values are unique across all keepers. Listed
+/// so that every table comes after the ones it points at, except where a
+/// reference goes back to itself or forward.
+struct UserTable {
+    name: &'static str,
+    refs: &'static [(&'static str, &'static str)],
+    unique: &'static [&'static str],
+}
+
+const USER_TABLES: [UserTable; 12] = [
+    UserTable {
+        name: "enclosures",
+        refs: &[],
+        unique: &[],
+    },
+    UserTable {
+        name: "cricket_colonies",
+        refs: &[],
+        unique: &["container_number"],
+    },
+    UserTable {
+        name: "tarantula_groups",
+        refs: &[],
+        unique: &[],
+    },
+    UserTable {
+        name: "tarantulas",
+        refs: &[
+            ("enclosure_id", "enclosures"),
+            ("group_id", "tarantula_groups"),
+            ("mother_id", "tarantulas"),
+            ("father_id", "tarantulas"),
+            ("egg_sac_id", "egg_sacs"),
+        ],
+        unique: &["enclosure_number"],
+    },
+    UserTable {
+        name: "breeding_pairings",
+        refs: &[("female_id", "tarantulas"), ("male_id", "tarantulas")],
+        unique: &[],
+    },
+    UserTable {
+        name: "egg_sacs",
+        refs: &[("pairing_id", "breeding_pairings")],
+        unique: &[],
+    },
+    UserTable {
+        name: "feeding_events",
+        refs: &[
+            ("tarantula_id", "tarantulas"),
+            ("cricket_colony_id", "cricket_colonies"),
+        ],
+        unique: &[],
+    },
+    UserTable {
+        name: "health_check_records",
+        refs: &[("tarantula_id", "tarantulas")],
+        unique: &[],
+    },
+    UserTable {
+        name: "molt_records",
+        refs: &[("tarantula_id", "tarantulas")],
+        unique: &[],
+    },
+    UserTable {
+        name: "maintenance_records",
+        refs: &[("enclosure_id", "enclosures")],
+        unique: &[],
+    },
+    UserTable {
+        name: "feeding_overrides",
+        refs: &[("tarantula_id", "tarantulas")],
+        unique: &[],
+    },
+    UserTable {
+        name: "tarantula_photos",
+        refs: &[("tarantula_id", "tarantulas")],
+        unique: &[],
+    },
+];
+
+#[derive(Debug, Clone)]
+pub struct UserSummary {
+    pub telegram_id: i64,
+    pub username: Option<String>,
+    pub first_name: Option<String>,
+    pub last_active: Option<String>,
+    pub tarantulas: i64,
+    pub groups: i64,
+    pub colonies: i64,
+    pub crickets: i64,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct VacuumReport {
+    pub bytes_before: i64,
+    pub bytes_after: i64,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ReseedReport {
+    pub species: usize,
+    pub feeding_schedules: usize,
+}
+
+/// Everything one keeper owns. Rows keep their ids so the references between
+/// them can be followed; an import gives them new ones.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UserExport {
+    pub format: u32,
+    pub exported_at: String,
+    pub user: TelegramUser,
+    pub tables: BTreeMap<String, Vec<Map<String, Value>>>,
+}
+
+impl UserExport {
+    pub fn row_count(&self) -> usize {
+        self.tables.values().map(Vec::len).sum()
+    }
+}
+
+/// A reference that could only be filled in once the row it points at had
+/// been imported.
+struct PendingRef {
+    table: &'static str,
+    id: i64,
+    column: &'static str,
+    target: &'static str,
+    old_id: i64,
+}
+
+impl TarantulaDB {
+    /// Rebuilds the file to drop free pages. Returns its size around that.
+    pub fn vacuum(&self) -> BotResult<VacuumReport> {
+        let conn = self.conn()?;
+        let size = || -> BotResult<i64> {
+            Ok(conn.query_row(
+                "SELECT page_count * page_size FROM pragma_page_count(), pragma_page_size()",
+                [],
+                |row| row.get(0),
+            )?)
+        };
+        let bytes_before = size()?;
+        conn.execute_batch("VACUUM")?;
+        Ok(VacuumReport {
+            bytes_before,
+            bytes_after: size()?,
+        })
+    }
+
+    /// Runs SQLite's integrity and foreign key checks. An empty list means
+    /// both passed.
+    pub fn integrity_check(&self) -> BotResult<Vec<String>> {
+        integrity_problems(&*self.conn()?)
+    }
+
+    pub fn user_summaries(&self) -> BotResult<Vec<UserSummary>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT
+                u.telegram_id, u.username, u.first_name, u.last_active,
+                (SELECT count(*) FROM tarantulas t WHERE t.user_id = u.telegram_id),
+                (SELECT count(*) FROM tarantula_groups g WHERE g.user_id = u.telegram_id),
+                (SELECT count(*) FROM cricket_colonies c WHERE c.user_id = u.telegram_id),
+                (SELECT coalesce(sum(c.current_count), 0)
+                 FROM cricket_colonies c WHERE c.user_id = u.telegram_id)
+             FROM telegram_users u
+             ORDER BY u.telegram_id",
+        )?;
+        let summaries = stmt
+            .query_map([], |row| {
+                Ok(UserSummary {
+                    telegram_id: row.get(0)?,
+                    username: row.get(1)?,
+                    first_name: row.get(2)?,
+                    last_active: row.get(3)?,
+                    tarantulas: row.get(4)?,
+                    groups: row.get(5)?,
+                    colonies: row.get(6)?,
+                    crickets: row.get(7)?,
+                })
+            })?
+            .collect::<Result<_, _>>()?;
+        Ok(summaries)
+    }
+
+    /// Brings species and feeding schedules back in line with the bundled
+    /// scripts. Species are updated in place, so tarantulas keep theirs.
+    pub fn reseed(&self) -> BotResult<ReseedReport> {
+        let mut conn = self.conn()?;
+        let report = transactionally(&mut conn, |tx| {
+            // The scripts write to unqualified names, which resolve to a temp
+            // table before the real one.
+            tx.execute_batch(
+                "CREATE TEMP TABLE tarantula_species AS
+                 SELECT * FROM main.tarantula_species WHERE false",
+            )?;
+            tx.execute_batch(SPECIES)?;
+            let species = tx.execute(
+                "INSERT INTO main.tarantula_species (
+                    id, scientific_name, common_name, adult_size_cm, temperament,
+                    humidity_requirement_percent, temperature_requirement_celsius
+                 )
+                 SELECT id, scientific_name, common_name, adult_size_cm, temperament,
+                        humidity_requirement_percent, temperature_requirement_celsius
+                 FROM temp.tarantula_species WHERE true
+                 ON CONFLICT(id) DO UPDATE SET
+                    scientific_name = excluded.scientific_name,
+                    common_name = excluded.common_name,
+                    adult_size_cm = excluded.adult_size_cm,
+                    temperament = excluded.temperament,
+                    humidity_requirement_percent = excluded.humidity_requirement_percent,
+                    temperature_requirement_celsius = excluded.temperature_requirement_celsius",
+                [],
+            )?;
+            tx.execute_batch("DROP TABLE temp.tarantula_species")?;
+
+            tx.execute_batch(SPECIES_FEEDING)?;
+            let feeding_schedules: i64 =
+                tx.query_row("SELECT count(*) FROM feeding_schedules", [], |row| {
+                    row.get(0)
+                })?;
+            Ok(ReseedReport {
+                species,
+                feeding_schedules: feeding_schedules as usize,
+            })
+        })?;
+        fill_default_enums(self.pool.clone())?;
+        Ok(report)
+    }
+
+    /// Moves everything `from` owns over to `into` and removes `from`.
+    /// Returns how many rows moved per table.
+    pub fn merge_users(&self, from: u64, into: u64) -> BotResult<Vec<(&'static str, usize)>> {
+        if from == into {
+            return Err(BotError::ValidationError(
+                "Cannot merge a user into themselves".to_string(),
+            ));
+        }
+        let mut conn = self.conn()?;
+        transactionally(&mut conn, |tx| {
+            require_user(tx, from)?;
+            require_user(tx, into)?;
+
+            let mut moved = Vec::new();
+            for table in &USER_TABLES {
+                let rows = tx.execute(
+                    &format!("UPDATE {} SET user_id = ?1 WHERE user_id = ?2", table.name),
+                    params![into, from],
+                )?;
+                moved.push((table.name, rows));
+            }
+            // The audit log isn't exported, as the ids it holds change on
+            // import, but it follows its keeper through a merge.
+            let rows = tx.execute(
+                "UPDATE audit_log SET user_id = ?1 WHERE user_id = ?2",
+                params![into, from],
+            )?;
+            moved.push(("audit_log", rows));
+            tx.execute(
+                "UPDATE telegram_users
+                 SET last_active = max(coalesce(last_active, ''),
+                                       (SELECT coalesce(last_active, '')
+                                        FROM telegram_users WHERE telegram_id = ?2))
+                 WHERE telegram_id = ?1",
+                params![into, from],
+            )?;
+            tx.execute(
+                "DELETE FROM telegram_users WHERE telegram_id = ?1",
+                params![from],
+            )?;
+            Ok(moved)
+        })
+    }
+
+    pub fn export_user(&self, user_id: u64) -> BotResult<UserExport> {
+        let mut conn = self.conn()?;
+        transactionally(&mut conn, |tx| {
+            let user = tx
+                .query_row(
+                    "SELECT telegram_id, username, first_name, last_name
+                     FROM telegram_users WHERE telegram_id = ?1",
+                    params![user_id],
+                    |row| {
+                        Ok(TelegramUser {
+                            telegram_id: row.get(0)?,
+                            username: row.get(1)?,
+                            first_name: row.get::<_, Option<String>>(2)?.unwrap_or_default(),
+                            last_name: row.get(3)?,
+                        })
+                    },
+                )
+                .optional()?
+                .ok_or_else(|| BotError::NotFound(format!("User {}", user_id)))?;
+
+            let mut tables = BTreeMap::new();
+            for table in &USER_TABLES {
+                let mut stmt = tx.prepare(&format!(
+                    "SELECT * FROM {} WHERE user_id = ?1 ORDER BY id",
+                    table.name
+                ))?;
+                let columns: Vec<String> =
+                    stmt.column_names().into_iter().map(String::from).collect();
+                let mut rows = stmt.query(params![user_id])?;
+                let mut exported = Vec::new();
+                while let Some(row) = rows.next()? {
+                    let mut fields = Map::new();
+                    for (i, column) in columns.iter().enumerate() {
+                        if column == "user_id" {
+                            continue;
+                        }
+                        fields.insert(column.clone(), to_json(row.get(i)?, table.name)?);
+                    }
+                    exported.push(fields);
+                }
+                tables.insert(table.name.to_string(), exported);
+            }
+
+            Ok(UserExport {
+                format: EXPORT_FORMAT,
+                exported_at: Utc::now().to_rfc3339(),
+                user,
+                tables,
+            })
+        })
+    }
+
+    /// Adds an export to the database as `into`, or as the exported user.
+    /// The rows get new ids, but enclosure and container numbers are unique
+    /// across all keepers, so an export using them can't go back into the
+    /// file it came from while they are still there. The receiving user must
+    /// not have any data yet. Returns how many rows went into each table.
+    pub fn import_user(
+        &self,
+        export: &UserExport,
+        into: Option<u64>,
+    ) -> BotResult<Vec<(&'static str, usize)>> {
+        if export.format != EXPORT_FORMAT {
+            return Err(BotError::ValidationError(format!(
+                "Export format {} is not supported, expected {}",
+                export.format, EXPORT_FORMAT
+            )));
+        }
+        if let Some(unknown) = export
+            .tables
+            .keys()
+            .find(|name| !USER_TABLES.iter().any(|t| t.name == name.as_str()))
+        {
+            return Err(BotError::ValidationError(format!(
+                "Export has unknown table {}",
+                unknown
+            )));
+        }
+        let user_id = into.unwrap_or(export.user.telegram_id);
+
+        let mut conn = self.conn()?;
+        transactionally(&mut conn, |tx| {
+            tx.execute(
+                "INSERT INTO telegram_users (telegram_id, username, first_name, last_name)
+                 VALUES (?1, ?2, ?3, ?4)
+                 ON CONFLICT(telegram_id) DO NOTHING",
+                params![
+                    user_id,
+                    export.user.username,
+                    export.user.first_name,
+                    export.user.last_name
+                ],
+            )?;
+            for table in &USER_TABLES {
+                let existing: i64 = tx.query_row(
+                    &format!("SELECT count(*) FROM {} WHERE user_id = ?1", table.name),
+                    params![user_id],
+                    |row| row.get(0),
+                )?;
+                if existing > 0 {
+                    return Err(BotError::ValidationError(format!(
+                        "User {} already has {} rows in {}, merge users instead",
+                        user_id, existing, table.name
+                    )));
+                }
+            }
+
+            let mut new_ids: HashMap<&str, HashMap<i64, i64>> = HashMap::new();
+            let mut pending = Vec::new();
+            let mut imported = Vec::new();
+            for table in &USER_TABLES {
+                let rows = export.tables.get(table.name).map_or(&[][..], Vec::as_slice);
+                let known = table_columns(tx, table.name)?;
+                for row in rows {
+                    let old_id = row.get("id").and_then(Value::as_i64).ok_or_else(|| {
+                        BotError::ValidationError(format!("A {} row has no id", table.name))
+                    })?;
+
+                    let mut columns = vec!["user_id".to_string()];
+                    let mut values = vec![SqlValue::Integer(user_id as i64)];
+                    let mut deferred = Vec::new();
+                    for (column, value) in row {
+                        if column == "id" || column == "user_id" {
+                            continue;
+                        }
+                        if !known.contains(column) {
+                            return Err(BotError::ValidationError(format!(
+                                "Export has unknown column {}.{}",
+                                table.name, column
+                            )));
+                        }
+                        let mut value = to_sql(value, table.name, column)?;
+                        if table.unique.contains(&column.as_str()) && value != SqlValue::Null {
+                            let taken = tx
+                                .query_row(
+                                    &format!("SELECT 1 FROM {} WHERE {} = ?1", table.name, column),
+                                    [&value],
+                                    |_| Ok(()),
+                                )
+                                .optional()?
+                                .is_some();
+                            if taken {
+                                return Err(BotError::ValidationError(format!(
+                                    "{}.{} {} is already taken in this database",
+                                    table.name, column, row[column]
+                                )));
+                            }
+                        }
+                        if let Some(&(column, target)) =
+                            table.refs.iter().find(|(name, _)| name == column)
+                        {
+                            if let SqlValue::Integer(old_ref) = value {
+                                match new_ids.get(target).and_then(|ids| ids.get(&old_ref)) {
+                                    Some(&new_ref) => value = SqlValue::Integer(new_ref),
+                                    None => {
+                                        deferred.push((column, target, old_ref));
+                                        value = SqlValue::Null;
+                                    }
+                                }
+                            }
+                        }
+                        columns.push(column.clone());
+                        values.push(value);
+                    }
+
+                    let placeholders = vec!["?"; columns.len()].join(", ");
+                    tx.execute(
+                        &format!(
+                            "INSERT INTO {} ({}) VALUES ({})",
+                            table.name,
+                            columns.join(", "),
+                            placeholders
+                        ),
+                        params_from_iter(values),
+                    )?;
+                    let id = tx.last_insert_rowid();
+                    new_ids.entry(table.name).or_default().insert(old_id, id);
+                    pending.extend(deferred.into_iter().map(|(column, target, old_id)| {
+                        PendingRef {
+                            table: table.name,
+                            id,
+                            column,
+                            target,
+                            old_id,
+                        }
+                    }));
+                }
+                imported.push((table.name, rows.len()));
+            }
+
+            for r in pending {
+                let new_ref = new_ids
+                    .get(r.target)
+                    .and_then(|ids| ids.get(&r.old_id))
+                    .ok_or_else(|| {
+                        BotError::ValidationError(format!(
+                            "{}.{} points at {} {}, which is not in the export",
+                            r.table, r.column, r.target, r.old_id
+                        ))
+                    })?;
+                tx.execute(
+                    &format!("UPDATE {} SET {} = ?1 WHERE id = ?2", r.table, r.column),
+                    params![new_ref, r.id],
+                )?;
+            }
+            Ok(imported)
+        })
+    }
+}
+
+/// Checks that `path` holds a sound spider-bot database that this version
+/// can read, before it replaces the live one.
+pub(crate) fn verify_backup(path: &Path) -> BotResult<()> {
+    let not_usable = |reason: String| BotError::ValidationError(format!("The backup {}", reason));
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(|e| not_usable(format!("can't be opened: {}", e)))?;
+    let problems =
+        integrity_problems(&conn).map_err(|e| not_usable(format!("can't be read: {}", e)))?;
+    if let Some(problem) = problems.first() {
+        return Err(not_usable(format!(
+            "failed the integrity check: {}",
+            problem
+        )));
+    }
+
+    let has_collection: i64 = conn.query_row(
+        "SELECT count(*) FROM sqlite_master WHERE type = 'table' AND name = 'tarantulas'",
+        [],
+        |row| row.get(0),
+    )?;
+    if has_collection == 0 {
+        return Err(not_usable("is not a spider-bot database".to_string()));
+    }
+    let version = migrations::schema_version(&conn)?;
+    if version > MIGRATIONS.len() as u32 {
+        return Err(not_usable(format!(
+            "is from a newer version of the bot (schema {})",
+            version
+        )));
+    }
+    Ok(())
+}
+
+fn integrity_problems(conn: &Connection) -> BotResult<Vec<String>> {
+    let mut problems = Vec::new();
+
+    let mut stmt = conn.prepare("PRAGMA integrity_check")?;
+    for line in stmt.query_map([], |row| row.get::<_, String>(0))? {
+        let line = line?;
+        if line != "ok" {
+            problems.push(line);
+        }
+    }
+
+    let mut stmt = conn.prepare("PRAGMA foreign_key_check")?;
+    let violations = stmt.query_map([], |row| {
+        Ok(format!(
+            "{} row {} points at a missing {} row",
+            row.get::<_, String>(0)?,
+            row.get::<_, Option<i64>>(1)?.unwrap_or_default(),
+            row.get::<_, String>(2)?
+        ))
+    })?;
+    for violation in violations {
+        problems.push(violation?);
+    }
+    Ok(problems)
+}
+
+fn require_user(tx: &Transaction, user_id: u64) -> BotResult<()> {
+    tx.query_row(
+        "SELECT 1 FROM telegram_users WHERE telegram_id = ?1",
+        params![user_id],
+        |_| Ok(()),
+    )
+    .optional()?
+    .ok_or_else(|| BotError::NotFound(format!("User {}", user_id)))
+}
+
+fn table_columns(tx: &Transaction, table: &str) -> BotResult<HashSet<String>> {
+    let mut stmt = tx.prepare("SELECT name FROM pragma_table_info(?1)")?;
+    let columns = stmt
+        .query_map(params![table], |row| row.get(0))?
+        .collect::<Result<_, _>>()?;
+    Ok(columns)
+}
+
+fn to_json(value: SqlValue, table: &str) -> BotResult<Value> {
+    Ok(match value {
+        SqlValue::Null => Value::Null,
+        SqlValue::Integer(i) => Value::from(i),
+        SqlValue::Real(f) => Value::from(f),
+        SqlValue::Text(s) => Value::String(s),
+        SqlValue::Blob(_) => {
+            return Err(BotError::OperationError(format!(
+                "{} holds binary data, which exports don't support",
+                table
+            )))
+        }
+    })
+}
+
+fn to_sql(value: &Value, table: &str, column: &str) -> BotResult<SqlValue> {
+    Ok(match value {
+        Value::Null => SqlValue::Null,
+        Value::Bool(b) => SqlValue::Integer(i64::from(*b)),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => SqlValue::Integer(i),
+            None => SqlValue::Real(n.as_f64().unwrap_or_default()),
+        },
+        Value::String(s) => SqlValue::Text(s.clone()),
+        Value::Array(_) | Value::Object(_) => {
+            return Err(BotError::ValidationError(format!(
+                "{}.{} must be a plain value",
+                table, column
+            )))
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::db::{
+        AddColonyParams, AddEggSacParams, AddPairingParams, AddTarantulaParams,
+        TarantulaOperations,
+    };
+    use chrono::NaiveDate;
+    use tempfile::TempDir;
+
+    const ALICE: u64 = 1;
+    const BOB: u64 = 2;
+
+    fn migrated_db() -> (TarantulaDB, TempDir) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tarantulas.sqlite");
+        migrations::migrate(&mut rusqlite::Connection::open(&path).unwrap()).unwrap();
+        (TarantulaDB::new(path.to_str().unwrap()).unwrap(), dir)
+    }
+
+    async fn add_user(db: &TarantulaDB, telegram_id: u64, name: &str) {
+        db.ensure_user_exists(&TelegramUser {
+            telegram_id,
+            username: None,
+            first_name: name.to_string(),
+            last_name: None,
+        })
+        .await
+        .unwrap();
+    }
+
+    async fn add_tarantula(db: &TarantulaDB, user_id: u64, name: &str) -> i64 {
+        db.add_tarantula(
+            user_id,
+            AddTarantulaParams {
+                name: name.to_string(),
+                species_id: 1,
+                acquisition_date: "2024-01-01".to_string(),
+                estimated_age_months: 12,
+                enclosure_number: None,
+                notes: None,
+            },
+        )
+        .await
+        .unwrap();
+        db.get_all_tarantulas(user_id)
+            .await
+            .unwrap()
+            .into_iter()
+            .find(|t| t.name == name)
+            .unwrap()
+            .id
+    }
+
+    /// A pair with two slings from their egg sac, which puts the slings in a
+    /// group and gives tarantulas references in both directions.
+    async fn add_family(db: &TarantulaDB, user_id: u64) {
+        let mother = add_tarantula(db, user_id, "Rosie").await;
+        let father = add_tarantula(db, user_id, "Boris").await;
+        let pairing_id = db
+            .record_pairing(
+                user_id,
+                AddPairingParams {
+                    female_id: mother,
+                    male_id: father,
+                    pairing_date: NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+                    notes: None,
+                },
+            )
+            .await
+            .unwrap();
+        let egg_sac_id = db
+            .record_egg_sac(
+                user_id,
+                AddEggSacParams {
+                    pairing_id,
+                    laid_date: NaiveDate::from_ymd_opt(2024, 5, 1).unwrap(),
+                    expected_pull_date: None,
+                    notes: None,
+                },
+            )
+            .await
+            .unwrap();
+        db.create_slings_from_egg_sac(user_id, egg_sac_id, 2, "Sling")
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn merging_moves_the_collection_and_drops_the_duplicate() {
+        let (db, _dir) = migrated_db();
+        add_user(&db, ALICE, "Alice").await;
+        add_user(&db, BOB, "Alice again").await;
+        add_family(&db, BOB).await;
+
+        let moved = db.merge_users(BOB, ALICE).unwrap();
+        assert!(moved.contains(&("tarantulas", 4)));
+
+        let users = db.user_summaries().unwrap();
+        assert_eq!(users.len(), 1);
+        assert_eq!(users[0].telegram_id, ALICE as i64);
+        assert_eq!(users[0].tarantulas, 4);
+        assert_eq!(users[0].groups, 1);
+        assert!(matches!(
+            db.merge_users(BOB, ALICE),
+            Err(BotError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn an_export_imports_as_another_user_with_its_references() {
+        let (db, _dir) = migrated_db();
+        add_user(&db, ALICE, "Alice").await;
+        add_family(&db, ALICE).await;
+
+        let export = db.export_user(ALICE).unwrap();
+        let json = serde_json::to_string(&export).unwrap();
+        let export: UserExport = serde_json::from_str(&json).unwrap();
+        db.import_user(&export, Some(BOB)).unwrap();
+
+        let sling = db
+            .get_all_tarantulas(BOB)
+            .await
+            .unwrap()
+            .into_iter()
+            .find(|t| t.name == "Sling #1")
+            .unwrap();
+        let ancestors = db.get_ancestors(BOB, sling.id, 1).await.unwrap();
+        let mut names: Vec<_> = ancestors.iter().map(|a| a.name.clone()).collect();
+        names.sort();
+        assert_eq!(names, ["Boris", "Rosie"]);
+        assert!(sling.group_id.is_some());
+        let slings_of_bobs_sac: i64 = db
+            .conn()
+            .unwrap()
+            .query_row(
+                "SELECT count(*) FROM tarantulas t JOIN egg_sacs e ON e.id = t.egg_sac_id
+                 WHERE t.user_id = ?1 AND e.user_id = ?1",
+                params![BOB],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(slings_of_bobs_sac, 2);
+        assert!(db.integrity_check().unwrap().is_empty());
+
+        assert!(matches!(
+            db.import_user(&export, Some(BOB)),
+            Err(BotError::ValidationError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn enclosure_and_container_numbers_stay_unique_across_keepers() {
+        let (db, _dir) = migrated_db();
+        add_user(&db, ALICE, "Alice").await;
+        db.add_tarantula(
+            ALICE,
+            AddTarantulaParams {
+                name: "Rosie".to_string(),
+                species_id: 1,
+                acquisition_date: "2024-01-01".to_string(),
+                estimated_age_months: 12,
+                enclosure_number: Some("A1".to_string()),
+                notes: None,
+            },
+        )
+        .await
+        .unwrap();
+        db.add_colony(
+            ALICE,
+            AddColonyParams {
+                colony_name: "Smalls".to_string(),
+                size_type_id: 1,
+                current_count: 50,
+                container_number: "C1".to_string(),
+                notes: None,
+            },
+        )
+        .await
+        .unwrap();
+        let export = db.export_user(ALICE).unwrap();
+
+        let refused = db.import_user(&export, Some(BOB));
+        assert!(
+            matches!(&refused, Err(BotError::ValidationError(e)) if e.contains("C1")),
+            "{:?}",
+            refused
+        );
+        assert!(db
+            .user_summaries()
+            .unwrap()
+            .iter()
+            .all(|u| u.telegram_id != BOB as i64));
+
+        let (other, _other_dir) = migrated_db();
+        other.import_user(&export, Some(BOB)).unwrap();
+        let rosie = &other.get_all_tarantulas(BOB).await.unwrap()[0];
+        assert_eq!(rosie.enclosure_number.as_deref(), Some("A1"));
+        let colonies = other.get_colony_status(BOB).await.unwrap();
+        assert_eq!(colonies[0].colony_name, "Smalls");
+    }
+
+    #[tokio::test]
+    async fn reseeding_keeps_species_ids_and_replaces_schedules() {
+        let (db, _dir) = migrated_db();
+        add_user(&db, ALICE, "Alice").await;
+        add_tarantula(&db, ALICE, "Rosie").await;
+        db.conn()
+            .unwrap()
+            .execute_batch(
+                "UPDATE tarantula_species SET common_name = 'typo' WHERE id = 1;
+                 DELETE FROM feeding_schedules WHERE species_id = 1;",
+            )
+            .unwrap();
+
+        let report = db.reseed().unwrap();
+        assert_eq!(report.species, 50);
+
+        let rosie = &db.get_all_tarantulas(ALICE).await.unwrap()[0];
+        assert_eq!(rosie.species_name, "Mexican Red Knee");
+        assert!(db.get_feeding_schedule(1, 3.0).await.unwrap().is_some());
+    }
+}
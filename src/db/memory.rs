@@ -0,0 +1,2368 @@
+//! In-memory [`TarantulaOperations`] backend for tests and local development.
+//!
+//! Mirrors [`TarantulaDB`](crate::db::db::TarantulaDB) including its validations
+//! and error messages. Nothing is persisted, and the species catalog is a small
+//! excerpt of the SQL seed rather than the full list.
+
+use crate::db::db::{
+    AddColonyParams, AddEggSacParams, AddPairingParams, AddTarantulaParams, CreateGroupParams,
+    RecordFilter, SetFeedingOverrideParams, TarantulaOperations,
+};
+use crate::db::init::{CRICKET_SIZES, FEEDING_FREQUENCIES};
+use crate::error::BotError;
+use crate::models::audit::{Change, ChangeKind, ChangeState, TarantulaState};
+use crate::models::breeding::{EggSacCounts, EggSacRecord, PairingRecord, DEFAULT_INCUBATION_DAYS};
+use crate::models::cricket::ColonyStatus;
+use crate::models::enums::{
+    CricketSize, EggSacStatus, FeedingStatus, HealthStatus, MoltStage, PairingOutcome,
+};
+use crate::models::feeding::{FeedingEvent, FeedingOverride, FeedingRecord};
+use crate::models::group::{GroupSummary, MAX_GROUP_SIZE};
+use crate::models::health::HealthRecord;
+use crate::models::import::{ImportPlan, ImportTarget};
+use crate::models::lineage::{LineageNode, Parent, ParentRole};
+use crate::models::models::DbDateTime;
+use crate::models::molt::MoltRecord;
+use crate::models::new::{Enclosure, FeedingFrequency, FeedingSchedule, MaintenanceRecord};
+use crate::models::tarantula::{
+    split_aliases, Tarantula, TarantulaField, TarantulaListItem, TarantulaPhoto, TarantulaSpecies,
+};
+use crate::models::user::{TelegramUser, Timezone};
+use crate::schedule::{AlertThresholds, ScheduleBand, TarantulaFacts};
+use crate::BotResult;
+use async_trait::async_trait;
+use chrono::{Duration, NaiveDate, NaiveDateTime, Timelike, Utc};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Mutex, MutexGuard};
+
+const DATETIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// (id, scientific name, common name, adult size in cm), as in infra/sql/0002_species.sql.
+const SPECIES: [(i64, &str, &str, f32); 3] = [
+    (1, "Brachypelma hamorii", "Mexican Red Knee", 14.0),
+    (8, "Grammostola rosea", "Chilean Rose", 12.0),
+    (22, "Pterinochilus murinus", "Orange Baboon", 13.0),
+];
+
+/// (species id, size category, body length, prey size, frequency, prey type, notes),
+/// as in infra/sql/0003_species_feeding.sql.
+#[rustfmt::skip]
+const FEEDING_SCHEDULES: [(i64, &str, f32, &str, &str, &str, &str); 12] = [
+    (1, "Spiderling", 0.5, "Pre-killed pinhead cricket", "2-3 times per week", "Pinhead crickets", "Very delicate at this stage; ensure food size is no larger than carapace"),
+    (1, "Juvenile", 3.0, "2-3 small crickets", "Every 5-7 days", "Small crickets, small roaches", "Good eater at this stage; watch for premolt signs"),
+    (1, "Sub-Adult", 8.0, "2-3 medium crickets", "Every 10-14 days", "Medium crickets, medium roaches", "May fast before molting; ensure proper humidity"),
+    (1, "Adult", 14.0, "3-4 large crickets", "Every 14-21 days", "Large crickets, adult roaches", "Adjust feeding based on abdomen size; may refuse food during breeding season"),
+    (8, "Spiderling", 0.5, "Pre-killed pinhead cricket", "2-3 times per week", "Pinhead crickets", "Slow growing species"),
+    (8, "Juvenile", 3.0, "1-2 small crickets", "Every 7-10 days", "Small crickets, small roaches", "May have irregular feeding patterns"),
+    (8, "Sub-Adult", 7.0, "2 medium crickets", "Every 14-21 days", "Medium crickets, medium roaches", "Known for fasting periods"),
+    (8, "Adult", 12.0, "2-3 large crickets", "Every 21-30 days", "Large crickets, adult roaches", "Famous for long fasting periods; dont worry if refusing food"),
+    (22, "Spiderling", 0.5, "Pre-killed pinhead cricket", "2-3 times per week", "Pinhead crickets", "Fast growing; defensive from early age"),
+    (22, "Juvenile", 3.0, "2 small crickets", "Every 5-7 days", "Small crickets, small roaches", "Use long tongs; ensure clear retreat path"),
+    (22, "Sub-Adult", 7.0, "2-3 medium crickets", "Every 7-10 days", "Medium crickets, medium roaches", "Extremely defensive; careful during maintenance"),
+    (22, "Adult", 13.0, "2-3 medium crickets", "Every 14 days", "Medium crickets, adult roaches", "Feed with extreme caution; best fed at night"),
+];
+
+struct Species {
+    scientific_name: String,
+    common_name: String,
+    adult_size_cm: f32,
+}
+
+struct TarantulaRow {
+    user_id: u64,
+    name: String,
+    species_id: i64,
+    acquisition_date: NaiveDate,
+    last_molt_date: Option<NaiveDate>,
+    estimated_age_months: Option<i32>,
+    molt_stage_id: Option<i64>,
+    health_status_id: Option<i64>,
+    last_health_check_date: Option<NaiveDate>,
+    enclosure_number: Option<String>,
+    enclosure_id: Option<i64>,
+    notes: Option<String>,
+    aliases: Option<String>,
+    group_id: Option<i64>,
+    egg_sac_id: Option<i64>,
+    mother_id: Option<i64>,
+    father_id: Option<i64>,
+    mother_external: Option<String>,
+    father_external: Option<String>,
+}
+
+impl TarantulaRow {
+    fn new(user_id: u64, name: String, species_id: i64, acquisition_date: NaiveDate) -> Self {
+        Self {
+            user_id,
+            name,
+            species_id,
+            acquisition_date,
+            last_molt_date: None,
+            estimated_age_months: None,
+            molt_stage_id: None,
+            health_status_id: None,
+            last_health_check_date: None,
+            enclosure_number: None,
+            enclosure_id: None,
+            notes: None,
+            aliases: None,
+            group_id: None,
+            egg_sac_id: None,
+            mother_id: None,
+            father_id: None,
+            mother_external: None,
+            father_external: None,
+        }
+    }
+
+    fn state(&self) -> TarantulaState {
+        TarantulaState {
+            molt_stage_id: self.molt_stage_id,
+            last_molt_date: self.last_molt_date,
+            health_status_id: self.health_status_id,
+            last_health_check_date: self.last_health_check_date,
+        }
+    }
+
+    fn set_state(&mut self, state: TarantulaState) {
+        self.molt_stage_id = state.molt_stage_id;
+        self.last_molt_date = state.last_molt_date;
+        self.health_status_id = state.health_status_id;
+        self.last_health_check_date = state.last_health_check_date;
+    }
+
+    /// Takes a check on `day` as the current status unless a later one is
+    /// logged.
+    fn checked(&mut self, status: HealthStatus, day: NaiveDate) {
+        if self.last_health_check_date.is_none_or(|last| last <= day) {
+            self.health_status_id = Some(status as i64);
+        }
+        self.last_health_check_date = self.last_health_check_date.max(Some(day));
+    }
+
+    /// Takes a molt on `day` as the current stage unless a later one is logged.
+    fn molted(&mut self, day: NaiveDate) {
+        if self.last_molt_date.is_none_or(|last| last <= day) {
+            self.molt_stage_id = Some(MoltStage::PostMolt as i64);
+        }
+        self.last_molt_date = self.last_molt_date.max(Some(day));
+    }
+}
+
+struct FeedingRow {
+    tarantula_id: i64,
+    feeding_date: NaiveDateTime,
+    colony_id: Option<i64>,
+    number_of_crickets: i32,
+    status: FeedingStatus,
+    notes: Option<String>,
+}
+
+struct HealthCheckRow {
+    tarantula_id: i64,
+    check_date: NaiveDate,
+    status: HealthStatus,
+    weight_grams: Option<f32>,
+    humidity_percent: Option<i32>,
+    temperature_celsius: Option<f32>,
+    notes: Option<String>,
+}
+
+struct MoltRow {
+    tarantula_id: i64,
+    molt_date: NaiveDate,
+    stage: MoltStage,
+    post_molt_length_cm: Option<f32>,
+    complications: Option<String>,
+    notes: Option<String>,
+}
+
+struct ColonyRow {
+    user_id: u64,
+    colony_name: String,
+    size_type_id: i64,
+    current_count: i32,
+    container_number: String,
+}
+
+struct PairingRow {
+    user_id: u64,
+    female_id: i64,
+    male_id: i64,
+    pairing_date: NaiveDate,
+    outcome: PairingOutcome,
+    notes: Option<String>,
+}
+
+struct EggSacRow {
+    user_id: u64,
+    pairing_id: i64,
+    laid_date: NaiveDate,
+    expected_pull_date: Option<NaiveDate>,
+    pulled_date: Option<NaiveDate>,
+    status: EggSacStatus,
+    egg_count: Option<i32>,
+    nymph_count: Option<i32>,
+    sling_count: Option<i32>,
+    notes: Option<String>,
+}
+
+struct GroupRow {
+    user_id: u64,
+    name: String,
+    species_id: i64,
+    acquisition_date: NaiveDate,
+    notes: Option<String>,
+}
+
+struct PhotoRow {
+    user_id: u64,
+    tarantula_id: i64,
+    file_id: String,
+    caption: Option<String>,
+    taken_at: NaiveDateTime,
+}
+
+struct ChangeRow {
+    user_id: u64,
+    kind: ChangeKind,
+    before: ChangeState,
+    after: ChangeState,
+    changed_at: NaiveDateTime,
+    undone_at: Option<NaiveDateTime>,
+}
+
+impl ChangeRow {
+    fn change(&self, id: i64, timezone: Timezone) -> Change {
+        Change {
+            id,
+            kind: self.kind,
+            before: self.before.clone(),
+            after: self.after.clone(),
+            changed_at: self.changed_at,
+            undone_at: self.undone_at,
+        }
+        .in_timezone(timezone)
+    }
+}
+
+struct OverrideRow {
+    user_id: u64,
+    frequency_id: Option<i64>,
+    prey_count: Option<i32>,
+    prey_size_id: Option<i64>,
+    expires_on: Option<NaiveDate>,
+    notes: Option<String>,
+}
+
+/// Rows keyed by an auto-incrementing id, like a SQLite rowid table.
+struct Table<T> {
+    rows: BTreeMap<i64, T>,
+    last_id: i64,
+}
+
+impl<T> Default for Table<T> {
+    fn default() -> Self {
+        Self {
+            rows: BTreeMap::new(),
+            last_id: 0,
+        }
+    }
+}
+
+impl<T> Table<T> {
+    fn insert(&mut self, row: T) -> i64 {
+        self.last_id += 1;
+        self.rows.insert(self.last_id, row);
+        self.last_id
+    }
+
+    fn get(&self, id: i64) -> Option<&T> {
+        self.rows.get(&id)
+    }
+
+    fn get_mut(&mut self, id: i64) -> Option<&mut T> {
+        self.rows.get_mut(&id)
+    }
+
+    fn remove(&mut self, id: i64) -> Option<T> {
+        self.rows.remove(&id)
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (i64, &T)> {
+        self.rows.iter().map(|(id, row)| (*id, row))
+    }
+}
+
+#[derive(Default)]
+struct State {
+    species: BTreeMap<i64, Species>,
+    schedules: Table<FeedingSchedule>,
+    frequencies: Table<FeedingFrequency>,
+    users: HashMap<u64, TelegramUser>,
+    timezones: HashMap<u64, Timezone>,
+    tarantulas: Table<TarantulaRow>,
+    feedings: Table<FeedingRow>,
+    health_checks: Table<HealthCheckRow>,
+    molts: Table<MoltRow>,
+    colonies: Table<ColonyRow>,
+    maintenance: Table<MaintenanceRecord>,
+    enclosures: Table<Enclosure>,
+    pairings: Table<PairingRow>,
+    egg_sacs: Table<EggSacRow>,
+    groups: Table<GroupRow>,
+    overrides: HashMap<i64, OverrideRow>,
+    photos: Table<PhotoRow>,
+    changes: Table<ChangeRow>,
+}
+
+impl State {
+    /// Rows reference `telegram_users`, so the user has to be registered first.
+    fn check_user(&self, user_id: u64) -> BotResult<()> {
+        if self.users.contains_key(&user_id) {
+            Ok(())
+        } else {
+            Err(foreign_key_violation())
+        }
+    }
+
+    fn timezone(&self, user_id: u64) -> Timezone {
+        self.timezones.get(&user_id).copied().unwrap_or_default()
+    }
+
+    fn check_species(&self, species_id: i64) -> BotResult<()> {
+        if self.species.contains_key(&species_id) {
+            Ok(())
+        } else {
+            Err(foreign_key_violation())
+        }
+    }
+
+    fn owned_tarantula(&self, id: i64, user_id: u64) -> BotResult<&TarantulaRow> {
+        self.tarantulas
+            .get(id)
+            .filter(|t| t.user_id == user_id)
+            .ok_or_else(|| {
+                BotError::NotFound(format!(
+                    "Tarantula with id {} not found or access denied",
+                    id
+                ))
+            })
+    }
+
+    /// Refuses to log something for `tarantulas` at `at` when that is still
+    /// to come or before one of them was acquired, and gives the keeper's day
+    /// it falls on, like the SQLite store.
+    fn check_logged_at(
+        &self,
+        user_id: u64,
+        tarantulas: &[i64],
+        at: NaiveDateTime,
+    ) -> BotResult<NaiveDate> {
+        let timezone = self.timezone(user_id);
+        if at > Utc::now().naive_utc() {
+            return Err(BotError::ValidationError(format!(
+                "{} is in the future",
+                timezone.local(at).format("%Y-%m-%d %H:%M")
+            )));
+        }
+        let day = timezone.day_of(at);
+        let acquired_later = tarantulas
+            .iter()
+            .filter_map(|&id| self.tarantulas.get(id).filter(|t| t.user_id == user_id))
+            .find(|t| day < t.acquisition_date);
+        match acquired_later {
+            Some(t) => Err(BotError::ValidationError(format!(
+                "{} was only acquired on {}",
+                t.name, t.acquisition_date
+            ))),
+            None => Ok(day),
+        }
+    }
+
+    /// What a write to `tarantulas` and `colonies` can move, leaving out the
+    /// rows the keeper doesn't own, like the SQLite store.
+    fn change_state(&self, user_id: u64, tarantulas: &[i64], colonies: &[i64]) -> ChangeState {
+        ChangeState {
+            tarantulas: tarantulas
+                .iter()
+                .filter_map(|&id| {
+                    let t = self.tarantulas.get(id).filter(|t| t.user_id == user_id)?;
+                    Some((id, t.state()))
+                })
+                .collect(),
+            colonies: colonies
+                .iter()
+                .filter_map(|&id| {
+                    let c = self.colonies.get(id).filter(|c| c.user_id == user_id)?;
+                    Some((id, c.current_count))
+                })
+                .collect(),
+            ..ChangeState::default()
+        }
+    }
+
+    fn record_change(
+        &mut self,
+        user_id: u64,
+        kind: ChangeKind,
+        before: ChangeState,
+        after: ChangeState,
+    ) -> Change {
+        let row = ChangeRow {
+            user_id,
+            kind,
+            before,
+            after,
+            changed_at: now(),
+            undone_at: None,
+        };
+        let change = row.change(0, self.timezone(user_id));
+        Change {
+            id: self.changes.insert(row),
+            ..change
+        }
+    }
+
+    fn frequency_by_name(&self, name: &str) -> Option<(i64, &FeedingFrequency)> {
+        self.frequencies
+            .iter()
+            .find(|(_, f)| f.frequency_name == name)
+    }
+
+    fn schedule_bands(&self, species_id: i64) -> Vec<ScheduleBand> {
+        let mut bands: Vec<ScheduleBand> = self
+            .schedules
+            .iter()
+            .filter(|(_, s)| s.species_id == species_id)
+            .map(|(_, s)| {
+                let frequency = self.frequency_by_name(&s.feeding_frequency);
+                ScheduleBand {
+                    size_category: s.size_category.clone(),
+                    body_length_cm: s.body_length_cm,
+                    prey_size: s.prey_size.clone(),
+                    prey_type: s.prey_type.clone(),
+                    frequency_name: s.feeding_frequency.clone(),
+                    min_days: frequency.map(|(_, f)| f.min_days),
+                    max_days: frequency.map(|(_, f)| f.max_days),
+                    notes: s.notes.clone(),
+                }
+            })
+            .collect();
+        bands.sort_by(|a, b| a.body_length_cm.total_cmp(&b.body_length_cm));
+        bands
+    }
+
+    fn feeding_override(&self, tarantula_id: i64, row: &OverrideRow) -> FeedingOverride {
+        let frequency = row.frequency_id.and_then(|id| self.frequencies.get(id));
+        FeedingOverride {
+            tarantula_id,
+            frequency_id: row.frequency_id,
+            frequency_name: frequency.map(|f| f.frequency_name.clone()),
+            min_days: frequency.map(|f| f.min_days),
+            max_days: frequency.map(|f| f.max_days),
+            prey_count: row.prey_count,
+            prey_size_id: row.prey_size_id,
+            prey_size: row
+                .prey_size_id
+                .and_then(cricket_size)
+                .map(|s| s.to_db_name().to_string()),
+            expires_on: row.expires_on,
+            notes: row.notes.clone(),
+        }
+    }
+
+    /// The last meal `tarantula_id` took; refusals don't count.
+    fn last_fed(&self, tarantula_id: i64) -> Option<NaiveDateTime> {
+        self.feedings
+            .iter()
+            .filter(|(_, f)| {
+                f.tarantula_id == tarantula_id && matches!(f.status, FeedingStatus::Accepted)
+            })
+            .map(|(_, f)| f.feeding_date)
+            .max()
+    }
+
+    fn pairing_record(&self, id: i64, pairing: &PairingRow) -> Option<PairingRecord> {
+        let female = self.tarantulas.get(pairing.female_id)?;
+        let male = self.tarantulas.get(pairing.male_id)?;
+        Some(PairingRecord {
+            id,
+            female_id: pairing.female_id,
+            female_name: female.name.clone(),
+            male_id: pairing.male_id,
+            male_name: male.name.clone(),
+            pairing_date: pairing.pairing_date,
+            outcome: pairing.outcome.to_db_name().to_string(),
+            egg_sac_count: self
+                .egg_sacs
+                .iter()
+                .filter(|(_, es)| es.pairing_id == id)
+                .count() as i32,
+            notes: pairing.notes.clone(),
+        })
+    }
+
+    fn egg_sac_record(&self, id: i64, egg_sac: &EggSacRow) -> Option<EggSacRecord> {
+        let pairing = self.pairings.get(egg_sac.pairing_id)?;
+        let female = self.tarantulas.get(pairing.female_id)?;
+        let male = self.tarantulas.get(pairing.male_id)?;
+        Some(EggSacRecord {
+            id,
+            pairing_id: egg_sac.pairing_id,
+            female_name: female.name.clone(),
+            male_name: male.name.clone(),
+            laid_date: egg_sac.laid_date,
+            expected_pull_date: egg_sac.expected_pull_date,
+            pulled_date: egg_sac.pulled_date,
+            status: egg_sac.status.to_db_name().to_string(),
+            egg_count: egg_sac.egg_count,
+            nymph_count: egg_sac.nymph_count,
+            sling_count: egg_sac.sling_count,
+            slings_created: self.slings_created(id),
+            notes: egg_sac.notes.clone(),
+        })
+    }
+
+    fn slings_created(&self, egg_sac_id: i64) -> i32 {
+        self.tarantulas
+            .iter()
+            .filter(|(_, t)| t.egg_sac_id == Some(egg_sac_id))
+            .count() as i32
+    }
+
+    fn insert_group(
+        &mut self,
+        user_id: u64,
+        name: &str,
+        species_id: i64,
+        acquisition_date: NaiveDate,
+        notes: Option<String>,
+    ) -> i64 {
+        self.groups.insert(GroupRow {
+            user_id,
+            name: name.to_string(),
+            species_id,
+            acquisition_date,
+            notes,
+        })
+    }
+
+    fn group_member_ids(&self, user_id: u64, group_id: i64) -> BotResult<Vec<i64>> {
+        let ids: Vec<i64> = match self.groups.get(group_id) {
+            Some(group) if group.user_id == user_id => self
+                .tarantulas
+                .iter()
+                .filter(|(_, t)| t.group_id == Some(group_id))
+                .map(|(id, _)| id)
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        if ids.is_empty() {
+            return Err(BotError::NotFound(format!(
+                "Group with id {} not found, access denied, or empty",
+                group_id
+            )));
+        }
+        Ok(ids)
+    }
+
+    fn is_descendant(&self, ancestor_id: i64, candidate_id: i64) -> bool {
+        let mut pending = vec![ancestor_id];
+        let mut seen = HashSet::new();
+        while let Some(parent_id) = pending.pop() {
+            for (id, _) in self
+                .tarantulas
+                .iter()
+                .filter(|(_, t)| t.mother_id == Some(parent_id) || t.father_id == Some(parent_id))
+            {
+                if id == candidate_id {
+                    return true;
+                }
+                if seen.insert(id) {
+                    pending.push(id);
+                }
+            }
+        }
+        false
+    }
+
+    fn take_crickets(&mut self, colony_id: i64, user_id: u64, count: i32) -> bool {
+        match self.colonies.get_mut(colony_id) {
+            Some(colony) if colony.user_id == user_id && colony.current_count >= count => {
+                colony.current_count -= count;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+pub struct InMemoryDB {
+    state: Mutex<State>,
+    thresholds: AlertThresholds,
+}
+
+impl Default for InMemoryDB {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl InMemoryDB {
+    /// Creates an empty store with the lookup tables and a few species seeded.
+    pub fn new() -> Self {
+        let mut state = State::default();
+        for (name, min_days, max_days, description) in FEEDING_FREQUENCIES {
+            state.frequencies.insert(FeedingFrequency {
+                id: 0,
+                frequency_name: name.to_string(),
+                min_days,
+                max_days,
+                description: Some(description.to_string()),
+            });
+        }
+        for (id, scientific_name, common_name, adult_size_cm) in SPECIES {
+            state.species.insert(
+                id,
+                Species {
+                    scientific_name: scientific_name.to_string(),
+                    common_name: common_name.to_string(),
+                    adult_size_cm,
+                },
+            );
+        }
+        for (species_id, size_category, body_length_cm, prey_size, frequency, prey_type, notes) in
+            FEEDING_SCHEDULES
+        {
+            state.schedules.insert(FeedingSchedule {
+                species_id,
+                size_category: size_category.to_string(),
+                body_length_cm,
+                prey_size: prey_size.to_string(),
+                feeding_frequency: frequency.to_string(),
+                prey_type: prey_type.to_string(),
+                notes: Some(notes.to_string()),
+                frequency_id: None,
+            });
+        }
+
+        Self {
+            state: Mutex::new(state),
+            thresholds: AlertThresholds::default(),
+        }
+    }
+
+    pub fn with_thresholds(mut self, thresholds: AlertThresholds) -> Self {
+        self.thresholds = thresholds;
+        self
+    }
+
+    fn state(&self) -> Result<MutexGuard<'_, State>, BotError> {
+        self.state
+            .lock()
+            .map_err(|_| BotError::OperationError("In-memory database is poisoned".to_string()))
+    }
+}
+
+#[async_trait]
+impl TarantulaOperations for InMemoryDB {
+    async fn add_tarantula(&self, user_id: u64, params: AddTarantulaParams) -> BotResult<()> {
+        let acquisition_date = NaiveDate::parse_from_str(&params.acquisition_date, "%Y-%m-%d")?;
+        let mut state = self.state()?;
+        state.check_user(user_id)?;
+        state.check_species(params.species_id)?;
+        if let Some(enclosure_number) = &params.enclosure_number {
+            if state
+                .tarantulas
+                .iter()
+                .any(|(_, t)| t.enclosure_number.as_ref() == Some(enclosure_number))
+            {
+                return Err(unique_violation("tarantulas.enclosure_number"));
+            }
+        }
+
+        let mut tarantula =
+            TarantulaRow::new(user_id, params.name, params.species_id, acquisition_date);
+        tarantula.estimated_age_months = Some(params.estimated_age_months as i32);
+        tarantula.enclosure_number = params.enclosure_number;
+        tarantula.notes = params.notes;
+        state.tarantulas.insert(tarantula);
+        Ok(())
+    }
+
+    async fn get_tarantula_by_id(&self, user_id: u64, id: i64) -> BotResult<Tarantula> {
+        let state = self.state()?;
+        let t = state
+            .tarantulas
+            .get(id)
+            .filter(|t| t.user_id == user_id)
+            .ok_or_else(|| BotError::NotFound(format!("Tarantula with id {} not found", id)))?;
+
+        Ok(Tarantula {
+            id,
+            name: t.name.clone(),
+            species_id: t.species_id,
+            acquisition_date: t.acquisition_date,
+            last_molt_date: t.last_molt_date,
+            estimated_age_months: t.estimated_age_months,
+            current_molt_stage_id: t.molt_stage_id,
+            current_health_status_id: t.health_status_id,
+            last_health_check_date: t.last_health_check_date,
+            enclosure_number: t.enclosure_number.clone(),
+            enclosure_id: t.enclosure_id,
+            notes: t.notes.clone(),
+            aliases: t.aliases.clone(),
+            mother_id: t.mother_id,
+            father_id: t.father_id,
+            mother_external: t.mother_external.clone(),
+            father_external: t.father_external.clone(),
+        })
+    }
+
+    async fn get_schedule_facts(&self, user_id: u64) -> BotResult<Vec<TarantulaFacts>> {
+        let state = self.state()?;
+        let timezone = state.timezone(user_id);
+        let mut facts: Vec<TarantulaFacts> = state
+            .tarantulas
+            .iter()
+            .filter(|(_, t)| t.user_id == user_id)
+            .filter_map(|(id, t)| {
+                let species = state.species.get(&t.species_id)?;
+                let last_molt_length_cm = state
+                    .molts
+                    .iter()
+                    .filter(|(_, m)| m.tarantula_id == id)
+                    .filter_map(|(molt_id, m)| {
+                        m.post_molt_length_cm
+                            .map(|length| ((m.molt_date, molt_id), length))
+                    })
+                    .max_by_key(|(key, _)| *key)
+                    .map(|(_, length)| length);
+                let group = t.group_id.and_then(|g| state.groups.get(g));
+
+                Some(TarantulaFacts {
+                    id,
+                    name: t.name.clone(),
+                    aliases: split_aliases(t.aliases.as_deref()),
+                    species_name: species.common_name.clone(),
+                    scientific_name: species.scientific_name.clone(),
+                    enclosure_number: t.enclosure_number.clone(),
+                    group_id: t.group_id,
+                    group_name: group.map(|g| g.name.clone()),
+                    acquisition_date: t.acquisition_date,
+                    estimated_age_months: t.estimated_age_months,
+                    adult_size_cm: Some(species.adult_size_cm),
+                    molt_stage: t.molt_stage_id.map(MoltStage::from_id),
+                    health_status: t.health_status_id.map(HealthStatus::from_id),
+                    last_molt_date: t.last_molt_date,
+                    last_molt_length_cm,
+                    last_health_check_date: t.last_health_check_date,
+                    last_fed: state.last_fed(id).map(|at| timezone.local(at)),
+                    schedule: state.schedule_bands(t.species_id),
+                    feeding_override: state
+                        .overrides
+                        .get(&id)
+                        .filter(|o| o.user_id == user_id)
+                        .map(|o| state.feeding_override(id, o)),
+                })
+            })
+            .collect();
+
+        facts.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(facts)
+    }
+
+    async fn update_tarantula_enclosure(
+        &self,
+        tarantula_id: i64,
+        enclosure_id: Option<i64>,
+        user_id: u64,
+    ) -> BotResult<()> {
+        let mut state = self.state()?;
+        if let Some(t) = state
+            .tarantulas
+            .get_mut(tarantula_id)
+            .filter(|t| t.user_id == user_id)
+        {
+            t.enclosure_id = enclosure_id;
+        }
+        Ok(())
+    }
+
+    async fn update_tarantula(
+        &self,
+        user_id: u64,
+        tarantula_id: i64,
+        field: TarantulaField,
+        value: Option<String>,
+    ) -> BotResult<()> {
+        let mut state = self.state()?;
+        match (field, &value) {
+            (TarantulaField::Name, None) => {
+                return Err(BotError::ValidationError(
+                    "A tarantula needs a name".to_string(),
+                ));
+            }
+            (TarantulaField::Enclosure, Some(number))
+                if state.tarantulas.iter().any(|(id, t)| {
+                    id != tarantula_id && t.enclosure_number.as_ref() == Some(number)
+                }) =>
+            {
+                return Err(BotError::ValidationError(format!(
+                    "Enclosure {} is already taken",
+                    number
+                )));
+            }
+            _ => {}
+        }
+
+        let t = state
+            .tarantulas
+            .get_mut(tarantula_id)
+            .filter(|t| t.user_id == user_id)
+            .ok_or_else(|| {
+                BotError::NotFound(format!("Tarantula with id {} not found", tarantula_id))
+            })?;
+        match field {
+            TarantulaField::Name => t.name = value.unwrap_or_default(),
+            TarantulaField::Enclosure => t.enclosure_number = value,
+            TarantulaField::Notes => t.notes = value,
+            TarantulaField::Aliases => t.aliases = value,
+        }
+        Ok(())
+    }
+
+    async fn add_photo(
+        &self,
+        user_id: u64,
+        tarantula_id: i64,
+        file_id: &str,
+        caption: Option<String>,
+    ) -> BotResult<i64> {
+        let mut state = self.state()?;
+        if state.owned_tarantula(tarantula_id, user_id).is_err() {
+            return Err(BotError::NotFound(format!(
+                "Tarantula with id {} not found",
+                tarantula_id
+            )));
+        }
+        Ok(state.photos.insert(PhotoRow {
+            user_id,
+            tarantula_id,
+            file_id: file_id.to_string(),
+            caption,
+            taken_at: now(),
+        }))
+    }
+
+    async fn get_photos(&self, user_id: u64, tarantula_id: i64) -> BotResult<Vec<TarantulaPhoto>> {
+        let state = self.state()?;
+        let timezone = state.timezone(user_id);
+        let mut photos: Vec<(i64, &PhotoRow)> = state
+            .photos
+            .iter()
+            .filter(|(_, p)| p.user_id == user_id && p.tarantula_id == tarantula_id)
+            .collect();
+        photos.sort_by_key(|(id, p)| Reverse((p.taken_at, *id)));
+        Ok(photos
+            .into_iter()
+            .map(|(id, p)| TarantulaPhoto {
+                id,
+                tarantula_id: p.tarantula_id,
+                file_id: p.file_id.clone(),
+                caption: p.caption.clone(),
+                taken_at: timezone
+                    .local(p.taken_at)
+                    .format(DATETIME_FORMAT)
+                    .to_string(),
+            })
+            .collect())
+    }
+
+    async fn delete_photo(&self, user_id: u64, photo_id: i64) -> BotResult<()> {
+        let mut state = self.state()?;
+        if state
+            .photos
+            .get(photo_id)
+            .is_none_or(|p| p.user_id != user_id)
+        {
+            return Err(BotError::NotFound(format!(
+                "Photo with id {} not found",
+                photo_id
+            )));
+        }
+        state.photos.remove(photo_id);
+        Ok(())
+    }
+
+    async fn record_feeding(&self, user_id: u64, event: FeedingEvent) -> BotResult<Change> {
+        let mut state = self.state()?;
+        state.owned_tarantula(event.tarantula_id, user_id)?;
+        let feeding_date = truncate_to_seconds(event.feeding_date.naive_utc());
+        state.check_logged_at(user_id, &[event.tarantula_id], feeding_date)?;
+        let touched = ([event.tarantula_id], [event.cricket_colony_id]);
+        let before = state.change_state(user_id, &touched.0, &touched.1);
+
+        if !state.take_crickets(event.cricket_colony_id, user_id, event.number_of_crickets) {
+            return Err(BotError::NotFound(
+                "Colony not found, access denied, or insufficient crickets".to_string(),
+            ));
+        }
+
+        let feeding_id = state.feedings.insert(FeedingRow {
+            tarantula_id: event.tarantula_id,
+            feeding_date,
+            colony_id: Some(event.cricket_colony_id),
+            number_of_crickets: event.number_of_crickets,
+            status: FeedingStatus::Accepted,
+            notes: event.notes,
+        });
+
+        let mut after = state.change_state(user_id, &touched.0, &touched.1);
+        after.feedings.push(feeding_id);
+        Ok(state.record_change(user_id, ChangeKind::Feeding, before, after))
+    }
+
+    async fn record_refusal(
+        &self,
+        user_id: u64,
+        tarantula_id: i64,
+        at: DbDateTime,
+    ) -> BotResult<Change> {
+        let mut state = self.state()?;
+        let at = truncate_to_seconds(at.naive_utc());
+        state.check_logged_at(user_id, &[tarantula_id], at)?;
+        state.owned_tarantula(tarantula_id, user_id)?;
+        let before = state.change_state(user_id, &[tarantula_id], &[]);
+        let feeding_id = state.feedings.insert(FeedingRow {
+            tarantula_id,
+            feeding_date: at,
+            colony_id: None,
+            number_of_crickets: 0,
+            status: FeedingStatus::Rejected,
+            notes: None,
+        });
+
+        let mut after = state.change_state(user_id, &[tarantula_id], &[]);
+        after.feedings.push(feeding_id);
+        Ok(state.record_change(user_id, ChangeKind::Refusal, before, after))
+    }
+
+    async fn get_feeding_records(
+        &self,
+        user_id: u64,
+        filter: &RecordFilter,
+    ) -> BotResult<Vec<FeedingRecord>> {
+        let state = self.state()?;
+        let timezone = state.timezone(user_id);
+        let mut records: Vec<(NaiveDateTime, i64, FeedingRecord)> = state
+            .feedings
+            .iter()
+            .filter_map(|(id, f)| {
+                let tarantula = state.tarantulas.get(f.tarantula_id)?;
+                let colony = f.colony_id.and_then(|id| state.colonies.get(id));
+                let fed_at = timezone.local(f.feeding_date);
+                let included = includes(filter, f.tarantula_id, fed_at.date(), f.status as i64);
+                (tarantula.user_id == user_id && included).then(|| {
+                    (
+                        f.feeding_date,
+                        id,
+                        FeedingRecord {
+                            tarantula_name: tarantula.name.clone(),
+                            feeding_date: fed_at.format(DATETIME_FORMAT).to_string(),
+                            colony_name: colony.map(|c| c.colony_name.clone()),
+                            number_of_crickets: f.number_of_crickets,
+                            status: f.status.to_db_name().to_string(),
+                            notes: f.notes.clone(),
+                        },
+                    )
+                })
+            })
+            .collect();
+
+        records.sort_by_key(|r| Reverse((r.0, r.1)));
+        Ok(records
+            .into_iter()
+            .skip(filter.offset as usize)
+            .take(filter.limit as usize)
+            .map(|(_, _, r)| r)
+            .collect())
+    }
+
+    async fn get_feeding_schedule(
+        &self,
+        species_id: i64,
+        body_length_cm: f32,
+    ) -> BotResult<Option<FeedingSchedule>> {
+        let state = self.state()?;
+        let schedule = state
+            .schedules
+            .iter()
+            .map(|(_, s)| s)
+            .filter(|s| s.species_id == species_id && s.body_length_cm >= body_length_cm)
+            .min_by(|a, b| a.body_length_cm.total_cmp(&b.body_length_cm))
+            .map(|s| FeedingSchedule {
+                frequency_id: state
+                    .frequency_by_name(&s.feeding_frequency)
+                    .map(|(id, _)| id),
+                ..s.clone()
+            });
+
+        Ok(schedule)
+    }
+
+    async fn get_feeding_frequency(&self, id: i64) -> BotResult<Option<FeedingFrequency>> {
+        let state = self.state()?;
+        Ok(state
+            .frequencies
+            .get(id)
+            .map(|f| FeedingFrequency { id, ..f.clone() }))
+    }
+
+    async fn get_feeding_frequencies(&self) -> BotResult<Vec<FeedingFrequency>> {
+        let state = self.state()?;
+        let mut frequencies: Vec<FeedingFrequency> = state
+            .frequencies
+            .iter()
+            .map(|(id, f)| FeedingFrequency { id, ..f.clone() })
+            .collect();
+        frequencies.sort_by_key(|f| (f.min_days, f.max_days));
+        Ok(frequencies)
+    }
+
+    async fn set_feeding_override(
+        &self,
+        user_id: u64,
+        params: SetFeedingOverrideParams,
+    ) -> BotResult<()> {
+        if params.frequency_id.is_none()
+            && params.prey_count.is_none()
+            && params.prey_size_id.is_none()
+        {
+            return Err(BotError::ValidationError(
+                "An override needs a frequency, prey count or prey size".to_string(),
+            ));
+        }
+        if params.prey_count.is_some_and(|c| c <= 0) {
+            return Err(BotError::ValidationError(
+                "Prey count must be positive".to_string(),
+            ));
+        }
+        let mut state = self.state()?;
+        let today = state.timezone(user_id).today();
+        if params.expires_on.is_some_and(|d| d < today) {
+            return Err(BotError::ValidationError(
+                "Expiry date is in the past".to_string(),
+            ));
+        }
+
+        state.owned_tarantula(params.tarantula_id, user_id)?;
+
+        if let Some(frequency_id) = params.frequency_id {
+            if state.frequencies.get(frequency_id).is_none() {
+                return Err(BotError::NotFound(format!(
+                    "Feeding frequency {} not found",
+                    frequency_id
+                )));
+            }
+        }
+        if let Some(prey_size_id) = params.prey_size_id {
+            if cricket_size(prey_size_id).is_none() {
+                return Err(BotError::NotFound(format!(
+                    "Prey size {} not found",
+                    prey_size_id
+                )));
+            }
+        }
+
+        state.overrides.insert(
+            params.tarantula_id,
+            OverrideRow {
+                user_id,
+                frequency_id: params.frequency_id,
+                prey_count: params.prey_count,
+                prey_size_id: params.prey_size_id,
+                expires_on: params.expires_on,
+                notes: params.notes,
+            },
+        );
+        Ok(())
+    }
+
+    async fn get_feeding_override(
+        &self,
+        user_id: u64,
+        tarantula_id: i64,
+    ) -> BotResult<Option<FeedingOverride>> {
+        let state = self.state()?;
+        let today = state.timezone(user_id).today();
+        Ok(state
+            .overrides
+            .get(&tarantula_id)
+            .filter(|o| o.user_id == user_id && o.expires_on.is_none_or(|d| d >= today))
+            .map(|o| state.feeding_override(tarantula_id, o)))
+    }
+
+    async fn clear_feeding_override(&self, user_id: u64, tarantula_id: i64) -> BotResult<()> {
+        let mut state = self.state()?;
+        match state.overrides.get(&tarantula_id) {
+            Some(o) if o.user_id == user_id => {
+                state.overrides.remove(&tarantula_id);
+                Ok(())
+            }
+            _ => Err(BotError::NotFound(format!(
+                "No feeding override for tarantula {}",
+                tarantula_id
+            ))),
+        }
+    }
+
+    async fn record_health_check(
+        &self,
+        user_id: u64,
+        tarantula_id: i64,
+        status: HealthStatus,
+        notes: Option<String>,
+        at: DbDateTime,
+    ) -> BotResult<Change> {
+        let mut state = self.state()?;
+        let at = truncate_to_seconds(at.naive_utc());
+        let day = state.check_logged_at(user_id, &[tarantula_id], at)?;
+        let before = state.change_state(user_id, &[tarantula_id], &[]);
+        let t = state
+            .tarantulas
+            .get_mut(tarantula_id)
+            .filter(|t| t.user_id == user_id)
+            .ok_or_else(|| {
+                BotError::NotFound(format!(
+                    "Tarantula with id {} not found or access denied",
+                    tarantula_id
+                ))
+            })?;
+        t.checked(status, day);
+
+        let check_id = state.health_checks.insert(HealthCheckRow {
+            tarantula_id,
+            check_date: day,
+            status,
+            weight_grams: Some(0.0),
+            humidity_percent: Some(55),
+            temperature_celsius: Some(20.0),
+            notes,
+        });
+
+        let mut after = state.change_state(user_id, &[tarantula_id], &[]);
+        after.health_checks.push(check_id);
+        Ok(state.record_change(user_id, ChangeKind::HealthCheck, before, after))
+    }
+
+    async fn get_health_records(
+        &self,
+        user_id: u64,
+        filter: &RecordFilter,
+    ) -> BotResult<Vec<HealthRecord>> {
+        let state = self.state()?;
+        let mut records: Vec<(NaiveDate, i64, HealthRecord)> = state
+            .health_checks
+            .iter()
+            .filter_map(|(id, h)| {
+                let tarantula = state.tarantulas.get(h.tarantula_id)?;
+                let included = includes(filter, h.tarantula_id, h.check_date, h.status as i64);
+                (tarantula.user_id == user_id && included).then(|| {
+                    (
+                        h.check_date,
+                        id,
+                        HealthRecord {
+                            tarantula_name: tarantula.name.clone(),
+                            check_date: h.check_date.to_string(),
+                            status: h.status.to_db_name().to_string(),
+                            weight_grams: h.weight_grams,
+                            humidity_percent: h.humidity_percent,
+                            temperature_celsius: h.temperature_celsius,
+                            notes: h.notes.clone(),
+                        },
+                    )
+                })
+            })
+            .collect();
+
+        records.sort_by_key(|r| Reverse((r.0, r.1)));
+        Ok(records
+            .into_iter()
+            .skip(filter.offset as usize)
+            .take(filter.limit as usize)
+            .map(|(_, _, r)| r)
+            .collect())
+    }
+
+    async fn record_molt(
+        &self,
+        tarantula_id: i64,
+        length_cm: f32,
+        complications: Option<String>,
+        notes: Option<String>,
+        user_id: u64,
+        at: DbDateTime,
+    ) -> BotResult<Change> {
+        let mut state = self.state()?;
+        let at = truncate_to_seconds(at.naive_utc());
+        let day = state.check_logged_at(user_id, &[tarantula_id], at)?;
+        let before = state.change_state(user_id, &[tarantula_id], &[]);
+        let t = state
+            .tarantulas
+            .get_mut(tarantula_id)
+            .filter(|t| t.user_id == user_id)
+            .ok_or_else(|| {
+                BotError::NotFound(format!(
+                    "Tarantula with id {} not found or access denied",
+                    tarantula_id
+                ))
+            })?;
+        t.molted(day);
+
+        let molt_id = state.molts.insert(MoltRow {
+            tarantula_id,
+            molt_date: day,
+            stage: MoltStage::PostMolt,
+            post_molt_length_cm: Some(length_cm),
+            complications,
+            notes,
+        });
+
+        let mut after = state.change_state(user_id, &[tarantula_id], &[]);
+        after.molts.push(molt_id);
+        Ok(state.record_change(user_id, ChangeKind::Molt, before, after))
+    }
+
+    async fn get_molt_records(
+        &self,
+        user_id: u64,
+        filter: &RecordFilter,
+    ) -> BotResult<Vec<MoltRecord>> {
+        let state = self.state()?;
+        let mut records: Vec<(NaiveDate, i64, MoltRecord)> = state
+            .molts
+            .iter()
+            .filter_map(|(id, m)| {
+                let tarantula = state.tarantulas.get(m.tarantula_id)?;
+                let included = includes(filter, m.tarantula_id, m.molt_date, m.stage as i64);
+                (tarantula.user_id == user_id && included).then(|| {
+                    (
+                        m.molt_date,
+                        id,
+                        MoltRecord {
+                            tarantula_name: tarantula.name.clone(),
+                            molt_date: m.molt_date.to_string(),
+                            stage: m.stage.to_db_name().to_string(),
+                            pre_molt_length_cm: None,
+                            post_molt_length_cm: m.post_molt_length_cm,
+                            complications: m.complications.clone(),
+                            notes: m.notes.clone(),
+                        },
+                    )
+                })
+            })
+            .collect();
+
+        records.sort_by_key(|r| Reverse((r.0, r.1)));
+        Ok(records
+            .into_iter()
+            .skip(filter.offset as usize)
+            .take(filter.limit as usize)
+            .map(|(_, _, r)| r)
+            .collect())
+    }
+
+    async fn add_colony(&self, user_id: u64, params: AddColonyParams) -> BotResult<()> {
+        let mut state = self.state()?;
+        state.check_user(user_id)?;
+        if cricket_size(params.size_type_id).is_none() {
+            return Err(foreign_key_violation());
+        }
+        if state
+            .colonies
+            .iter()
+            .any(|(_, c)| c.container_number == params.container_number)
+        {
+            return Err(unique_violation("cricket_colonies.container_number"));
+        }
+
+        state.colonies.insert(ColonyRow {
+            user_id,
+            colony_name: params.colony_name,
+            size_type_id: params.size_type_id,
+            current_count: params.current_count,
+            container_number: params.container_number,
+        });
+        Ok(())
+    }
+
+    async fn get_colony_status(&self, user_id: u64) -> BotResult<Vec<ColonyStatus>> {
+        let state = self.state()?;
+        let week_ago = now() - Duration::days(7);
+        let mut colonies: Vec<ColonyStatus> = state
+            .colonies
+            .iter()
+            .filter(|(_, c)| c.user_id == user_id)
+            .filter_map(|(id, c)| {
+                let size_type = cricket_size(c.size_type_id)?;
+                let used: i32 = state
+                    .feedings
+                    .iter()
+                    .filter(|(_, f)| f.colony_id == Some(id) && f.feeding_date >= week_ago)
+                    .map(|(_, f)| f.number_of_crickets)
+                    .sum();
+
+                Some(ColonyStatus {
+                    id,
+                    colony_name: c.colony_name.clone(),
+                    current_count: c.current_count,
+                    size_type,
+                    crickets_used_7_days: used,
+                    weeks_remaining: (used > 0)
+                        .then(|| c.current_count as f64 / (used as f64 / 7.0)),
+                })
+            })
+            .collect();
+
+        // SQLite sorts NULLs first.
+        colonies.sort_by(|a, b| match (a.weeks_remaining, b.weeks_remaining) {
+            (Some(a), Some(b)) => a.total_cmp(&b),
+            (a, b) => a.is_some().cmp(&b.is_some()),
+        });
+        Ok(colonies)
+    }
+
+    async fn update_colony_count(
+        &self,
+        colony_id: i64,
+        adjustment: i32,
+        user_id: u64,
+    ) -> BotResult<Change> {
+        let mut state = self.state()?;
+        let before = state.change_state(user_id, &[], &[colony_id]);
+        let colony = state
+            .colonies
+            .get_mut(colony_id)
+            .filter(|c| c.user_id == user_id)
+            .ok_or_else(|| {
+                BotError::NotFound(format!(
+                    "Colony with id {} not found or access denied",
+                    colony_id
+                ))
+            })?;
+        colony.current_count += adjustment;
+
+        let after = state.change_state(user_id, &[], &[colony_id]);
+        Ok(state.record_change(user_id, ChangeKind::ColonyCount, before, after))
+    }
+
+    async fn get_changes(&self, user_id: u64, offset: u32, limit: u32) -> BotResult<Vec<Change>> {
+        let state = self.state()?;
+        let timezone = state.timezone(user_id);
+        Ok(state
+            .changes
+            .rows
+            .iter()
+            .rev()
+            .filter(|(_, c)| c.user_id == user_id)
+            .skip(offset as usize)
+            .take(limit as usize)
+            .map(|(&id, c)| c.change(id, timezone))
+            .collect())
+    }
+
+    async fn undo_change(&self, user_id: u64, change_id: i64) -> BotResult<Change> {
+        let mut state = self.state()?;
+        let timezone = state.timezone(user_id);
+        let change = state
+            .changes
+            .get(change_id)
+            .filter(|c| c.user_id == user_id)
+            .map(|c| c.change(change_id, timezone))
+            .ok_or_else(|| {
+                BotError::NotFound(format!("Change with id {} not found", change_id))
+            })?;
+        if change.undone_at.is_some() {
+            return Err(BotError::ValidationError(
+                "That change was already undone".to_string(),
+            ));
+        }
+        let refunds: Vec<(i64, i32)> = change.colony_refunds().collect();
+        if refunds.iter().any(|&(id, refund)| {
+            state
+                .colonies
+                .get(id)
+                .is_some_and(|c| c.user_id == user_id && c.current_count + refund < 0)
+        }) {
+            return Err(BotError::ValidationError(
+                "The colony no longer has the crickets to take back".to_string(),
+            ));
+        }
+
+        for &id in &change.after.feedings {
+            state.feedings.remove(id);
+        }
+        for &id in &change.after.health_checks {
+            state.health_checks.remove(id);
+        }
+        for &id in &change.after.molts {
+            state.molts.remove(id);
+        }
+        for (id, refund) in refunds {
+            if let Some(colony) = state.colonies.get_mut(id).filter(|c| c.user_id == user_id) {
+                colony.current_count += refund;
+            }
+        }
+        for (&id, before) in &change.before.tarantulas {
+            let Some(after) = change.after.tarantulas.get(&id) else {
+                continue;
+            };
+            if let Some(t) = state.tarantulas.get_mut(id).filter(|t| t.user_id == user_id) {
+                t.set_state(t.state().reverted(before, after));
+            }
+        }
+
+        let row = state
+            .changes
+            .get_mut(change_id)
+            .expect("the change was read above");
+        row.undone_at = Some(now());
+        Ok(row.change(change_id, timezone))
+    }
+
+    async fn create_maintenance_record(&self, record: MaintenanceRecord) -> BotResult<i64> {
+        let mut state = self.state()?;
+        state.check_user(record.user_id as u64)?;
+        if state.enclosures.get(record.enclosure_id).is_none() {
+            return Err(foreign_key_violation());
+        }
+        Ok(state.maintenance.insert(record))
+    }
+
+    async fn get_maintenance_history(
+        &self,
+        enclosure_id: i64,
+        user_id: u64,
+    ) -> BotResult<Vec<MaintenanceRecord>> {
+        let state = self.state()?;
+        let mut records: Vec<MaintenanceRecord> = state
+            .maintenance
+            .iter()
+            .filter(|(_, r)| r.enclosure_id == enclosure_id && r.user_id == user_id as i64)
+            .map(|(id, r)| MaintenanceRecord {
+                id: Some(id),
+                ..r.clone()
+            })
+            .collect();
+        records.sort_by_key(|r| Reverse(r.maintenance_date));
+        Ok(records)
+    }
+
+    async fn create_enclosure(&self, enclosure: Enclosure) -> BotResult<i64> {
+        let mut state = self.state()?;
+        state.check_user(enclosure.user_id as u64)?;
+        Ok(state.enclosures.insert(enclosure))
+    }
+
+    async fn get_enclosure(&self, id: i64, user_id: u64) -> BotResult<Enclosure> {
+        let state = self.state()?;
+        state
+            .enclosures
+            .get(id)
+            .filter(|e| e.user_id == user_id as i64)
+            .map(|e| Enclosure {
+                id: Some(id),
+                ..e.clone()
+            })
+            .ok_or(BotError::Database(rusqlite::Error::QueryReturnedNoRows))
+    }
+
+    async fn get_enclosures(&self, user_id: u64) -> BotResult<Vec<Enclosure>> {
+        let state = self.state()?;
+        Ok(state
+            .enclosures
+            .iter()
+            .filter(|(_, e)| e.user_id == user_id as i64)
+            .map(|(id, e)| Enclosure {
+                id: Some(id),
+                ..e.clone()
+            })
+            .collect())
+    }
+
+    async fn ensure_user_exists(&self, user: &TelegramUser) -> BotResult<()> {
+        let mut state = self.state()?;
+        state.users.insert(
+            user.telegram_id,
+            TelegramUser {
+                telegram_id: user.telegram_id,
+                username: user.username.clone(),
+                first_name: user.first_name.clone(),
+                last_name: user.last_name.clone(),
+            },
+        );
+        Ok(())
+    }
+
+    async fn get_timezone(&self, user_id: u64) -> BotResult<Timezone> {
+        Ok(self.state()?.timezone(user_id))
+    }
+
+    async fn set_timezone(&self, user_id: u64, timezone: Timezone) -> BotResult<()> {
+        let mut state = self.state()?;
+        if !state.users.contains_key(&user_id) {
+            return Err(BotError::NotFound(format!("User {} not found", user_id)));
+        }
+        state.timezones.insert(user_id, timezone);
+        Ok(())
+    }
+
+    async fn record_pairing(&self, user_id: u64, params: AddPairingParams) -> BotResult<i64> {
+        if params.female_id == params.male_id {
+            return Err(BotError::ValidationError(
+                "A tarantula cannot be paired with itself".to_string(),
+            ));
+        }
+
+        let mut state = self.state()?;
+        for id in [params.female_id, params.male_id] {
+            state.owned_tarantula(id, user_id)?;
+        }
+        let known_male = |id: i64| {
+            state.pairings.iter().any(|(_, p)| p.male_id == id)
+                || state.tarantulas.iter().any(|(_, t)| t.father_id == Some(id))
+        };
+        let known_female = |id: i64| {
+            state.pairings.iter().any(|(_, p)| p.female_id == id)
+                || state.tarantulas.iter().any(|(_, t)| t.mother_id == Some(id))
+        };
+        for (id, known_as, known) in [
+            (params.female_id, "male", known_male(params.female_id)),
+            (params.male_id, "female", known_female(params.male_id)),
+        ] {
+            if known {
+                return Err(BotError::ValidationError(format!(
+                    "{} is recorded as a {}, pair it the other way round",
+                    state.owned_tarantula(id, user_id)?.name,
+                    known_as
+                )));
+            }
+        }
+
+        Ok(state.pairings.insert(PairingRow {
+            user_id,
+            female_id: params.female_id,
+            male_id: params.male_id,
+            pairing_date: params.pairing_date,
+            outcome: PairingOutcome::Pending,
+            notes: params.notes,
+        }))
+    }
+
+    async fn update_pairing_outcome(
+        &self,
+        user_id: u64,
+        pairing_id: i64,
+        outcome: PairingOutcome,
+    ) -> BotResult<()> {
+        let mut state = self.state()?;
+        let pairing = state
+            .pairings
+            .get_mut(pairing_id)
+            .filter(|p| p.user_id == user_id)
+            .ok_or_else(|| {
+                BotError::NotFound(format!(
+                    "Pairing with id {} not found or access denied",
+                    pairing_id
+                ))
+            })?;
+        pairing.outcome = outcome;
+        Ok(())
+    }
+
+    async fn get_pairings(&self, user_id: u64) -> BotResult<Vec<PairingRecord>> {
+        let state = self.state()?;
+        let mut records: Vec<PairingRecord> = state
+            .pairings
+            .iter()
+            .filter(|(_, p)| p.user_id == user_id)
+            .filter_map(|(id, p)| state.pairing_record(id, p))
+            .collect();
+        records.sort_by_key(|r| Reverse((r.pairing_date, r.id)));
+        Ok(records)
+    }
+
+    async fn record_egg_sac(&self, user_id: u64, params: AddEggSacParams) -> BotResult<i64> {
+        let expected_pull_date = params
+            .expected_pull_date
+            .unwrap_or(params.laid_date + Duration::days(DEFAULT_INCUBATION_DAYS));
+
+        if expected_pull_date < params.laid_date {
+            return Err(BotError::ValidationError(
+                "Expected pulling date cannot be before the sac was laid".to_string(),
+            ));
+        }
+
+        let mut state = self.state()?;
+        let pairing = state
+            .pairings
+            .get_mut(params.pairing_id)
+            .filter(|p| p.user_id == user_id)
+            .ok_or_else(|| {
+                BotError::NotFound(format!(
+                    "Pairing with id {} not found or access denied",
+                    params.pairing_id
+                ))
+            })?;
+
+        if params.laid_date < pairing.pairing_date {
+            return Err(BotError::ValidationError(
+                "Egg sac cannot be laid before the pairing date".to_string(),
+            ));
+        }
+
+        // A sac is the best evidence of a successful pairing.
+        if matches!(pairing.outcome, PairingOutcome::Pending) {
+            pairing.outcome = PairingOutcome::Successful;
+        }
+
+        Ok(state.egg_sacs.insert(EggSacRow {
+            user_id,
+            pairing_id: params.pairing_id,
+            laid_date: params.laid_date,
+            expected_pull_date: Some(expected_pull_date),
+            pulled_date: None,
+            status: EggSacStatus::Incubating,
+            egg_count: None,
+            nymph_count: None,
+            sling_count: None,
+            notes: params.notes,
+        }))
+    }
+
+    async fn update_egg_sac_status(
+        &self,
+        user_id: u64,
+        egg_sac_id: i64,
+        status: EggSacStatus,
+    ) -> BotResult<()> {
+        let mut state = self.state()?;
+        let today = state.timezone(user_id).today();
+        let egg_sac = state
+            .egg_sacs
+            .get_mut(egg_sac_id)
+            .filter(|es| es.user_id == user_id)
+            .ok_or_else(|| {
+                BotError::NotFound(format!(
+                    "Egg sac with id {} not found or access denied",
+                    egg_sac_id
+                ))
+            })?;
+        egg_sac.status = status;
+        if matches!(status, EggSacStatus::Pulled) {
+            egg_sac.pulled_date = Some(today);
+        }
+        Ok(())
+    }
+
+    async fn update_egg_sac_counts(
+        &self,
+        user_id: u64,
+        egg_sac_id: i64,
+        counts: EggSacCounts,
+    ) -> BotResult<()> {
+        let mut state = self.state()?;
+        let egg_sac = state
+            .egg_sacs
+            .get_mut(egg_sac_id)
+            .filter(|es| es.user_id == user_id)
+            .ok_or_else(|| {
+                BotError::NotFound(format!(
+                    "Egg sac with id {} not found or access denied",
+                    egg_sac_id
+                ))
+            })?;
+        egg_sac.egg_count = counts.egg_count.or(egg_sac.egg_count);
+        egg_sac.nymph_count = counts.nymph_count.or(egg_sac.nymph_count);
+        egg_sac.sling_count = counts.sling_count.or(egg_sac.sling_count);
+        Ok(())
+    }
+
+    async fn get_egg_sacs(&self, user_id: u64) -> BotResult<Vec<EggSacRecord>> {
+        let state = self.state()?;
+        let mut records: Vec<EggSacRecord> = state
+            .egg_sacs
+            .iter()
+            .filter(|(_, es)| es.user_id == user_id)
+            .filter_map(|(id, es)| state.egg_sac_record(id, es))
+            .collect();
+        records.sort_by_key(|r| Reverse((r.laid_date, r.id)));
+        Ok(records)
+    }
+
+    async fn get_egg_sacs_due_pulling(
+        &self,
+        user_id: u64,
+        within_days: i64,
+    ) -> BotResult<Vec<EggSacRecord>> {
+        let state = self.state()?;
+        let horizon = state.timezone(user_id).today() + Duration::days(within_days);
+        let mut records: Vec<EggSacRecord> = state
+            .egg_sacs
+            .iter()
+            .filter(|(_, es)| {
+                es.user_id == user_id
+                    && matches!(es.status, EggSacStatus::Incubating)
+                    && es.expected_pull_date.is_some_and(|d| d <= horizon)
+            })
+            .filter_map(|(id, es)| state.egg_sac_record(id, es))
+            .collect();
+        records.sort_by_key(|r| r.expected_pull_date);
+        Ok(records)
+    }
+
+    async fn create_slings_from_egg_sac(
+        &self,
+        user_id: u64,
+        egg_sac_id: i64,
+        count: i32,
+        name_prefix: &str,
+    ) -> BotResult<Vec<i64>> {
+        if !(1..=MAX_GROUP_SIZE).contains(&count) {
+            return Err(BotError::ValidationError(format!(
+                "Sling count must be between 1 and {}",
+                MAX_GROUP_SIZE
+            )));
+        }
+
+        let mut state = self.state()?;
+        let not_found = || {
+            BotError::NotFound(format!(
+                "Egg sac with id {} not found or access denied",
+                egg_sac_id
+            ))
+        };
+        let egg_sac = state
+            .egg_sacs
+            .get(egg_sac_id)
+            .filter(|es| es.user_id == user_id)
+            .ok_or_else(not_found)?;
+        let pairing = state
+            .pairings
+            .get(egg_sac.pairing_id)
+            .ok_or_else(not_found)?;
+        let (female_id, male_id) = (pairing.female_id, pairing.male_id);
+        let female = state.tarantulas.get(female_id).ok_or_else(not_found)?;
+        let male = state.tarantulas.get(male_id).ok_or_else(not_found)?;
+
+        let species_id = female.species_id;
+        let hatch_date = egg_sac
+            .pulled_date
+            .unwrap_or_else(|| state.timezone(user_id).today());
+        let notes = format!(
+            "From egg sac #{} ({} x {})",
+            egg_sac_id, female.name, male.name
+        );
+        let already_created = state.slings_created(egg_sac_id);
+
+        let group_id = (count > 1).then(|| {
+            state.insert_group(
+                user_id,
+                name_prefix,
+                species_id,
+                hatch_date,
+                Some(notes.clone()),
+            )
+        });
+
+        let mut ids = Vec::with_capacity(count as usize);
+        for n in 1..=count {
+            let mut sling = TarantulaRow::new(
+                user_id,
+                format!("{} #{}", name_prefix, already_created + n),
+                species_id,
+                hatch_date,
+            );
+            sling.estimated_age_months = Some(0);
+            sling.notes = Some(notes.clone());
+            sling.egg_sac_id = Some(egg_sac_id);
+            sling.mother_id = Some(female_id);
+            sling.father_id = Some(male_id);
+            sling.group_id = group_id;
+            ids.push(state.tarantulas.insert(sling));
+        }
+
+        if let Some(egg_sac) = state.egg_sacs.get_mut(egg_sac_id) {
+            egg_sac.sling_count = egg_sac.sling_count.or(Some(count));
+        }
+
+        Ok(ids)
+    }
+
+    async fn set_tarantula_parents(
+        &self,
+        user_id: u64,
+        tarantula_id: i64,
+        mother: Parent,
+        father: Parent,
+    ) -> BotResult<()> {
+        if let (Parent::Internal(m), Parent::Internal(f)) = (&mother, &father) {
+            if m == f {
+                return Err(BotError::ValidationError(
+                    "Mother and father must be different tarantulas".to_string(),
+                ));
+            }
+        }
+
+        let mut state = self.state()?;
+        state.owned_tarantula(tarantula_id, user_id)?;
+
+        for parent in [&mother, &father] {
+            let Parent::Internal(parent_id) = parent else {
+                continue;
+            };
+            if *parent_id == tarantula_id {
+                return Err(BotError::ValidationError(
+                    "A tarantula cannot be its own parent".to_string(),
+                ));
+            }
+            state.owned_tarantula(*parent_id, user_id)?;
+
+            if state.is_descendant(tarantula_id, *parent_id) {
+                return Err(BotError::ValidationError(format!(
+                    "Tarantula {} descends from {} and cannot be its parent",
+                    parent_id, tarantula_id
+                )));
+            }
+        }
+
+        let split = |parent: Parent| match parent {
+            Parent::Unknown => (None, None),
+            Parent::Internal(id) => (Some(id), None),
+            Parent::External(breeder) => (None, Some(breeder)),
+        };
+        let (mother_id, mother_external) = split(mother);
+        let (father_id, father_external) = split(father);
+
+        if let Some(t) = state.tarantulas.get_mut(tarantula_id) {
+            t.mother_id = mother_id;
+            t.mother_external = mother_external;
+            t.father_id = father_id;
+            t.father_external = father_external;
+        }
+        Ok(())
+    }
+
+    async fn get_ancestors(
+        &self,
+        user_id: u64,
+        tarantula_id: i64,
+        max_depth: i32,
+    ) -> BotResult<Vec<LineageNode>> {
+        let state = self.state()?;
+        let owned = |id: i64| state.tarantulas.get(id).filter(|t| t.user_id == user_id);
+        if owned(tarantula_id).is_none() {
+            return Ok(Vec::new());
+        }
+
+        let mut lineage = vec![(tarantula_id, 0)];
+        let mut seen: HashSet<(i64, i32)> = lineage.iter().copied().collect();
+        let mut next = 0;
+        while let Some(&(id, depth)) = lineage.get(next) {
+            next += 1;
+            if depth + 1 >= max_depth {
+                continue;
+            }
+            let Some(child) = state.tarantulas.get(id) else {
+                continue;
+            };
+            for parent_id in [child.mother_id, child.father_id].into_iter().flatten() {
+                if owned(parent_id).is_some() && seen.insert((parent_id, depth + 1)) {
+                    lineage.push((parent_id, depth + 1));
+                }
+            }
+        }
+
+        let mut nodes = Vec::new();
+        for (id, depth) in lineage {
+            let Some(child) = state.tarantulas.get(id) else {
+                continue;
+            };
+            for (role, parent_id, external) in [
+                (ParentRole::Mother, child.mother_id, &child.mother_external),
+                (ParentRole::Father, child.father_id, &child.father_external),
+            ] {
+                if parent_id.is_none() && external.is_none() {
+                    continue;
+                }
+                let parent = parent_id.and_then(|p| owned(p).map(|t| (p, t)));
+                nodes.push(LineageNode {
+                    id: parent.map(|(p, _)| p),
+                    name: parent
+                        .map(|(_, t)| t.name.clone())
+                        .or_else(|| external.clone())
+                        .unwrap_or_default(),
+                    relative_id: id,
+                    role,
+                    depth: depth + 1,
+                });
+            }
+        }
+
+        nodes.sort_by_key(|n| (n.depth, n.relative_id, n.role == ParentRole::Father));
+        Ok(nodes)
+    }
+
+    async fn get_descendants(
+        &self,
+        user_id: u64,
+        tarantula_id: i64,
+        max_depth: i32,
+    ) -> BotResult<Vec<LineageNode>> {
+        let state = self.state()?;
+        if state
+            .tarantulas
+            .get(tarantula_id)
+            .is_none_or(|t| t.user_id != user_id)
+        {
+            return Ok(Vec::new());
+        }
+
+        let children_of = |parent_id: i64| {
+            state
+                .tarantulas
+                .iter()
+                .filter(move |(_, c)| {
+                    c.user_id == user_id
+                        && (c.mother_id == Some(parent_id) || c.father_id == Some(parent_id))
+                })
+                .map(move |(id, c)| {
+                    let role = if c.mother_id == Some(parent_id) {
+                        ParentRole::Mother
+                    } else {
+                        ParentRole::Father
+                    };
+                    (id, parent_id, role)
+                })
+        };
+
+        let mut rows: Vec<(i64, i64, ParentRole, i32)> = Vec::new();
+        let mut seen = HashSet::new();
+        for (id, parent_id, role) in children_of(tarantula_id) {
+            if seen.insert((id, parent_id, role, 1)) {
+                rows.push((id, parent_id, role, 1));
+            }
+        }
+        let mut next = 0;
+        while let Some(&(parent_id, _, _, depth)) = rows.get(next) {
+            next += 1;
+            if depth >= max_depth {
+                continue;
+            }
+            for (id, parent_id, role) in children_of(parent_id) {
+                let row = (id, parent_id, role, depth + 1);
+                if seen.insert(row) {
+                    rows.push(row);
+                }
+            }
+        }
+
+        let mut nodes: Vec<LineageNode> = rows
+            .into_iter()
+            .filter_map(|(id, relative_id, role, depth)| {
+                state.tarantulas.get(id).map(|t| LineageNode {
+                    id: Some(id),
+                    name: t.name.clone(),
+                    relative_id,
+                    role,
+                    depth,
+                })
+            })
+            .collect();
+        nodes.sort_by(|a, b| (a.depth, &a.name).cmp(&(b.depth, &b.name)));
+        Ok(nodes)
+    }
+
+    async fn create_group(&self, user_id: u64, params: CreateGroupParams) -> BotResult<i64> {
+        if !(1..=MAX_GROUP_SIZE).contains(&params.count) {
+            return Err(BotError::ValidationError(format!(
+                "Group size must be between 1 and {}",
+                MAX_GROUP_SIZE
+            )));
+        }
+
+        let mut state = self.state()?;
+        state.check_user(user_id)?;
+        state.check_species(params.species_id)?;
+        let group_id = state.insert_group(
+            user_id,
+            &params.name,
+            params.species_id,
+            params.acquisition_date,
+            params.notes,
+        );
+
+        for n in 1..=params.count {
+            let mut member = TarantulaRow::new(
+                user_id,
+                format!("{} #{}", params.name, n),
+                params.species_id,
+                params.acquisition_date,
+            );
+            member.group_id = Some(group_id);
+            state.tarantulas.insert(member);
+        }
+        Ok(group_id)
+    }
+
+    async fn get_groups(&self, user_id: u64) -> BotResult<Vec<GroupSummary>> {
+        let state = self.state()?;
+        let now = Utc::now().naive_utc();
+        let mut groups: Vec<GroupSummary> = state
+            .groups
+            .iter()
+            .filter(|(_, g)| g.user_id == user_id)
+            .filter_map(|(id, g)| {
+                let species = state.species.get(&g.species_id)?;
+                let members: Vec<i64> = state
+                    .tarantulas
+                    .iter()
+                    .filter(|(_, t)| t.group_id == Some(id))
+                    .map(|(id, _)| id)
+                    .collect();
+                let last_fed = members.iter().filter_map(|&m| state.last_fed(m)).max();
+
+                Some(GroupSummary {
+                    id,
+                    name: g.name.clone(),
+                    species_id: g.species_id,
+                    species_name: species.common_name.clone(),
+                    acquisition_date: g.acquisition_date,
+                    member_count: members.len() as i32,
+                    days_since_feeding: last_fed
+                        .map(|fed| (now - fed).num_milliseconds() as f64 / 86_400_000.0),
+                    notes: g.notes.clone(),
+                })
+            })
+            .collect();
+        groups.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(groups)
+    }
+
+    async fn get_group_members(
+        &self,
+        user_id: u64,
+        group_id: i64,
+    ) -> BotResult<Vec<TarantulaListItem>> {
+        Ok(self
+            .get_all_tarantulas(user_id)
+            .await?
+            .into_iter()
+            .filter(|t| t.group_id == Some(group_id))
+            .collect())
+    }
+
+    async fn record_group_feeding(
+        &self,
+        user_id: u64,
+        group_id: i64,
+        colony_id: i64,
+        crickets_per_member: i32,
+        at: DbDateTime,
+    ) -> BotResult<Change> {
+        if crickets_per_member <= 0 {
+            return Err(BotError::ValidationError(
+                "Crickets per sling must be positive".to_string(),
+            ));
+        }
+
+        let mut state = self.state()?;
+        let members = state.group_member_ids(user_id, group_id)?;
+        let feeding_date = truncate_to_seconds(at.naive_utc());
+        state.check_logged_at(user_id, &members, feeding_date)?;
+        let total = members.len() as i32 * crickets_per_member;
+        let before = state.change_state(user_id, &members, &[colony_id]);
+
+        if !state.take_crickets(colony_id, user_id, total) {
+            return Err(BotError::NotFound(format!(
+                "Colony not found, access denied, or fewer than {} crickets",
+                total
+            )));
+        }
+
+        let mut feedings = Vec::with_capacity(members.len());
+        for &tarantula_id in &members {
+            feedings.push(state.feedings.insert(FeedingRow {
+                tarantula_id,
+                feeding_date,
+                colony_id: Some(colony_id),
+                number_of_crickets: crickets_per_member,
+                status: FeedingStatus::Accepted,
+                notes: Some("Group feeding".to_string()),
+            }));
+        }
+
+        let mut after = state.change_state(user_id, &members, &[colony_id]);
+        after.feedings = feedings;
+        Ok(state.record_change(user_id, ChangeKind::GroupFeeding, before, after))
+    }
+
+    async fn record_group_health_check(
+        &self,
+        user_id: u64,
+        group_id: i64,
+        status: HealthStatus,
+        at: DbDateTime,
+    ) -> BotResult<Change> {
+        let mut state = self.state()?;
+        let members = state.group_member_ids(user_id, group_id)?;
+        let at = truncate_to_seconds(at.naive_utc());
+        let day = state.check_logged_at(user_id, &members, at)?;
+        let before = state.change_state(user_id, &members, &[]);
+        let mut checks = Vec::with_capacity(members.len());
+
+        for &tarantula_id in &members {
+            let Some(t) = state
+                .tarantulas
+                .get_mut(tarantula_id)
+                .filter(|t| t.user_id == user_id)
+            else {
+                continue;
+            };
+            t.checked(status, day);
+            checks.push(state.health_checks.insert(HealthCheckRow {
+                tarantula_id,
+                check_date: day,
+                status,
+                weight_grams: None,
+                humidity_percent: None,
+                temperature_celsius: None,
+                notes: Some("Group health check".to_string()),
+            }));
+        }
+
+        let mut after = state.change_state(user_id, &members, &[]);
+        after.health_checks = checks;
+        Ok(state.record_change(user_id, ChangeKind::GroupHealthCheck, before, after))
+    }
+
+    async fn record_group_molt(
+        &self,
+        user_id: u64,
+        group_id: i64,
+        length_cm: Option<f32>,
+        at: DbDateTime,
+    ) -> BotResult<Change> {
+        let mut state = self.state()?;
+        let members = state.group_member_ids(user_id, group_id)?;
+        let at = truncate_to_seconds(at.naive_utc());
+        let day = state.check_logged_at(user_id, &members, at)?;
+        let before = state.change_state(user_id, &members, &[]);
+        let mut molts = Vec::with_capacity(members.len());
+
+        for &tarantula_id in &members {
+            let Some(t) = state
+                .tarantulas
+                .get_mut(tarantula_id)
+                .filter(|t| t.user_id == user_id)
+            else {
+                continue;
+            };
+            t.molted(day);
+            molts.push(state.molts.insert(MoltRow {
+                tarantula_id,
+                molt_date: day,
+                stage: MoltStage::PostMolt,
+                post_molt_length_cm: length_cm,
+                complications: None,
+                notes: Some("Group molt".to_string()),
+            }));
+        }
+
+        let mut after = state.change_state(user_id, &members, &[]);
+        after.molts = molts;
+        Ok(state.record_change(user_id, ChangeKind::GroupMolt, before, after))
+    }
+
+    async fn split_from_group(
+        &self,
+        user_id: u64,
+        tarantula_id: i64,
+        new_name: Option<String>,
+    ) -> BotResult<()> {
+        let mut state = self.state()?;
+        let t = state
+            .tarantulas
+            .get_mut(tarantula_id)
+            .filter(|t| t.user_id == user_id && t.group_id.is_some())
+            .ok_or_else(|| {
+                BotError::NotFound(format!(
+                    "Tarantula with id {} not found or not part of a group",
+                    tarantula_id
+                ))
+            })?;
+        t.group_id = None;
+        if let Some(name) = new_name {
+            t.name = name;
+        }
+        Ok(())
+    }
+
+    async fn get_species(&self) -> BotResult<Vec<TarantulaSpecies>> {
+        let state = self.state()?;
+        Ok(state
+            .species
+            .iter()
+            .map(|(&id, s)| TarantulaSpecies {
+                id,
+                scientific_name: s.scientific_name.clone(),
+                common_name: Some(s.common_name.clone()),
+            })
+            .collect())
+    }
+
+    async fn import_collection(&self, user_id: u64, plan: &ImportPlan) -> BotResult<()> {
+        let mut state = self.state()?;
+        state.check_user(user_id)?;
+        let timezone = state.timezone(user_id);
+
+        // Everything is checked before the first row goes in, so a failed
+        // import leaves nothing behind.
+        for t in &plan.tarantulas {
+            state.check_species(t.species_id)?;
+            if let Some(number) = &t.enclosure_number {
+                if state
+                    .tarantulas
+                    .iter()
+                    .any(|(_, existing)| existing.enclosure_number.as_ref() == Some(number))
+                {
+                    return Err(BotError::ValidationError(format!(
+                        "Enclosure {} is already taken, change it in the file and send it again",
+                        number
+                    )));
+                }
+            }
+        }
+        let targets = plan
+            .feedings
+            .iter()
+            .map(|f| f.tarantula)
+            .chain(plan.molts.iter().map(|m| m.tarantula));
+        for target in targets {
+            if let ImportTarget::Existing(id) = target {
+                if state
+                    .tarantulas
+                    .get(id)
+                    .is_none_or(|t| t.user_id != user_id)
+                {
+                    return Err(BotError::NotFound(format!(
+                        "Tarantula with id {} not found or access denied",
+                        id
+                    )));
+                }
+            }
+        }
+        for colony_id in plan.feedings.iter().filter_map(|f| f.colony_id) {
+            if state
+                .colonies
+                .get(colony_id)
+                .is_none_or(|c| c.user_id != user_id)
+            {
+                return Err(BotError::NotFound(
+                    "Colony not found or access denied".to_string(),
+                ));
+            }
+        }
+
+        let mut new_ids = Vec::with_capacity(plan.tarantulas.len());
+        for t in &plan.tarantulas {
+            let mut row =
+                TarantulaRow::new(user_id, t.name.clone(), t.species_id, t.acquisition_date);
+            row.estimated_age_months = t.estimated_age_months;
+            row.enclosure_number = t.enclosure_number.clone();
+            row.notes = t.notes.clone();
+            new_ids.push(state.tarantulas.insert(row));
+        }
+        let id_of = |target: ImportTarget| match target {
+            ImportTarget::Existing(id) => id,
+            ImportTarget::New(index) => new_ids[index],
+        };
+
+        for f in &plan.feedings {
+            state.feedings.insert(FeedingRow {
+                tarantula_id: id_of(f.tarantula),
+                feeding_date: timezone.utc(f.fed_at),
+                colony_id: f.colony_id,
+                number_of_crickets: f.number_of_crickets,
+                status: f.status,
+                notes: f.notes.clone(),
+            });
+        }
+        for m in &plan.molts {
+            let tarantula_id = id_of(m.tarantula);
+            state.molts.insert(MoltRow {
+                tarantula_id,
+                molt_date: m.molt_date,
+                stage: MoltStage::PostMolt,
+                post_molt_length_cm: m.length_cm,
+                complications: m.complications.clone(),
+                notes: m.notes.clone(),
+            });
+            if let Some(t) = state.tarantulas.get_mut(tarantula_id) {
+                if t.last_molt_date.is_none_or(|last| last < m.molt_date) {
+                    t.last_molt_date = Some(m.molt_date);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn ping(&self) -> BotResult<()> {
+        self.state().map(|_| ())
+    }
+
+    fn alert_thresholds(&self) -> AlertThresholds {
+        self.thresholds
+    }
+}
+
+fn cricket_size(id: i64) -> Option<CricketSize> {
+    CRICKET_SIZES.into_iter().find(|s| *s as i64 == id)
+}
+
+/// SQLite keeps timestamps with second precision.
+fn truncate_to_seconds(at: NaiveDateTime) -> NaiveDateTime {
+    at.with_nanosecond(0).unwrap_or(at)
+}
+
+fn now() -> NaiveDateTime {
+    truncate_to_seconds(Utc::now().naive_utc())
+}
+
+/// Constraint failures are reported as the same errors SQLite raises.
+/// Whether a record of `tarantula_id` on the keeper's `day` with `status_id`
+/// passes `filter`.
+fn includes(filter: &RecordFilter, tarantula_id: i64, day: NaiveDate, status_id: i64) -> bool {
+    filter.tarantula_id.is_none_or(|id| id == tarantula_id)
+        && filter.from.is_none_or(|from| day >= from)
+        && filter.to.is_none_or(|to| day <= to)
+        && filter.status_id.is_none_or(|id| id == status_id)
+}
+
+fn unique_violation(column: &str) -> BotError {
+    BotError::Database(rusqlite::Error::SqliteFailure(
+        rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_CONSTRAINT_UNIQUE),
+        Some(format!("UNIQUE constraint failed: {}", column)),
+    ))
+}
+
+fn foreign_key_violation() -> BotError {
+    BotError::Database(rusqlite::Error::SqliteFailure(
+        rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_CONSTRAINT_FOREIGNKEY),
+        Some("FOREIGN KEY constraint failed".to_string()),
+    ))
+}
@@ -0,0 +1,365 @@
+//! Wraps a backend so each [`TarantulaOperations`] call runs in a `db` span
+//! carrying the ids and sizes it was called with, and its duration is recorded
+//! per method.
+
+use crate::db::db::{
+    AddColonyParams, AddEggSacParams, AddPairingParams, AddTarantulaParams, CreateGroupParams,
+    PoolUsage, RecordFilter, SetFeedingOverrideParams, TarantulaOperations,
+};
+use crate::metrics::Metrics;
+use crate::models::audit::Change;
+use crate::models::breeding::{EggSacCounts, EggSacRecord, PairingRecord};
+use crate::models::cricket::ColonyStatus;
+use crate::models::enums::{EggSacStatus, HealthStatus, PairingOutcome};
+use crate::models::feeding::{FeedingEvent, FeedingOverride, FeedingRecord};
+use crate::models::group::GroupSummary;
+use crate::models::health::{HealthAlert, HealthRecord};
+use crate::models::import::ImportPlan;
+use crate::models::lineage::{LineageNode, Parent};
+use crate::models::models::DbDateTime;
+use crate::models::molt::MoltRecord;
+use crate::models::new::{Enclosure, FeedingFrequency, FeedingSchedule, MaintenanceRecord};
+use crate::models::tarantula::{
+    MaintenanceTask, Tarantula, TarantulaField, TarantulaListItem, TarantulaPhoto,
+    TarantulaSpecies,
+};
+use crate::models::user::{TelegramUser, Timezone};
+use crate::schedule::{AlertThresholds, FeedingPlan, TarantulaFacts};
+use crate::BotResult;
+use async_trait::async_trait;
+use std::future::Future;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Instant;
+use tracing::{info_span, Instrument};
+
+pub struct MeteredDB {
+    inner: Arc<dyn TarantulaOperations + Send + Sync>,
+    metrics: Arc<Metrics>,
+}
+
+impl MeteredDB {
+    pub fn new(inner: Arc<dyn TarantulaOperations + Send + Sync>, metrics: Arc<Metrics>) -> Self {
+        Self { inner, metrics }
+    }
+
+    async fn timed<T>(
+        &self,
+        method: &str,
+        call: impl Future<Output = BotResult<T>>,
+    ) -> BotResult<T> {
+        let started = Instant::now();
+        let result = call.await;
+        self.metrics.db_query(method, started.elapsed());
+        if let Err(e) = &result {
+            tracing::warn!(error = %e, "Storage call failed");
+        }
+        result
+    }
+}
+
+/// Forwards every listed method to the wrapped backend through
+/// [`MeteredDB::timed`]. Methods with default bodies are listed too, so they
+/// are timed and traced under their own name rather than the calls they make.
+///
+/// The span records only the fields in brackets after each method: an
+/// argument by name, or `name = expr` for part of one. Spans end up in every
+/// log line and trace, so names, notes and whole imports stay out of them.
+macro_rules! metered {
+    (@field $field:ident) => {
+        $field
+    };
+    (@field $field:ident $value:expr) => {
+        $value
+    };
+    ($(
+        $method:ident($($arg:ident: $ty:ty),*) -> $ret:ty
+            [$($field:ident $(= $value:expr)?),*];
+    )*) => {
+        #[async_trait]
+        impl TarantulaOperations for MeteredDB {
+            $(
+                async fn $method(&self, $($arg: $ty),*) -> BotResult<$ret> {
+                    let span = info_span!(
+                        "db",
+                        method = stringify!($method)
+                        $(, $field = metered!(@field $field $($value)?))*
+                    );
+                    self.timed(stringify!($method), self.inner.$method($($arg),*))
+                        .instrument(span)
+                        .await
+                }
+            )*
+
+            fn pool_usage(&self) -> Option<PoolUsage> {
+                self.inner.pool_usage()
+            }
+
+            fn alert_thresholds(&self) -> AlertThresholds {
+                self.inner.alert_thresholds()
+            }
+        }
+    };
+}
+
+metered! {
+    add_tarantula(user_id: u64, params: AddTarantulaParams) -> ()
+        [user_id, species_id = params.species_id];
+    get_tarantula_by_id(user_id: u64, id: i64) -> Tarantula [user_id, id];
+    get_schedule_facts(user_id: u64) -> Vec<TarantulaFacts> [user_id];
+    get_all_tarantulas(user_id: u64) -> Vec<TarantulaListItem> [user_id];
+    get_tarantulas_due_feeding(user_id: u64) -> Vec<TarantulaListItem> [user_id];
+    get_feeding_plan(user_id: u64, tarantula_id: i64) -> FeedingPlan [user_id, tarantula_id];
+    update_tarantula_enclosure(tarantula_id: i64, enclosure_id: Option<i64>, user_id: u64) -> ()
+        [tarantula_id, enclosure_id, user_id];
+    update_tarantula(
+        user_id: u64,
+        tarantula_id: i64,
+        field: TarantulaField,
+        value: Option<String>
+    ) -> () [user_id, tarantula_id, field = tracing::field::debug(field)];
+
+    add_photo(user_id: u64, tarantula_id: i64, file_id: &str, caption: Option<String>) -> i64
+        [user_id, tarantula_id];
+    get_photos(user_id: u64, tarantula_id: i64) -> Vec<TarantulaPhoto> [user_id, tarantula_id];
+    delete_photo(user_id: u64, photo_id: i64) -> () [user_id, photo_id];
+
+    record_feeding(user_id: u64, event: FeedingEvent) -> Change
+        [user_id, tarantula_id = event.tarantula_id, crickets = event.number_of_crickets];
+    record_refusal(user_id: u64, tarantula_id: i64, at: DbDateTime) -> Change
+        [user_id, tarantula_id];
+    get_recent_feeding_records(user_id: u64, limit: i32) -> Vec<FeedingRecord> [user_id, limit];
+    get_feeding_records(user_id: u64, filter: &RecordFilter) -> Vec<FeedingRecord>
+        [user_id, offset = filter.offset, limit = filter.limit];
+    get_feeding_schedule(species_id: i64, body_length_cm: f32) -> Option<FeedingSchedule>
+        [species_id, body_length_cm];
+    get_feeding_frequency(id: i64) -> Option<FeedingFrequency> [id];
+    get_feeding_frequencies() -> Vec<FeedingFrequency> [];
+
+    set_feeding_override(user_id: u64, params: SetFeedingOverrideParams) -> ()
+        [user_id, tarantula_id = params.tarantula_id];
+    get_feeding_override(user_id: u64, tarantula_id: i64) -> Option<FeedingOverride>
+        [user_id, tarantula_id];
+    clear_feeding_override(user_id: u64, tarantula_id: i64) -> () [user_id, tarantula_id];
+
+    record_health_check(
+        user_id: u64,
+        tarantula_id: i64,
+        status: HealthStatus,
+        notes: Option<String>,
+        at: DbDateTime
+    ) -> Change [user_id, tarantula_id, status = tracing::field::debug(status)];
+    get_recent_health_records(user_id: u64, limit: i32) -> Vec<HealthRecord> [user_id, limit];
+    get_health_records(user_id: u64, filter: &RecordFilter) -> Vec<HealthRecord>
+        [user_id, offset = filter.offset, limit = filter.limit];
+    get_health_alerts(user_id: u64) -> Vec<HealthAlert> [user_id];
+
+    record_molt(
+        tarantula_id: i64,
+        length_cm: f32,
+        complications: Option<String>,
+        notes: Option<String>,
+        user_id: u64,
+        at: DbDateTime
+    ) -> Change [tarantula_id, length_cm, user_id];
+    get_recent_molt_records(user_id: u64, limit: i32) -> Vec<MoltRecord> [user_id, limit];
+    get_molt_records(user_id: u64, filter: &RecordFilter) -> Vec<MoltRecord>
+        [user_id, offset = filter.offset, limit = filter.limit];
+
+    add_colony(user_id: u64, params: AddColonyParams) -> () [user_id];
+    get_colony_status(user_id: u64) -> Vec<ColonyStatus> [user_id];
+    update_colony_count(colony_id: i64, adjustment: i32, user_id: u64) -> Change
+        [colony_id, adjustment, user_id];
+
+    get_changes(user_id: u64, offset: u32, limit: u32) -> Vec<Change> [user_id, offset, limit];
+    undo_change(user_id: u64, change_id: i64) -> Change [user_id, change_id];
+
+    create_maintenance_record(record: MaintenanceRecord) -> i64
+        [user_id = record.user_id, enclosure_id = record.enclosure_id];
+    get_maintenance_history(enclosure_id: i64, user_id: u64) -> Vec<MaintenanceRecord>
+        [enclosure_id, user_id];
+    get_maintenance_tasks(user_id: u64) -> Vec<MaintenanceTask> [user_id];
+
+    create_enclosure(enclosure: Enclosure) -> i64 [user_id = enclosure.user_id];
+    get_enclosure(id: i64, user_id: u64) -> Enclosure [id, user_id];
+    get_enclosures(user_id: u64) -> Vec<Enclosure> [user_id];
+
+    ensure_user_exists(user: &TelegramUser) -> () [user_id = user.telegram_id];
+    get_timezone(user_id: u64) -> Timezone [user_id];
+    set_timezone(user_id: u64, timezone: Timezone) -> () [user_id];
+
+    record_pairing(user_id: u64, params: AddPairingParams) -> i64
+        [user_id, female_id = params.female_id, male_id = params.male_id];
+    update_pairing_outcome(user_id: u64, pairing_id: i64, outcome: PairingOutcome) -> ()
+        [user_id, pairing_id, outcome = tracing::field::debug(outcome)];
+    get_pairings(user_id: u64) -> Vec<PairingRecord> [user_id];
+
+    record_egg_sac(user_id: u64, params: AddEggSacParams) -> i64
+        [user_id, pairing_id = params.pairing_id];
+    update_egg_sac_status(user_id: u64, egg_sac_id: i64, status: EggSacStatus) -> ()
+        [user_id, egg_sac_id, status = tracing::field::debug(status)];
+    update_egg_sac_counts(user_id: u64, egg_sac_id: i64, counts: EggSacCounts) -> ()
+        [user_id, egg_sac_id];
+    get_egg_sacs(user_id: u64) -> Vec<EggSacRecord> [user_id];
+    get_egg_sacs_due_pulling(user_id: u64, within_days: i64) -> Vec<EggSacRecord>
+        [user_id, within_days];
+    create_slings_from_egg_sac(
+        user_id: u64,
+        egg_sac_id: i64,
+        count: i32,
+        name_prefix: &str
+    ) -> Vec<i64> [user_id, egg_sac_id, count];
+
+    set_tarantula_parents(user_id: u64, tarantula_id: i64, mother: Parent, father: Parent) -> ()
+        [user_id, tarantula_id];
+    get_ancestors(user_id: u64, tarantula_id: i64, max_depth: i32) -> Vec<LineageNode>
+        [user_id, tarantula_id, max_depth];
+    get_descendants(user_id: u64, tarantula_id: i64, max_depth: i32) -> Vec<LineageNode>
+        [user_id, tarantula_id, max_depth];
+
+    create_group(user_id: u64, params: CreateGroupParams) -> i64
+        [user_id, species_id = params.species_id, count = params.count];
+    get_groups(user_id: u64) -> Vec<GroupSummary> [user_id];
+    get_group_members(user_id: u64, group_id: i64) -> Vec<TarantulaListItem> [user_id, group_id];
+    record_group_feeding(
+        user_id: u64,
+        group_id: i64,
+        colony_id: i64,
+        crickets_per_member: i32,
+        at: DbDateTime
+    ) -> Change [user_id, group_id, colony_id, crickets_per_member];
+    record_group_health_check(
+        user_id: u64,
+        group_id: i64,
+        status: HealthStatus,
+        at: DbDateTime
+    ) -> Change [user_id, group_id, status = tracing::field::debug(status)];
+    record_group_molt(
+        user_id: u64,
+        group_id: i64,
+        length_cm: Option<f32>,
+        at: DbDateTime
+    ) -> Change [user_id, group_id, length_cm];
+    split_from_group(user_id: u64, tarantula_id: i64, new_name: Option<String>) -> ()
+        [user_id, tarantula_id];
+
+    get_species() -> Vec<TarantulaSpecies> [];
+    import_collection(user_id: u64, plan: &ImportPlan) -> ()
+        [
+            user_id,
+            tarantulas = plan.tarantulas.len(),
+            feedings = plan.feedings.len(),
+            molts = plan.molts.len()
+        ];
+    ping() -> () [];
+    backup_to(path: &Path) -> () [path = tracing::field::display(path.display())];
+    restore_from(path: &Path) -> () [path = tracing::field::display(path.display())];
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::memory::InMemoryDB;
+    use crate::models::import::ImportedTarantula;
+    use chrono::NaiveDate;
+    use std::io;
+    use std::sync::Mutex;
+    use tracing_subscriber::fmt::format::FmtSpan;
+    use tracing_subscriber::fmt::MakeWriter;
+
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'a> MakeWriter<'a> for Captured {
+        type Writer = Captured;
+
+        fn make_writer(&'a self) -> Self::Writer {
+            self.clone()
+        }
+    }
+
+    #[tokio::test]
+    async fn failed_calls_are_logged_in_a_span_with_their_ids() {
+        let captured = Captured::default();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(captured.clone())
+            .with_ansi(false)
+            .finish();
+        let _default = tracing::subscriber::set_default(subscriber);
+
+        let metrics = Arc::new(Metrics::new());
+        let db = MeteredDB::new(Arc::new(InMemoryDB::new()), metrics.clone());
+        assert!(db.get_tarantula_by_id(7, 99).await.is_err());
+
+        let logs = String::from_utf8(captured.0.lock().unwrap().clone()).unwrap();
+        assert!(
+            logs.contains("db{method=\"get_tarantula_by_id\" user_id=7 id=99}: "),
+            "{}",
+            logs
+        );
+        assert!(logs.contains("Storage call failed"), "{}", logs);
+        assert!(metrics.render(None).contains(
+            "spider_bot_db_query_duration_seconds_count{method=\"get_tarantula_by_id\"} 1"
+        ));
+    }
+
+    #[tokio::test]
+    async fn spans_carry_ids_and_sizes_but_no_names() {
+        let captured = Captured::default();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(captured.clone())
+            .with_ansi(false)
+            .with_span_events(FmtSpan::CLOSE)
+            .finish();
+        let _default = tracing::subscriber::set_default(subscriber);
+
+        let db = MeteredDB::new(Arc::new(InMemoryDB::new()), Arc::new(Metrics::new()));
+        let user = TelegramUser {
+            telegram_id: 7,
+            username: Some("rosie_keeper".to_string()),
+            first_name: "Alice".to_string(),
+            last_name: Some("Liddell".to_string()),
+        };
+        db.ensure_user_exists(&user).await.unwrap();
+        let plan = ImportPlan {
+            tarantulas: vec![ImportedTarantula {
+                name: "Rosie".to_string(),
+                species_id: 1,
+                acquisition_date: NaiveDate::from_ymd_opt(2020, 5, 1).unwrap(),
+                estimated_age_months: None,
+                enclosure_number: None,
+                notes: Some("Bought from Bob".to_string()),
+            }],
+            ..ImportPlan::default()
+        };
+        let _ = db.import_collection(7, &plan).await;
+
+        let logs = String::from_utf8(captured.0.lock().unwrap().clone()).unwrap();
+        assert!(
+            logs.contains("db{method=\"ensure_user_exists\" user_id=7}"),
+            "{}",
+            logs
+        );
+        assert!(
+            logs.contains(
+                "db{method=\"import_collection\" user_id=7 tarantulas=1 feedings=0 molts=0}"
+            ),
+            "{}",
+            logs
+        );
+        for private in ["rosie_keeper", "Alice", "Liddell", "Rosie", "Bob"] {
+            assert!(!logs.contains(private), "{} in {}", private, logs);
+        }
+    }
+}
@@ -0,0 +1,250 @@
+//! The schema scripts from infra/sql, embedded so a binary can bring a
+//! database file up to date by itself. The number of the last applied script
+//! is kept in SQLite's `user_version`.
+
+use crate::BotResult;
+use rusqlite::Connection;
+
+pub struct Migration {
+    pub version: u32,
+    pub name: &'static str,
+    pub sql: &'static str,
+}
+
+pub(crate) const SPECIES: &str = include_str!("../../infra/sql/0002_species.sql");
+pub(crate) const SPECIES_FEEDING: &str = include_str!("../../infra/sql/0003_species_feeding.sql");
+
+/// In the order they are applied, `version` counting up from 1.
+pub const MIGRATIONS: [Migration; 11] = [
+    Migration {
+        version: 1,
+        name: "0001_init",
+        sql: include_str!("../../infra/sql/0001_init.sql"),
+    },
+    Migration {
+        version: 2,
+        name: "0002_species",
+        sql: SPECIES,
+    },
+    Migration {
+        version: 3,
+        name: "0003_species_feeding",
+        sql: SPECIES_FEEDING,
+    },
+    Migration {
+        version: 4,
+        name: "0004_breeding",
+        sql: include_str!("../../infra/sql/0004_breeding.sql"),
+    },
+    Migration {
+        version: 5,
+        name: "0005_lineage",
+        sql: include_str!("../../infra/sql/0005_lineage.sql"),
+    },
+    Migration {
+        version: 6,
+        name: "0006_tarantula_groups",
+        sql: include_str!("../../infra/sql/0006_tarantula_groups.sql"),
+    },
+    Migration {
+        version: 7,
+        name: "0007_feeding_overrides",
+        sql: include_str!("../../infra/sql/0007_feeding_overrides.sql"),
+    },
+    Migration {
+        version: 8,
+        name: "0008_tarantula_photos",
+        sql: include_str!("../../infra/sql/0008_tarantula_photos.sql"),
+    },
+    Migration {
+        version: 9,
+        name: "0009_tarantula_aliases",
+        sql: include_str!("../../infra/sql/0009_tarantula_aliases.sql"),
+    },
+    Migration {
+        version: 10,
+        name: "0010_audit_log",
+        sql: include_str!("../../infra/sql/0010_audit_log.sql"),
+    },
+    Migration {
+        version: 11,
+        name: "0011_timezones",
+        sql: include_str!("../../infra/sql/0011_timezones.sql"),
+    },
+];
+
+/// Databases set up by running the scripts with sqlite3 have no version.
+/// The newest of these that holds tells how far they got; the data scripts
+/// 0002 and 0003 are safe to run again.
+const UNVERSIONED_PROBES: [(u32, &str); 9] = [
+    (
+        11,
+        "SELECT count(*) FROM pragma_table_info('telegram_users') WHERE name = 'timezone'",
+    ),
+    (
+        10,
+        "SELECT count(*) FROM sqlite_master WHERE type = 'table' AND name = 'audit_log'",
+    ),
+    (
+        9,
+        "SELECT count(*) FROM pragma_table_info('tarantulas') WHERE name = 'aliases'",
+    ),
+    (
+        8,
+        "SELECT count(*) FROM sqlite_master WHERE type = 'table' AND name = 'tarantula_photos'",
+    ),
+    (
+        7,
+        "SELECT count(*) FROM sqlite_master WHERE type = 'table' AND name = 'feeding_overrides'",
+    ),
+    (
+        6,
+        "SELECT count(*) FROM sqlite_master WHERE type = 'table' AND name = 'tarantula_groups'",
+    ),
+    (
+        5,
+        "SELECT count(*) FROM pragma_table_info('tarantulas') WHERE name = 'mother_id'",
+    ),
+    (
+        4,
+        "SELECT count(*) FROM sqlite_master WHERE type = 'table' AND name = 'breeding_pairings'",
+    ),
+    (
+        1,
+        "SELECT count(*) FROM sqlite_master WHERE type = 'table' AND name = 'tarantulas'",
+    ),
+];
+
+pub fn schema_version(conn: &Connection) -> BotResult<u32> {
+    Ok(conn.query_row("PRAGMA user_version", [], |row| row.get(0))?)
+}
+
+/// Whether the database is missing any of [`MIGRATIONS`].
+pub fn is_pending(conn: &Connection) -> BotResult<bool> {
+    Ok(schema_version(conn)? < MIGRATIONS.len() as u32)
+}
+
+/// Applies the migrations the database hasn't seen, each in its own
+/// transaction, and returns their names. An unversioned database that already
+/// has tables is stamped with what it contains first.
+pub fn migrate(conn: &mut Connection) -> BotResult<Vec<&'static str>> {
+    if schema_version(conn)? == 0 {
+        let baseline = unversioned_baseline(conn)?;
+        if baseline > 0 {
+            log::info!("Unversioned database is at migration {}", baseline);
+            conn.pragma_update(None, "user_version", baseline)?;
+        }
+    }
+
+    let current = schema_version(conn)?;
+    let mut applied = Vec::new();
+    for migration in MIGRATIONS.iter().filter(|m| m.version > current) {
+        let tx = conn.transaction()?;
+        tx.execute_batch(migration.sql)?;
+        tx.pragma_update(None, "user_version", migration.version)?;
+        tx.commit()?;
+        log::info!("Applied migration {}", migration.name);
+        applied.push(migration.name);
+    }
+    Ok(applied)
+}
+
+fn unversioned_baseline(conn: &Connection) -> BotResult<u32> {
+    for (version, probe) in UNVERSIONED_PROBES {
+        let found: i64 = conn.query_row(probe, [], |row| row.get(0))?;
+        if found > 0 {
+            return Ok(version);
+        }
+    }
+    Ok(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migrating_twice_applies_everything_once() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        assert_eq!(migrate(&mut conn).unwrap().len(), MIGRATIONS.len());
+        assert!(migrate(&mut conn).unwrap().is_empty());
+        assert!(!is_pending(&conn).unwrap());
+    }
+
+    #[test]
+    fn unversioned_databases_only_get_what_they_lack() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        for migration in &MIGRATIONS[..5] {
+            conn.execute_batch(migration.sql).unwrap();
+        }
+
+        assert_eq!(
+            migrate(&mut conn).unwrap(),
+            vec![
+                "0006_tarantula_groups",
+                "0007_feeding_overrides",
+                "0008_tarantula_photos",
+                "0009_tarantula_aliases",
+                "0010_audit_log",
+                "0011_timezones"
+            ]
+        );
+        assert_eq!(schema_version(&conn).unwrap(), 11);
+    }
+
+    #[test]
+    fn days_and_instants_are_brought_into_one_form() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        for migration in &MIGRATIONS[..10] {
+            conn.execute_batch(migration.sql).unwrap();
+        }
+        conn.execute_batch(
+            "PRAGMA user_version = 10;
+             INSERT INTO telegram_users (telegram_id, first_name) VALUES (1, 'Alice');
+             INSERT INTO molt_stages (id, stage_name) VALUES (1, 'Normal');
+             INSERT INTO health_statuses (id, status_name) VALUES (1, 'Healthy');
+             INSERT INTO feeding_statuses (id, status_name) VALUES (1, 'Accepted');
+             INSERT INTO tarantulas (id, name, species_id, acquisition_date, user_id,
+                                     last_molt_date)
+             VALUES (1, 'Rosie', 1, '2025-01-01', 1, '2025-03-01 18:30:00');
+             INSERT INTO molt_records (tarantula_id, molt_date, molt_stage_id, user_id)
+             VALUES (1, '2025-03-01 18:30:00', 1, 1);
+             INSERT INTO health_check_records (tarantula_id, check_date, health_status_id, user_id)
+             VALUES (1, '2025-03-01 00:00:00', 1, 1);
+             INSERT INTO feeding_events (tarantula_id, feeding_date, number_of_crickets,
+                                         feeding_status_id, user_id)
+             VALUES (1, '2025-03-01T18:30:00.250Z', 2, 1, 1);",
+        )
+        .unwrap();
+
+        assert_eq!(migrate(&mut conn).unwrap(), vec!["0011_timezones"]);
+        let row: (String, String, String, String, String) = conn
+            .query_row(
+                "SELECT t.last_molt_date, m.molt_date, h.check_date, f.feeding_date,
+                        u.timezone
+                 FROM tarantulas t, molt_records m, health_check_records h,
+                      feeding_events f, telegram_users u",
+                [],
+                |row| {
+                    Ok((
+                        row.get(0)?,
+                        row.get(1)?,
+                        row.get(2)?,
+                        row.get(3)?,
+                        row.get(4)?,
+                    ))
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            row,
+            (
+                "2025-03-01".to_string(),
+                "2025-03-01".to_string(),
+                "2025-03-01".to_string(),
+                "2025-03-01 18:30:00".to_string(),
+                "UTC".to_string()
+            )
+        );
+    }
+}
@@ -1,2 +1,40 @@
+#[allow(clippy::module_inception)]
 pub mod db;
 mod init;
+pub mod maintenance;
+pub mod memory;
+pub mod metered;
+pub mod migrations;
+
+#[cfg(test)]
+mod contract_tests;
+
+use crate::config::{Backend, DatabaseConfig};
+use crate::db::db::{TarantulaDB, TarantulaOperations};
+use crate::db::memory::InMemoryDB;
+use crate::error::BotError;
+use crate::schedule::AlertThresholds;
+use crate::BotResult;
+use std::sync::Arc;
+
+/// Opens the configured backend, applying `thresholds` to its alerts.
+pub fn open(
+    config: &DatabaseConfig,
+    thresholds: AlertThresholds,
+) -> BotResult<Arc<dyn TarantulaOperations + Send + Sync>> {
+    match config.backend {
+        Backend::Memory => {
+            log::warn!("Using the in-memory database, nothing will be persisted");
+            Ok(Arc::new(InMemoryDB::new().with_thresholds(thresholds)))
+        }
+        Backend::Sqlite => {
+            let path = config.path.to_str().ok_or_else(|| {
+                BotError::Config(format!(
+                    "database.path is not valid UTF-8: {}",
+                    config.path.display()
+                ))
+            })?;
+            Ok(Arc::new(TarantulaDB::new(path)?.with_thresholds(thresholds)))
+        }
+    }
+}
@@ -24,6 +24,43 @@ pub enum BotError {
     #[error("Operation failed: {0}")]
     OperationError(String),
 
+    #[error("Invalid configuration: {0}")]
+    Config(String),
+
+    #[error("Export failed: {0}")]
+    Export(String),
+
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
     #[error("Dialog failed: {0}")]
     DialogErr(#[from] dialogue::InMemStorageError),
 }
+
+impl BotError {
+    /// A stable label for the variant, for metrics.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            BotError::Database(_) => "database",
+            BotError::DatabasePool(_) => "database_pool",
+            BotError::Telegram(_) => "telegram",
+            BotError::DateParse(_) => "date_parse",
+            BotError::NotFound(_) => "not_found",
+            BotError::ValidationError(_) => "validation",
+            BotError::OperationError(_) => "operation",
+            BotError::Config(_) => "config",
+            BotError::Export(_) => "export",
+            BotError::Io(_) => "io",
+            BotError::DialogErr(_) => "dialogue",
+        }
+    }
+
+    /// Whether the failure is on our side rather than something the keeper
+    /// sent. Only these are worth an operator's attention.
+    pub fn is_internal(&self) -> bool {
+        !matches!(
+            self,
+            BotError::NotFound(_) | BotError::ValidationError(_) | BotError::DateParse(_)
+        )
+    }
+}
@@ -0,0 +1,16 @@
+pub mod app;
+pub mod backup;
+pub mod bot;
+pub mod config;
+pub mod db;
+pub mod error;
+pub mod metrics;
+pub mod models;
+pub mod repl;
+pub mod schedule;
+pub mod telemetry;
+
+use crate::error::BotError;
+use rusqlite::Result;
+
+pub type BotResult<T> = Result<T, BotError>;
@@ -1,25 +1,25 @@
-mod bot;
-mod db;
-mod error;
-mod models;
-
-use crate::bot::bot::TarantulaBot;
-use crate::error::BotError;
-use rusqlite::Result;
-
-pub type BotResult<T> = Result<T, BotError>;
+use spider_bot::bot::bot::TarantulaBot;
+use spider_bot::config::Config;
+use spider_bot::BotResult;
+use std::process::ExitCode;
 
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    env_logger::init();
+async fn main() -> ExitCode {
+    let _telemetry = spider_bot::telemetry::init("spider-bot");
 
-    let token = std::env::var("TELEGRAM_BOT_TOKEN")
-        .map_err(|_| BotError::OperationError("TELEGRAM_BOT_TOKEN not set".to_string()))?;
+    match run().await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            log::error!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
 
-    let bot = TarantulaBot::new(&token);
+async fn run() -> BotResult<()> {
+    let config = Config::load()?;
+    let bot = TarantulaBot::new(&config)?;
 
     log::info!("Starting tarantula management bot...");
-    bot.run().await;
-
-    Ok(())
+    bot.run(config.server).await
 }
@@ -0,0 +1,232 @@
+//! Prometheus metrics, served at `/metrics`. Every [`Metrics`] owns its
+//! registry, so bots started side by side in tests count separately.
+
+use crate::db::db::PoolUsage;
+use crate::error::BotError;
+use prometheus::core::Collector;
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge, IntGaugeVec, Opts,
+    Registry, TextEncoder,
+};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// SQLite answers most queries in well under a millisecond.
+const DB_QUERY_BUCKETS: [f64; 11] = [
+    0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0,
+];
+
+pub struct Metrics {
+    registry: Registry,
+    commands: IntCounterVec,
+    callbacks: IntCounterVec,
+    inline_queries: IntCounter,
+    handler_errors: IntCounterVec,
+    db_queries: HistogramVec,
+    notifications: IntCounterVec,
+    pool_connections: IntGaugeVec,
+    pool_max_connections: IntGauge,
+    dialogues: IntGaugeVec,
+    backups: IntCounterVec,
+    last_backup: IntGauge,
+    /// Which state each chat's open dialogue is in, so a new state or an exit
+    /// moves the right gauge.
+    dialogue_states: Mutex<HashMap<i64, &'static str>>,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        let registry = Registry::new();
+        Self {
+            commands: register(
+                &registry,
+                IntCounterVec::new(
+                    Opts::new("spider_bot_commands_total", "Slash commands handled"),
+                    &["command"],
+                ),
+            ),
+            callbacks: register(
+                &registry,
+                IntCounterVec::new(
+                    Opts::new("spider_bot_callbacks_total", "Button presses handled"),
+                    &["callback"],
+                ),
+            ),
+            inline_queries: register(
+                &registry,
+                IntCounter::new("spider_bot_inline_queries_total", "Inline queries answered"),
+            ),
+            handler_errors: register(
+                &registry,
+                IntCounterVec::new(
+                    Opts::new(
+                        "spider_bot_handler_errors_total",
+                        "Update handlers that failed",
+                    ),
+                    &["kind"],
+                ),
+            ),
+            db_queries: register(
+                &registry,
+                HistogramVec::new(
+                    HistogramOpts::new(
+                        "spider_bot_db_query_duration_seconds",
+                        "Time spent in storage calls",
+                    )
+                    .buckets(DB_QUERY_BUCKETS.to_vec()),
+                    &["method"],
+                ),
+            ),
+            notifications: register(
+                &registry,
+                IntCounterVec::new(
+                    Opts::new(
+                        "spider_bot_notifications_total",
+                        "Scheduled notifications by category and outcome",
+                    ),
+                    &["category", "outcome"],
+                ),
+            ),
+            pool_connections: register(
+                &registry,
+                IntGaugeVec::new(
+                    Opts::new(
+                        "spider_bot_db_pool_connections",
+                        "Open database connections",
+                    ),
+                    &["state"],
+                ),
+            ),
+            pool_max_connections: register(
+                &registry,
+                IntGauge::new(
+                    "spider_bot_db_pool_max_connections",
+                    "Connections the database pool may open",
+                ),
+            ),
+            dialogues: register(
+                &registry,
+                IntGaugeVec::new(
+                    Opts::new(
+                        "spider_bot_dialogues_active",
+                        "Chats waiting for an answer, by dialogue state",
+                    ),
+                    &["state"],
+                ),
+            ),
+            backups: register(
+                &registry,
+                IntCounterVec::new(
+                    Opts::new("spider_bot_backups_total", "Database backups by outcome"),
+                    &["outcome"],
+                ),
+            ),
+            last_backup: register(
+                &registry,
+                IntGauge::new(
+                    "spider_bot_last_backup_timestamp_seconds",
+                    "When the last backup was written, as a Unix timestamp",
+                ),
+            ),
+            dialogue_states: Mutex::new(HashMap::new()),
+            registry,
+        }
+    }
+
+    pub fn command_handled(&self, command: &str) {
+        self.commands.with_label_values(&[command]).inc();
+    }
+
+    pub fn callback_handled(&self, callback: &str) {
+        self.callbacks.with_label_values(&[callback]).inc();
+    }
+
+    pub fn inline_query_handled(&self) {
+        self.inline_queries.inc();
+    }
+
+    pub fn handler_failed(&self, error: &BotError) {
+        self.handler_errors.with_label_values(&[error.kind()]).inc();
+    }
+
+    pub fn db_query(&self, method: &str, elapsed: Duration) {
+        self.db_queries
+            .with_label_values(&[method])
+            .observe(elapsed.as_secs_f64());
+    }
+
+    pub fn notification(&self, category: &str, sent: bool) {
+        let outcome = if sent { "sent" } else { "failed" };
+        self.notifications
+            .with_label_values(&[category, outcome])
+            .inc();
+    }
+
+    pub fn backup(&self, written: bool) {
+        let outcome = if written { "written" } else { "failed" };
+        self.backups.with_label_values(&[outcome]).inc();
+        if written {
+            self.last_backup.set(chrono::Utc::now().timestamp());
+        }
+    }
+
+    pub fn dialogue_entered(&self, chat_id: i64, state: &'static str) {
+        let mut states = self.dialogue_states();
+        if let Some(previous) = states.insert(chat_id, state) {
+            self.dialogues.with_label_values(&[previous]).dec();
+        }
+        self.dialogues.with_label_values(&[state]).inc();
+    }
+
+    pub fn dialogue_exited(&self, chat_id: i64) {
+        if let Some(previous) = self.dialogue_states().remove(&chat_id) {
+            self.dialogues.with_label_values(&[previous]).dec();
+        }
+    }
+
+    fn dialogue_states(&self) -> std::sync::MutexGuard<'_, HashMap<i64, &'static str>> {
+        // The map only mirrors the gauges, a panic mid-update can't corrupt it.
+        self.dialogue_states
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Renders every metric in the Prometheus text format. Pool gauges are
+    /// read at scrape time; backends without a pool leave them at zero.
+    pub fn render(&self, pool: Option<PoolUsage>) -> String {
+        if let Some(pool) = pool {
+            let active = pool.connections.saturating_sub(pool.idle);
+            self.pool_connections
+                .with_label_values(&["active"])
+                .set(active.into());
+            self.pool_connections
+                .with_label_values(&["idle"])
+                .set(pool.idle.into());
+            self.pool_max_connections.set(pool.max.into());
+        }
+
+        let mut buffer = Vec::new();
+        if let Err(e) = TextEncoder::new().encode(&self.registry.gather(), &mut buffer) {
+            log::error!("Failed to encode metrics: {}", e);
+        }
+        String::from_utf8(buffer).unwrap_or_default()
+    }
+}
+
+fn register<M: Collector + Clone + 'static>(
+    registry: &Registry,
+    metric: prometheus::Result<M>,
+) -> M {
+    let metric = metric.expect("metric options are valid");
+    registry
+        .register(Box::new(metric.clone()))
+        .expect("metric names are unique");
+    metric
+}
@@ -0,0 +1,135 @@
+use crate::models::user::Timezone;
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// The writes the audit log keeps, stored by id.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Feeding = 1,
+    Refusal = 2,
+    HealthCheck = 3,
+    Molt = 4,
+    ColonyCount = 5,
+    GroupFeeding = 6,
+    GroupHealthCheck = 7,
+    GroupMolt = 8,
+}
+
+impl ChangeKind {
+    pub fn from_id(id: i64) -> Option<ChangeKind> {
+        match id {
+            1 => Some(ChangeKind::Feeding),
+            2 => Some(ChangeKind::Refusal),
+            3 => Some(ChangeKind::HealthCheck),
+            4 => Some(ChangeKind::Molt),
+            5 => Some(ChangeKind::ColonyCount),
+            6 => Some(ChangeKind::GroupFeeding),
+            7 => Some(ChangeKind::GroupHealthCheck),
+            8 => Some(ChangeKind::GroupMolt),
+            _ => None,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ChangeKind::Feeding => "Feeding",
+            ChangeKind::Refusal => "Refused meal",
+            ChangeKind::HealthCheck => "Health check",
+            ChangeKind::Molt => "Molt",
+            ChangeKind::ColonyCount => "Colony count",
+            ChangeKind::GroupFeeding => "Group feeding",
+            ChangeKind::GroupHealthCheck => "Group health check",
+            ChangeKind::GroupMolt => "Group molt",
+        }
+    }
+
+    pub fn emoji(self) -> &'static str {
+        match self {
+            ChangeKind::Feeding | ChangeKind::GroupFeeding => "🍽",
+            ChangeKind::Refusal => "🚫",
+            ChangeKind::HealthCheck | ChangeKind::GroupHealthCheck => "🏥",
+            ChangeKind::Molt | ChangeKind::GroupMolt => "🐚",
+            ChangeKind::ColonyCount => "🦗",
+        }
+    }
+}
+
+/// The parts of a tarantula that a logged write can move.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct TarantulaState {
+    pub molt_stage_id: Option<i64>,
+    pub last_molt_date: Option<NaiveDate>,
+    pub health_status_id: Option<i64>,
+    pub last_health_check_date: Option<NaiveDate>,
+}
+
+impl TarantulaState {
+    /// Puts back what went from `before` to `after`, leaving the fields that
+    /// change didn't touch as they are now.
+    pub fn reverted(self, before: &TarantulaState, after: &TarantulaState) -> TarantulaState {
+        let mut reverted = self;
+        if (before.molt_stage_id, before.last_molt_date)
+            != (after.molt_stage_id, after.last_molt_date)
+        {
+            reverted.molt_stage_id = before.molt_stage_id;
+            reverted.last_molt_date = before.last_molt_date;
+        }
+        if (before.health_status_id, before.last_health_check_date)
+            != (after.health_status_id, after.last_health_check_date)
+        {
+            reverted.health_status_id = before.health_status_id;
+            reverted.last_health_check_date = before.last_health_check_date;
+        }
+        reverted
+    }
+}
+
+/// Everything one write touched, as it stood on one side of it. Stored as
+/// JSON, so fields can be added as long as they default.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ChangeState {
+    /// Crickets in each colony, by colony id.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub colonies: BTreeMap<i64, i32>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tarantulas: BTreeMap<i64, TarantulaState>,
+    /// Records the write added, so only ever set after it.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub feedings: Vec<i64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub health_checks: Vec<i64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub molts: Vec<i64>,
+}
+
+/// One entry in a keeper's audit log.
+#[derive(Debug, Clone)]
+pub struct Change {
+    pub id: i64,
+    pub kind: ChangeKind,
+    pub before: ChangeState,
+    pub after: ChangeState,
+    pub changed_at: NaiveDateTime,
+    pub undone_at: Option<NaiveDateTime>,
+}
+
+impl Change {
+    /// The change with its times, stored in UTC, on the keeper's clock.
+    pub fn in_timezone(self, timezone: Timezone) -> Change {
+        Change {
+            changed_at: timezone.local(self.changed_at),
+            undone_at: self.undone_at.map(|at| timezone.local(at)),
+            ..self
+        }
+    }
+
+    /// How many crickets each colony gains by undoing this, skipping the
+    /// ones it didn't move.
+    pub fn colony_refunds(&self) -> impl Iterator<Item = (i64, i32)> + '_ {
+        self.before.colonies.iter().filter_map(|(&id, &before)| {
+            let after = self.after.colonies.get(&id).copied().unwrap_or(before);
+            (before != after).then_some((id, before - after))
+        })
+    }
+}
@@ -0,0 +1,42 @@
+use chrono::NaiveDate;
+use serde::Serialize;
+
+/// Days between a sac being laid and the usual pulling date when no explicit date is given.
+pub const DEFAULT_INCUBATION_DAYS: i64 = 45;
+
+#[derive(Debug, Serialize, Clone)]
+pub struct PairingRecord {
+    pub id: i64,
+    pub female_id: i64,
+    pub female_name: String,
+    pub male_id: i64,
+    pub male_name: String,
+    pub pairing_date: NaiveDate,
+    pub outcome: String,
+    pub egg_sac_count: i32,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct EggSacRecord {
+    pub id: i64,
+    pub pairing_id: i64,
+    pub female_name: String,
+    pub male_name: String,
+    pub laid_date: NaiveDate,
+    pub expected_pull_date: Option<NaiveDate>,
+    pub pulled_date: Option<NaiveDate>,
+    pub status: String,
+    pub egg_count: Option<i32>,
+    pub nymph_count: Option<i32>,
+    pub sling_count: Option<i32>,
+    pub slings_created: i32,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct EggSacCounts {
+    pub egg_count: Option<i32>,
+    pub nymph_count: Option<i32>,
+    pub sling_count: Option<i32>,
+}
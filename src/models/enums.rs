@@ -1,6 +1,6 @@
 use serde::Serialize;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum HealthStatus {
     Healthy = 1,
     Monitor = 2,
@@ -8,7 +8,7 @@ pub enum HealthStatus {
 }
 
 impl HealthStatus {
-    pub fn to_db_name(&self) -> &'static str {
+    pub fn to_db_name(self) -> &'static str {
         match self {
             HealthStatus::Healthy => "Healthy",
             HealthStatus::Monitor => "Monitor",
@@ -31,14 +31,6 @@ impl HealthStatus {
             _ => HealthStatus::Healthy,
         }
     }
-    
-    pub fn to_id(&self) -> usize {
-        match self {
-            HealthStatus::Healthy => 1,
-            HealthStatus::Monitor => 2,
-            HealthStatus::Critical => 3,
-        }
-    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -52,7 +44,7 @@ pub enum FeedingStatus {
 }
 
 impl FeedingStatus {
-    pub fn to_db_name(&self) -> &'static str {
+    pub fn to_db_name(self) -> &'static str {
         match self {
             FeedingStatus::Accepted => "Accepted",
             FeedingStatus::Rejected => "Rejected",
@@ -75,7 +67,7 @@ impl FeedingStatus {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MoltStage {
     Normal = 1,
     PreMolt = 2,
@@ -85,7 +77,7 @@ pub enum MoltStage {
 }
 
 impl MoltStage {
-    pub fn to_db_name(&self) -> &'static str {
+    pub fn to_db_name(self) -> &'static str {
         match self {
             MoltStage::Normal => "Normal",
             MoltStage::PreMolt => "Pre-molt",
@@ -104,9 +96,19 @@ impl MoltStage {
             MoltStage::Failed => "Experiencing molt complications",
         }
     }
+
+    pub fn from_id(id: i64) -> MoltStage {
+        match id {
+            2 => MoltStage::PreMolt,
+            3 => MoltStage::Molting,
+            4 => MoltStage::PostMolt,
+            5 => MoltStage::Failed,
+            _ => MoltStage::Normal,
+        }
+    }
 }
 
-#[derive(Debug, Clone, Copy, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub enum CricketSize {
     Pinhead = 1,
     Small = 2,
@@ -117,7 +119,7 @@ pub enum CricketSize {
 }
 
 impl CricketSize {
-    pub fn to_db_name(&self) -> &'static str {
+    pub fn to_db_name(self) -> &'static str {
         match self {
             CricketSize::Pinhead => "Pinhead",
             CricketSize::Small => "Small",
@@ -138,4 +140,69 @@ impl CricketSize {
             CricketSize::Unknown => 0.0,
         }
     }
-}
\ No newline at end of file
+}
+#[derive(Debug, Clone, Copy)]
+pub enum PairingOutcome {
+    Pending = 1,
+    Successful = 2,
+    NoInterest = 3,
+    Aggression = 4,
+    MaleKilled = 5,
+}
+
+impl PairingOutcome {
+    pub fn to_db_name(self) -> &'static str {
+        match self {
+            PairingOutcome::Pending => "Pending",
+            PairingOutcome::Successful => "Successful",
+            PairingOutcome::NoInterest => "No interest",
+            PairingOutcome::Aggression => "Aggression",
+            PairingOutcome::MaleKilled => "Male killed",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            PairingOutcome::Pending => "Pairing logged, outcome not known yet",
+            PairingOutcome::Successful => "Insertion observed or egg sac produced",
+            PairingOutcome::NoInterest => "One or both spiders showed no interest",
+            PairingOutcome::Aggression => "Pairing aborted due to aggression",
+            PairingOutcome::MaleKilled => "Female killed the male",
+        }
+    }
+
+    pub fn from_id(id: i64) -> PairingOutcome {
+        match id {
+            2 => PairingOutcome::Successful,
+            3 => PairingOutcome::NoInterest,
+            4 => PairingOutcome::Aggression,
+            5 => PairingOutcome::MaleKilled,
+            _ => PairingOutcome::Pending,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum EggSacStatus {
+    Incubating = 1,
+    Pulled = 2,
+    Failed = 3,
+}
+
+impl EggSacStatus {
+    pub fn to_db_name(self) -> &'static str {
+        match self {
+            EggSacStatus::Incubating => "Incubating",
+            EggSacStatus::Pulled => "Pulled",
+            EggSacStatus::Failed => "Failed",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            EggSacStatus::Incubating => "Sac is still with the mother",
+            EggSacStatus::Pulled => "Sac was pulled for artificial incubation",
+            EggSacStatus::Failed => "Sac was eaten, dropped or turned out infertile",
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use crate::models::models::DbDateTime;
+use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -16,8 +17,25 @@ pub struct FeedingEvent {
 pub struct FeedingRecord {
     pub tarantula_name: String,
     pub feeding_date: String,
-    pub colony_name: String,
+    /// None for refusals and feedings imported from elsewhere.
+    pub colony_name: Option<String>,
     pub number_of_crickets: i32,
     pub status: String,
     pub notes: Option<String>,
 }
+
+/// A keeper-set replacement for the species schedule of one tarantula.
+/// Unset fields fall back to the species schedule.
+#[derive(Debug, Serialize, Clone)]
+pub struct FeedingOverride {
+    pub tarantula_id: i64,
+    pub frequency_id: Option<i64>,
+    pub frequency_name: Option<String>,
+    pub min_days: Option<i32>,
+    pub max_days: Option<i32>,
+    pub prey_count: Option<i32>,
+    pub prey_size_id: Option<i64>,
+    pub prey_size: Option<String>,
+    pub expires_on: Option<NaiveDate>,
+    pub notes: Option<String>,
+}
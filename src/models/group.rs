@@ -0,0 +1,43 @@
+use crate::models::tarantula::TarantulaListItem;
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+/// Upper bound for slings created or fed in one bulk action.
+pub const MAX_GROUP_SIZE: i32 = 500;
+
+#[derive(Debug, Serialize, Clone)]
+pub struct GroupSummary {
+    pub id: i64,
+    pub name: String,
+    pub species_id: i64,
+    pub species_name: String,
+    pub acquisition_date: NaiveDate,
+    pub member_count: i32,
+    pub days_since_feeding: Option<f64>,
+    pub notes: Option<String>,
+}
+
+/// Folds group members into a single entry per group, named after the group
+/// with its member count. The first member of each group stands in for the rest.
+pub fn collapse_groups(items: Vec<TarantulaListItem>) -> Vec<TarantulaListItem> {
+    let mut counts: HashMap<i64, usize> = HashMap::new();
+    for group_id in items.iter().filter_map(|t| t.group_id) {
+        *counts.entry(group_id).or_default() += 1;
+    }
+
+    let mut seen = HashSet::new();
+    items
+        .into_iter()
+        .filter_map(|mut t| match (t.group_id, t.group_name.clone()) {
+            (Some(group_id), Some(group_name)) => {
+                if !seen.insert(group_id) {
+                    return None;
+                }
+                t.name = format!("{} ({} slings)", group_name, counts[&group_id]);
+                Some(t)
+            }
+            _ => Some(t),
+        })
+        .collect()
+}
@@ -0,0 +1,66 @@
+use crate::models::enums::FeedingStatus;
+use chrono::{NaiveDate, NaiveDateTime};
+use std::fmt;
+
+/// Which tarantula an imported feeding or molt belongs to: one already in the
+/// collection, or one of the plan's new tarantulas by position.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImportTarget {
+    Existing(i64),
+    New(usize),
+}
+
+#[derive(Debug, Clone)]
+pub struct ImportedTarantula {
+    pub name: String,
+    pub species_id: i64,
+    pub acquisition_date: NaiveDate,
+    pub estimated_age_months: Option<i32>,
+    pub enclosure_number: Option<String>,
+    pub notes: Option<String>,
+}
+
+/// A past feeding. Imported feedings take no crickets from a colony.
+#[derive(Debug, Clone)]
+pub struct ImportedFeeding {
+    pub tarantula: ImportTarget,
+    pub fed_at: NaiveDateTime,
+    pub colony_id: Option<i64>,
+    pub number_of_crickets: i32,
+    pub status: FeedingStatus,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ImportedMolt {
+    pub tarantula: ImportTarget,
+    pub molt_date: NaiveDate,
+    pub length_cm: Option<f32>,
+    pub complications: Option<String>,
+    pub notes: Option<String>,
+}
+
+/// Everything an import creates, checked and ready to be written at once.
+#[derive(Clone, Default)]
+pub struct ImportPlan {
+    pub tarantulas: Vec<ImportedTarantula>,
+    pub feedings: Vec<ImportedFeeding>,
+    pub molts: Vec<ImportedMolt>,
+}
+
+impl ImportPlan {
+    pub fn is_empty(&self) -> bool {
+        self.tarantulas.is_empty() && self.feedings.is_empty() && self.molts.is_empty()
+    }
+}
+
+/// Counts only, plans can hold years of history.
+impl fmt::Debug for ImportPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ImportPlan")
+            .field("tarantulas", &self.tarantulas.len())
+            .field("feedings", &self.feedings.len())
+            .field("molts", &self.molts.len())
+            .finish()
+    }
+}
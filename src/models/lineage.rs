@@ -0,0 +1,81 @@
+use serde::Serialize;
+use std::collections::HashSet;
+
+/// How many generations back two tarantulas are compared before a pairing.
+pub const CLOSE_ANCESTRY_GENERATIONS: i32 = 3;
+
+#[derive(Debug, Clone)]
+pub enum Parent {
+    Unknown,
+    Internal(i64),
+    External(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum ParentRole {
+    Mother,
+    Father,
+}
+
+impl ParentRole {
+    pub fn symbol(self) -> &'static str {
+        match self {
+            ParentRole::Mother => "♀",
+            ParentRole::Father => "♂",
+        }
+    }
+}
+
+/// One edge of a pedigree. For ancestors `relative_id` is the child the node is a
+/// parent of, for descendants it is the parent the node descends from.
+#[derive(Debug, Clone, Serialize)]
+pub struct LineageNode {
+    pub id: Option<i64>,
+    pub name: String,
+    pub relative_id: i64,
+    pub role: ParentRole,
+    pub depth: i32,
+}
+
+impl LineageNode {
+    pub fn is_external(&self) -> bool {
+        self.id.is_none()
+    }
+}
+
+/// Returns the names of in-collection tarantulas that appear in both pedigrees,
+/// counting each tarantula itself so parent/offspring pairings are caught too.
+pub fn shared_ancestors(
+    first_id: i64,
+    first: &[LineageNode],
+    second_id: i64,
+    second: &[LineageNode],
+) -> Vec<String> {
+    let first_ids: HashSet<i64> = first
+        .iter()
+        .filter_map(|n| n.id)
+        .chain(std::iter::once(first_id))
+        .collect();
+
+    let mut seen = HashSet::new();
+    let mut shared = Vec::new();
+    for (id, name) in second
+        .iter()
+        .filter_map(|n| n.id.map(|id| (id, n.name.clone())))
+        .chain(std::iter::once((second_id, String::new())))
+    {
+        if first_ids.contains(&id) && seen.insert(id) {
+            let name = if name.is_empty() {
+                first
+                    .iter()
+                    .find(|n| n.id == Some(id))
+                    .map(|n| n.name.clone())
+                    .unwrap_or_else(|| format!("#{}", id))
+            } else {
+                name
+            };
+            shared.push(name);
+        }
+    }
+    shared
+}
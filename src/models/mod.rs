@@ -1,6 +1,12 @@
+pub mod audit;
+pub mod breeding;
 pub mod cricket;
 pub mod feeding;
+pub mod group;
 pub mod health;
+pub mod import;
+pub mod lineage;
+#[allow(clippy::module_inception)]
 pub mod models;
 pub mod molt;
 pub mod tarantula;
@@ -1,9 +1,19 @@
 use chrono::{DateTime, NaiveDateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct DbDateTime(DateTime<Utc>);
 
+impl DbDateTime {
+    pub fn from_naive_utc(naive: NaiveDateTime) -> Self {
+        DbDateTime(DateTime::from_naive_utc_and_offset(naive, Utc))
+    }
+
+    pub fn naive_utc(&self) -> NaiveDateTime {
+        self.0.naive_utc()
+    }
+}
+
 impl Default for DbDateTime {
     fn default() -> Self {
         DbDateTime(Utc::now())
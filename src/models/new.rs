@@ -1,8 +1,7 @@
-use crate::models::models::DbDateTime;
 use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Enclosure {
     pub id: Option<i64>,
     pub name: String,
@@ -14,7 +13,7 @@ pub struct Enclosure {
     pub user_id: i64,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FeedingSchedule {
     pub species_id: i64,
     pub size_category: String,
@@ -26,7 +25,7 @@ pub struct FeedingSchedule {
     pub frequency_id: Option<i64>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FeedingFrequency {
     pub id: i64,
     pub frequency_name: String,
@@ -35,7 +34,7 @@ pub struct FeedingFrequency {
     pub description: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MaintenanceRecord {
     pub id: Option<i64>,
     pub enclosure_id: i64,
@@ -45,57 +44,3 @@ pub struct MaintenanceRecord {
     pub notes: Option<String>,
     pub user_id: i64,
 }
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct Tarantula {
-    pub id: i64,
-    pub name: String,
-    pub species_id: i64,
-    pub acquisition_date: NaiveDate,
-    pub last_molt_date: Option<NaiveDate>,
-    pub estimated_age_months: Option<i32>,
-    pub current_molt_stage_id: Option<i64>,
-    pub current_health_status_id: Option<i64>,
-    pub last_health_check_date: Option<NaiveDate>,
-    pub enclosure_number: Option<String>,
-    pub notes: Option<String>,
-    pub user_id: i64,
-    pub enclosure_id: Option<i64>,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct CricketColony {
-    pub id: Option<i64>,
-    pub colony_name: String,
-    pub size_type_id: i64,
-    pub current_count: i32,
-    pub last_count_date: NaiveDate,
-    pub container_number: String,
-    pub notes: Option<String>,
-    pub user_id: i64, 
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct FeedingEvent {
-    pub id: Option<i64>,
-    pub tarantula_id: i64,
-    pub feeding_date: DbDateTime,
-    pub cricket_colony_id: i64,
-    pub number_of_crickets: i32,
-    pub feeding_status_id: i64,
-    pub notes: Option<String>,
-    pub user_id: i64,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct HealthCheckRecord {
-    pub id: Option<i64>,
-    pub tarantula_id: i64,
-    pub check_date: NaiveDate,
-    pub health_status_id: i64,
-    pub weight_grams: Option<f32>,
-    pub humidity_percent: Option<i32>,
-    pub temperature_celsius: Option<f32>,
-    pub notes: Option<String>,
-    pub user_id: i64,
-}
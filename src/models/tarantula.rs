@@ -1,5 +1,4 @@
 use chrono::NaiveDate;
-use rusqlite::Row;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -14,7 +13,14 @@ pub struct Tarantula {
     pub current_health_status_id: Option<i64>,
     pub last_health_check_date: Option<NaiveDate>,
     pub enclosure_number: Option<String>,
+    pub enclosure_id: Option<i64>,
     pub notes: Option<String>,
+    /// Other names the keeper calls it by, comma separated.
+    pub aliases: Option<String>,
+    pub mother_id: Option<i64>,
+    pub father_id: Option<i64>,
+    pub mother_external: Option<String>,
+    pub father_external: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -25,6 +31,8 @@ pub struct TarantulaListItem {
     pub enclosure_number: Option<String>,
     pub days_since_feeding: Option<f64>,
     pub current_status: String,
+    pub group_id: Option<i64>,
+    pub group_name: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -36,3 +44,61 @@ pub struct MaintenanceTask {
     pub required_action: String,
     pub priority: i32,
 }
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TarantulaSpecies {
+    pub id: i64,
+    pub scientific_name: String,
+    pub common_name: Option<String>,
+}
+
+/// A picture of a tarantula, kept as the Telegram file it was sent as.
+#[derive(Debug, Clone, Serialize)]
+pub struct TarantulaPhoto {
+    pub id: i64,
+    pub tarantula_id: i64,
+    pub file_id: String,
+    pub caption: Option<String>,
+    pub taken_at: String,
+}
+
+/// The details a keeper can change from a tarantula's profile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TarantulaField {
+    Name = 1,
+    Enclosure = 2,
+    Notes = 3,
+    Aliases = 4,
+}
+
+impl TarantulaField {
+    pub fn from_id(id: u8) -> Option<TarantulaField> {
+        match id {
+            1 => Some(TarantulaField::Name),
+            2 => Some(TarantulaField::Enclosure),
+            3 => Some(TarantulaField::Notes),
+            4 => Some(TarantulaField::Aliases),
+            _ => None,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            TarantulaField::Name => "Name",
+            TarantulaField::Enclosure => "Enclosure",
+            TarantulaField::Notes => "Notes",
+            TarantulaField::Aliases => "Aliases",
+        }
+    }
+}
+
+/// The names in a comma separated alias list, trimmed, without empty ones.
+pub fn split_aliases(aliases: Option<&str>) -> Vec<String> {
+    aliases
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|a| !a.is_empty())
+        .map(str::to_string)
+        .collect()
+}
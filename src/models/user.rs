@@ -1,3 +1,5 @@
+use chrono::{Duration, FixedOffset, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::{Tz, TZ_VARIANTS};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -6,4 +8,163 @@ pub struct TelegramUser {
     pub username: Option<String>,
     pub first_name: String,
     pub last_name: Option<String>,
-}
\ No newline at end of file
+}
+
+/// A keeper's timezone: a zone from the tz database such as "Europe/Berlin",
+/// which follows its daylight saving changes, or a fixed offset from UTC.
+/// Times are stored in UTC and shown, and split into days, on the keeper's
+/// clock.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Timezone {
+    Zone(Tz),
+    Offset(FixedOffset),
+}
+
+impl Default for Timezone {
+    fn default() -> Self {
+        Timezone::Zone(Tz::UTC)
+    }
+}
+
+impl Timezone {
+    /// The furthest any timezone is from UTC, at UTC+14:00.
+    const MAX_OFFSET_MINUTES: i32 = 14 * 60;
+
+    /// The tz database zone called `name`, in any case, e.g. "europe/berlin"
+    /// or "CET".
+    pub fn from_zone_name(name: &str) -> Option<Timezone> {
+        TZ_VARIANTS
+            .iter()
+            .find(|tz| tz.name().eq_ignore_ascii_case(name))
+            .map(|&tz| Timezone::Zone(tz))
+    }
+
+    /// A fixed offset, with no offset at all being UTC.
+    pub fn from_offset_minutes(offset_minutes: i32) -> Option<Timezone> {
+        if offset_minutes == 0 {
+            return Some(Timezone::default());
+        }
+        (offset_minutes.abs() <= Self::MAX_OFFSET_MINUTES)
+            .then(|| FixedOffset::east_opt(offset_minutes * 60))
+            .flatten()
+            .map(Timezone::Offset)
+    }
+
+    /// Reads back what [`Timezone::name`] wrote.
+    pub fn from_name(name: &str) -> Option<Timezone> {
+        Self::from_zone_name(name).or_else(|| {
+            let offset: FixedOffset = name.parse().ok()?;
+            Self::from_offset_minutes(offset.local_minus_utc() / 60)
+        })
+    }
+
+    /// The zone's name, or the offset as "+05:30", for storing.
+    pub fn name(self) -> String {
+        match self {
+            Timezone::Zone(tz) => tz.name().to_string(),
+            Timezone::Offset(offset) => offset.to_string(),
+        }
+    }
+
+    /// `utc` on the keeper's clock.
+    pub fn local(self, utc: NaiveDateTime) -> NaiveDateTime {
+        match self {
+            Timezone::Zone(tz) => tz.from_utc_datetime(&utc).naive_local(),
+            Timezone::Offset(offset) => offset.from_utc_datetime(&utc).naive_local(),
+        }
+    }
+
+    /// The UTC time at which the keeper's clock shows `local`. A time the
+    /// clocks skip when they spring forward is taken an hour later, and one
+    /// they show twice when they fall back is the first of the two.
+    pub fn utc(self, local: NaiveDateTime) -> NaiveDateTime {
+        match self {
+            Timezone::Zone(tz) => utc_on(&tz, local),
+            Timezone::Offset(offset) => utc_on(&offset, local),
+        }
+    }
+
+    pub fn now(self) -> NaiveDateTime {
+        self.local(Utc::now().naive_utc())
+    }
+
+    pub fn today(self) -> NaiveDate {
+        self.now().date()
+    }
+
+    /// The keeper's day that `utc` falls on.
+    pub fn day_of(self, utc: NaiveDateTime) -> NaiveDate {
+        self.local(utc).date()
+    }
+
+    /// "Europe/Berlin", "UTC", "UTC+2" or "UTC-3:30".
+    pub fn label(self) -> String {
+        let offset = match self {
+            Timezone::Zone(tz) => return tz.name().to_string(),
+            Timezone::Offset(offset) => offset.local_minus_utc() / 60,
+        };
+        let (hours, minutes) = (offset / 60, offset.abs() % 60);
+        match minutes {
+            0 => format!("UTC{:+}", hours),
+            _ => format!(
+                "UTC{}{}:{:02}",
+                if offset < 0 { '-' } else { '+' },
+                hours.abs(),
+                minutes
+            ),
+        }
+    }
+}
+
+fn utc_on<Z: TimeZone>(zone: &Z, local: NaiveDateTime) -> NaiveDateTime {
+    zone.from_local_datetime(&local)
+        .earliest()
+        .or_else(|| {
+            zone.from_local_datetime(&(local + Duration::hours(1)))
+                .earliest()
+        })
+        .map(|at| at.naive_utc())
+        .unwrap_or(local)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(text: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn zones_follow_daylight_saving() {
+        let berlin = Timezone::from_zone_name("europe/berlin").unwrap();
+        assert_eq!(berlin.local(at("2025-01-15 12:00")), at("2025-01-15 13:00"));
+        assert_eq!(berlin.local(at("2025-07-15 12:00")), at("2025-07-15 14:00"));
+        assert_eq!(berlin.utc(at("2025-07-15 14:00")), at("2025-07-15 12:00"));
+        assert_eq!(
+            berlin.day_of(at("2025-07-15 22:30")),
+            at("2025-07-16 00:00").date()
+        );
+    }
+
+    #[test]
+    fn skipped_and_repeated_times_still_convert() {
+        let berlin = Timezone::from_zone_name("Europe/Berlin").unwrap();
+        // 02:30 doesn't happen on 30 March 2025 and happens twice on 26 October.
+        assert_eq!(berlin.utc(at("2025-03-30 02:30")), at("2025-03-30 01:30"));
+        assert_eq!(berlin.utc(at("2025-10-26 02:30")), at("2025-10-26 00:30"));
+    }
+
+    #[test]
+    fn names_read_back_as_the_same_timezone() {
+        for timezone in [
+            Timezone::default(),
+            Timezone::from_zone_name("CET").unwrap(),
+            Timezone::from_offset_minutes(330).unwrap(),
+            Timezone::from_offset_minutes(-600).unwrap(),
+        ] {
+            assert_eq!(Timezone::from_name(&timezone.name()), Some(timezone));
+        }
+        assert_eq!(Timezone::from_name("Mars/Olympus_Mons"), None);
+    }
+}
@@ -0,0 +1,204 @@
+//! A terminal frontend for clicking through the bot without Telegram. Each
+//! screen is printed with numbered buttons: type a number to tap one, a
+//! `/command` to run it, or any other text to answer the current prompt.
+//! While a prompt is open numbers answer it, and `#n` taps button `n`.
+
+use crate::app::commands::{error_screen, Command};
+use crate::app::dialogue::DialogueState;
+use crate::app::screen::{Button, Document, Outcome, Screen, Transition, View};
+use crate::app::{App, Session};
+use crate::models::user::TelegramUser;
+use crate::BotResult;
+use std::fs;
+use std::io::{self, BufRead, Write};
+use teloxide::utils::command::BotCommands;
+
+const HINT: &str = "Type a button number, a /command, or q to quit.";
+
+pub struct Repl<W> {
+    app: App,
+    user_id: u64,
+    dialogue: Option<DialogueState>,
+    buttons: Vec<Button>,
+    out: W,
+}
+
+impl<W: Write> Repl<W> {
+    pub fn new(app: App, user_id: u64, out: W) -> Self {
+        Self {
+            app,
+            user_id,
+            dialogue: None,
+            buttons: Vec::new(),
+            out,
+        }
+    }
+
+    /// Shows the main menu, then handles `input` line by line until `q` or EOF.
+    pub async fn run(&mut self, input: impl BufRead) -> io::Result<()> {
+        writeln!(self.out, "{}", HINT)?;
+        self.handle("/start").await?;
+
+        let mut lines = input.lines();
+        loop {
+            write!(self.out, "> ")?;
+            self.out.flush()?;
+            let Some(line) = lines.next().transpose()? else {
+                break;
+            };
+            match line.trim() {
+                "q" | "quit" => break,
+                "" => {}
+                line => self.handle(line).await?,
+            }
+        }
+        writeln!(self.out)
+    }
+
+    async fn handle(&mut self, line: &str) -> io::Result<()> {
+        let session = Session {
+            user_id: self.user_id,
+            dialogue: self.dialogue.clone(),
+        };
+
+        let result = if line.starts_with('/') {
+            match Command::parse(line, "") {
+                Ok(cmd) => self.app.command(&self.local_user(), cmd).await,
+                Err(e) => return writeln!(self.out, "{}", e),
+            }
+        } else if let Some(n) = self.button_number(line) {
+            match self.buttons.get(n.wrapping_sub(1)) {
+                Some(button) => self.app.tap(&session, &button.callback).await,
+                None => return writeln!(self.out, "There is no button {}.", n),
+            }
+        } else {
+            match self.app.reply(&session, line).await {
+                Ok(outcome) if outcome.views.is_empty() => return writeln!(self.out, "{}", HINT),
+                result => result,
+            }
+        };
+
+        self.show(result)
+    }
+
+    /// A bare number taps a button unless a prompt is waiting for an answer,
+    /// in which case only `#n` does.
+    fn button_number(&self, line: &str) -> Option<usize> {
+        match line.strip_prefix('#') {
+            Some(n) => n.parse().ok(),
+            None if self.dialogue.is_none() => line.parse().ok(),
+            None => None,
+        }
+    }
+
+    fn show(&mut self, result: BotResult<Outcome>) -> io::Result<()> {
+        let outcome = result.unwrap_or_else(|e| Outcome::send(error_screen(&e)));
+        match outcome.dialogue {
+            Transition::Stay => {}
+            Transition::Enter(state) => self.dialogue = Some(state),
+            Transition::Exit => self.dialogue = None,
+        }
+        for view in outcome.views {
+            match view {
+                View::Replace(screen) | View::Send(screen) => self.render(screen)?,
+                View::Photo(photo) => self.render(Screen::new(
+                    format!("[photo {}]\n{}", photo.file_id, photo.caption),
+                    photo.buttons,
+                ))?,
+                View::Document(document) => self.save(document)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes a document to the working directory, where a chat would offer a
+    /// download.
+    fn save(&mut self, document: Document) -> io::Result<()> {
+        fs::write(&document.file_name, &document.contents)?;
+        writeln!(
+            self.out,
+            "\n{}\nSaved {} ({} bytes)",
+            document.caption,
+            document.file_name,
+            document.contents.len()
+        )
+    }
+
+    /// Prints a screen. Screens without buttons, like prompts, leave the
+    /// previous buttons tappable, just as older messages stay usable in a chat.
+    fn render(&mut self, screen: Screen) -> io::Result<()> {
+        writeln!(self.out, "\n{}", screen.text)?;
+        if screen.buttons.is_empty() {
+            return Ok(());
+        }
+
+        self.buttons.clear();
+        for row in screen.buttons {
+            let labels: Vec<String> = row
+                .into_iter()
+                .map(|button| {
+                    let label = format!("[{}] {}", self.buttons.len() + 1, button.label);
+                    self.buttons.push(button);
+                    label
+                })
+                .collect();
+            writeln!(self.out, "  {}", labels.join("   "))?;
+        }
+        Ok(())
+    }
+
+    fn local_user(&self) -> TelegramUser {
+        TelegramUser {
+            telegram_id: self.user_id,
+            username: None,
+            first_name: "Local keeper".to_string(),
+            last_name: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::memory::InMemoryDB;
+    use std::sync::Arc;
+
+    async fn session(script: &str) -> String {
+        let mut out = Vec::new();
+        Repl::new(App::new(Arc::new(InMemoryDB::new())), 1, &mut out)
+            .run(script.as_bytes())
+            .await
+            .unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[tokio::test]
+    async fn numbers_tap_the_buttons_of_the_last_screen() {
+        let out = session("1\n1\n").await;
+
+        assert!(out.contains("Welcome to your Tarantula Management System!"));
+        assert!(out.contains("[1] 🕷 List Tarantulas   [2] 📊 Status Overview"));
+        assert!(out.contains("No tarantulas found in the database.\n  [1] « Back to Menu"));
+        assert_eq!(out.matches("Welcome to your").count(), 2);
+    }
+
+    #[tokio::test]
+    async fn prompts_take_numbers_as_answers() {
+        let out = session("/addtarantula Rosie 8 2024-01-01 12 calm\n8\n#1\nbig\n6\n2\n").await;
+
+        assert!(out.contains("Please enter the molt size in centimeters:"));
+        assert!(out.contains("Please send me the size in centimeters (e.g., 12.5)"));
+        assert!(out.contains("Recording molt with size: 6cm"));
+        assert!(out.contains("• Recent Molts: 1 (30 days)"));
+    }
+
+    #[tokio::test]
+    async fn command_errors_are_shown_instead_of_ending_the_session() {
+        let out = session("/addpairing 1 9 2024-05-01 oops\n/nope\n42\nhello\nq\n1\n").await;
+
+        assert!(out.contains("❌ "));
+        assert!(out.contains("There is no button 42."));
+        assert_eq!(out.matches(HINT).count(), 2);
+        assert_eq!(out.matches("Welcome to your").count(), 1);
+    }
+}
@@ -0,0 +1,660 @@
+//! Feeding, health and maintenance rules.
+//!
+//! The database only loads [`TarantulaFacts`]; everything that decides whether a
+//! tarantula is due, how big it probably is or what needs attention lives here.
+
+use crate::models::enums::{HealthStatus, MoltStage};
+use crate::models::feeding::FeedingOverride;
+use crate::models::health::HealthAlert;
+use crate::models::tarantula::{MaintenanceTask, TarantulaListItem};
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+use serde::Deserialize;
+
+/// Species at or below this adult size are treated as dwarfs: they start smaller
+/// and reach adult size in roughly half the time.
+pub const DWARF_ADULT_SIZE_CM: f32 = 8.0;
+/// Used when a species has no adult size on record.
+pub const DEFAULT_ADULT_SIZE_CM: f32 = 12.0;
+pub const HEALTH_CHECK_INTERVAL_DAYS: i64 = 30;
+pub const FEEDING_STRIKE_DAYS: i64 = 14;
+pub const EXTENDED_PRE_MOLT_DAYS: i64 = 180;
+/// Keepers of slings acquired more than this long ago are assumed to have an adult.
+const ASSUMED_ADULT_AFTER_DAYS: i64 = 730;
+
+/// When a tarantula starts showing up in health alerts and maintenance tasks.
+/// The `[alerts]` section of the configuration, defaulting to the constants
+/// above.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AlertThresholds {
+    pub health_check_days: i64,
+    pub feeding_strike_days: i64,
+    pub pre_molt_days: i64,
+}
+
+impl Default for AlertThresholds {
+    fn default() -> Self {
+        Self {
+            health_check_days: HEALTH_CHECK_INTERVAL_DAYS,
+            feeding_strike_days: FEEDING_STRIKE_DAYS,
+            pre_molt_days: EXTENDED_PRE_MOLT_DAYS,
+        }
+    }
+}
+
+/// One row of a species feeding schedule, already joined with its frequency.
+#[derive(Debug, Clone)]
+pub struct ScheduleBand {
+    pub size_category: String,
+    pub body_length_cm: f32,
+    pub prey_size: String,
+    pub prey_type: String,
+    pub frequency_name: String,
+    pub min_days: Option<i32>,
+    pub max_days: Option<i32>,
+    pub notes: Option<String>,
+}
+
+/// Everything the rules need to know about one tarantula.
+#[derive(Debug, Clone)]
+pub struct TarantulaFacts {
+    pub id: i64,
+    pub name: String,
+    pub aliases: Vec<String>,
+    pub species_name: String,
+    pub scientific_name: String,
+    pub enclosure_number: Option<String>,
+    pub group_id: Option<i64>,
+    pub group_name: Option<String>,
+    pub acquisition_date: NaiveDate,
+    pub estimated_age_months: Option<i32>,
+    pub adult_size_cm: Option<f32>,
+    pub molt_stage: Option<MoltStage>,
+    pub health_status: Option<HealthStatus>,
+    pub last_molt_date: Option<NaiveDate>,
+    pub last_molt_length_cm: Option<f32>,
+    pub last_health_check_date: Option<NaiveDate>,
+    pub last_fed: Option<NaiveDateTime>,
+    /// Species schedule, ordered by body length.
+    pub schedule: Vec<ScheduleBand>,
+    pub feeding_override: Option<FeedingOverride>,
+}
+
+impl TarantulaFacts {
+    fn in_pre_molt(&self) -> bool {
+        matches!(
+            self.molt_stage,
+            Some(MoltStage::PreMolt) | Some(MoltStage::Molting)
+        )
+    }
+
+    fn active_override(&self, today: NaiveDate) -> Option<&FeedingOverride> {
+        self.feeding_override
+            .as_ref()
+            .filter(|o| o.expires_on.is_none_or(|d| d >= today))
+    }
+
+    fn days_since_feeding(&self, now: NaiveDateTime) -> Option<f64> {
+        self.last_fed
+            .map(|fed| (now - fed).num_seconds() as f64 / 86_400.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeedingState {
+    NeverFed,
+    Overdue,
+    Due,
+    NotDue,
+    PreMolt,
+    Unscheduled,
+}
+
+#[derive(Debug, Clone)]
+pub struct FeedingPlan {
+    pub state: FeedingState,
+    pub size_cm: f32,
+    pub band: Option<ScheduleBand>,
+    pub frequency_name: Option<String>,
+    pub min_days: Option<i32>,
+    pub max_days: Option<i32>,
+    pub prey_size: Option<String>,
+    pub prey_count: Option<i32>,
+    pub days_since_feeding: Option<f64>,
+    pub next_due: Option<NaiveDate>,
+    pub has_override: bool,
+    pub override_expires_on: Option<NaiveDate>,
+    pub reasons: Vec<String>,
+}
+
+impl FeedingPlan {
+    pub fn is_due(&self) -> bool {
+        matches!(
+            self.state,
+            FeedingState::NeverFed | FeedingState::Overdue | FeedingState::Due
+        )
+    }
+
+    /// Short status used in lists and reminders.
+    pub fn status(&self) -> String {
+        match self.state {
+            FeedingState::NeverFed => "Never fed".to_string(),
+            FeedingState::Overdue => format!(
+                "Overdue feeding ({})",
+                self.frequency_name.as_deref().unwrap_or("no schedule")
+            ),
+            FeedingState::Due => "Due for feeding".to_string(),
+            FeedingState::NotDue => "Fed recently".to_string(),
+            FeedingState::PreMolt => "In pre-molt".to_string(),
+            FeedingState::Unscheduled => "No feeding schedule".to_string(),
+        }
+    }
+}
+
+/// Best guess at the current body length: last measured molt, then age, then
+/// how long the keeper has had it.
+pub fn estimate_size_cm(facts: &TarantulaFacts, today: NaiveDate) -> (f32, String) {
+    if let Some(length) = facts.last_molt_length_cm {
+        return (length, format!("Size {:.1} cm from the last molt", length));
+    }
+
+    let adult = facts.adult_size_cm.unwrap_or(DEFAULT_ADULT_SIZE_CM);
+    let dwarf = adult <= DWARF_ADULT_SIZE_CM;
+    let days_kept = (today - facts.acquisition_date).num_days().max(0);
+
+    if let Some(age) = facts.estimated_age_months {
+        let age = age as i64 + days_kept / 30;
+        // Dwarfs mature in about half the time of regular species
+        let scale = if dwarf { 2 } else { 1 };
+        let fraction = match age * scale {
+            a if a < 6 => 0.2,
+            a if a < 12 => 0.4,
+            a if a < 24 => 0.6,
+            _ => 0.8,
+        };
+        return (
+            adult * fraction,
+            format!("Size estimated from an age of {} months", age),
+        );
+    }
+
+    if days_kept > ASSUMED_ADULT_AFTER_DAYS {
+        return (
+            adult,
+            "Assumed adult after two years in the collection".to_string(),
+        );
+    }
+
+    let fraction = if dwarf { 0.3 } else { 0.4 };
+    (
+        adult * fraction,
+        format!(
+            "Size estimated from the {} adult size",
+            if dwarf { "dwarf species" } else { "species" }
+        ),
+    )
+}
+
+/// Picks the smallest band that fits, or the largest band for anything bigger.
+fn schedule_band(schedule: &[ScheduleBand], size_cm: f32) -> Option<&ScheduleBand> {
+    schedule
+        .iter()
+        .find(|b| b.body_length_cm >= size_cm)
+        .or_else(|| schedule.last())
+}
+
+pub fn feeding_plan(facts: &TarantulaFacts, now: NaiveDateTime) -> FeedingPlan {
+    let today = now.date();
+    let (size_cm, size_reason) = estimate_size_cm(facts, today);
+    let band = schedule_band(&facts.schedule, size_cm).cloned();
+    let active_override = facts.active_override(today);
+    let mut reasons = vec![size_reason];
+
+    if let Some(b) = &band {
+        reasons.push(format!(
+            "Species schedule: {} - {}",
+            b.size_category, b.frequency_name
+        ));
+    }
+
+    let mut frequency_name = band.as_ref().map(|b| b.frequency_name.clone());
+    let mut min_days = band.as_ref().and_then(|b| b.min_days);
+    let mut max_days = band.as_ref().and_then(|b| b.max_days);
+    let mut prey_size = band.as_ref().map(|b| b.prey_size.clone());
+    let mut prey_count = None;
+
+    if let Some(o) = active_override {
+        if let (Some(name), Some(min), Some(max)) = (&o.frequency_name, o.min_days, o.max_days) {
+            frequency_name = Some(format!("{}, custom", name));
+            min_days = Some(min);
+            max_days = Some(max);
+        }
+        prey_size = o.prey_size.clone().or(prey_size);
+        prey_count = o.prey_count;
+        reasons.push(match o.expires_on {
+            Some(d) => format!("Custom schedule until {}", d),
+            None => "Custom schedule".to_string(),
+        });
+    }
+
+    let days_since_feeding = facts.days_since_feeding(now);
+    let (state, next_due) = if facts.in_pre_molt() {
+        reasons.push("Feeding paused during pre-molt".to_string());
+        (FeedingState::PreMolt, None)
+    } else {
+        match (min_days, max_days, facts.last_fed, days_since_feeding) {
+            (None, _, _, _) | (_, None, _, _) => (FeedingState::Unscheduled, None),
+            (_, _, None, _) | (_, _, _, None) => (FeedingState::NeverFed, Some(today)),
+            (Some(min), Some(max), Some(fed), Some(days)) => {
+                let next_due = fed.date() + Duration::days(min as i64);
+                let state = if days > max as f64 {
+                    FeedingState::Overdue
+                } else if days >= min as f64 {
+                    FeedingState::Due
+                } else {
+                    FeedingState::NotDue
+                };
+                (state, Some(next_due))
+            }
+        }
+    };
+
+    FeedingPlan {
+        state,
+        size_cm,
+        band,
+        frequency_name,
+        min_days,
+        max_days,
+        prey_size,
+        prey_count,
+        days_since_feeding,
+        next_due,
+        has_override: active_override.is_some(),
+        override_expires_on: active_override.and_then(|o| o.expires_on),
+        reasons,
+    }
+}
+
+fn list_item(
+    facts: &TarantulaFacts,
+    days_since_feeding: Option<f64>,
+    status: String,
+) -> TarantulaListItem {
+    TarantulaListItem {
+        id: facts.id,
+        name: facts.name.clone(),
+        species_name: facts.species_name.clone(),
+        enclosure_number: facts.enclosure_number.clone(),
+        days_since_feeding,
+        current_status: status,
+        group_id: facts.group_id,
+        group_name: facts.group_name.clone(),
+    }
+}
+
+/// Collection overview, ordered by name.
+pub fn collection_status(facts: &[TarantulaFacts], now: NaiveDateTime) -> Vec<TarantulaListItem> {
+    let mut items: Vec<TarantulaListItem> = facts
+        .iter()
+        .map(|f| {
+            let plan = feeding_plan(f, now);
+            let status = if f.molt_stage == Some(MoltStage::PreMolt) {
+                MoltStage::PreMolt.to_db_name().to_string()
+            } else if f.health_status == Some(HealthStatus::Critical) {
+                HealthStatus::Critical.to_db_name().to_string()
+            } else if plan.is_due() {
+                "Needs feeding".to_string()
+            } else {
+                "Normal".to_string()
+            };
+            list_item(f, plan.days_since_feeding, status)
+        })
+        .collect();
+    items.sort_by(|a, b| a.name.cmp(&b.name));
+    items
+}
+
+/// Tarantulas that should be fed now, never-fed first, then longest since feeding.
+pub fn due_feedings(facts: &[TarantulaFacts], now: NaiveDateTime) -> Vec<TarantulaListItem> {
+    let mut due: Vec<TarantulaListItem> = facts
+        .iter()
+        .filter_map(|f| {
+            let plan = feeding_plan(f, now);
+            plan.is_due()
+                .then(|| list_item(f, plan.days_since_feeding, plan.status()))
+        })
+        .collect();
+    due.sort_by(|a, b| {
+        let key = |t: &TarantulaListItem| t.days_since_feeding.unwrap_or(f64::MAX);
+        key(b).total_cmp(&key(a))
+    });
+    due
+}
+
+pub fn health_alert(
+    facts: &TarantulaFacts,
+    now: NaiveDateTime,
+    thresholds: &AlertThresholds,
+) -> Option<HealthAlert> {
+    let today = now.date();
+    let days_since_check = facts.last_health_check_date.map(|d| (today - d).num_days());
+    let days_since_feeding = facts.days_since_feeding(now).map(|d| d as i64);
+    let pre_molt = facts.molt_stage == Some(MoltStage::PreMolt);
+    let days_in_pre_molt = facts.last_molt_date.map(|d| (today - d).num_days());
+
+    let (alert_type, days_in_state) = match (days_since_check, days_since_feeding, days_in_pre_molt)
+    {
+        (Some(days), _, _) if days >= thresholds.health_check_days => {
+            ("Overdue Health Check", days)
+        }
+        (_, Some(days), _) if days >= thresholds.feeding_strike_days && !pre_molt => {
+            ("Extended Feeding Strike", days)
+        }
+        (_, _, Some(days)) if pre_molt && days >= thresholds.pre_molt_days => {
+            ("Extended Pre-molt", days)
+        }
+        _ => return None,
+    };
+
+    Some(HealthAlert {
+        id: facts.id,
+        name: facts.name.clone(),
+        scientific_name: facts.scientific_name.clone(),
+        alert_type: alert_type.to_string(),
+        days_in_state: days_in_state as i32,
+    })
+}
+
+/// All open health alerts, longest-standing first.
+pub fn health_alerts(
+    facts: &[TarantulaFacts],
+    now: NaiveDateTime,
+    thresholds: &AlertThresholds,
+) -> Vec<HealthAlert> {
+    let mut alerts: Vec<HealthAlert> = facts
+        .iter()
+        .filter_map(|f| health_alert(f, now, thresholds))
+        .collect();
+    alerts.sort_by_key(|a| std::cmp::Reverse(a.days_in_state));
+    alerts
+}
+
+/// Outstanding care tasks, most urgent first.
+pub fn maintenance_tasks(
+    facts: &[TarantulaFacts],
+    now: NaiveDateTime,
+    thresholds: &AlertThresholds,
+) -> Vec<MaintenanceTask> {
+    let today = now.date();
+    let mut tasks: Vec<MaintenanceTask> = facts
+        .iter()
+        .filter_map(|f| {
+            let check_overdue = f
+                .last_health_check_date
+                .is_some_and(|d| (today - d).num_days() >= thresholds.health_check_days);
+            let (action, priority) = if check_overdue {
+                ("Health Check Required", 1)
+            } else if feeding_plan(f, now).is_due() {
+                ("Feeding Due", 2)
+            } else if f.molt_stage == Some(MoltStage::PreMolt) {
+                ("Monitor for Molt", 3)
+            } else {
+                return None;
+            };
+
+            Some(MaintenanceTask {
+                id: f.id,
+                name: f.name.clone(),
+                enclosure_number: f
+                    .enclosure_number
+                    .clone()
+                    .unwrap_or_else(|| "No enclosure".to_string()),
+                scientific_name: f.scientific_name.clone(),
+                required_action: action.to_string(),
+                priority,
+            })
+        })
+        .collect();
+    tasks.sort_by(|a, b| {
+        a.priority
+            .cmp(&b.priority)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    tasks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn now() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 6, 1)
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap()
+    }
+
+    fn band(category: &str, length: f32, min: i32, max: i32) -> ScheduleBand {
+        ScheduleBand {
+            size_category: category.to_string(),
+            body_length_cm: length,
+            prey_size: format!("{} prey", category),
+            prey_type: "Crickets".to_string(),
+            frequency_name: format!("Every {}-{} days", min, max),
+            min_days: Some(min),
+            max_days: Some(max),
+            notes: None,
+        }
+    }
+
+    fn facts() -> TarantulaFacts {
+        TarantulaFacts {
+            id: 1,
+            name: "Rosie".to_string(),
+            aliases: Vec::new(),
+            species_name: "Chilean Rose".to_string(),
+            scientific_name: "Grammostola rosea".to_string(),
+            enclosure_number: None,
+            group_id: None,
+            group_name: None,
+            acquisition_date: NaiveDate::from_ymd_opt(2026, 1, 1).unwrap(),
+            estimated_age_months: None,
+            adult_size_cm: Some(14.0),
+            molt_stage: None,
+            health_status: None,
+            last_molt_date: None,
+            last_molt_length_cm: Some(5.0),
+            last_health_check_date: None,
+            last_fed: None,
+            schedule: vec![
+                band("Spiderling", 2.0, 3, 4),
+                band("Juvenile", 6.0, 5, 7),
+                band("Adult", 14.0, 14, 21),
+            ],
+            feeding_override: None,
+        }
+    }
+
+    fn fed_days_ago(days: i64) -> Option<NaiveDateTime> {
+        Some(now() - Duration::days(days))
+    }
+
+    #[test]
+    fn never_fed_is_due_today() {
+        let plan = feeding_plan(&facts(), now());
+        assert_eq!(plan.state, FeedingState::NeverFed);
+        assert_eq!(plan.next_due, Some(now().date()));
+        assert!(plan.is_due());
+    }
+
+    #[test]
+    fn feeding_window_follows_size_band() {
+        let mut f = facts();
+        f.last_fed = fed_days_ago(4);
+        let plan = feeding_plan(&f, now());
+        assert_eq!(plan.band.unwrap().size_category, "Juvenile");
+        assert_eq!(plan.state, FeedingState::NotDue);
+        assert_eq!(plan.next_due, Some(now().date() + Duration::days(1)));
+
+        f.last_fed = fed_days_ago(6);
+        assert_eq!(feeding_plan(&f, now()).state, FeedingState::Due);
+
+        f.last_fed = fed_days_ago(8);
+        let plan = feeding_plan(&f, now());
+        assert_eq!(plan.state, FeedingState::Overdue);
+        assert_eq!(plan.status(), "Overdue feeding (Every 5-7 days)");
+    }
+
+    #[test]
+    fn oversized_tarantula_uses_largest_band() {
+        let mut f = facts();
+        f.last_molt_length_cm = Some(18.0);
+        let plan = feeding_plan(&f, now());
+        assert_eq!(plan.band.unwrap().size_category, "Adult");
+    }
+
+    #[test]
+    fn pre_molt_pauses_feeding() {
+        let mut f = facts();
+        f.molt_stage = Some(MoltStage::PreMolt);
+        f.last_fed = fed_days_ago(40);
+        let plan = feeding_plan(&f, now());
+        assert_eq!(plan.state, FeedingState::PreMolt);
+        assert!(!plan.is_due());
+        assert!(due_feedings(&[f.clone()], now()).is_empty());
+        assert!(health_alert(&f, now(), &AlertThresholds::default()).is_none());
+    }
+
+    #[test]
+    fn extended_pre_molt_raises_alert() {
+        let mut f = facts();
+        f.molt_stage = Some(MoltStage::PreMolt);
+        f.last_molt_date = Some(now().date() - Duration::days(200));
+        let alert = health_alert(&f, now(), &AlertThresholds::default()).unwrap();
+        assert_eq!(alert.alert_type, "Extended Pre-molt");
+        assert_eq!(alert.days_in_state, 200);
+    }
+
+    #[test]
+    fn thresholds_decide_when_alerts_start() {
+        let mut f = facts();
+        f.last_fed = fed_days_ago(1);
+        f.last_health_check_date = Some(now().date() - Duration::days(10));
+        assert!(health_alert(&f, now(), &AlertThresholds::default()).is_none());
+
+        let strict = AlertThresholds {
+            health_check_days: 7,
+            ..AlertThresholds::default()
+        };
+        let alert = health_alert(&f, now(), &strict).unwrap();
+        assert_eq!(alert.alert_type, "Overdue Health Check");
+        assert_eq!(
+            maintenance_tasks(&[f], now(), &strict)[0].required_action,
+            "Health Check Required"
+        );
+    }
+
+    #[test]
+    fn dwarf_species_start_smaller_and_mature_faster() {
+        let mut dwarf = facts();
+        dwarf.adult_size_cm = Some(6.0);
+        dwarf.last_molt_length_cm = None;
+        dwarf.acquisition_date = now().date();
+        let (size, _) = estimate_size_cm(&dwarf, now().date());
+        assert!((size - 1.8).abs() < 0.01);
+        assert_eq!(
+            feeding_plan(&dwarf, now()).band.unwrap().size_category,
+            "Spiderling"
+        );
+
+        let mut regular = dwarf.clone();
+        regular.adult_size_cm = Some(14.0);
+        dwarf.estimated_age_months = Some(8);
+        regular.estimated_age_months = Some(8);
+        let (dwarf_size, _) = estimate_size_cm(&dwarf, now().date());
+        let (regular_size, _) = estimate_size_cm(&regular, now().date());
+        assert!((dwarf_size - 6.0 * 0.6).abs() < 0.01);
+        assert!((regular_size - 14.0 * 0.4).abs() < 0.01);
+    }
+
+    fn feeding_override(expires_on: Option<NaiveDate>) -> FeedingOverride {
+        FeedingOverride {
+            tarantula_id: 1,
+            frequency_id: Some(11),
+            frequency_name: Some("Every 21-30 days".to_string()),
+            min_days: Some(21),
+            max_days: Some(30),
+            prey_count: Some(1),
+            prey_size_id: Some(4),
+            prey_size: Some("Large".to_string()),
+            expires_on,
+            notes: None,
+        }
+    }
+
+    #[test]
+    fn override_replaces_species_frequency() {
+        let mut f = facts();
+        f.last_fed = fed_days_ago(10);
+        assert_eq!(feeding_plan(&f, now()).state, FeedingState::Overdue);
+
+        f.feeding_override = Some(feeding_override(None));
+        let plan = feeding_plan(&f, now());
+        assert_eq!(plan.state, FeedingState::NotDue);
+        assert_eq!(plan.max_days, Some(30));
+        assert_eq!(plan.prey_size.as_deref(), Some("Large"));
+        assert_eq!(plan.prey_count, Some(1));
+        assert_eq!(plan.next_due, Some(now().date() + Duration::days(11)));
+    }
+
+    #[test]
+    fn expired_override_is_ignored() {
+        let mut f = facts();
+        f.last_fed = fed_days_ago(10);
+        f.feeding_override = Some(feeding_override(Some(now().date() - Duration::days(1))));
+        let plan = feeding_plan(&f, now());
+        assert_eq!(plan.state, FeedingState::Overdue);
+        assert!(!plan.has_override);
+
+        f.feeding_override = Some(feeding_override(Some(now().date())));
+        assert_eq!(feeding_plan(&f, now()).state, FeedingState::NotDue);
+    }
+
+    #[test]
+    fn override_schedules_species_without_bands() {
+        let mut f = facts();
+        f.schedule.clear();
+        assert_eq!(feeding_plan(&f, now()).state, FeedingState::Unscheduled);
+
+        f.feeding_override = Some(feeding_override(None));
+        assert_eq!(feeding_plan(&f, now()).state, FeedingState::NeverFed);
+    }
+
+    #[test]
+    fn due_feedings_list_never_fed_first() {
+        let mut fed = facts();
+        fed.id = 2;
+        fed.last_fed = fed_days_ago(9);
+        let mut recent = facts();
+        recent.id = 3;
+        recent.last_fed = fed_days_ago(1);
+
+        let due = due_feedings(&[fed, facts(), recent], now());
+        assert_eq!(due.iter().map(|t| t.id).collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!(due[0].current_status, "Never fed");
+    }
+
+    #[test]
+    fn maintenance_prioritises_health_checks() {
+        let mut overdue_check = facts();
+        overdue_check.name = "B".to_string();
+        overdue_check.last_fed = fed_days_ago(1);
+        overdue_check.last_health_check_date = Some(now().date() - Duration::days(31));
+        let hungry = facts();
+
+        let tasks = maintenance_tasks(&[hungry, overdue_check], now(), &AlertThresholds::default());
+        assert_eq!(tasks[0].required_action, "Health Check Required");
+        assert_eq!(tasks[1].required_action, "Feeding Due");
+    }
+}
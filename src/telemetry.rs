@@ -0,0 +1,78 @@
+//! Log and trace output. `RUST_LOG` filters as before, `LOG_FORMAT=json`
+//! writes one JSON object per line, and setting `OTEL_EXPORTER_OTLP_ENDPOINT`
+//! (e.g. `http://localhost:4318`) also ships spans to an OpenTelemetry
+//! collector over OTLP/HTTP. Records from the `log` crate are forwarded too.
+
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::SpanExporter;
+use opentelemetry_sdk::trace::TracerProvider;
+use opentelemetry_sdk::{runtime, Resource};
+use std::env;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, EnvFilter};
+
+/// Flushes buffered spans to the collector when dropped, so keep it alive
+/// until the program exits.
+pub struct Telemetry {
+    provider: Option<TracerProvider>,
+}
+
+impl Drop for Telemetry {
+    fn drop(&mut self) {
+        if let Some(provider) = self.provider.take() {
+            if let Err(e) = provider.shutdown() {
+                eprintln!("Failed to flush traces: {}", e);
+            }
+        }
+    }
+}
+
+/// Installs the global subscriber, writing to stderr. `service` names this
+/// process in exported traces unless `OTEL_SERVICE_NAME` is set. Must run
+/// inside the Tokio runtime when exporting.
+pub fn init(service: &str) -> Telemetry {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    let json = env::var("LOG_FORMAT").is_ok_and(|f| f.eq_ignore_ascii_case("json"));
+    let (plain, json) = if json {
+        (None, Some(fmt::layer().json().with_writer(std::io::stderr)))
+    } else {
+        (Some(fmt::layer().with_writer(std::io::stderr)), None)
+    };
+
+    let (provider, export_error) = match env::var("OTEL_EXPORTER_OTLP_ENDPOINT") {
+        Ok(_) => match otlp_provider(service) {
+            Ok(provider) => (Some(provider), None),
+            Err(e) => (None, Some(e)),
+        },
+        Err(_) => (None, None),
+    };
+    let otel = provider.as_ref().map(|provider| {
+        tracing_opentelemetry::layer().with_tracer(provider.tracer(service.to_string()))
+    });
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(plain)
+        .with(json)
+        .with(otel)
+        .init();
+
+    if let Some(e) = export_error {
+        tracing::error!("Not exporting traces: {}", e);
+    }
+    Telemetry { provider }
+}
+
+/// The exporter reads the endpoint and headers from the standard `OTEL_*`
+/// variables itself.
+fn otlp_provider(service: &str) -> Result<TracerProvider, opentelemetry::trace::TraceError> {
+    let exporter = SpanExporter::builder().with_http().build()?;
+    let service = env::var("OTEL_SERVICE_NAME").unwrap_or_else(|_| service.to_string());
+
+    Ok(TracerProvider::builder()
+        .with_batch_exporter(exporter, runtime::Tokio)
+        .with_resource(Resource::new([KeyValue::new("service.name", service)]))
+        .build())
+}